@@ -0,0 +1,404 @@
+//! A minimal [Debug Adapter Protocol] server, which allows scripts to be
+//! debugged from editors.
+//!
+//! The server communicates over stdin and stdout and supports launching a
+//! single script, line breakpoints, stepping and inspecting local variables.
+//!
+//! [Debug Adapter Protocol]: https://microsoft.github.io/debug-adapter-protocol/
+
+use crate::{Config, ExitCode, Io};
+use anyhow::{anyhow, bail, Context as _, Result};
+use rune::runtime::debugger::{DebugStep, DebugStop, Debugger};
+use rune::runtime::VmExecution;
+use rune::termcolor::NoColor;
+use rune::{Context, Diagnostics, Options, Source, Sources, Unit, Vm};
+use rune_modules::capture_io::CaptureIo;
+use serde_json::{json, Value as Json};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The only thread reported to the client.
+const THREAD_ID: i64 = 1;
+
+/// Serve the debug adapter protocol until the client disconnects.
+pub(crate) async fn run(
+    io: &mut Io<'_>,
+    c: &Config,
+    flags: &crate::debug::Flags,
+    options: &Options,
+) -> Result<ExitCode> {
+    // NB: stdout carries the protocol, so output written by the script is
+    // captured and forwarded to the client as output events instead.
+    let capture_io = CaptureIo::new();
+    let context = flags.shared.context_with_capture(c, &capture_io)?;
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    let mut server = Server {
+        io,
+        context: &context,
+        capture_io: &capture_io,
+        options,
+        seq: 0,
+        session: None,
+    };
+
+    while let Some(request) = read_message(&mut input)? {
+        if !server.handle(&request).await? {
+            break;
+        }
+    }
+
+    Ok(ExitCode::Success)
+}
+
+/// A script being debugged.
+struct Session {
+    unit: Arc<Unit>,
+    sources: Sources,
+    debugger: Debugger,
+    execution: VmExecution<Vm>,
+    stop_on_entry: bool,
+}
+
+struct Server<'a, 'io> {
+    io: &'a mut Io<'io>,
+    context: &'a Context,
+    capture_io: &'a CaptureIo,
+    options: &'a Options,
+    /// Sequence number of the last message sent.
+    seq: i64,
+    session: Option<Session>,
+}
+
+impl Server<'_, '_> {
+    /// Handle a single request. Returns `false` if the server should stop.
+    async fn handle(&mut self, request: &Json) -> Result<bool> {
+        let command = request["command"].as_str().unwrap_or_default();
+        let arguments = &request["arguments"];
+
+        let body = match command {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+            })),
+            "launch" => self.launch(arguments),
+            "setBreakpoints" => self.set_breakpoints(arguments),
+            "configurationDone" | "threads" | "stackTrace" | "scopes" | "variables"
+                if self.session.is_none() =>
+            {
+                Err(anyhow!("no script has been launched"))
+            }
+            "configurationDone" => Ok(Json::Null),
+            "threads" => Ok(json!({
+                "threads": [{ "id": THREAD_ID, "name": "main" }],
+            })),
+            "stackTrace" => Ok(self.stack_trace()),
+            "scopes" => Ok(json!({
+                "scopes": [{
+                    "name": "Locals",
+                    "variablesReference": arguments["frameId"].as_i64().unwrap_or_default() + 1,
+                    "expensive": false,
+                }],
+            })),
+            "variables" => Ok(self.variables(arguments)),
+            "continue" => Ok(json!({ "allThreadsContinued": true })),
+            "next" | "stepIn" | "stepOut" | "disconnect" => Ok(Json::Null),
+            _ => Err(anyhow!("unsupported command `{}`", command)),
+        };
+
+        match body {
+            Ok(body) => self.respond(request, true, body, None)?,
+            Err(error) => self.respond(request, false, Json::Null, Some(error.to_string()))?,
+        }
+
+        let step = match command {
+            "launch" if self.session.is_some() => {
+                self.event("initialized", Json::Null)?;
+                return Ok(true);
+            }
+            "configurationDone" => match &self.session {
+                Some(session) if session.stop_on_entry => DebugStep::Into,
+                Some(..) => DebugStep::Continue,
+                None => return Ok(true),
+            },
+            "continue" => DebugStep::Continue,
+            "next" => DebugStep::Over,
+            "stepIn" => DebugStep::Into,
+            "stepOut" => DebugStep::Out,
+            "disconnect" => return Ok(false),
+            _ => return Ok(true),
+        };
+
+        self.resume(step, command == "configurationDone").await?;
+        Ok(true)
+    }
+
+    /// Compile the program to debug.
+    fn launch(&mut self, arguments: &Json) -> Result<Json> {
+        let program = arguments["program"]
+            .as_str()
+            .context("missing `program` argument")?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::from_path(Path::new(program))?);
+
+        let mut diagnostics = Diagnostics::new();
+
+        let result = rune::prepare(&mut sources)
+            .with_context(self.context)
+            .with_diagnostics(&mut diagnostics)
+            .with_options(self.options)
+            .build();
+
+        if !diagnostics.is_empty() {
+            let mut out = NoColor::new(Vec::new());
+            diagnostics.emit(&mut out, &sources)?;
+            self.output(&String::from_utf8_lossy(&out.into_inner()))?;
+        }
+
+        let unit = match result {
+            Ok(unit) => Arc::new(unit),
+            Err(..) => bail!("failed to compile `{}`", program),
+        };
+
+        let mut vm = Vm::new(Arc::new(self.context.runtime()), unit.clone());
+        let execution = vm.execute(&["main"], ())?.into_owned();
+        let debugger = Debugger::new(unit.clone(), &sources);
+
+        self.session = Some(Session {
+            unit,
+            sources,
+            debugger,
+            execution,
+            stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or_default(),
+        });
+
+        Ok(Json::Null)
+    }
+
+    /// Replace the breakpoints of a source.
+    fn set_breakpoints(&mut self, arguments: &Json) -> Result<Json> {
+        let session = self
+            .session
+            .as_mut()
+            .context("no script has been launched")?;
+        let path = Path::new(arguments["source"]["path"].as_str().unwrap_or_default());
+
+        let source_id = session.sources.source_ids().find(
+            |id| matches!(session.sources.path(*id), Some(source) if same_file(source, path)),
+        );
+
+        let mut breakpoints = Vec::new();
+
+        if let Some(source_id) = source_id {
+            session.debugger.clear_breakpoints(source_id);
+        }
+
+        for breakpoint in arguments["breakpoints"].as_array().into_iter().flatten() {
+            let line = breakpoint["line"].as_u64().unwrap_or_default() as usize;
+
+            let actual = source_id.and_then(|source_id| {
+                session
+                    .debugger
+                    .set_breakpoint(source_id, line.saturating_sub(1))
+            });
+
+            breakpoints.push(match actual {
+                Some(actual) => json!({ "verified": true, "line": actual + 1 }),
+                None => json!({ "verified": false, "line": line }),
+            });
+        }
+
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn stack_trace(&self) -> Json {
+        let mut frames = Vec::new();
+
+        if let Some(session) = &self.session {
+            let debug = session.unit.debug_info();
+
+            for (id, frame) in session
+                .debugger
+                .backtrace(session.execution.vm())
+                .iter()
+                .enumerate()
+            {
+                let name = frame
+                    .function
+                    .and_then(|hash| debug?.functions.get(&hash))
+                    .map(|signature| signature.path.to_string())
+                    .unwrap_or_else(|| String::from("<unknown>"));
+
+                let mut json = json!({ "id": id, "name": name, "line": 0, "column": 0 });
+
+                if let Some(location) = frame.location {
+                    json["line"] = json!(location.line + 1);
+                    json["column"] = json!(1);
+                    json["source"] = json!({
+                        "name": session.sources.name(location.source_id),
+                        "path": session.sources.path(location.source_id),
+                    });
+                }
+
+                frames.push(json);
+            }
+        }
+
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn variables(&self, arguments: &Json) -> Json {
+        let mut variables = Vec::new();
+        let reference = arguments["variablesReference"].as_u64().unwrap_or_default();
+
+        if let (Some(session), Some(index)) = (&self.session, (reference as usize).checked_sub(1)) {
+            let vm = session.execution.vm();
+
+            if let Some(frame) = session.debugger.backtrace(vm).get(index) {
+                for (name, value) in session.debugger.locals(vm, frame) {
+                    variables.push(json!({
+                        "name": name,
+                        "value": format!("{:?}", value),
+                        "variablesReference": 0,
+                    }));
+                }
+            }
+        }
+
+        json!({ "variables": variables })
+    }
+
+    /// Resume execution and report why it stopped.
+    async fn resume(&mut self, step: DebugStep, entry: bool) -> Result<()> {
+        let session = match &mut self.session {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+
+        let result = session
+            .debugger
+            .async_resume(&mut session.execution, step)
+            .await;
+
+        let captured = self.capture_io.drain();
+
+        if !captured.is_empty() {
+            self.output(&String::from_utf8_lossy(&captured))?;
+        }
+
+        let session = match &self.session {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+
+        let reason = match result {
+            Ok(DebugStop::Complete(value)) => {
+                self.output(&format!("== {:?}\n", value))?;
+                return self.terminate(0);
+            }
+            Ok(DebugStop::Breakpoint) => "breakpoint",
+            Ok(..) if entry => "entry",
+            Ok(..) => "step",
+            Err(error) => {
+                let mut out = NoColor::new(Vec::new());
+                error.emit(&mut out, &session.sources)?;
+                self.output(&String::from_utf8_lossy(&out.into_inner()))?;
+                return self.terminate(1);
+            }
+        };
+
+        self.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        )
+    }
+
+    fn terminate(&mut self, exit_code: i64) -> Result<()> {
+        self.session = None;
+        self.event("exited", json!({ "exitCode": exit_code }))?;
+        self.event("terminated", Json::Null)
+    }
+
+    fn output(&mut self, output: &str) -> Result<()> {
+        self.event("output", json!({ "category": "stdout", "output": output }))
+    }
+
+    fn respond(
+        &mut self,
+        request: &Json,
+        success: bool,
+        body: Json,
+        message: Option<String>,
+    ) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": success,
+            "message": message,
+            "body": body,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn send(&mut self, mut message: Json) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+
+        let message = serde_json::to_vec(&message)?;
+        write!(self.io.stdout, "Content-Length: {}\r\n\r\n", message.len())?;
+        self.io.stdout.write_all(&message)?;
+        self.io.stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Test if two paths refer to the same file.
+///
+/// The client might spell the path of a source differently from how it was
+/// given when launching, like through a symbolic link or a relative path.
+fn same_file(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Read a single message. Returns `None` once the input is closed.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Json>> {
+    let mut length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+
+    let length = length.context("missing Content-Length header")?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
@@ -0,0 +1,217 @@
+use crate::{ExitCode, Io, SharedFlags};
+use anyhow::Result;
+use rune::runtime::debugger::{DebugFrame, DebugLocation, DebugStep, DebugStop, Debugger};
+use rune::termcolor::{Color, ColorSpec, WriteColor};
+use rune::{Context, SourceId, Sources, Unit, Vm};
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+const HELP: &str = "\
+Commands:
+  break [<file>:]<line>   Set a breakpoint (alias: b).
+  delete [<file>:]<line>  Remove a breakpoint (alias: d).
+  continue                Run until the next breakpoint (alias: c, run).
+  step                    Step to the next line, entering calls (alias: s).
+  next                    Step to the next line, over calls (alias: n).
+  out                     Step out of the current function (alias: o).
+  locals                  Print the variables of the current function (alias: l).
+  print <name>            Print a single variable (alias: p).
+  backtrace               Print the call stack (alias: bt).
+  help                    Show this help (alias: h).
+  quit                    Stop debugging (alias: q).";
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Serve the Debug Adapter Protocol over stdin and stdout instead of
+    /// starting an interactive session.
+    #[structopt(long)]
+    pub(crate) dap: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+/// Debug the main function of the given unit interactively.
+pub(crate) async fn run(
+    io: &mut Io<'_>,
+    context: &Context,
+    unit: Arc<Unit>,
+    sources: &Sources,
+) -> Result<ExitCode> {
+    let runtime = Arc::new(context.runtime());
+
+    let mut vm = Vm::new(runtime, unit.clone());
+    let mut execution = vm.execute(&["main"], ())?;
+    let mut debugger = Debugger::new(unit.clone(), sources);
+
+    writeln!(
+        io.stdout,
+        "Type `continue` to start running the script, or `help` for a list of commands"
+    )?;
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        {
+            let mut o = io.stdout.lock();
+            o.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+            write!(o, "(debug) ")?;
+            o.set_color(&ColorSpec::new())?;
+            o.flush()?;
+        }
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(ExitCode::Success),
+        };
+
+        let (command, argument) = match line.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line.trim(), ""),
+        };
+
+        let step = match command {
+            "" => continue,
+            "c" | "continue" | "run" => DebugStep::Continue,
+            "s" | "step" => DebugStep::Into,
+            "n" | "next" => DebugStep::Over,
+            "o" | "out" => DebugStep::Out,
+            "b" | "break" => {
+                match parse_location(sources, argument) {
+                    Some((source_id, line)) => match debugger.set_breakpoint(source_id, line) {
+                        Some(line) => {
+                            let name = sources.name(source_id).unwrap_or("?");
+                            writeln!(io.stdout, "Breakpoint set at {}:{}", name, line + 1)?;
+                        }
+                        None => writeln!(io.stdout, "No code at or after `{}`", argument)?,
+                    },
+                    None => writeln!(io.stdout, "Bad location `{}`", argument)?,
+                }
+
+                continue;
+            }
+            "d" | "delete" => {
+                match parse_location(sources, argument) {
+                    Some((source_id, line)) if debugger.remove_breakpoint(source_id, line) => {
+                        writeln!(io.stdout, "Breakpoint removed")?;
+                    }
+                    _ => writeln!(io.stdout, "No breakpoint at `{}`", argument)?,
+                }
+
+                continue;
+            }
+            "l" | "locals" => {
+                let vm = execution.vm();
+
+                if let Some(frame) = debugger.backtrace(vm).first() {
+                    for (name, value) in debugger.locals(vm, frame) {
+                        writeln!(io.stdout, "{} = {:?}", name, value)?;
+                    }
+                }
+
+                continue;
+            }
+            "p" | "print" => {
+                let vm = execution.vm();
+
+                let value = debugger.backtrace(vm).first().and_then(|frame| {
+                    debugger
+                        .locals(vm, frame)
+                        .into_iter()
+                        .find(|(name, _)| name == argument)
+                });
+
+                match value {
+                    Some((_, value)) => writeln!(io.stdout, "{:?}", value)?,
+                    None => writeln!(io.stdout, "No variable named `{}`", argument)?,
+                }
+
+                continue;
+            }
+            "bt" | "backtrace" => {
+                for (n, frame) in debugger.backtrace(execution.vm()).iter().enumerate() {
+                    write!(io.stdout, "#{} ", n)?;
+                    emit_frame(io, &unit, sources, frame)?;
+                }
+
+                continue;
+            }
+            "h" | "help" => {
+                writeln!(io.stdout, "{}", HELP)?;
+                continue;
+            }
+            "q" | "quit" => return Ok(ExitCode::Success),
+            _ => {
+                writeln!(io.stdout, "Unknown command `{}`, try `help`", command)?;
+                continue;
+            }
+        };
+
+        match debugger.async_resume(&mut execution, step).await {
+            Ok(DebugStop::Complete(value)) => {
+                writeln!(io.stdout, "== {:?}", value)?;
+                return Ok(ExitCode::Success);
+            }
+            Ok(stop) => {
+                if let DebugStop::Breakpoint = stop {
+                    write!(io.stdout, "Breakpoint hit in ")?;
+                }
+
+                if let Some(frame) = debugger.backtrace(execution.vm()).first() {
+                    emit_frame(io, &unit, sources, frame)?;
+                }
+            }
+            Err(error) => {
+                error.emit(io.stdout, sources)?;
+                return Ok(ExitCode::VmError);
+            }
+        }
+    }
+}
+
+/// Parse a `[<file>:]<line>` location, where the line is one-based.
+fn parse_location(sources: &Sources, input: &str) -> Option<(SourceId, usize)> {
+    let (source_id, line) = match input.rsplit_once(':') {
+        Some((name, line)) => {
+            let source_id = sources
+                .source_ids()
+                .find(|id| sources.name(*id) == Some(name))?;
+            (source_id, line)
+        }
+        None => (sources.source_ids().next()?, input),
+    };
+
+    let line = line.trim().parse::<usize>().ok()?.checked_sub(1)?;
+    Some((source_id, line))
+}
+
+/// Print the function and line a frame is at.
+fn emit_frame(io: &mut Io<'_>, unit: &Unit, sources: &Sources, frame: &DebugFrame) -> Result<()> {
+    let signature = frame
+        .function
+        .and_then(|hash| unit.debug_info()?.functions.get(&hash));
+
+    match signature {
+        Some(signature) => write!(io.stdout, "{}", signature)?,
+        None => write!(io.stdout, "<unknown>")?,
+    }
+
+    if let Some(DebugLocation {
+        source_id, line, ..
+    }) = frame.location
+    {
+        if let Some(source) = sources.get(source_id) {
+            write!(io.stdout, " at {}:{}", source.name(), line + 1)?;
+
+            if let Some(text) = source.line_range(line).and_then(|r| source.get(r)) {
+                writeln!(io.stdout)?;
+                write!(io.stdout, "{:>5} | {}", line + 1, text.trim_end())?;
+            }
+        }
+    }
+
+    writeln!(io.stdout)?;
+    Ok(())
+}
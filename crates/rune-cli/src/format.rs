@@ -0,0 +1,49 @@
+use crate::{ExitCode, Io, SharedFlags};
+use anyhow::{Context, Result};
+use rune::{Diagnostics, Source, Sources};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Exit with a non-zero exit-code if the file is not formatted, instead of
+    /// formatting it
+    #[structopt(long)]
+    check: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+pub(crate) fn run(io: &mut Io<'_>, flags: &Flags, path: &Path) -> Result<ExitCode> {
+    let source =
+        Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
+
+    let formatted = match rune::fmt::layout_source(source.as_str()) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            let mut sources = Sources::new();
+            let source_id = sources.insert(source);
+
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.error(source_id, error);
+            diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+            return Ok(ExitCode::Failure);
+        }
+    };
+
+    if formatted == source.as_str() {
+        return Ok(ExitCode::Success);
+    }
+
+    if flags.check {
+        writeln!(io.stdout, "Would reformat: {}", path.display())?;
+        return Ok(ExitCode::Failure);
+    }
+
+    fs::write(path, formatted).with_context(|| format!("writing file: {}", path.display()))?;
+    writeln!(io.stdout, "Formatted: {}", path.display())?;
+    Ok(ExitCode::Success)
+}
@@ -63,8 +63,12 @@ use tracing_subscriber::filter::EnvFilter;
 
 mod benches;
 mod check;
+mod dap;
+mod debug;
 mod doc;
+mod format;
 mod loader;
+mod repl;
 mod run;
 mod tests;
 mod visitor;
@@ -82,12 +86,18 @@ enum Command {
     Check(check::Flags),
     /// Build documentation.
     Doc(doc::Flags),
+    /// Format the given sources
+    Fmt(format::Flags),
     /// Run all tests but do not execute
     Test(tests::Flags),
     /// Run the given program as a benchmark
     Bench(benches::Flags),
     /// Run the designated script
     Run(run::Flags),
+    /// Start an interactive session
+    Repl(repl::Flags),
+    /// Debug the designated script
+    Debug(debug::Flags),
 }
 
 impl Command {
@@ -95,6 +105,7 @@ impl Command {
         match self {
             Command::Check(..) => {}
             Command::Doc(..) => {}
+            Command::Fmt(..) => {}
             Command::Test(..) => {
                 c.test = true;
             }
@@ -102,8 +113,10 @@ impl Command {
                 c.test = true;
             }
             Command::Run(args) => {
-                args.propagate_related_flags();
+                args.propagate_related_flags(c);
             }
+            Command::Repl(..) => {}
+            Command::Debug(..) => {}
         }
     }
 
@@ -111,9 +124,12 @@ impl Command {
         match self {
             Command::Check(..) => "Checking",
             Command::Doc(..) => "Building documentation",
+            Command::Fmt(..) => "Formatting",
             Command::Test(..) => "Testing",
             Command::Bench(..) => "Benchmarking",
             Command::Run(..) => "Running",
+            Command::Repl(..) => "Starting",
+            Command::Debug(..) => "Debugging",
         }
     }
 
@@ -121,16 +137,23 @@ impl Command {
         match self {
             Command::Check(args) => &args.shared,
             Command::Doc(args) => &args.shared,
+            Command::Fmt(args) => &args.shared,
             Command::Test(args) => &args.shared,
             Command::Bench(args) => &args.shared,
             Command::Run(args) => &args.shared,
+            Command::Repl(args) => &args.shared,
+            Command::Debug(args) => &args.shared,
         }
     }
 
     fn bins_test(&self) -> Option<WorkspaceFilter<'_>> {
         if !matches!(
             self,
-            Command::Run(..) | Command::Check(..) | Command::Doc(..)
+            Command::Run(..)
+                | Command::Debug(..)
+                | Command::Check(..)
+                | Command::Doc(..)
+                | Command::Fmt(..)
         ) {
             return None;
         }
@@ -147,7 +170,7 @@ impl Command {
     fn tests_test(&self) -> Option<WorkspaceFilter<'_>> {
         if !matches!(
             self,
-            Command::Test(..) | Command::Check(..) | Command::Doc(..)
+            Command::Test(..) | Command::Check(..) | Command::Doc(..) | Command::Fmt(..)
         ) {
             return None;
         }
@@ -164,7 +187,11 @@ impl Command {
     fn examples_test(&self) -> Option<WorkspaceFilter<'_>> {
         if !matches!(
             self,
-            Command::Run(..) | Command::Check(..) | Command::Doc(..)
+            Command::Run(..)
+                | Command::Debug(..)
+                | Command::Check(..)
+                | Command::Doc(..)
+                | Command::Fmt(..)
         ) {
             return None;
         }
@@ -181,7 +208,7 @@ impl Command {
     fn benches_test(&self) -> Option<WorkspaceFilter<'_>> {
         if !matches!(
             self,
-            Command::Bench(..) | Command::Check(..) | Command::Doc(..)
+            Command::Bench(..) | Command::Check(..) | Command::Doc(..) | Command::Fmt(..)
         ) {
             return None;
         }
@@ -204,6 +231,14 @@ struct SharedFlags {
     #[structopt(long)]
     experimental: bool,
 
+    /// Allow scripts to set and remove environment variables.
+    ///
+    /// Scripts see a copy of the environment of this process, which is
+    /// read-only unless this is enabled. Changes are never applied to the
+    /// environment of this process.
+    #[structopt(long)]
+    mutable_env: bool,
+
     /// Recursively load all files in the given directory.
     #[structopt(long)]
     recursive: bool,
@@ -272,12 +307,15 @@ struct Config {
     verbose: bool,
     /// The explicit paths to load.
     entries: Vec<Entry>,
+    /// Arguments to pass to the script.
+    args: Vec<String>,
 }
 
 impl SharedFlags {
     /// Construct a rune context according to the specified argument.
     fn context(&self, c: &Config) -> Result<Context, ContextError> {
         let mut context = rune_modules::default_context()?;
+        context.install(&self.env_module(c)?)?;
 
         if self.experimental {
             context.install(&rune_modules::experiments::module(true)?)?;
@@ -295,6 +333,7 @@ impl SharedFlags {
         let mut context = rune_modules::with_config(false)?;
 
         context.install(&rune_modules::capture_io::module(io)?)?;
+        context.install(&self.env_module(c)?)?;
 
         if self.experimental {
             context.install(&rune_modules::experiments::module(true)?)?;
@@ -306,6 +345,20 @@ impl SharedFlags {
 
         Ok(context)
     }
+
+    /// Construct the `env` module, which forwards the arguments given after
+    /// `--` to the script and gives it a virtual copy of the environment.
+    fn env_module(&self, c: &Config) -> Result<rune::Module, ContextError> {
+        let vars = std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)));
+
+        let config = rune_modules::env::Config::new()
+            .with_args(c.args.iter().cloned())
+            .with_vars(vars)
+            .with_mutable_vars(self.mutable_env);
+
+        rune_modules::env::module_with_config(config)
+    }
 }
 
 #[derive(Debug, Clone, StructOpt)]
@@ -339,7 +392,15 @@ impl Args {
                 options.test(true);
                 options.bytecode(false);
             }
-            Command::Bench(_) | Command::Doc(..) | Command::Run(_) => (),
+            Command::Debug(_) => {
+                options.debug_info(true);
+                options.bytecode(false);
+            }
+            Command::Bench(_)
+            | Command::Doc(..)
+            | Command::Fmt(..)
+            | Command::Run(_)
+            | Command::Repl(_) => (),
         }
 
         for option in &self.cmd.shared().compiler_options {
@@ -546,6 +607,19 @@ fn populate_config(io: &mut Io<'_>, c: &mut Config, args: &Args) -> Result<()> {
 async fn main_with_out(io: &mut Io<'_>, mut args: Args) -> Result<ExitCode> {
     let mut c = Config::default();
     args.cmd.propagate_related_flags(&mut c);
+
+    if let Command::Repl(flags) = &args.cmd {
+        let options = args.options()?;
+        return repl::run(io, &c, flags, &options).await;
+    }
+
+    if let Command::Debug(flags) = &args.cmd {
+        if flags.dap {
+            let options = args.options()?;
+            return dap::run(io, &c, flags, &options).await;
+        }
+    }
+
     populate_config(io, &mut c, &args)?;
 
     let entries = std::mem::take(&mut c.entries);
@@ -598,6 +672,7 @@ async fn run_path(
     match &args.cmd {
         Command::Check(flags) => check::run(io, c, flags, options, path),
         Command::Doc(flags) => doc::run(io, c, flags, options, path),
+        Command::Fmt(flags) => format::run(io, flags, path),
         Command::Test(flags) => {
             let capture_io = rune_modules::capture_io::CaptureIo::new();
             let context = flags.shared.context_with_capture(c, &capture_io)?;
@@ -637,5 +712,11 @@ async fn run_path(
             let load = loader::load(io, &context, args, options, path, visitor::Attribute::None)?;
             run::run(io, c, flags, &context, load.unit, &load.sources).await
         }
+        Command::Debug(flags) => {
+            let context = flags.shared.context(c)?;
+            let load = loader::load(io, &context, args, options, path, visitor::Attribute::None)?;
+            debug::run(io, &context, load.unit, &load.sources).await
+        }
+        Command::Repl(..) => Ok(ExitCode::Success),
     }
 }
@@ -0,0 +1,573 @@
+use crate::{Config, ExitCode, Io, SharedFlags};
+use anyhow::{anyhow, Result};
+use rune::ast::{self, Spanned};
+use rune::runtime::RuntimeContext;
+use rune::termcolor::{Color, ColorSpec, WriteColor};
+use rune::{Context, Diagnostics, FromValue, Options, Source, SourceId, Sources, Unit, Value, Vm};
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The name of the function generated to evaluate each input.
+const ENTRY: &str = "repl__eval";
+
+const HELP: &str = "\
+Enter items (functions, types, imports) or statements to evaluate them.
+Declaring an item again replaces the earlier one with the same name.
+
+Commands:
+  :type <expr>  Evaluate an expression and print its type.
+  :dis          Print the instructions of the last compiled input.
+  :load <path>  Load and evaluate the given file.
+  :help         Show this help.
+  :quit         Exit the repl.";
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Include source code references in the output of `:dis` (only available
+    /// if -O debug-info=true).
+    #[structopt(long)]
+    with_source: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+/// Run an interactive session. Any paths specified are loaded before the
+/// prompt is shown.
+pub(crate) async fn run(
+    io: &mut Io<'_>,
+    c: &Config,
+    flags: &Flags,
+    options: &Options,
+) -> Result<ExitCode> {
+    let context = flags.shared.context(c)?;
+    let mut repl = Repl::new(&context, options, flags)?;
+
+    for path in &flags.shared.paths {
+        repl.load(io, path).await?;
+    }
+
+    let mut editor = Editor::<()>::new()?;
+    let mut input = String::new();
+
+    loop {
+        let prompt = if input.is_empty() { "rune> " } else { "  ... " };
+
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            // NB: ctrl-c discards the current input, like in a shell.
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.into()),
+        };
+
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str());
+        }
+
+        if input.is_empty() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(command) = line.trim().strip_prefix(':') {
+                match Command::parse(command) {
+                    Command::Quit => break,
+                    command => repl.command(io, command).await?,
+                }
+
+                continue;
+            }
+        }
+
+        // An empty line forces evaluation of incomplete input, so that the
+        // error is reported instead of waiting for more input.
+        let force = line.trim().is_empty();
+        input.push_str(&line);
+        input.push('\n');
+
+        if !force && repl.is_incomplete(&input) {
+            continue;
+        }
+
+        let input = std::mem::take(&mut input);
+        repl.eval(io, &input, Mode::Print).await?;
+    }
+
+    Ok(ExitCode::Success)
+}
+
+/// A `:<command>` entered at the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command<'a> {
+    /// Evaluate an expression and print its type.
+    Type(&'a str),
+    /// Print the instructions of the last compiled input.
+    Dis,
+    /// Load and evaluate the given file.
+    Load(&'a str),
+    /// Show the help.
+    Help,
+    /// Exit the repl.
+    Quit,
+    /// A command which isn't recognized.
+    Unknown(&'a str),
+}
+
+impl<'a> Command<'a> {
+    /// Parse a command, without its leading `:`.
+    fn parse(command: &'a str) -> Self {
+        let (command, argument) = match command.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (command.trim(), ""),
+        };
+
+        match command {
+            "t" | "type" => Self::Type(argument),
+            "dis" => Self::Dis,
+            "l" | "load" => Self::Load(argument),
+            "h" | "help" => Self::Help,
+            "q" | "quit" => Self::Quit,
+            command => Self::Unknown(command),
+        }
+    }
+}
+
+/// How the result of an evaluation should be presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Debug print the value.
+    Print,
+    /// Print the type of the value and discard any bindings.
+    Type,
+    /// Don't print anything.
+    Quiet,
+}
+
+/// The state of an interactive session.
+struct Repl<'a> {
+    context: &'a Context,
+    runtime: Arc<RuntimeContext>,
+    options: &'a Options,
+    flags: &'a Flags,
+    /// Items which have been declared so far.
+    items: Vec<Decl>,
+    /// Names of variables declared so far.
+    names: Vec<String>,
+    /// Values of variables declared so far.
+    values: Vec<Value>,
+    /// The last unit compiled and its sources, used by `:dis`.
+    last: Option<(Arc<Unit>, Sources)>,
+}
+
+/// An item declared in the session.
+#[derive(Debug, Clone, PartialEq)]
+struct Decl {
+    /// The name the item is declared under, if it has one.
+    name: Option<String>,
+    /// The source of the item.
+    text: String,
+}
+
+/// An input which has been split up into items and statements.
+#[derive(Default)]
+struct Input {
+    items: Vec<Decl>,
+    body: String,
+    value: Option<String>,
+    names: Vec<String>,
+}
+
+impl<'a> Repl<'a> {
+    fn new(context: &'a Context, options: &'a Options, flags: &'a Flags) -> Result<Self> {
+        Ok(Self {
+            context,
+            runtime: Arc::new(context.runtime()),
+            options,
+            flags,
+            items: Vec::new(),
+            names: Vec::new(),
+            values: Vec::new(),
+            last: None,
+        })
+    }
+
+    /// Handle a `:<command>`.
+    async fn command(&mut self, io: &mut Io<'_>, command: Command<'_>) -> Result<()> {
+        match command {
+            Command::Type(expr) => {
+                self.eval(io, expr, Mode::Type).await?;
+            }
+            Command::Dis => match &self.last {
+                Some((unit, sources)) => {
+                    let mut o = io.stdout.lock();
+                    unit.emit_instructions(&mut o, sources, self.flags.with_source)?;
+                }
+                None => {
+                    writeln!(io.stdout, "Nothing has been compiled yet")?;
+                }
+            },
+            Command::Load(path) => {
+                self.load(io, Path::new(path)).await?;
+            }
+            Command::Help => {
+                writeln!(io.stdout, "{}", HELP)?;
+            }
+            Command::Quit => (),
+            Command::Unknown(command) => {
+                writeln!(io.stdout, "Unknown command `:{}`, try `:help`", command)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load the file at the given path into the session.
+    ///
+    /// A file which can't be read is reported, without ending the session.
+    async fn load(&mut self, io: &mut Io<'_>, path: &Path) -> Result<()> {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(error) => {
+                let error = anyhow!(error).context(format!("reading file: {}", path.display()));
+                let mut o = io.stdout.lock();
+                o.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+                let result = crate::format_errors(&mut o, error.as_ref());
+                o.set_color(&ColorSpec::new())?;
+                result?;
+                return Ok(());
+            }
+        };
+
+        self.eval(io, &input, Mode::Quiet).await
+    }
+
+    /// Test if the given input is incomplete, in which case more lines should
+    /// be read before it's evaluated.
+    fn is_incomplete(&self, input: &str) -> bool {
+        let wrapped = wrap(input);
+
+        match rune::parse::parse_all::<ast::Block>(&wrapped, SourceId::empty(), false) {
+            // NB: an error at the closing brace we added means that the parser
+            // ran out of input.
+            Err(error) => error.span().start.into_usize() >= wrapped.len() - 2,
+            Ok(..) => false,
+        }
+    }
+
+    /// Compile and evaluate the given input.
+    async fn eval(&mut self, io: &mut Io<'_>, input: &str, mode: Mode) -> Result<()> {
+        let input = match split_input(input) {
+            Ok(input) => input,
+            Err(error) => {
+                let mut sources = Sources::new();
+                let source_id = sources.insert(Source::new("<repl>", wrap(input)));
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.error(source_id, error);
+                diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+                return Ok(());
+            }
+        };
+
+        let mut items = self.items.clone();
+        declare(&mut items, input.items);
+
+        let mut names = self.names.clone();
+
+        for name in input.names {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        let mut source = String::new();
+
+        for decl in &items {
+            source.push_str(&decl.text);
+            source.push('\n');
+        }
+
+        source.push_str(&format!(
+            "\npub async fn {}({}) {{\n{}\nlet repl__value = {};\n(repl__value, [{}])\n}}\n",
+            ENTRY,
+            self.names.join(", "),
+            input.body,
+            input.value.as_deref().unwrap_or("()"),
+            names.join(", "),
+        ));
+
+        let mut sources = Sources::new();
+        sources.insert(Source::new("<repl>", source));
+
+        let mut diagnostics = if self.flags.shared.warnings {
+            Diagnostics::new()
+        } else {
+            Diagnostics::without_warnings()
+        };
+
+        let result = rune::prepare(&mut sources)
+            .with_context(self.context)
+            .with_diagnostics(&mut diagnostics)
+            .with_options(self.options)
+            .build();
+
+        diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+
+        let unit = match result {
+            Ok(unit) => Arc::new(unit),
+            Err(..) => return Ok(()),
+        };
+
+        let values = self.execute(io, unit.clone(), &sources, mode).await?;
+        self.last = Some((unit, sources));
+
+        if let Some(values) = values {
+            self.items = items;
+            self.names = names;
+            self.values = values;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a compiled input and present its result. Returns the new
+    /// values of all variables if the input should be committed to the
+    /// session.
+    async fn execute(
+        &self,
+        io: &mut Io<'_>,
+        unit: Arc<Unit>,
+        sources: &Sources,
+        mode: Mode,
+    ) -> Result<Option<Vec<Value>>> {
+        let mut vm = Vm::new(self.runtime.clone(), unit);
+
+        let output = match vm.execute(&[ENTRY], self.values.clone()) {
+            Ok(mut execution) => execution.async_complete().await,
+            Err(error) => Err(error),
+        };
+
+        let (value, values) = match output.and_then(<(Value, Vec<Value>)>::from_value) {
+            Ok(output) => output,
+            Err(error) => {
+                error.emit(io.stdout, sources)?;
+                return Ok(None);
+            }
+        };
+
+        match mode {
+            Mode::Print => {
+                if !matches!(value, Value::Unit) {
+                    let mut s = String::new();
+
+                    match vm.with(|| value.string_debug(&mut s)) {
+                        Ok(result) => result?,
+                        Err(error) => {
+                            error.emit(io.stdout, sources)?;
+                            return Ok(None);
+                        }
+                    }
+
+                    writeln!(io.stdout, "{}", s)?;
+                }
+            }
+            Mode::Type => {
+                writeln!(io.stdout, "{}", value.type_info()?)?;
+                return Ok(None);
+            }
+            Mode::Quiet => (),
+        }
+
+        Ok(Some(values))
+    }
+}
+
+/// Wrap the input in a block so that it can be parsed as a sequence of
+/// statements.
+fn wrap(input: &str) -> String {
+    format!("{{\n{}\n}}", input)
+}
+
+/// Split an input into the items it declares and the statements to evaluate.
+fn split_input(input: &str) -> Result<Input, rune::parse::ParseError> {
+    let wrapped = wrap(input);
+    let block = rune::parse::parse_all::<ast::Block>(&wrapped, SourceId::empty(), false)?;
+
+    let mut out = Input::default();
+    let mut it = block.statements.iter().peekable();
+
+    while let Some(stmt) = it.next() {
+        let text = &wrapped[stmt.span().range()];
+
+        match stmt {
+            ast::Stmt::Item(item, _) => {
+                let name = match item {
+                    ast::Item::Fn(item) => Some(&item.name),
+                    ast::Item::Enum(item) => Some(&item.name),
+                    ast::Item::Struct(item) => Some(&item.ident),
+                    ast::Item::Trait(item) => Some(&item.name),
+                    ast::Item::Mod(item) => Some(&item.name),
+                    ast::Item::Const(item) => Some(&item.name),
+                    _ => None,
+                };
+
+                out.items.push(Decl {
+                    name: name.map(|ident| wrapped[ident.span().range()].to_owned()),
+                    text: text.to_owned(),
+                });
+            }
+            ast::Stmt::Expr(..) if it.peek().is_none() => {
+                out.value = Some(text.to_owned());
+            }
+            stmt => {
+                if let ast::Stmt::Local(local) = stmt {
+                    pat_names(&wrapped, &local.pat, &mut out.names);
+                }
+
+                out.body.push_str(text);
+                out.body.push('\n');
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Add newly declared items to the ones declared so far. An item replaces an
+/// earlier item with the same name, so that things like functions can be
+/// redefined, and an item without a name replaces an identical one.
+fn declare(items: &mut Vec<Decl>, new: Vec<Decl>) {
+    for decl in new {
+        items.retain(|d| match (&d.name, &decl.name) {
+            (Some(a), Some(b)) => a != b,
+            _ => d.text != decl.text,
+        });
+
+        items.push(decl);
+    }
+}
+
+/// Collect the names of the variables bound by the given pattern.
+fn pat_names(source: &str, pat: &ast::Pat, names: &mut Vec<String>) {
+    let mut push = |ident: &ast::Ident| {
+        let name = &source[ident.span().range()];
+
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_owned());
+        }
+    };
+
+    match pat {
+        ast::Pat::PatPath(pat) => {
+            if let (ast::PathSegment::Ident(ident), true) =
+                (&pat.path.first, pat.path.rest.is_empty())
+            {
+                push(ident);
+            }
+        }
+        ast::Pat::PatAt(pat) => {
+            push(&pat.ident);
+            pat_names(source, &pat.pat, names);
+        }
+        ast::Pat::PatVec(pat) => {
+            for (pat, _) in &pat.items {
+                pat_names(source, pat, names);
+            }
+        }
+        ast::Pat::PatTuple(pat) => {
+            for (pat, _) in &pat.items {
+                pat_names(source, pat, names);
+            }
+        }
+        ast::Pat::PatObject(pat) => {
+            for (pat, _) in &pat.items {
+                pat_names(source, pat, names);
+            }
+        }
+        ast::Pat::PatBinding(pat) => {
+            pat_names(source, &pat.pat, names);
+        }
+        ast::Pat::PatOr(pat) => {
+            pat_names(source, &pat.first, names);
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{declare, split_input, wrap, Command, Decl};
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("let a = 1;"), "{\nlet a = 1;\n}");
+        assert_eq!(wrap(""), "{\n\n}");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(Command::parse("t 1 + 2"), Command::Type("1 + 2"));
+        assert_eq!(Command::parse("type  [1, 2] "), Command::Type("[1, 2]"));
+        assert_eq!(Command::parse("dis"), Command::Dis);
+        assert_eq!(Command::parse("l script.rn"), Command::Load("script.rn"));
+        assert_eq!(Command::parse("load"), Command::Load(""));
+        assert_eq!(Command::parse("h"), Command::Help);
+        assert_eq!(Command::parse(" help "), Command::Help);
+        assert_eq!(Command::parse("q"), Command::Quit);
+        assert_eq!(Command::parse("quit"), Command::Quit);
+        assert_eq!(Command::parse("nope 1"), Command::Unknown("nope"));
+    }
+
+    #[test]
+    fn test_split_input() {
+        let input =
+            split_input("fn foo() { 1 }\nlet (a, [b, c]) = (1, [2, 3]);\na + foo()").unwrap();
+        assert_eq!(
+            input.items,
+            [Decl {
+                name: Some(String::from("foo")),
+                text: String::from("fn foo() { 1 }"),
+            }]
+        );
+        assert_eq!(input.body, "let (a, [b, c]) = (1, [2, 3]);\n");
+        assert_eq!(input.value.as_deref(), Some("a + foo()"));
+        assert_eq!(input.names, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_redefine_items() {
+        let mut items = Vec::new();
+        declare(
+            &mut items,
+            split_input("fn foo() { 1 }\nuse std::iter;").unwrap().items,
+        );
+        declare(&mut items, split_input("fn bar() { foo() }").unwrap().items);
+        declare(
+            &mut items,
+            split_input("fn foo() { 2 }\nuse std::iter;").unwrap().items,
+        );
+
+        let texts = items.iter().map(|d| d.text.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            ["fn bar() { foo() }", "fn foo() { 2 }", "use std::iter;"]
+        );
+
+        let mut source = texts.join("\n");
+        source.push_str("\npub fn main() { bar() }");
+
+        let context = rune::Context::with_default_modules().unwrap();
+        let mut sources = rune::Sources::new();
+        sources.insert(rune::Source::new("<repl>", source));
+        let unit = rune::prepare(&mut sources).with_context(&context).build();
+        assert!(unit.is_ok(), "redefined items should compile");
+    }
+}
@@ -52,10 +52,17 @@ pub(crate) struct Flags {
 
     #[structopt(flatten)]
     pub(crate) shared: SharedFlags,
+
+    /// Arguments to pass to the script, which are available through
+    /// `env::args()`.
+    #[structopt(last = true)]
+    pub(crate) args: Vec<String>,
 }
 
 impl Flags {
-    pub(crate) fn propagate_related_flags(&mut self) {
+    pub(crate) fn propagate_related_flags(&mut self, c: &mut Config) {
+        c.args = self.args.clone();
+
         if self.dump {
             self.dump_constants = true;
             self.dump_unit = true;
@@ -54,7 +54,7 @@ pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version.txt"))
 pub use crate::connection::stdio;
 pub use crate::connection::{Input, Output};
 pub use crate::server::Server;
-pub use crate::state::State;
+pub use crate::state::{RenameError, State};
 use anyhow::Result;
 use rune::{Context, Options};
 use tokio::sync::mpsc;
@@ -69,6 +69,14 @@ pub fn run(context: Context, options: Options) -> Result<()> {
     server.request_handler::<lsp::request::Initialize, _, _>(initialize);
 
     server.request_handler::<lsp::request::GotoDefinition, _, _>(goto_definition);
+    server.request_handler::<lsp::request::HoverRequest, _, _>(hover);
+    server.request_handler::<lsp::request::Completion, _, _>(completion);
+    server.request_handler::<lsp::request::SignatureHelpRequest, _, _>(signature_help);
+    server.request_handler::<lsp::request::References, _, _>(references);
+    server.request_handler::<lsp::request::Rename, _, _>(rename);
+    server.request_handler::<lsp::request::DocumentSymbolRequest, _, _>(document_symbol);
+    server.request_handler::<lsp::request::WorkspaceSymbol, _, _>(workspace_symbol);
+    server.request_handler::<lsp::request::Formatting, _, _>(formatting);
 
     server.notification_handler::<lsp::notification::DidOpenTextDocument, _, _>(
         did_open_text_document,
@@ -124,6 +132,20 @@ async fn initialize(
             lsp::TextDocumentSyncKind::INCREMENTAL,
         )),
         definition_provider: Some(lsp::OneOf::Left(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        completion_provider: Some(lsp::CompletionOptions {
+            trigger_characters: Some(vec![String::from("."), String::from(":")]),
+            ..Default::default()
+        }),
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec![String::from("("), String::from(",")]),
+            ..Default::default()
+        }),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        rename_provider: Some(lsp::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+        document_formatting_provider: Some(lsp::OneOf::Left(true)),
         ..Default::default()
     };
 
@@ -160,6 +182,118 @@ async fn goto_definition(
     Ok(position.map(lsp::GotoDefinitionResponse::Scalar))
 }
 
+/// Handle hover requests.
+async fn hover(state: State, _: Output, params: lsp::HoverParams) -> Result<Option<lsp::Hover>> {
+    Ok(state
+        .hover(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await)
+}
+
+/// Handle completion requests.
+async fn completion(
+    state: State,
+    _: Output,
+    params: lsp::CompletionParams,
+) -> Result<Option<lsp::CompletionResponse>> {
+    let completions = state
+        .complete(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+        )
+        .await;
+
+    Ok(completions.map(lsp::CompletionResponse::Array))
+}
+
+/// Handle signature help requests.
+async fn signature_help(
+    state: State,
+    _: Output,
+    params: lsp::SignatureHelpParams,
+) -> Result<Option<lsp::SignatureHelp>> {
+    Ok(state
+        .signature_help(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await)
+}
+
+/// Handle requests for references.
+async fn references(
+    state: State,
+    _: Output,
+    params: lsp::ReferenceParams,
+) -> Result<Option<Vec<lsp::Location>>> {
+    Ok(state
+        .find_references(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            params.context.include_declaration,
+        )
+        .await)
+}
+
+/// Handle rename requests.
+async fn rename(
+    state: State,
+    output: Output,
+    params: lsp::RenameParams,
+) -> Result<Option<lsp::WorkspaceEdit>> {
+    let result = state
+        .rename(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            &params.new_name,
+        )
+        .await;
+
+    match result {
+        Ok(edit) => Ok(Some(edit)),
+        Err(error) => {
+            output
+                .notification::<lsp::notification::ShowMessage>(lsp::ShowMessageParams {
+                    typ: lsp::MessageType::WARNING,
+                    message: error.to_string(),
+                })
+                .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Handle document symbol requests.
+async fn document_symbol(
+    state: State,
+    _: Output,
+    params: lsp::DocumentSymbolParams,
+) -> Result<Option<lsp::DocumentSymbolResponse>> {
+    let symbols = state.document_symbols(&params.text_document.uri).await;
+    Ok(symbols.map(lsp::DocumentSymbolResponse::Nested))
+}
+
+/// Handle workspace symbol requests.
+async fn workspace_symbol(
+    state: State,
+    _: Output,
+    params: lsp::WorkspaceSymbolParams,
+) -> Result<Option<Vec<lsp::SymbolInformation>>> {
+    Ok(Some(state.workspace_symbols(&params.query).await))
+}
+
+/// Handle formatting requests.
+async fn formatting(
+    state: State,
+    _: Output,
+    params: lsp::DocumentFormattingParams,
+) -> Result<Option<Vec<lsp::TextEdit>>> {
+    Ok(state.format(&params.text_document.uri).await)
+}
+
 /// Handle open text document.
 async fn did_open_text_document(
     state: State,
@@ -10,11 +10,13 @@ use lsp::Url;
 use ropey::Rope;
 use rune::ast::{Span, Spanned};
 use rune::compile::{
-    CompileError, CompileVisitor, ComponentRef, FileSourceLoader, Item, LinkerError, Location,
-    MetaKind, MetaRef, SourceMeta,
+    CompileError, CompileVisitor, ComponentRef, ContextSignature, FileSourceLoader, Item, ItemBuf,
+    LinkerError, Location, MetaKind, MetaRef, SourceMeta,
 };
 use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
-use rune::{Context, Options, SourceId};
+use rune::runtime::debug::{DebugArgs, DebugSignature};
+use rune::runtime::Unit;
+use rune::{Context, Hash, InstFnKind, Options, SourceId};
 use tokio::sync::RwLockWriteGuard;
 use tokio::sync::{mpsc, RwLock};
 
@@ -77,14 +79,17 @@ impl State {
 
         let source = sources.get(uri)?;
         let offset = source.lsp_position_to_offset(position);
-        let def = source.find_definition_at(Span::point(offset))?;
+        let (_, def) = source.find_definition_at(Span::point(offset))?;
 
         let url = match def.source.path() {
             Some(path) => Url::from_file_path(path).ok()?,
             None => uri.clone(),
         };
 
-        let source = source.build_sources.as_ref()?.get(def.source.source_id())?;
+        let source = source
+            .build_sources
+            .as_ref()?
+            .get(def.source.source_id()?)?;
 
         let (l, c) = source.pos_to_utf16cu_linecol(def.source.span().start.into_usize());
         let start = lsp::Position {
@@ -106,6 +111,134 @@ impl State {
         Some(location)
     }
 
+    /// Describe the item at the given uri and LSP position.
+    pub async fn hover(&self, uri: &Url, position: lsp::Position) -> Option<lsp::Hover> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+        let (span, def) = source.find_definition_at(Span::point(offset))?;
+        let value = source.describe(&self.inner.context, def)?;
+
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(source.span_to_lsp_range(span)),
+        })
+    }
+
+    /// Collect completions at the given uri and LSP position.
+    pub async fn complete(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<Vec<lsp::CompletionItem>> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+        source.complete(&self.inner.context, offset)
+    }
+
+    /// Provide signature help for the call surrounding the given uri and LSP
+    /// position.
+    pub async fn signature_help(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<lsp::SignatureHelp> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+        source.signature_help(&self.inner.context, offset)
+    }
+
+    /// Find all references to the symbol at the given uri and LSP position.
+    pub async fn find_references(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Option<Vec<lsp::Location>> {
+        let sources = self.inner.sources.read().await;
+        let (target, _) = sources.target_at(uri, position)?;
+
+        let locations = sources
+            .references(&target, include_declaration)
+            .into_iter()
+            .map(|reference| reference.location)
+            .collect();
+
+        Some(locations)
+    }
+
+    /// Rename the symbol at the given uri and LSP position in every open
+    /// source.
+    pub async fn rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Result<lsp::WorkspaceEdit, RenameError> {
+        let sources = self.inner.sources.read().await;
+        sources.rename(uri, position, new_name)
+    }
+
+    /// Build an outline of the items declared in the given uri.
+    pub async fn document_symbols(&self, uri: &Url) -> Option<Vec<lsp::DocumentSymbol>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+        Some(source.document_symbols())
+    }
+
+    /// Format the given document.
+    pub async fn format(&self, uri: &Url) -> Option<Vec<lsp::TextEdit>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+        source.format()
+    }
+
+    /// Find symbols matching the given query in every open source.
+    pub async fn workspace_symbols(&self, query: &str) -> Vec<lsp::SymbolInformation> {
+        let sources = self.inner.sources.read().await;
+        let query = query.to_lowercase();
+
+        let mut symbols = Vec::new();
+
+        for (url, source) in &sources.sources {
+            for (item, info) in source.declared_items() {
+                let (name, kind) = match (item.last(), symbol_kind(source, item, info.kind)) {
+                    (Some(ComponentRef::Str(name)), Some(kind)) => (name, kind),
+                    _ => continue,
+                };
+
+                if !name.to_lowercase().contains(&query) {
+                    continue;
+                }
+
+                let range = match source.declaration_name_span(item, info) {
+                    Some(span) => source.span_to_lsp_range(span),
+                    None => continue,
+                };
+
+                #[allow(deprecated)]
+                symbols.push(lsp::SymbolInformation {
+                    name: name.to_owned(),
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    location: lsp::Location::new(url.clone(), range),
+                    container_name: item.parent().map(|parent| parent.to_string()),
+                });
+            }
+        }
+
+        symbols
+    }
+
     /// Rebuild the current project.
     pub async fn rebuild(&self, output: &Output) -> Result<()> {
         let mut inner = self.inner.sources.write().await;
@@ -133,7 +266,7 @@ impl State {
             let mut diagnostics = rune::Diagnostics::new();
             let mut visitor = Visitor::new(Index::default());
 
-            let _ = rune::prepare(&mut sources)
+            let result = rune::prepare(&mut sources)
                 .with_context(&self.inner.context)
                 .with_diagnostics(&mut diagnostics)
                 .with_options(&self.inner.options)
@@ -221,13 +354,23 @@ impl State {
                 }
             }
 
-            builds.push((url.clone(), sources, visitor.into_index()));
+            let mut index = visitor.into_index();
+
+            if let Ok(unit) = &result {
+                index.insert_signatures(unit);
+            }
+
+            builds.push((url.clone(), sources, index, result.is_ok()));
         }
 
-        for (url, build_sources, index) in builds {
+        for (url, build_sources, index, ok) in builds {
             if let Some(source) = inner.sources.get_mut(&url) {
-                source.index = index;
-                source.build_sources = Some(build_sources);
+                // Keep the index of the last good build around while the
+                // source is being edited, so that completions keep working.
+                if ok || source.build_sources.is_none() {
+                    source.index = index;
+                    source.build_sources = Some(build_sources);
+                }
             }
         }
 
@@ -279,49 +422,1046 @@ impl Sources {
             build_sources: None,
         };
 
-        self.sources.insert(url, source)
-    }
+        self.sources.insert(url, source)
+    }
+
+    /// Get the source at the given url.
+    pub fn get(&self, url: &Url) -> Option<&Source> {
+        self.sources.get(url)
+    }
+
+    /// Get the mutable source at the given url.
+    pub fn get_mut(&mut self, url: &Url) -> Option<&mut Source> {
+        self.sources.get_mut(url)
+    }
+
+    /// Remove the given url as a source.
+    pub fn remove(&mut self, url: &Url) {
+        if let Some(source) = self.sources.remove(url) {
+            self.removed.push((url.clone(), source));
+        }
+    }
+
+    /// Resolve the symbol at the given position into something which can be
+    /// referenced, and the name it's currently referenced by.
+    fn target_at(&self, url: &Url, position: lsp::Position) -> Option<(Target, Box<str>)> {
+        let source = self.get(url)?;
+        let offset = source.lsp_position_to_offset(position);
+        let point = Span::point(offset);
+
+        if let Some((_, field_use)) = source.find_field_use_at(point) {
+            let item = match &field_use.item {
+                Some(item) => item.clone(),
+                None => self.unique_field_owner(&field_use.field)?,
+            };
+
+            let target = Target::Field {
+                item,
+                field: field_use.field.clone(),
+            };
+
+            return Some((target, field_use.field.clone()));
+        }
+
+        for (item, fields) in &source.index.fields {
+            for (field, f) in fields {
+                match f.location {
+                    Some(location) if is_local(location) && contains(location.span, point) => {
+                        let target = Target::Field {
+                            item: item.clone(),
+                            field: field.clone(),
+                        };
+
+                        return Some((target, field.clone()));
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if let Some((_, def)) = source.find_definition_at(point) {
+            let target = Target::from_definition(url, def)?;
+            let name = source.definition_name(def)?;
+            return Some((target, name.into()));
+        }
+
+        for def in source.index.definitions.values() {
+            if let DefinitionSource::Location(location) = &def.source {
+                if is_local(*location) && contains(location.span, point) {
+                    let name = source.definition_text(def)?;
+                    let target = Target::Declaration {
+                        url: url.clone(),
+                        span: location.span,
+                    };
+                    return Some((target, name.into()));
+                }
+            }
+        }
+
+        for (item, info) in source.declared_items() {
+            let span = match source.declaration_name_span(item, info) {
+                Some(span) if contains(span, point) => span,
+                _ => continue,
+            };
+
+            let meta = info.source.as_ref()?;
+
+            let target = Target::Declaration {
+                url: meta_url(meta).unwrap_or_else(|| url.clone()),
+                span: meta.location.span,
+            };
+
+            let name = source.text_at(span)?;
+            return Some((target, name.into()));
+        }
+
+        None
+    }
+
+    /// Rename the symbol at the given uri and LSP position.
+    fn rename(
+        &self,
+        url: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Result<lsp::WorkspaceEdit, RenameError> {
+        if !is_ident(new_name) {
+            return Err(RenameError::InvalidName(new_name.into()));
+        }
+
+        let (target, old_name) = match self.target_at(url, position) {
+            Some(target) => target,
+            None => {
+                // NB: a field is only unresolved if the struct or variant it
+                // belongs to isn't known where it's used.
+                let field_use = self.get(url).and_then(|source| {
+                    let offset = source.lsp_position_to_offset(position);
+                    source.find_field_use_at(Span::point(offset))
+                });
+
+                return Err(match field_use {
+                    Some((_, field_use)) => RenameError::UnknownFieldOwner(field_use.field.clone()),
+                    None => RenameError::MissingSymbol,
+                });
+            }
+        };
+
+        // NB: a use like `value.field` could belong to any struct or variant
+        // with a field of that name, so renaming a field is refused unless
+        // every such use is known to belong to some struct or variant.
+        if let Target::Field { field, .. } = &target {
+            let unresolved = self
+                .sources
+                .values()
+                .flat_map(|source| source.index.field_uses.values())
+                .any(|field_use| field_use.field == *field && field_use.item.is_none());
+
+            if unresolved {
+                return Err(RenameError::UnknownFieldOwner(field.clone()));
+            }
+        }
+
+        let mut changes = std::collections::HashMap::<Url, Vec<lsp::TextEdit>>::new();
+
+        for reference in self.references(&target, true) {
+            // Shorthand fields like `Struct { field }` both bind and name a
+            // field, so they have to be expanded when renaming either.
+            let new_text = match (&target, reference.shorthand) {
+                (Target::Field { .. }, true) => format!("{}: {}", new_name, old_name),
+                (Target::Declaration { .. }, true) => format!("{}: {}", old_name, new_name),
+                _ => new_name.to_owned(),
+            };
+
+            changes
+                .entry(reference.location.uri)
+                .or_default()
+                .push(lsp::TextEdit {
+                    range: reference.location.range,
+                    new_text,
+                });
+        }
+
+        Ok(lsp::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        })
+    }
+
+    /// Find the only struct or variant declaring a field with the given name.
+    fn unique_field_owner(&self, field: &str) -> Option<ItemBuf> {
+        let mut owners = self
+            .sources
+            .values()
+            .flat_map(|source| source.index.fields.iter())
+            .filter(|(_, fields)| fields.contains_key(field))
+            .map(|(item, _)| item);
+
+        let owner = owners.next()?;
+
+        if owners.any(|other| other != owner) {
+            return None;
+        }
+
+        Some(owner.clone())
+    }
+
+    /// Collect all references to the given target in open sources.
+    fn references(&self, target: &Target, include_declaration: bool) -> Vec<Reference> {
+        let mut seen = hashbrown::HashSet::new();
+        let mut references = Vec::new();
+
+        let mut push = |url: &Url, range: lsp::Range, shorthand: bool| {
+            let key = (
+                range.start.line,
+                range.start.character,
+                range.end.line,
+                range.end.character,
+            );
+
+            if seen.insert((url.clone(), key)) {
+                references.push(Reference {
+                    location: lsp::Location::new(url.clone(), range),
+                    shorthand,
+                });
+            }
+        };
+
+        for (url, source) in &self.sources {
+            match target {
+                Target::Declaration { .. } => {
+                    for (span, def) in &source.index.definitions {
+                        if Target::from_definition(url, def).as_ref() != Some(target) {
+                            continue;
+                        }
+
+                        if let Some(span) = source.use_name_span(*span, def) {
+                            let shorthand = source.is_shorthand_field(span);
+                            push(url, source.span_to_lsp_range(span), shorthand);
+                        }
+                    }
+
+                    if !include_declaration {
+                        continue;
+                    }
+
+                    for (item, info) in source.declared_items() {
+                        let meta = match &info.source {
+                            Some(meta) => meta,
+                            None => continue,
+                        };
+
+                        let declaration = Target::Declaration {
+                            url: meta_url(meta).unwrap_or_else(|| url.clone()),
+                            span: meta.location.span,
+                        };
+
+                        if &declaration != target {
+                            continue;
+                        }
+
+                        if let Some(span) = source.declaration_name_span(item, info) {
+                            push(url, source.span_to_lsp_range(span), false);
+                        }
+                    }
+
+                    for def in source.index.definitions.values() {
+                        if let DefinitionSource::Location(location) = &def.source {
+                            let declaration = Target::Declaration {
+                                url: url.clone(),
+                                span: location.span,
+                            };
+
+                            if is_local(*location) && &declaration == target {
+                                let shorthand = source.is_shorthand_field(location.span);
+                                push(url, source.span_to_lsp_range(location.span), shorthand);
+                            }
+                        }
+                    }
+                }
+                Target::Field { item, field } => {
+                    let unique = self.unique_field_owner(field).as_ref() == Some(item);
+
+                    for (span, field_use) in &source.index.field_uses {
+                        if field_use.field != *field {
+                            continue;
+                        }
+
+                        let matches = match &field_use.item {
+                            Some(i) => i == item,
+                            None => unique,
+                        };
+
+                        if matches {
+                            let shorthand = source.is_shorthand_local(*span);
+                            push(url, source.span_to_lsp_range(*span), shorthand);
+                        }
+                    }
+
+                    if !include_declaration {
+                        continue;
+                    }
+
+                    let location = source
+                        .index
+                        .fields
+                        .get(item)
+                        .and_then(|fields| fields.get(field))
+                        .and_then(|f| f.location);
+
+                    if let Some(location) = location.filter(|l| is_local(*l)) {
+                        push(url, source.span_to_lsp_range(location.span), false);
+                    }
+                }
+            }
+        }
+
+        references
+    }
+}
+
+/// The reason a symbol couldn't be renamed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RenameError {
+    /// The new name is not a valid identifier.
+    InvalidName(Box<str>),
+    /// There is no symbol to rename at the given position.
+    MissingSymbol,
+    /// The field is used somewhere without it being known which struct or
+    /// variant it belongs to, so not every use could be renamed.
+    UnknownFieldOwner(Box<str>),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(name) => {
+                write!(f, "cannot rename to `{}`: not a valid identifier", name)
+            }
+            Self::MissingSymbol => write!(f, "cannot rename: no symbol at this position"),
+            Self::UnknownFieldOwner(field) => write!(
+                f,
+                "cannot rename field `{}`: the struct or variant it belongs to is not known",
+                field
+            ),
+        }
+    }
+}
+
+/// Something which can be referenced.
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// An item or local variable declared at the given location.
+    Declaration { url: Url, span: Span },
+    /// A named field in the given struct or variant.
+    Field { item: ItemBuf, field: Box<str> },
+}
+
+impl Target {
+    /// Get the target that the given definition found in the given url refers
+    /// to.
+    fn from_definition(url: &Url, def: &Definition) -> Option<Self> {
+        match &def.source {
+            DefinitionSource::SourceMeta(meta) => Some(Self::Declaration {
+                url: meta_url(meta).unwrap_or_else(|| url.clone()),
+                span: meta.location.span,
+            }),
+            DefinitionSource::Location(location) if is_local(*location) => {
+                Some(Self::Declaration {
+                    url: url.clone(),
+                    span: location.span,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A reference to a target.
+struct Reference {
+    /// The location of the reference.
+    location: lsp::Location,
+    /// If the reference is a shorthand field, like `Struct { field }`.
+    shorthand: bool,
+}
+
+/// A single open source.
+pub struct Source {
+    /// The content of the current source.
+    content: Rope,
+    /// Indexes used to answer queries.
+    index: Index,
+    /// Loaded Rune sources for this source file. Will be present after the
+    /// source file has been built.
+    build_sources: Option<rune::Sources>,
+}
+
+impl Source {
+    /// Find the definition at the given span, and the span of the use which
+    /// refers to it.
+    pub fn find_definition_at(&self, span: Span) -> Option<(Span, &Definition)> {
+        // Include spans which start at the same position but are larger.
+        let end = Span::new(span.start, u32::MAX);
+        let (found_span, definition) = self.index.definitions.range(..=end).rev().next()?;
+
+        if span.start >= found_span.start && span.end <= found_span.end {
+            tracing::trace!("found {:?}", definition);
+            return Some((*found_span, definition));
+        }
+
+        None
+    }
+
+    /// Describe the given definition as markdown, suitable for hovers.
+    fn describe(&self, context: &Context, def: &Definition) -> Option<String> {
+        let mut out = String::new();
+
+        out.push_str("```rune\n");
+
+        match (def.kind, &def.item) {
+            (DefinitionKind::Local, _) => {
+                let name = self.definition_text(def)?;
+                out.push_str(&format!("let {}", name));
+            }
+            (DefinitionKind::Module, _) => {
+                let source_id = def.source.source_id()?;
+                let source = self.build_sources.as_ref()?.get(source_id)?;
+                out.push_str(&format!("mod {}", source.name()));
+            }
+            (DefinitionKind::Function, Some(item)) => match &def.source {
+                DefinitionSource::Context => {
+                    let signatures = context_signatures(context, item);
+
+                    if signatures.is_empty() {
+                        out.push_str(&format!("fn {}", item));
+                    } else {
+                        out.push_str(&signatures.join("\n"));
+                    }
+                }
+                _ => match self.function_signature(def) {
+                    Some(signature) => out.push_str(&format!("fn {}", signature)),
+                    None => out.push_str(&format!("fn {}", item)),
+                },
+            },
+            (kind, Some(item)) => {
+                out.push_str(&format!("{} {}", kind.keyword(), item));
+
+                if let Some(fields) = self.index.fields.get(item) {
+                    let fields = fields.keys().map(|f| f.as_ref()).collect::<Vec<_>>();
+                    out.push_str(&format!(" {{ {} }}", fields.join(", ")));
+                }
+            }
+            (kind, None) => {
+                out.push_str(kind.keyword());
+            }
+        }
+
+        out.push_str("\n```");
+
+        if let Some(docs) = def.item.as_ref().and_then(|item| self.index.docs.get(item)) {
+            out.push_str("\n\n---\n\n");
+            out.push_str(&format_docs(docs));
+        }
+
+        Some(out)
+    }
+
+    /// Get the source text that the given definition points to.
+    fn definition_text(&self, def: &Definition) -> Option<&str> {
+        self.build_text_at(def.source.source_id()?, def.source.span())
+    }
+
+    /// Get the text at the given span in the sources of the last good build.
+    fn build_text_at(&self, source_id: SourceId, span: Span) -> Option<&str> {
+        let source = self.build_sources.as_ref()?.get(source_id)?;
+        source.get(span.start.into_usize()..span.end.into_usize())
+    }
+
+    /// Get the signature of a script function, as recorded in the debug
+    /// information of the last good build.
+    fn function_signature(&self, def: &Definition) -> Option<&DebugSignature> {
+        let item = def.item.as_ref()?;
+        self.index.signatures.get(&Hash::type_hash(item))
+    }
+
+    /// Try to figure out the type of the receiver expression at the end of
+    /// the given text, which is followed by the `.` at the given byte offset.
+    fn receiver_type(&self, text: &str, offset: usize) -> Option<Receiver> {
+        let text = text.trim_end();
+
+        let start = text
+            .rfind(|c: char| !is_ident_char(c))
+            .map(|n| n + 1)
+            .unwrap_or(0);
+
+        // NB: fields, paths and calls can't be resolved without type
+        // information.
+        if text[..start].ends_with(['.', ':']) {
+            return None;
+        }
+
+        match &text[start..] {
+            "" => None,
+            "self" => self.self_type(offset),
+            name => self.local_type(name, offset),
+        }
+    }
+
+    /// Find the type of `self` in the instance function enclosing the given
+    /// byte offset.
+    fn self_type(&self, offset: usize) -> Option<Receiver> {
+        let point = Span::point(offset);
+
+        let (item, _) = self
+            .index
+            .items
+            .iter()
+            .filter(|(_, info)| matches!(info.kind, MetaKind::Function { .. }))
+            .filter_map(|(item, info)| Some((item, info.source.as_ref()?.location)))
+            .filter(|(_, location)| is_local(*location) && contains(location.span, point))
+            .min_by_key(|(_, location)| {
+                location.span.end.into_usize() - location.span.start.into_usize()
+            })?;
+
+        let parent = item.parent()?;
+
+        if !is_type(self.index.items.get(parent)?.kind) {
+            return None;
+        }
+
+        Some(Receiver::Script(parent.to_owned()))
+    }
+
+    /// Find the type of the local variable with the given name which is in
+    /// scope at the given byte offset, as reported by the compiler.
+    fn local_type(&self, name: &str, offset: usize) -> Option<Receiver> {
+        // NB: declarations without a known type are considered too, so that a
+        // variable which is shadowed isn't given the type of an earlier one.
+        let span = self
+            .index
+            .definitions
+            .values()
+            .filter(|def| matches!(def.kind, DefinitionKind::Local))
+            .map(|def| def.source.span())
+            .chain(self.index.variable_types.keys().copied())
+            .filter(|span| span.end.into_usize() <= offset)
+            .filter(|span| self.build_text_at(SourceId::new(0), *span) == Some(name))
+            .max_by_key(|span| span.start)?;
+
+        let type_hash = *self.index.variable_types.get(&span)?;
+
+        let item = self
+            .index
+            .items
+            .iter()
+            .find(|(item, info)| is_type(info.kind) && Hash::type_hash(*item) == type_hash);
+
+        match item {
+            Some((item, _)) => Some(Receiver::Script(item.clone())),
+            None => Some(Receiver::Native(type_hash)),
+        }
+    }
+
+    /// Collect completions at the given byte offset.
+    fn complete(&self, context: &Context, offset: usize) -> Option<Vec<lsp::CompletionItem>> {
+        let text = self.to_string();
+        let before = text.get(..offset)?;
+
+        let start = before
+            .rfind(|c: char| !is_ident_char(c))
+            .map(|n| n + 1)
+            .unwrap_or(0);
+        let prefix = &before[start..];
+        let rest = &before[..start];
+
+        let mut completions = Completions::new(prefix);
+
+        if let Some(receiver) = rest.strip_suffix('.') {
+            // NB: when the type of the receiver is unknown, every field and
+            // instance function is a candidate.
+            let receiver = self.receiver_type(receiver, start);
+
+            for (item, fields) in &self.index.fields {
+                let is_candidate = match &receiver {
+                    Some(Receiver::Script(ty)) => &**item == ty || item.parent() == Some(&**ty),
+                    Some(Receiver::Native(..)) => false,
+                    None => true,
+                };
+
+                if !is_candidate {
+                    continue;
+                }
+
+                for field in fields.keys() {
+                    completions.push(
+                        field,
+                        lsp::CompletionItemKind::FIELD,
+                        Some(item.to_string()),
+                    );
+                }
+            }
+
+            for (item, info) in &self.index.items {
+                if !matches!(info.kind, MetaKind::Function { .. }) {
+                    continue;
+                }
+
+                let parent = match item.parent() {
+                    Some(parent) => parent,
+                    None => continue,
+                };
+
+                let is_candidate = match &receiver {
+                    Some(Receiver::Script(ty)) => parent == &**ty,
+                    Some(Receiver::Native(..)) => false,
+                    None => self
+                        .index
+                        .items
+                        .get(parent)
+                        .map(|parent| is_type(parent.kind))
+                        .unwrap_or_default(),
+                };
+
+                if let (true, Some(ComponentRef::Str(name))) = (is_candidate, item.last()) {
+                    completions.push(
+                        name,
+                        lsp::CompletionItemKind::METHOD,
+                        Some(item.to_string()),
+                    );
+                }
+            }
+
+            for (_, signature) in context.iter_functions() {
+                if let ContextSignature::Instance {
+                    type_hash,
+                    name: InstFnKind::Instance(name),
+                    ..
+                } = signature
+                {
+                    let is_candidate = match &receiver {
+                        Some(Receiver::Script(..)) => false,
+                        Some(Receiver::Native(hash)) => hash == type_hash,
+                        None => true,
+                    };
+
+                    if is_candidate {
+                        completions.push(
+                            name,
+                            lsp::CompletionItemKind::METHOD,
+                            Some(signature.to_string()),
+                        );
+                    }
+                }
+            }
+        } else if let Some(path) = path_before(rest) {
+            let parent = ItemBuf::with_item(&path);
+
+            for (item, info) in &self.index.items {
+                if item.parent() != Some(&*parent) {
+                    continue;
+                }
+
+                if let Some(ComponentRef::Str(name)) = item.last() {
+                    completions.push(name, completion_kind(info.kind), Some(item.to_string()));
+                }
+            }
+
+            for item in context.iter_path_children(&path) {
+                let name = match item.last() {
+                    Some(ComponentRef::Crate(name) | ComponentRef::Str(name)) => name,
+                    _ => continue,
+                };
+
+                let signatures = context_signatures(context, &item);
+
+                if signatures.is_empty() {
+                    completions.push(
+                        name,
+                        lsp::CompletionItemKind::MODULE,
+                        Some(item.to_string()),
+                    );
+                } else {
+                    completions.push(
+                        name,
+                        lsp::CompletionItemKind::FUNCTION,
+                        Some(signatures.join("\n")),
+                    );
+                }
+            }
+        } else {
+            for def in self.index.definitions.values() {
+                if !matches!(def.kind, DefinitionKind::Local)
+                    || def.source.span().start.into_usize() > offset
+                {
+                    continue;
+                }
+
+                if let Some(name) = self.definition_text(def).filter(|name| *name != "self") {
+                    completions.push(name, lsp::CompletionItemKind::VARIABLE, None);
+                }
+            }
+
+            for (item, info) in &self.index.items {
+                if !item.parent().map(Item::is_empty).unwrap_or_default() {
+                    continue;
+                }
+
+                if let Some(ComponentRef::Str(name)) = item.last() {
+                    completions.push(name, completion_kind(info.kind), Some(item.to_string()));
+                }
+            }
+
+            for item in context.iter_path_children(&[]) {
+                if let Some(ComponentRef::Crate(name) | ComponentRef::Str(name)) = item.last() {
+                    completions.push(name, lsp::CompletionItemKind::MODULE, None);
+                }
+            }
+        }
+
+        Some(completions.into_items())
+    }
+
+    /// Build signature help for the call surrounding the given byte offset.
+    fn signature_help(&self, context: &Context, offset: usize) -> Option<lsp::SignatureHelp> {
+        let text = self.to_string();
+        let before = text.get(..offset)?;
+
+        let (open, mut active) = enclosing_call(before)?;
+        let callee = before[..open].trim_end();
+        let start = callee
+            .rfind(|c: char| !is_ident_char(c))
+            .map(|n| n + 1)
+            .unwrap_or(0);
+        let name = &callee[start..];
+
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut signatures = Vec::new();
+
+        if callee[..start].trim_end().ends_with('.') {
+            // Method calls pass the receiver as the implicit first argument.
+            active += 1;
+
+            for (item, info) in &self.index.items {
+                if !matches!(info.kind, MetaKind::Function { .. }) {
+                    continue;
+                }
+
+                if item.last() != Some(ComponentRef::Str(name)) {
+                    continue;
+                }
+
+                if let Some(source) = &info.source {
+                    let def = Definition {
+                        kind: DefinitionKind::Function,
+                        source: DefinitionSource::SourceMeta(source.clone()),
+                        item: Some(item.clone()),
+                    };
+
+                    signatures.extend(self.script_signature_information(&def));
+                }
+            }
+
+            for (_, signature) in context.iter_functions() {
+                if let ContextSignature::Instance {
+                    name: InstFnKind::Instance(n),
+                    args,
+                    ..
+                } = signature
+                {
+                    if n.as_ref() == name {
+                        let args = args.map(|args| args + 1);
+                        signatures.push(native_signature_information(signature, args));
+                    }
+                }
+            }
+        } else {
+            let (_, def) = self.find_definition_at(Span::new(callee.len() - 1, callee.len()))?;
+
+            if !matches!(def.kind, DefinitionKind::Function) {
+                return None;
+            }
+
+            match (&def.source, &def.item) {
+                (DefinitionSource::Context, Some(item)) => {
+                    for (_, signature) in context.iter_functions() {
+                        if let ContextSignature::Function { item: i, args, .. } = signature {
+                            if i == item {
+                                signatures.push(native_signature_information(signature, *args));
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    signatures.extend(self.script_signature_information(def));
+                }
+            }
+        }
+
+        if signatures.is_empty() {
+            return None;
+        }
+
+        Some(lsp::SignatureHelp {
+            signatures,
+            active_signature: Some(0),
+            active_parameter: Some(active as u32),
+        })
+    }
+
+    /// Construct signature information for a script function.
+    fn script_signature_information(&self, def: &Definition) -> Option<lsp::SignatureInformation> {
+        let signature = self.function_signature(def)?;
+
+        let parameters = match &signature.args {
+            DebugArgs::Named(args) => args
+                .iter()
+                .map(|arg| lsp::ParameterInformation {
+                    label: lsp::ParameterLabel::Simple(arg.to_string()),
+                    documentation: None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let documentation = def
+            .item
+            .as_ref()
+            .and_then(|item| self.index.docs.get(item))
+            .map(|docs| {
+                lsp::Documentation::MarkupContent(lsp::MarkupContent {
+                    kind: lsp::MarkupKind::Markdown,
+                    value: format_docs(docs),
+                })
+            });
+
+        Some(lsp::SignatureInformation {
+            label: format!("fn {}", signature),
+            documentation,
+            parameters: Some(parameters),
+            active_parameter: None,
+        })
+    }
+
+    /// Find the field use at the given span.
+    fn find_field_use_at(&self, span: Span) -> Option<(Span, &FieldUse)> {
+        let end = Span::new(span.start, u32::MAX);
+        let (found_span, field_use) = self.index.field_uses.range(..=end).rev().next()?;
+
+        if contains(*found_span, span) {
+            return Some((*found_span, field_use));
+        }
+
+        None
+    }
+
+    /// Test if the given span is both a field use and a local variable, like
+    /// in `Struct { field }`.
+    fn is_shorthand_field(&self, span: Span) -> bool {
+        matches!(self.index.field_uses.get(&span), Some(f) if f.item.is_some())
+    }
+
+    /// Test if the given field use is also the use or declaration of a local
+    /// variable, like in `Struct { field }`.
+    fn is_shorthand_local(&self, span: Span) -> bool {
+        self.index.definitions.iter().any(|(s, def)| {
+            matches!(def.kind, DefinitionKind::Local) && (*s == span || def.source.span() == span)
+        })
+    }
+
+    /// Get the text at the given span in this source.
+    fn text_at(&self, span: Span) -> Option<String> {
+        let range = span.start.into_usize()..span.end.into_usize();
+
+        if range.end > self.content.len_bytes() {
+            return None;
+        }
+
+        Some(self.content.byte_slice(range).to_string())
+    }
+
+    /// Get the name the given definition is referred to by.
+    fn definition_name(&self, def: &Definition) -> Option<String> {
+        match (&def.item, def.kind) {
+            (_, DefinitionKind::Local) => Some(self.definition_text(def)?.to_owned()),
+            (Some(item), _) => match item.last()? {
+                ComponentRef::Str(name) => Some(name.to_owned()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Narrow the span of a use down to the span of the name being referenced,
+    /// so that `bar` is picked out of `foo::bar`.
+    fn use_name_span(&self, span: Span, def: &Definition) -> Option<Span> {
+        if matches!(def.kind, DefinitionKind::Local) {
+            return Some(span);
+        }
+
+        let name = self.definition_name(def)?;
+        let text = self.text_at(span)?;
+        let n = find_word(&text, &name, true)?;
+        let start = span.start.into_usize() + n;
+        Some(Span::new(start, start + name.len()))
+    }
+
+    /// Iterate over items which are declared in this source.
+    fn declared_items(&self) -> impl Iterator<Item = (&ItemBuf, &ItemInfo)> {
+        self.index
+            .items
+            .iter()
+            .filter(|(_, info)| matches!(&info.source, Some(meta) if is_local(meta.location)))
+    }
+
+    /// Find the span of the name of a declared item.
+    fn declaration_name_span(&self, item: &Item, info: &ItemInfo) -> Option<Span> {
+        let meta = info.source.as_ref()?;
+
+        let name = match item.last()? {
+            ComponentRef::Str(name) => name,
+            _ => return None,
+        };
+
+        let text = self.text_at(meta.location.span)?;
 
-    /// Get the source at the given url.
-    pub fn get(&self, url: &Url) -> Option<&Source> {
-        self.sources.get(url)
-    }
+        // Skip over doc comments and attributes preceding the declaration.
+        let mut offset = 0;
 
-    /// Get the mutable source at the given url.
-    pub fn get_mut(&mut self, url: &Url) -> Option<&mut Source> {
-        self.sources.get_mut(url)
+        for line in text.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+
+            if !trimmed.starts_with("//") && !trimmed.starts_with('#') {
+                if let Some(n) = find_word(line, name, false) {
+                    let start = meta.location.span.start.into_usize() + offset + n;
+                    return Some(Span::new(start, start + name.len()));
+                }
+            }
+
+            offset += line.len();
+        }
+
+        None
     }
 
-    /// Remove the given url as a source.
-    pub fn remove(&mut self, url: &Url) {
-        if let Some(source) = self.sources.remove(url) {
-            self.removed.push((url.clone(), source));
+    /// Build an outline of the items declared in this source.
+    fn document_symbols(&self) -> Vec<lsp::DocumentSymbol> {
+        let mut by_parent = BTreeMap::<Option<ItemBuf>, Vec<ItemBuf>>::new();
+        let mut symbols = HashMap::new();
+
+        for (item, info) in self.declared_items() {
+            let symbol = match self.document_symbol(item, info) {
+                Some(symbol) => symbol,
+                None => continue,
+            };
+
+            symbols.insert(item.clone(), symbol);
+        }
+
+        for item in symbols.keys() {
+            let mut parent = item.parent();
+
+            let parent = loop {
+                match parent {
+                    Some(p) if symbols.contains_key(p) => break Some(p.to_owned()),
+                    Some(p) => parent = p.parent(),
+                    None => break None,
+                }
+            };
+
+            by_parent.entry(parent).or_default().push(item.clone());
+        }
+
+        return build(None, &mut by_parent, &mut symbols);
+
+        fn build(
+            parent: Option<ItemBuf>,
+            by_parent: &mut BTreeMap<Option<ItemBuf>, Vec<ItemBuf>>,
+            symbols: &mut HashMap<ItemBuf, lsp::DocumentSymbol>,
+        ) -> Vec<lsp::DocumentSymbol> {
+            let mut out = Vec::new();
+
+            for item in by_parent.remove(&parent).unwrap_or_default() {
+                if let Some(mut symbol) = symbols.remove(&item) {
+                    let children = build(Some(item), by_parent, symbols);
+
+                    if !children.is_empty() {
+                        symbol
+                            .children
+                            .get_or_insert_with(Vec::new)
+                            .extend(children);
+                    }
+
+                    out.push(symbol);
+                }
+            }
+
+            out.sort_by_key(|symbol| (symbol.range.start.line, symbol.range.start.character));
+            out
         }
     }
-}
 
-/// A single open source.
-pub struct Source {
-    /// The content of the current source.
-    content: Rope,
-    /// Indexes used to answer queries.
-    index: Index,
-    /// Loaded Rune sources for this source file. Will be present after the
-    /// source file has been built.
-    build_sources: Option<rune::Sources>,
-}
+    /// Construct the document symbol for a single declared item, including
+    /// its fields.
+    fn document_symbol(&self, item: &Item, info: &ItemInfo) -> Option<lsp::DocumentSymbol> {
+        let name = match item.last()? {
+            ComponentRef::Str(name) => name,
+            _ => return None,
+        };
 
-impl Source {
-    /// Find the definition at the given span.
-    pub fn find_definition_at(&self, span: Span) -> Option<&Definition> {
-        let (found_span, definition) = self.index.definitions.range(..=span).rev().next()?;
+        let kind = symbol_kind(self, item, info.kind)?;
+        let meta = info.source.as_ref()?;
+        let range = self.span_to_lsp_range(meta.location.span);
+        let selection_range = self.span_to_lsp_range(self.declaration_name_span(item, info)?);
+
+        let children = self.index.fields.get(item).map(|fields| {
+            fields
+                .iter()
+                .filter_map(|(field, f)| {
+                    let location = f.location.filter(|l| is_local(*l))?;
+                    let range = self.span_to_lsp_range(location.span);
+
+                    #[allow(deprecated)]
+                    Some(lsp::DocumentSymbol {
+                        name: field.to_string(),
+                        detail: None,
+                        kind: lsp::SymbolKind::FIELD,
+                        tags: None,
+                        deprecated: None,
+                        range,
+                        selection_range: range,
+                        children: None,
+                    })
+                })
+                .collect()
+        });
+
+        #[allow(deprecated)]
+        Some(lsp::DocumentSymbol {
+            name: name.to_owned(),
+            detail: Some(item.to_string()),
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children,
+        })
+    }
 
-        if span.start >= found_span.start && span.end <= found_span.end {
-            tracing::trace!("found {:?}", definition);
-            return Some(definition);
+    /// Format the source, producing an edit which replaces the whole document
+    /// if anything changed. Sources which fail to parse are left alone.
+    fn format(&self) -> Option<Vec<lsp::TextEdit>> {
+        let text = self.content.to_string();
+        let formatted = rune::fmt::layout_source(&text).ok()?;
+
+        if formatted == text {
+            return Some(Vec::new());
         }
 
-        None
+        let range = self.span_to_lsp_range(Span::new(0, text.len()));
+        Some(vec![lsp::TextEdit::new(range, formatted)])
     }
 
     /// Modify the given lsp range in the file.
@@ -360,11 +1500,14 @@ impl Source {
         lsp::Position::new(line as u32, col_char as u32)
     }
 
-    /// Offset in the rope to lsp position.
+    /// Lsp position to byte offset in the rope.
     fn lsp_position_to_offset(&self, position: lsp::Position) -> usize {
         let line = self.content.line_to_char(position.line as usize);
-        self.content
-            .utf16_cu_to_char(line + position.character as usize)
+        let line = self.content.char_to_utf16_cu(line);
+        let c = self
+            .content
+            .utf16_cu_to_char(line + position.character as usize);
+        self.content.char_to_byte(c)
     }
 
     /// Iterate over the text chunks in the source.
@@ -489,10 +1632,314 @@ where
     }
 }
 
+/// Test if the given character can be part of an identifier.
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Test if the given string is a valid identifier, which excludes keywords.
+fn is_ident(name: &str) -> bool {
+    rune::parse::parse_all::<rune::ast::Ident>(name, SourceId::EMPTY, false).is_ok()
+}
+
+/// Find the first (or last if `rev` is set) occurrence of `word` in `text`
+/// which isn't part of a larger identifier.
+fn find_word(text: &str, word: &str, rev: bool) -> Option<usize> {
+    let is_word = |&(n, _): &(usize, &str)| {
+        let before = text[..n].chars().next_back();
+        let after = text[n + word.len()..].chars().next();
+        !before.map(is_ident_char).unwrap_or_default()
+            && !after.map(is_ident_char).unwrap_or_default()
+    };
+
+    let (n, _) = if rev {
+        text.rmatch_indices(word).find(is_word)?
+    } else {
+        text.match_indices(word).find(is_word)?
+    };
+
+    Some(n)
+}
+
+/// Test if the outer span contains the inner span.
+fn contains(outer: Span, inner: Span) -> bool {
+    inner.start >= outer.start && inner.end <= outer.end
+}
+
+/// Test if the given location is in the source being built, as opposed to a
+/// module loaded from elsewhere.
+fn is_local(location: Location) -> bool {
+    location.source_id.into_index() == 0
+}
+
+/// Get the url of the file that the given meta was declared in.
+fn meta_url(meta: &SourceMeta) -> Option<Url> {
+    Url::from_file_path(meta.path.as_deref()?).ok()
+}
+
+/// Get the symbol kind of a declared item.
+fn symbol_kind(source: &Source, item: &Item, kind: MetaKind) -> Option<lsp::SymbolKind> {
+    let kind = match kind {
+        MetaKind::UnitStruct | MetaKind::TupleStruct | MetaKind::Struct => lsp::SymbolKind::STRUCT,
+        MetaKind::UnitVariant | MetaKind::TupleVariant | MetaKind::StructVariant => {
+            lsp::SymbolKind::ENUM_MEMBER
+        }
+        MetaKind::Enum => lsp::SymbolKind::ENUM,
+        MetaKind::Function { .. } | MetaKind::ConstFn => {
+            let is_method = item
+                .parent()
+                .and_then(|parent| source.index.items.get(parent))
+                .map(|parent| is_type(parent.kind))
+                .unwrap_or_default();
+
+            if is_method {
+                lsp::SymbolKind::METHOD
+            } else {
+                lsp::SymbolKind::FUNCTION
+            }
+        }
+        MetaKind::Const => lsp::SymbolKind::CONSTANT,
+        MetaKind::Module => lsp::SymbolKind::MODULE,
+        MetaKind::Trait => lsp::SymbolKind::INTERFACE,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// Parse the `a::b::` path which immediately precedes a completion.
+fn path_before(mut rest: &str) -> Option<Vec<&str>> {
+    let mut path = Vec::new();
+
+    while let Some(head) = rest.strip_suffix("::") {
+        let start = head
+            .rfind(|c: char| !is_ident_char(c))
+            .map(|n| n + 1)
+            .unwrap_or(0);
+
+        if start == head.len() {
+            break;
+        }
+
+        path.push(&head[start..]);
+        rest = &head[..start];
+    }
+
+    if path.is_empty() {
+        return None;
+    }
+
+    path.reverse();
+    Some(path)
+}
+
+/// Find the opening parenthesis of the call that encloses the end of the given
+/// text, and the index of the argument being written.
+fn enclosing_call(text: &str) -> Option<(usize, usize)> {
+    let mut depth = 0usize;
+    let mut active = 0;
+
+    for (n, c) in text.char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => return Some((n, active)),
+            '[' | '{' if depth == 0 => return None,
+            '(' | '[' | '{' => depth -= 1,
+            ',' if depth == 0 => active += 1,
+            ';' if depth == 0 => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Format collected doc comments into markdown.
+fn format_docs(docs: &[String]) -> String {
+    let lines = docs
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+        .collect::<Vec<_>>();
+
+    lines.join("\n")
+}
+
+/// Test if the given meta kind describes a type which can have instance
+/// functions.
+fn is_type(kind: MetaKind) -> bool {
+    matches!(
+        kind,
+        MetaKind::UnitStruct | MetaKind::TupleStruct | MetaKind::Struct | MetaKind::Enum
+    )
+}
+
+/// Get the completion kind corresponding to the given meta kind.
+fn completion_kind(kind: MetaKind) -> lsp::CompletionItemKind {
+    match kind {
+        MetaKind::UnitStruct | MetaKind::TupleStruct | MetaKind::Struct => {
+            lsp::CompletionItemKind::STRUCT
+        }
+        MetaKind::UnitVariant | MetaKind::TupleVariant | MetaKind::StructVariant => {
+            lsp::CompletionItemKind::ENUM_MEMBER
+        }
+        MetaKind::Enum => lsp::CompletionItemKind::ENUM,
+        MetaKind::Function { .. } | MetaKind::ConstFn => lsp::CompletionItemKind::FUNCTION,
+        MetaKind::Const => lsp::CompletionItemKind::CONSTANT,
+        MetaKind::Module => lsp::CompletionItemKind::MODULE,
+        MetaKind::Trait => lsp::CompletionItemKind::INTERFACE,
+        _ => lsp::CompletionItemKind::TEXT,
+    }
+}
+
+/// Describe all native signatures registered for the given item.
+fn context_signatures(context: &Context, item: &Item) -> Vec<String> {
+    let mut signatures = context
+        .iter_functions()
+        .filter_map(|(_, signature)| match signature {
+            ContextSignature::Function { item: i, .. } if &**i == item => {
+                Some(signature.to_string())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    signatures.sort();
+    signatures
+}
+
+/// Construct signature information for a native function taking the given
+/// number of arguments.
+fn native_signature_information(
+    signature: &ContextSignature,
+    args: Option<usize>,
+) -> lsp::SignatureInformation {
+    let parameters = args.map(|args| {
+        (0..args)
+            .map(|n| lsp::ParameterInformation {
+                label: lsp::ParameterLabel::Simple(
+                    if n == 0 && args > 0 && matches!(signature, ContextSignature::Instance { .. })
+                    {
+                        String::from("self")
+                    } else {
+                        format!("#{}", n)
+                    },
+                ),
+                documentation: None,
+            })
+            .collect()
+    });
+
+    lsp::SignatureInformation {
+        label: signature.to_string(),
+        documentation: None,
+        parameters,
+        active_parameter: None,
+    }
+}
+
+/// The statically known type of the receiver of a field access or instance
+/// function call.
+enum Receiver {
+    /// A type declared in a script.
+    Script(ItemBuf),
+    /// A native type with the given hash.
+    Native(Hash),
+}
+
+/// A collection of completions being built, filtered by the prefix being
+/// completed.
+struct Completions<'a> {
+    prefix: &'a str,
+    seen: hashbrown::HashSet<String>,
+    items: Vec<lsp::CompletionItem>,
+}
+
+impl<'a> Completions<'a> {
+    /// Construct a new collection of completions.
+    fn new(prefix: &'a str) -> Self {
+        Self {
+            prefix,
+            seen: Default::default(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Push a completion, unless it doesn't match the prefix or has already
+    /// been added.
+    fn push(&mut self, label: &str, kind: lsp::CompletionItemKind, detail: Option<String>) {
+        if !label.starts_with(self.prefix) || !self.seen.insert(label.to_owned()) {
+            return;
+        }
+
+        self.items.push(lsp::CompletionItem {
+            label: label.to_owned(),
+            kind: Some(kind),
+            detail,
+            ..Default::default()
+        });
+    }
+
+    /// Convert into the collected completion items.
+    fn into_items(self) -> Vec<lsp::CompletionItem> {
+        self.items
+    }
+}
+
 #[derive(Default)]
 pub struct Index {
     /// Spans mapping to their corresponding definitions.
     definitions: BTreeMap<Span, Definition>,
+    /// Items registered during the build.
+    items: BTreeMap<ItemBuf, ItemInfo>,
+    /// Documentation collected for items.
+    docs: HashMap<ItemBuf, Vec<String>>,
+    /// Fields declared in structs and variants.
+    fields: BTreeMap<ItemBuf, BTreeMap<Box<str>, Field>>,
+    /// Spans mapping to uses of named fields.
+    field_uses: BTreeMap<Span, FieldUse>,
+    /// Types of local variables reported by the compiler, keyed by the span
+    /// of the variable's name.
+    variable_types: HashMap<Span, Hash>,
+    /// Signatures of the functions in the last good build.
+    signatures: HashMap<Hash, DebugSignature>,
+}
+
+impl Index {
+    /// Insert the function signatures from the debug information of the given
+    /// unit.
+    fn insert_signatures(&mut self, unit: &Unit) {
+        if let Some(debug) = unit.debug_info() {
+            for (hash, signature) in &debug.functions {
+                self.signatures.insert(*hash, signature.clone());
+            }
+        }
+    }
+}
+
+/// A field declared in a struct or variant.
+#[derive(Default)]
+pub struct Field {
+    /// Where the name of the field is declared.
+    location: Option<Location>,
+    /// Documentation of the field.
+    docs: Vec<String>,
+}
+
+/// The use of a named field.
+pub struct FieldUse {
+    /// The struct or variant the field belongs to, if statically known.
+    item: Option<ItemBuf>,
+    /// The name of the field.
+    field: Box<str>,
+}
+
+/// Information on an item registered during the build.
+#[derive(Debug, Clone)]
+pub struct ItemInfo {
+    /// The kind of the item.
+    pub(crate) kind: MetaKind,
+    /// Where the item was declared.
+    pub(crate) source: Option<SourceMeta>,
 }
 
 /// A definition source.
@@ -504,22 +1951,25 @@ pub enum DefinitionSource {
     Location(Location),
     /// A complete compile source.
     SourceMeta(SourceMeta),
+    /// A native item defined in the context.
+    Context,
 }
 
 impl DefinitionSource {
     fn span(&self) -> Span {
         match self {
-            Self::Source(..) => Span::empty(),
+            Self::Source(..) | Self::Context => Span::empty(),
             Self::Location(location) => location.span,
             Self::SourceMeta(compile_source) => compile_source.location.span,
         }
     }
 
-    fn source_id(&self) -> SourceId {
+    fn source_id(&self) -> Option<SourceId> {
         match self {
-            Self::Source(source_id) => *source_id,
-            Self::Location(location) => location.source_id,
-            Self::SourceMeta(compile_source) => compile_source.location.source_id,
+            Self::Source(source_id) => Some(*source_id),
+            Self::Location(location) => Some(location.source_id),
+            Self::SourceMeta(compile_source) => Some(compile_source.location.source_id),
+            Self::Context => None,
         }
     }
 
@@ -537,6 +1987,8 @@ pub struct Definition {
     pub(crate) kind: DefinitionKind,
     /// The id of the source id the definition corresponds to.
     pub(crate) source: DefinitionSource,
+    /// The item being defined, if any.
+    pub(crate) item: Option<ItemBuf>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -563,6 +2015,20 @@ pub enum DefinitionKind {
     Module,
 }
 
+impl DefinitionKind {
+    /// The keyword used to describe the definition.
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::UnitStruct | Self::TupleStruct | Self::Struct => "struct",
+            Self::UnitVariant | Self::TupleVariant | Self::StructVariant => "variant",
+            Self::Enum => "enum",
+            Self::Function => "fn",
+            Self::Local => "let",
+            Self::Module => "mod",
+        }
+    }
+}
+
 struct Visitor {
     index: Index,
 }
@@ -580,14 +2046,29 @@ impl Visitor {
 }
 
 impl CompileVisitor for Visitor {
+    fn register_meta(&mut self, meta: MetaRef<'_>) {
+        let source = match meta.source {
+            Some(source) => source,
+            None => return,
+        };
+
+        self.index.items.insert(
+            meta.item.to_owned(),
+            ItemInfo {
+                kind: meta.kind,
+                source: Some(source.clone()),
+            },
+        );
+    }
+
     fn visit_meta(&mut self, location: Location, meta: MetaRef<'_>) {
         if location.source_id.into_index() != 0 {
             return;
         }
 
         let source = match meta.source {
-            Some(source) => source,
-            None => return,
+            Some(source) => DefinitionSource::SourceMeta(source.clone()),
+            None => DefinitionSource::Context,
         };
 
         let kind = match &meta.kind {
@@ -604,7 +2085,8 @@ impl CompileVisitor for Visitor {
 
         let definition = Definition {
             kind,
-            source: DefinitionSource::SourceMeta(source.clone()),
+            source,
+            item: Some(meta.item.to_owned()),
         };
 
         if let Some(d) = self.index.definitions.insert(location.span, definition) {
@@ -620,6 +2102,7 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind: DefinitionKind::Local,
             source: DefinitionSource::Location(Location::new(source_id, var_span)),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -627,6 +2110,14 @@ impl CompileVisitor for Visitor {
         }
     }
 
+    fn visit_variable_type(&mut self, location: Location, type_hash: Hash) {
+        if location.source_id.into_index() != 0 {
+            return;
+        }
+
+        self.index.variable_types.insert(location.span, type_hash);
+    }
+
     fn visit_mod(&mut self, source_id: SourceId, span: Span) {
         if source_id.into_index() != 0 {
             return;
@@ -635,12 +2126,55 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind: DefinitionKind::Module,
             source: DefinitionSource::Source(source_id),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
             tracing::warn!("replaced definition: {:?}", d.kind)
         }
     }
+
+    fn visit_doc_comment(&mut self, _: Location, item: &Item, docstr: &str) {
+        self.index
+            .docs
+            .entry(item.to_owned())
+            .or_default()
+            .push(docstr.to_owned());
+    }
+
+    fn visit_field(&mut self, location: Location, item: &Item, field: &str) {
+        self.index
+            .fields
+            .entry(item.to_owned())
+            .or_default()
+            .entry(field.into())
+            .or_default()
+            .location = Some(location);
+    }
+
+    fn visit_field_use(&mut self, location: Location, item: Option<&Item>, field: &str) {
+        if location.source_id.into_index() != 0 {
+            return;
+        }
+
+        let field_use = FieldUse {
+            item: item.map(Item::to_owned),
+            field: field.into(),
+        };
+
+        self.index.field_uses.insert(location.span, field_use);
+    }
+
+    fn visit_field_doc_comment(&mut self, _: Location, item: &Item, field: &str, docstr: &str) {
+        self.index
+            .fields
+            .entry(item.to_owned())
+            .or_default()
+            .entry(field.into())
+            .or_default()
+            .docs
+            .push(docstr.to_owned());
+    }
 }
 
 struct SourceLoader<'a> {
@@ -712,3 +2246,285 @@ impl<'a> rune::compile::SourceLoader for SourceLoader<'a> {
         self.base.load(root, item, span)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build the given text as an open source, the same way a rebuild does.
+    fn build(context: &Context, text: &str) -> Source {
+        let mut sources = rune::Sources::new();
+        sources.insert(rune::Source::new("main", text));
+
+        let mut diagnostics = rune::Diagnostics::new();
+        let mut visitor = Visitor::new(Index::default());
+
+        let result = rune::prepare(&mut sources)
+            .with_context(context)
+            .with_diagnostics(&mut diagnostics)
+            .with_visitor(&mut visitor)
+            .build();
+
+        let unit = result.expect("failed to build test source");
+
+        let mut index = visitor.into_index();
+        index.insert_signatures(&unit);
+
+        Source {
+            content: Rope::from(text),
+            index,
+            build_sources: Some(sources),
+        }
+    }
+
+    /// Open the given text as the only source.
+    fn open(context: &Context, text: &str) -> (Url, Sources) {
+        let url = Url::parse("file:///main.rn").unwrap();
+        let mut sources = Sources::default();
+        sources.sources.insert(url.clone(), build(context, text));
+        (url, sources)
+    }
+
+    /// Get the LSP position of the byte offset right before the first
+    /// occurrence of `at`.
+    fn position_of(sources: &Sources, url: &Url, text: &str, at: &str) -> lsp::Position {
+        let offset = text.find(at).expect("missing text");
+        sources.get(url).unwrap().offset_to_lsp_position(offset)
+    }
+
+    /// Find the byte offset right after the first occurrence of `at`.
+    fn after(text: &str, at: &str) -> usize {
+        text.find(at).expect("missing text") + at.len()
+    }
+
+    /// Collect the labels of the completions at the given offset.
+    fn complete(context: &Context, source: &Source, offset: usize) -> Vec<String> {
+        let items = source.complete(context, offset).expect("no completions");
+        items.into_iter().map(|item| item.label).collect()
+    }
+
+    const SOURCE: &str = r#"
+struct Point { x, y }
+struct Size { w, h }
+
+impl Point {
+    fn len(self) {
+        self.x + self.y
+    }
+}
+
+impl Size {
+    fn area(self) {
+        self.w * self.h
+    }
+}
+
+/// Add two numbers.
+fn add(a, b) {
+    a + b
+}
+
+pub fn main() {
+    let p = Point { x: 1, y: 2 };
+    let s = "hello";
+    p.len();
+    s.len();
+    add(1, 2)
+}
+"#;
+
+    #[test]
+    fn test_hover() {
+        let context = Context::with_default_modules().unwrap();
+        let source = build(&context, SOURCE);
+
+        let offset = after(SOURCE, "    add(");
+        let (_, def) = source
+            .find_definition_at(Span::point(offset - 2))
+            .expect("no definition");
+        let hover = source.describe(&context, def).expect("no description");
+
+        assert!(hover.contains("fn add(a, b)"), "{}", hover);
+        assert!(hover.contains("Add two numbers."), "{}", hover);
+    }
+
+    #[test]
+    fn test_complete_receiver() {
+        let context = Context::with_default_modules().unwrap();
+        let source = build(&context, SOURCE);
+
+        let labels = complete(&context, &source, after(SOURCE, "    p."));
+        assert!(labels.iter().any(|l| l == "x"), "{:?}", labels);
+        assert!(labels.iter().any(|l| l == "len"), "{:?}", labels);
+        assert!(
+            !labels.iter().any(|l| l == "w" || l == "area"),
+            "{:?}",
+            labels
+        );
+        assert!(!labels.iter().any(|l| l == "push_str"), "{:?}", labels);
+
+        let labels = complete(&context, &source, after(SOURCE, "        self.w * self."));
+        assert!(labels.iter().any(|l| l == "h"), "{:?}", labels);
+        assert!(
+            !labels.iter().any(|l| l == "x" || l == "len"),
+            "{:?}",
+            labels
+        );
+
+        let labels = complete(&context, &source, after(SOURCE, "    s."));
+        assert!(labels.iter().any(|l| l == "len"), "{:?}", labels);
+        assert!(labels.iter().any(|l| l == "push_str"), "{:?}", labels);
+        assert!(
+            !labels.iter().any(|l| l == "x" || l == "area"),
+            "{:?}",
+            labels
+        );
+    }
+
+    #[test]
+    fn test_complete_variant_receiver() {
+        let text = r#"
+enum Shape { Circle { r }, Square { side } }
+
+impl Shape {
+    fn area(self) { 0 }
+}
+
+pub fn main() {
+    let v = Shape::Circle { r: 1 };
+    v.area();
+    let v = v.area();
+    v.area();
+}
+"#;
+
+        let context = Context::with_default_modules().unwrap();
+        let source = build(&context, text);
+
+        let labels = complete(&context, &source, after(text, "    v."));
+        assert!(labels.iter().any(|l| l == "r"), "{:?}", labels);
+        assert!(labels.iter().any(|l| l == "area"), "{:?}", labels);
+        assert!(!labels.iter().any(|l| l == "push_str"), "{:?}", labels);
+
+        // NB: the shadowing variable has an unknown type.
+        let offset = text.rfind("    v.").unwrap() + "    v.".len();
+        let labels = complete(&context, &source, offset);
+        assert!(labels.iter().any(|l| l == "push_str"), "{:?}", labels);
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let context = Context::with_default_modules().unwrap();
+        let text = "pub fn main() { std::string::Str }";
+
+        // NB: completions are requested while the source is being edited.
+        let mut source = build(&context, "pub fn main() { }");
+        source.content = Rope::from(text);
+
+        let labels = complete(&context, &source, after(text, "std::string::Str"));
+        assert_eq!(labels, vec![String::from("String")]);
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let context = Context::with_default_modules().unwrap();
+        let source = build(&context, SOURCE);
+
+        let help = source
+            .signature_help(&context, after(SOURCE, "add(1, "))
+            .expect("no signature help");
+
+        assert_eq!(help.active_parameter, Some(1));
+        assert_eq!(help.signatures.len(), 1);
+        assert_eq!(help.signatures[0].label, "fn add(a, b)");
+    }
+
+    #[test]
+    fn test_references() {
+        let context = Context::with_default_modules().unwrap();
+        let (url, sources) = open(&context, SOURCE);
+
+        let lines = |include_declaration| {
+            let position = position_of(&sources, &url, SOURCE, "add(1, 2)");
+            let (target, name) = sources.target_at(&url, position).expect("no target");
+            assert_eq!(&*name, "add");
+
+            let mut lines = sources
+                .references(&target, include_declaration)
+                .into_iter()
+                .map(|reference| reference.location.range.start.line)
+                .collect::<Vec<_>>();
+
+            lines.sort();
+            lines
+        };
+
+        assert_eq!(lines(false), vec![26]);
+        assert_eq!(lines(true), vec![17, 26]);
+    }
+
+    #[test]
+    fn test_rename() {
+        let context = Context::with_default_modules().unwrap();
+        let (url, sources) = open(&context, SOURCE);
+
+        let position = position_of(&sources, &url, SOURCE, "w, h }");
+        let edit = sources.rename(&url, position, "width").expect("rename");
+        let mut edits = edit.changes.unwrap().remove(&url).unwrap();
+        edits.sort_by_key(|edit| edit.range.start);
+
+        let lines = edits.iter().map(|e| e.range.start.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 12]);
+        assert!(edits.iter().all(|e| e.new_text == "width"));
+
+        assert_eq!(
+            sources.rename(&url, position, "not valid"),
+            Err(RenameError::InvalidName("not valid".into()))
+        );
+
+        assert_eq!(
+            sources.rename(&url, position, "fn"),
+            Err(RenameError::InvalidName("fn".into()))
+        );
+
+        let position = position_of(&sources, &url, SOURCE, "\npub fn main");
+        assert_eq!(
+            sources.rename(&url, position, "other"),
+            Err(RenameError::MissingSymbol)
+        );
+    }
+
+    #[test]
+    fn test_rename_unknown_field_owner() {
+        let context = Context::with_default_modules().unwrap();
+
+        let text = r#"
+struct A { value }
+struct B { value }
+
+fn get(v) {
+    v.value
+}
+
+pub fn main() {
+    get(A { value: 1 }) + get(B { value: 2 })
+}
+"#;
+
+        let (url, sources) = open(&context, text);
+        let position = position_of(&sources, &url, text, "value\n}");
+
+        assert_eq!(
+            sources.rename(&url, position, "other"),
+            Err(RenameError::UnknownFieldOwner("value".into()))
+        );
+
+        // Any use with an unknown owner could refer to the declared field.
+        let position = position_of(&sources, &url, text, "value }");
+
+        assert_eq!(
+            sources.rename(&url, position, "other"),
+            Err(RenameError::UnknownFieldOwner("value".into()))
+        );
+    }
+}
@@ -124,6 +124,52 @@ pub(crate) fn expand_install_with(
         }
     }
 
+    let protocol = &tokens.protocol;
+
+    if attrs.clone {
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#protocol::CLONE, |this: &#ident #generics| {
+                ::std::clone::Clone::clone(this)
+            })?;
+        });
+    }
+
+    if attrs.cmp {
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#protocol::CMP, |a: &#ident #generics, b: &#ident #generics| {
+                ::std::cmp::Ord::cmp(a, b)
+            })?;
+        });
+    }
+
+    if attrs.partial_cmp {
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#protocol::PARTIAL_CMP, |a: &#ident #generics, b: &#ident #generics| {
+                ::std::cmp::PartialOrd::partial_cmp(a, b)
+            })?;
+        });
+    }
+
+    if attrs.eq {
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#protocol::EQ, |a: &#ident #generics, b: &#ident #generics| {
+                ::std::cmp::PartialEq::eq(a, b)
+            })?;
+        });
+    }
+
+    if attrs.hash {
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#protocol::HASH, |this: &#ident #generics| {
+                let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+                ::std::hash::Hash::hash(this, &mut hasher);
+                ::std::hash::Hasher::finish(&hasher) as i64
+            })?;
+
+            module.key::<#ident #generics>()?;
+        });
+    }
+
     if let Some(install_with) = &attrs.install_with {
         installers.push(quote_spanned! { input.span() =>
             #install_with(module)?;
@@ -421,8 +467,6 @@ where
     Ok(quote! {
         impl #impl_generics #any for #ident #ty_generics #where_clause {
             fn type_hash() -> #hash {
-                // Safety: `Hash` asserts that it is layout compatible with `TypeId`.
-                // TODO: remove this once we can have transmute-like functionality in a const fn.
                 #hash::from_type_id(std::any::TypeId::of::<Self>())
             }
         }
@@ -69,6 +69,17 @@ pub(crate) struct TypeAttrs {
     pub(crate) install_with: Option<syn::Path>,
     /// `#[rune(parse = "..")]` type attribute.
     pub(crate) parse: ParseKind,
+    /// `#[rune(clone)]` to implement the `CLONE` protocol using `Clone`.
+    pub(crate) clone: bool,
+    /// `#[rune(cmp)]` to implement the `CMP` protocol using `Ord`.
+    pub(crate) cmp: bool,
+    /// `#[rune(partial_cmp)]` to implement the `PARTIAL_CMP` protocol using
+    /// `PartialOrd`.
+    pub(crate) partial_cmp: bool,
+    /// `#[rune(eq)]` to implement the `EQ` protocol using `PartialEq`.
+    pub(crate) eq: bool,
+    /// `#[rune(hash)]` to implement the `HASH` protocol using `Hash`.
+    pub(crate) hash: bool,
 }
 
 /// Parsed variant attributes.
@@ -493,6 +504,26 @@ impl Context {
 
                         attrs.install_with = Some(install_with);
                     }
+                    // Parse `#[rune(clone)]`.
+                    Meta(Path(word)) if word == CLONE => {
+                        attrs.clone = true;
+                    }
+                    // Parse `#[rune(cmp)]`.
+                    Meta(Path(word)) if word == CMP => {
+                        attrs.cmp = true;
+                    }
+                    // Parse `#[rune(partial_cmp)]`.
+                    Meta(Path(word)) if word == PARTIAL_CMP => {
+                        attrs.partial_cmp = true;
+                    }
+                    // Parse `#[rune(eq)]`.
+                    Meta(Path(word)) if word == EQ => {
+                        attrs.eq = true;
+                    }
+                    // Parse `#[rune(hash)]`.
+                    Meta(Path(word)) if word == HASH => {
+                        attrs.hash = true;
+                    }
                     meta => {
                         self.errors
                             .push(syn::Error::new_spanned(meta, "unsupported type attribute"));
@@ -18,6 +18,11 @@ pub const PARSE: Symbol = Symbol("parse");
 pub const NAME: Symbol = Symbol("name");
 pub const MODULE: Symbol = Symbol("module");
 pub const INSTALL_WITH: Symbol = Symbol("install_with");
+pub const CLONE: Symbol = Symbol("clone");
+pub const CMP: Symbol = Symbol("cmp");
+pub const EQ: Symbol = Symbol("eq");
+pub const HASH: Symbol = Symbol("hash");
+pub const PARTIAL_CMP: Symbol = Symbol("partial_cmp");
 
 pub const CONSTRUCTOR: Symbol = Symbol("constructor");
 pub const GET: Symbol = Symbol("get");
@@ -240,6 +240,58 @@ pub fn to_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///     Ok(module)
 /// }
 /// ```
+///
+/// ## `#[rune(clone)]` attribute
+///
+/// This implements the `CLONE` protocol using the type's `Clone`
+/// implementation, which allows values of the type to be cloned by scripts and
+/// as part of the script values that contain them.
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any, Clone)]
+/// #[rune(clone)]
+/// struct Handle(u32);
+/// ```
+///
+/// ## `#[rune(cmp)]` and `#[rune(partial_cmp)]` attributes
+///
+/// These implement the `CMP` and `PARTIAL_CMP` protocols using the type's
+/// `Ord` and `PartialOrd` implementations, which allows values of the type to
+/// be compared with operators like `<` and to be sorted.
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any, PartialEq, Eq, PartialOrd, Ord)]
+/// #[rune(cmp, partial_cmp)]
+/// struct Version(u32, u32);
+///
+/// fn install() -> Result<rune::Module, rune::ContextError> {
+///     let mut module = rune::Module::new();
+///     module.ty::<Version>()?;
+///     Ok(module)
+/// }
+/// ```
+///
+/// ## `#[rune(eq)]` and `#[rune(hash)]` attributes
+///
+/// These implement the `EQ` and `HASH` protocols using the type's `PartialEq`
+/// and `Hash` implementations. Together they allow values of the type to be
+/// used as keys in `HashMap` and `HashSet`.
+///
+/// Keys hold on to their own copy of the value, so `#[rune(hash)]` also
+/// registers the type through `Module::key`, which requires it to implement
+/// `Clone`, `Send` and `Sync`.
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any, Clone, PartialEq, Eq, Hash)]
+/// #[rune(eq, hash)]
+/// struct Id(u64);
+/// ```
 #[proc_macro_derive(Any, attributes(rune))]
 pub fn any(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let derive = syn::parse_macro_input!(input as any::Derive);
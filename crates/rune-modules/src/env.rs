@@ -0,0 +1,238 @@
+//! The native `env` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.12.0", features = ["env"] }
+//! ```
+//!
+//! Since the arguments and environment which are visible to scripts are up to
+//! the embedder, this module is not installed by
+//! [default_context][crate::default_context]. Install it into your context
+//! with the arguments to pass along:
+//!
+//! ```rust
+//! use rune_modules::env::Config;
+//!
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::env::module_with_config(
+//!     Config::new().with_args(["a", "b", "c"]),
+//! )?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To prevent scripts from accessing the environment of the process, provide a
+//! virtual environment for them to use instead:
+//!
+//! ```rust
+//! use rune_modules::env::Config;
+//!
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::env::module_with_config(
+//!     Config::new()
+//!         .with_vars([("HOME", "/home/rune")])
+//!         .with_current_dir("/home/rune"),
+//! )?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Scripts can only set and remove environment variables if this is enabled
+//! through [Config::with_mutable_vars]. Note that a virtual environment doesn't
+//! apply to processes spawned through the `process` module, which inherit the
+//! environment of the current process unless it's cleared with
+//! `Command::env_clear`.
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     for arg in env::args() {
+//!         println(`${arg}`);
+//!     }
+//!
+//!     if let Some(home) = env::var("HOME") {
+//!         println(`${home}`);
+//!     }
+//! }
+//! ```
+
+use crate::path::Path;
+use parking_lot::Mutex;
+use rune::runtime::{Object, VmError};
+use rune::{ContextError, Module, ToValue as _};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Construct the `env` module, which uses the environment of the current
+/// process and the arguments it was started with, excluding the name of the
+/// program.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    module_with_config(Config::new().with_args(std::env::args().skip(1)))
+}
+
+/// Construct the `env` module with the given configuration.
+pub fn module_with_config(config: Config) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("env");
+
+    let Config {
+        args,
+        vars,
+        mutable_vars,
+        current_dir,
+    } = config;
+
+    let vars = Vars(vars.map(|vars| Arc::new(Mutex::new(vars))));
+
+    module.function(&["args"], move || args.clone())?;
+
+    let v = vars.clone();
+    module.function(&["var"], move |key: &str| v.var(key))?;
+    let v = vars.clone();
+    module.function(&["vars"], move || v.vars())?;
+
+    if mutable_vars {
+        let v = vars.clone();
+        module.function(&["set_var"], move |key: &str, value: &str| {
+            v.set_var(key, value)
+        })?;
+        module.function(&["remove_var"], move |key: &str| vars.remove_var(key))?;
+    }
+
+    module.function(&["current_dir"], move || match &current_dir {
+        Some(dir) => Ok(Path::from(dir.clone())),
+        None => Ok::<_, io::Error>(Path::from(std::env::current_dir()?)),
+    })?;
+
+    Ok(module)
+}
+
+/// The configuration of the `env` module.
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    args: Vec<String>,
+    vars: Option<HashMap<String, String>>,
+    mutable_vars: bool,
+    current_dir: Option<PathBuf>,
+}
+
+impl Config {
+    /// Construct a configuration which uses the environment of the current
+    /// process and doesn't have any arguments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the arguments returned by `env::args()`.
+    pub fn with_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Use the given variables as the environment instead of the environment
+    /// of the current process.
+    ///
+    /// Variables set by the script are only visible to scripts using the same
+    /// module. They are not passed on to processes spawned by the script.
+    pub fn with_vars<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars = vars.into_iter().map(|(k, v)| (k.into(), v.into()));
+        self.vars = Some(vars.collect());
+        self
+    }
+
+    /// Allow scripts to set and remove environment variables through
+    /// `env::set_var` and `env::remove_var`, which are otherwise not available.
+    ///
+    /// Unless a virtual environment is provided through [Config::with_vars],
+    /// this changes the environment of the current process, which is shared
+    /// with the host and every thread in it.
+    pub fn with_mutable_vars(mut self, mutable_vars: bool) -> Self {
+        self.mutable_vars = mutable_vars;
+        self
+    }
+
+    /// Set the directory returned by `env::current_dir()` instead of using the
+    /// working directory of the current process.
+    pub fn with_current_dir<P>(mut self, current_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.current_dir = Some(current_dir.into());
+        self
+    }
+}
+
+/// Environment variables, which are either virtual or the ones of the current
+/// process.
+#[derive(Clone)]
+struct Vars(Option<Arc<Mutex<HashMap<String, String>>>>);
+
+impl Vars {
+    /// Get the value of an environment variable, or `None` if it isn't set or
+    /// isn't valid unicode.
+    fn var(&self, key: &str) -> Option<String> {
+        match &self.0 {
+            Some(vars) => vars.lock().get(key).cloned(),
+            None => std::env::var(key).ok(),
+        }
+    }
+
+    /// Get all environment variables as an object, skipping the ones which
+    /// aren't valid unicode.
+    fn vars(&self) -> Result<Object, VmError> {
+        let mut object = Object::new();
+
+        match &self.0 {
+            Some(vars) => {
+                for (key, value) in vars.lock().iter() {
+                    object.insert(key.clone(), value.clone().to_value()?);
+                }
+            }
+            None => {
+                for (key, value) in std::env::vars_os() {
+                    if let (Ok(key), Ok(value)) = (key.into_string(), value.into_string()) {
+                        object.insert(key, value.to_value()?);
+                    }
+                }
+            }
+        }
+
+        Ok(object)
+    }
+
+    fn set_var(&self, key: &str, value: &str) {
+        match &self.0 {
+            Some(vars) => {
+                vars.lock().insert(key.to_owned(), value.to_owned());
+            }
+            None => std::env::set_var(key, value),
+        }
+    }
+
+    fn remove_var(&self, key: &str) {
+        match &self.0 {
+            Some(vars) => {
+                vars.lock().remove(key);
+            }
+            None => std::env::remove_var(key),
+        }
+    }
+}
@@ -20,26 +20,560 @@
 //! # }
 //! ```
 //!
+//! Or, to only give scripts access to a single directory, install the scoped
+//! variant of it:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::fs::scoped_module("sandbox")?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! File metadata reports times as a `time::SystemTime`, so install the `time`
+//! module as well to work with them.
+//!
 //! Use it in Rune:
 //!
 //! ```rust,ignore
 //! fn main() {
 //!     let file = fs::read_to_string("file.txt").await?;
-//!     println(`{file}`);
+//!     println(`${file}`);
+//!
+//!     let dir = fs::read_dir(".").await?;
+//!
+//!     while let Some(entry) = dir.next().await? {
+//!         println(`${entry.path()}`);
+//!     }
 //! }
 //! ```
 
+use crate::path::{Path, PathArg};
+use crate::time::SystemTime;
+use rune::runtime::{FromValue, Protocol, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::ffi::OsString;
+use std::fmt;
+use std::fmt::Write as _;
 use std::io;
+use std::path::{self, Component, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
-use rune::{Module, ContextError};
+use tokio::io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader};
+
+/// Register an async function which has access to the scope of the module.
+macro_rules! scoped_function {
+    ($module:ident, $scope:ident, $name:expr, $f:path, $($arg:ident: $ty:ty),*) => {{
+        let scope = $scope.clone();
+        $module.async_function($name, move |$($arg: $ty),*| $f(scope.clone(), $($arg),*))?;
+    }};
+}
 
 /// Construct the `fs` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    install(Scope { root: None })
+}
+
+/// Construct the `fs` module, where every path is resolved relative to the
+/// given root directory and is prevented from escaping it.
+///
+/// Absolute paths are treated as relative to the root, `..` components are not
+/// allowed to go above it, and paths which resolve through symbolic links to a
+/// location outside of the root are rejected with a permission denied error,
+/// including links whose target doesn't exist.
+///
+/// Paths are checked before the operation is performed, so the scope can't
+/// protect against a symbolic link being swapped in concurrently by someone
+/// else who can write to the root. Where supported, files are opened without
+/// following a symbolic link in the final component, but directory operations
+/// like `create_dir`, `remove_file`, `remove_dir_all`, `rename`, `metadata`
+/// and `read_dir` act on the path as it was resolved. Only rely on the scope if
+/// nothing else modifies the root while scripts are running.
+pub fn scoped_module<P>(root: P) -> Result<Module, ContextError>
+where
+    P: Into<PathBuf>,
+{
+    install(Scope {
+        root: Some(Arc::new(root.into())),
+    })
+}
+
+fn install(scope: Scope) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("fs");
-    module.async_function(&["read_to_string"], read_to_string)?;
+    module.ty::<File>()?;
+    module.ty::<Metadata>()?;
+    module.ty::<ReadDir>()?;
+    module.ty::<DirEntry>()?;
+
+    scoped_function!(module, scope, &["read_to_string"], read_to_string, path: PathArg);
+    scoped_function!(module, scope, &["read"], read, path: PathArg);
+    scoped_function!(module, scope, &["write"], write, path: PathArg, contents: Contents);
+    scoped_function!(module, scope, &["append"], append, path: PathArg, contents: Contents);
+    scoped_function!(module, scope, &["create_dir"], create_dir, path: PathArg);
+    scoped_function!(module, scope, &["create_dir_all"], create_dir_all, path: PathArg);
+    scoped_function!(module, scope, &["remove_file"], remove_file, path: PathArg);
+    scoped_function!(module, scope, &["remove_dir"], remove_dir, path: PathArg);
+    scoped_function!(module, scope, &["remove_dir_all"], remove_dir_all, path: PathArg);
+    scoped_function!(module, scope, &["rename"], rename, from: PathArg, to: PathArg);
+    scoped_function!(module, scope, &["copy"], copy, from: PathArg, to: PathArg);
+    scoped_function!(module, scope, &["exists"], exists, path: PathArg);
+    scoped_function!(module, scope, &["metadata"], metadata, path: PathArg);
+    scoped_function!(module, scope, &["canonicalize"], canonicalize, path: PathArg);
+    scoped_function!(module, scope, &["read_dir"], read_dir, path: PathArg);
+
+    scoped_function!(module, scope, &["File", "open"], File::open, path: PathArg);
+    scoped_function!(module, scope, &["File", "create"], File::create, path: PathArg);
+    module.async_inst_fn("read_line", File::read_line)?;
+    module.async_inst_fn("read_to_string", File::read_to_string)?;
+    module.async_inst_fn("write", File::write)?;
+    module.async_inst_fn("flush", File::flush)?;
+    module.async_inst_fn("sync_all", File::sync_all)?;
+
+    module.inst_fn("len", Metadata::len)?;
+    module.inst_fn("is_dir", Metadata::is_dir)?;
+    module.inst_fn("is_file", Metadata::is_file)?;
+    module.inst_fn("is_symlink", Metadata::is_symlink)?;
+    module.inst_fn("is_readonly", Metadata::is_readonly)?;
+    module.inst_fn("modified", Metadata::modified)?;
+    module.inst_fn(Protocol::STRING_DEBUG, Metadata::string_debug)?;
+
+    module.async_inst_fn("next", ReadDir::next)?;
+
+    module.inst_fn("path", DirEntry::path)?;
+    module.inst_fn("file_name", DirEntry::file_name)?;
+    module.async_inst_fn("metadata", DirEntry::metadata)?;
+    module.inst_fn(Protocol::STRING_DEBUG, DirEntry::string_debug)?;
     Ok(module)
 }
 
-async fn read_to_string(path: &str) -> io::Result<String> {
-    fs::read_to_string(path).await
+/// The directory that paths are resolved in.
+#[derive(Clone)]
+struct Scope {
+    root: Option<Arc<PathBuf>>,
+}
+
+/// The maximum number of symbolic links which are followed while resolving a
+/// path in a scoped module.
+const MAX_SYMLINKS: usize = 40;
+
+impl Scope {
+    /// Resolve a path passed in by a script into the path to operate on.
+    ///
+    /// For a scoped module, the path is walked one component at a time and
+    /// every symbolic link along the way is resolved by following its target
+    /// within the root. So a link pointing outside of the root is rejected,
+    /// regardless of whether its target exists or not.
+    async fn resolve(&self, path: &path::Path) -> io::Result<PathBuf> {
+        let root = match &self.root {
+            Some(root) => root,
+            None => return Ok(path.to_owned()),
+        };
+
+        let root = fs::canonicalize(&**root).await?;
+
+        // Components which remain to be resolved, in reverse order.
+        let mut pending = path.components().rev().map(owned).collect::<Vec<_>>();
+        let mut resolved = PathBuf::new();
+        let mut symlinks = 0;
+
+        while let Some(c) = pending.pop() {
+            let name = match c {
+                Owned::RootDir => {
+                    resolved.clear();
+                    continue;
+                }
+                Owned::CurDir => continue,
+                Owned::ParentDir => {
+                    if !resolved.pop() {
+                        return Err(escapes_root(path));
+                    }
+
+                    continue;
+                }
+                Owned::Normal(name) => name,
+            };
+
+            let current = root.join(&resolved).join(&name);
+
+            let metadata = match fs::symlink_metadata(&current).await {
+                Ok(metadata) => metadata,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    resolved.push(name);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            if !metadata.file_type().is_symlink() {
+                resolved.push(name);
+                continue;
+            }
+
+            symlinks += 1;
+
+            if symlinks > MAX_SYMLINKS {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("too many symbolic links in `{}`", path.display()),
+                ));
+            }
+
+            let target = fs::read_link(&current).await?;
+
+            // NB: absolute targets have to point into the root, relative ones
+            // are resolved from the directory containing the link.
+            let target = if target.is_absolute() {
+                resolved.clear();
+
+                match target.strip_prefix(&root) {
+                    Ok(target) => target.to_owned(),
+                    Err(..) => return Err(escapes_root(path)),
+                }
+            } else {
+                target
+            };
+
+            pending.extend(target.components().rev().map(owned));
+        }
+
+        Ok(root.join(resolved))
+    }
+
+    /// Open a file with the given options.
+    ///
+    /// For a scoped module the file is opened without following a symbolic
+    /// link where supported, so that a link which is put in place of the file
+    /// after its path has been resolved can't be used to escape the root.
+    async fn open(&self, path: &path::Path, options: &mut fs::OpenOptions) -> io::Result<fs::File> {
+        let path = self.resolve(path).await?;
+
+        #[cfg(unix)]
+        if self.root.is_some() {
+            options.custom_flags(libc::O_NOFOLLOW);
+        }
+
+        options.open(path).await
+    }
+}
+
+/// An owned path component, where prefixes are treated like the root.
+enum Owned {
+    RootDir,
+    CurDir,
+    ParentDir,
+    Normal(OsString),
+}
+
+fn owned(c: Component<'_>) -> Owned {
+    match c {
+        Component::Prefix(..) | Component::RootDir => Owned::RootDir,
+        Component::CurDir => Owned::CurDir,
+        Component::ParentDir => Owned::ParentDir,
+        Component::Normal(name) => Owned::Normal(name.to_owned()),
+    }
+}
+
+fn escapes_root(path: &path::Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("path `{}` escapes the root directory", path.display()),
+    )
+}
+
+/// The contents to write to a file, which are either a string or bytes.
+struct Contents(Vec<u8>);
+
+impl FromValue for Contents {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(Self(match value {
+            Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+            value => String::from_value(value)?.into_bytes(),
+        }))
+    }
+}
+
+async fn read_to_string(scope: Scope, path: PathArg) -> io::Result<String> {
+    let mut file = scope
+        .open(&path.0, fs::OpenOptions::new().read(true))
+        .await?;
+    let mut string = String::new();
+    file.read_to_string(&mut string).await?;
+    Ok(string)
+}
+
+async fn read(scope: Scope, path: PathArg) -> io::Result<rune::runtime::Bytes> {
+    let mut file = scope
+        .open(&path.0, fs::OpenOptions::new().read(true))
+        .await?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).await?;
+    Ok(rune::runtime::Bytes::from_vec(bytes))
+}
+
+async fn write(scope: Scope, path: PathArg, contents: Contents) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    let mut file = scope.open(&path.0, &mut options).await?;
+    file.write_all(&contents.0).await
+}
+
+async fn append(scope: Scope, path: PathArg, contents: Contents) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.create(true).append(true);
+    let mut file = scope.open(&path.0, &mut options).await?;
+    file.write_all(&contents.0).await
+}
+
+async fn create_dir(scope: Scope, path: PathArg) -> io::Result<()> {
+    fs::create_dir(scope.resolve(&path.0).await?).await
+}
+
+async fn create_dir_all(scope: Scope, path: PathArg) -> io::Result<()> {
+    fs::create_dir_all(scope.resolve(&path.0).await?).await
+}
+
+async fn remove_file(scope: Scope, path: PathArg) -> io::Result<()> {
+    fs::remove_file(scope.resolve(&path.0).await?).await
+}
+
+async fn remove_dir(scope: Scope, path: PathArg) -> io::Result<()> {
+    fs::remove_dir(scope.resolve(&path.0).await?).await
+}
+
+async fn remove_dir_all(scope: Scope, path: PathArg) -> io::Result<()> {
+    fs::remove_dir_all(scope.resolve(&path.0).await?).await
+}
+
+async fn rename(scope: Scope, from: PathArg, to: PathArg) -> io::Result<()> {
+    fs::rename(scope.resolve(&from.0).await?, scope.resolve(&to.0).await?).await
+}
+
+/// Copy the contents of one file to another, returning the number of bytes
+/// copied.
+///
+/// In a scoped module the files are opened like any other file, so that
+/// neither of them can be a symbolic link out of the root.
+async fn copy(scope: Scope, from: PathArg, to: PathArg) -> io::Result<u64> {
+    if scope.root.is_none() {
+        return fs::copy(&from.0, &to.0).await;
+    }
+
+    let mut from = scope
+        .open(&from.0, fs::OpenOptions::new().read(true))
+        .await?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    let mut to = scope.open(&to.0, &mut options).await?;
+
+    let n = tokio::io::copy(&mut from, &mut to).await?;
+    to.flush().await?;
+    Ok(n)
+}
+
+/// Test if the path exists.
+async fn exists(scope: Scope, path: PathArg) -> bool {
+    match scope.resolve(&path.0).await {
+        Ok(path) => fs::metadata(path).await.is_ok(),
+        Err(..) => false,
+    }
+}
+
+async fn metadata(scope: Scope, path: PathArg) -> io::Result<Metadata> {
+    let inner = fs::metadata(scope.resolve(&path.0).await?).await?;
+    Ok(Metadata { inner })
+}
+
+/// Get the absolute form of the path with all intermediate components
+/// normalized and symbolic links resolved.
+///
+/// In a scoped module the returned path is relative to the root, like the
+/// paths passed in by scripts, so that the root directory isn't revealed.
+async fn canonicalize(scope: Scope, path: PathArg) -> io::Result<Path> {
+    let canonical = fs::canonicalize(scope.resolve(&path.0).await?).await?;
+
+    let root = match &scope.root {
+        Some(root) => fs::canonicalize(&**root).await?,
+        None => return Ok(Path::from(canonical)),
+    };
+
+    match canonical.strip_prefix(&root) {
+        Ok(relative) => Ok(Path::from(PathBuf::from("/").join(relative))),
+        Err(..) => Err(escapes_root(&path.0)),
+    }
+}
+
+/// Read the entries of a directory.
+///
+/// The returned value is a stream of entries, which are read through its
+/// `next` function.
+async fn read_dir(scope: Scope, path: PathArg) -> io::Result<ReadDir> {
+    let inner = fs::read_dir(scope.resolve(&path.0).await?).await?;
+
+    Ok(ReadDir {
+        inner,
+        path: path.0,
+    })
+}
+
+/// An open file.
+///
+/// Reads are buffered, so that the file can efficiently be read line by line.
+#[derive(Any)]
+struct File {
+    inner: BufReader<fs::File>,
+}
+
+impl File {
+    /// Open a file in read-only mode.
+    async fn open(scope: Scope, path: PathArg) -> io::Result<File> {
+        let file = scope
+            .open(&path.0, fs::OpenOptions::new().read(true))
+            .await?;
+
+        Ok(File {
+            inner: BufReader::new(file),
+        })
+    }
+
+    /// Open a file in write-only mode, creating it if it doesn't exist and
+    /// truncating it if it does.
+    async fn create(scope: Scope, path: PathArg) -> io::Result<File> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        let file = scope.open(&path.0, &mut options).await?;
+
+        Ok(File {
+            inner: BufReader::new(file),
+        })
+    }
+
+    /// Read the next line from the file without its line ending, returning
+    /// `None` once the end of the file is reached.
+    async fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+
+        if self.inner.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        if line.ends_with('\n') {
+            line.pop();
+
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+
+        Ok(Some(line))
+    }
+
+    /// Read the rest of the file into a string.
+    async fn read_to_string(&mut self) -> io::Result<String> {
+        let mut string = String::new();
+        self.inner.read_to_string(&mut string).await?;
+        Ok(string)
+    }
+
+    /// Write a string or bytes to the file.
+    async fn write(&mut self, contents: Contents) -> io::Result<()> {
+        self.inner.write_all(&contents.0).await
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush().await
+    }
+
+    /// Flush the file and sync all of its data and metadata to disk.
+    async fn sync_all(&mut self) -> io::Result<()> {
+        self.inner.flush().await?;
+        self.inner.get_ref().sync_all().await
+    }
+}
+
+/// Metadata about a file or directory.
+#[derive(Any)]
+struct Metadata {
+    inner: std::fs::Metadata,
+}
+
+impl Metadata {
+    /// The size of the file in bytes.
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.inner.file_type().is_symlink()
+    }
+
+    fn is_readonly(&self) -> bool {
+        self.inner.permissions().readonly()
+    }
+
+    /// The last modification time, if it's available on the platform.
+    fn modified(&self) -> Option<SystemTime> {
+        Some(SystemTime::from(self.inner.modified().ok()?))
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{:?}", self.inner)
+    }
+}
+
+/// A stream over the entries in a directory.
+#[derive(Any)]
+struct ReadDir {
+    inner: fs::ReadDir,
+    /// The path as it was passed in by the script, used to construct the path
+    /// of entries without revealing the root directory of a scoped module.
+    path: PathBuf,
+}
+
+impl ReadDir {
+    /// Get the next entry in the directory.
+    async fn next(&mut self) -> io::Result<Option<DirEntry>> {
+        let inner = match self.inner.next_entry().await? {
+            Some(inner) => inner,
+            None => return Ok(None),
+        };
+
+        let path = self.path.join(inner.file_name());
+        Ok(Some(DirEntry { inner, path }))
+    }
+}
+
+/// An entry in a directory.
+#[derive(Any)]
+struct DirEntry {
+    inner: fs::DirEntry,
+    path: PathBuf,
+}
+
+impl DirEntry {
+    fn path(&self) -> Path {
+        Path::from(self.path.clone())
+    }
+
+    fn file_name(&self) -> String {
+        self.inner.file_name().to_string_lossy().into_owned()
+    }
+
+    async fn metadata(&self) -> io::Result<Metadata> {
+        let inner = self.inner.metadata().await?;
+        Ok(Metadata { inner })
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "DirEntry({:?})", self.path)
+    }
 }
@@ -46,6 +46,7 @@
 //!
 //! See each module for documentation:
 //! * [core]
+//! * [env]
 //! * [experiments]
 //! * [fmt]
 //! * [fs]
@@ -53,6 +54,7 @@
 //! * [io]
 //! * [json]
 //! * [macros]
+//! * [path]
 //! * [process]
 //! * [rand]
 //! * [signal]
@@ -63,6 +65,7 @@
 //! ## Features
 //!
 //! * `core` for the [core module][toml]
+//! * `env` for the [env module][env]
 //! * `experiments` for the [experiments module][experiments]
 //! * `fmt` for the [fmt module][fmt]
 //! * `fs` for the [fs module][fs]
@@ -71,6 +74,7 @@
 //! * `io` for the [io module][io]
 //! * `json` for the [json module][json]
 //! * `macros` for the [macros module][macros]
+//! * `path` for the [path module][path]
 //! * `process` for the [process module][process]
 //! * `rand` for the [rand module][rand]
 //! * `signal` for the [signal module][signal]
@@ -79,6 +83,7 @@
 //! * `toml` for the [toml module][toml]
 //!
 //! [core]: https://docs.rs/rune-modules/0/rune_modules/core/
+//! [env]: https://docs.rs/rune-modules/0/rune_modules/env/
 //! [experiments]: https://docs.rs/rune-modules/0/rune_modules/experiments/
 //! [fmt]: https://docs.rs/rune-modules/0/rune_modules/fmt/
 //! [fs]: https://docs.rs/rune-modules/0/rune_modules/fs/
@@ -86,6 +91,7 @@
 //! [io]: https://docs.rs/rune-modules/0/rune_modules/io/
 //! [json]: https://docs.rs/rune-modules/0/rune_modules/json/
 //! [macros]: https://docs.rs/rune-modules/0/rune_modules/macros/
+//! [path]: https://docs.rs/rune-modules/0/rune_modules/path/
 //! [process]: https://docs.rs/rune-modules/0/rune_modules/process/
 //! [rand]: https://docs.rs/rune-modules/0/rune_modules/rand/
 //! [signal]: https://docs.rs/rune-modules/0/rune_modules/signal/
@@ -103,6 +109,9 @@ pub mod experiments;
 #[cfg(feature = "capture-io")]
 pub mod capture_io;
 
+#[cfg(feature = "env")]
+pub mod env;
+
 #[cfg(feature = "disable-io")]
 pub mod disable_io;
 
@@ -145,6 +154,7 @@ modules! {
     io, "io",
     json, "json",
     macros, "macros",
+    path, "path",
     process, "process",
     rand, "rand",
     signal, "signal",
@@ -0,0 +1,191 @@
+//! The native `path` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.12.0", features = ["path"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::path::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use path::Path;
+//!
+//! fn main() {
+//!     let path = Path::new("target").join("debug").join("rune.exe");
+//!     println(`${path.file_name()} ${path.extension()}`);
+//! }
+//! ```
+//!
+//! Paths are accepted in place of strings by the `fs` and `process` modules.
+//! This module doesn't access the file system, so resolving a path is done
+//! through `fs::canonicalize`, which respects the root of a scoped `fs`
+//! module.
+
+use rune::runtime::{FromValue, Protocol, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::ffi::OsString;
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// Construct the `path` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("path");
+    module.ty::<Path>()?;
+
+    module.function(&["Path", "new"], Path::new)?;
+    module.inst_fn("join", Path::join)?;
+    module.inst_fn("parent", Path::parent)?;
+    module.inst_fn("file_name", Path::file_name)?;
+    module.inst_fn("file_stem", Path::file_stem)?;
+    module.inst_fn("extension", Path::extension)?;
+    module.inst_fn("with_file_name", Path::with_file_name)?;
+    module.inst_fn("with_extension", Path::with_extension)?;
+    module.inst_fn("components", Path::components)?;
+    module.inst_fn("is_absolute", Path::is_absolute)?;
+    module.inst_fn("is_relative", Path::is_relative)?;
+    module.inst_fn("starts_with", Path::starts_with)?;
+    module.inst_fn("ends_with", Path::ends_with)?;
+    module.inst_fn("to_string", |p: &Path| p.to_string())?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Path::string_display)?;
+    module.inst_fn(Protocol::STRING_DEBUG, Path::string_debug)?;
+    Ok(module)
+}
+
+/// An owned file system path.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Any)]
+#[rune(clone, eq, partial_cmp, cmp, hash)]
+pub struct Path {
+    inner: PathBuf,
+}
+
+impl Path {
+    /// Construct a path from a string or another path.
+    fn new(path: PathArg) -> Self {
+        Self::from(path.0)
+    }
+
+    /// Access the underlying path.
+    pub fn as_path(&self) -> &std::path::Path {
+        &self.inner
+    }
+
+    /// Convert into the underlying path.
+    pub fn into_path_buf(self) -> PathBuf {
+        self.inner
+    }
+
+    /// Construct a new path by adjoining another path or string to this one.
+    ///
+    /// If the other path is absolute, it replaces this path.
+    fn join(&self, other: PathArg) -> Self {
+        Self::from(self.inner.join(other.0))
+    }
+
+    fn parent(&self) -> Option<Self> {
+        Some(Self::from(self.inner.parent()?.to_owned()))
+    }
+
+    fn file_name(&self) -> Option<String> {
+        Some(self.inner.file_name()?.to_string_lossy().into_owned())
+    }
+
+    fn file_stem(&self) -> Option<String> {
+        Some(self.inner.file_stem()?.to_string_lossy().into_owned())
+    }
+
+    fn extension(&self) -> Option<String> {
+        Some(self.inner.extension()?.to_string_lossy().into_owned())
+    }
+
+    fn with_file_name(&self, file_name: &str) -> Self {
+        Self::from(self.inner.with_file_name(file_name))
+    }
+
+    fn with_extension(&self, extension: &str) -> Self {
+        Self::from(self.inner.with_extension(extension))
+    }
+
+    /// The components of the path as strings, where the root of an absolute
+    /// path is its own component.
+    fn components(&self) -> Vec<String> {
+        self.inner
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn is_absolute(&self) -> bool {
+        self.inner.is_absolute()
+    }
+
+    fn is_relative(&self) -> bool {
+        self.inner.is_relative()
+    }
+
+    fn starts_with(&self, base: PathArg) -> bool {
+        self.inner.starts_with(base.0)
+    }
+
+    fn ends_with(&self, child: PathArg) -> bool {
+        self.inner.ends_with(child.0)
+    }
+
+    fn string_display(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{}", self)
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{:?}", self.inner)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.display().fmt(f)
+    }
+}
+
+impl From<PathBuf> for Path {
+    fn from(inner: PathBuf) -> Self {
+        Self { inner }
+    }
+}
+
+/// An argument which is either a [Path] or a string.
+pub(crate) struct PathArg(pub(crate) PathBuf);
+
+impl PathArg {
+    /// Convert into an os string, for arguments which aren't necessarily paths
+    /// like the arguments to a command.
+    pub(crate) fn into_os_string(self) -> OsString {
+        self.0.into_os_string()
+    }
+}
+
+impl FromValue for PathArg {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        if let Value::Any(any) = &value {
+            if let Some(path) = any.borrow_ref()?.downcast_borrow_ref::<Path>() {
+                return Ok(Self(path.inner.clone()));
+            }
+        }
+
+        Ok(Self(PathBuf::from(String::from_value(value)?)))
+    }
+}
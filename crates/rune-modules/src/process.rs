@@ -23,73 +23,164 @@
 //! Use it in Rune:
 //!
 //! ```rust,ignore
-//! use process::Command;
+//! use process::{Command, Stdio};
 //!
 //! fn main() {
 //!     let command = Command::new("ls");
-//!     command.run().await;
+//!     command.current_dir("/");
+//!     command.stdout(Stdio::piped());
+//!
+//!     let child = command.spawn()?;
+//!     let stdout = child.stdout().unwrap();
+//!
+//!     while let Some(line) = stdout.next().await? {
+//!         println(`${line}`);
+//!     }
+//!
+//!     let status = child.wait().await?;
+//!     println(`${status}`);
 //! }
 //! ```
+//!
+//! Since `process::exit` terminates the process that the script is running
+//! in, it's only available if the module is constructed with `stdio` enabled.
 
-use rune::{Any, Module, ContextError};
-use rune::runtime::{Bytes, Shared, Value, VmError, Protocol};
+use crate::path::PathArg;
+use rune::runtime::{Bytes, FromValue, Protocol, Shared, Value, VmError};
+use rune::{Any, ContextError, Module};
 use std::fmt;
 use std::io;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader,
+};
 use tokio::process;
 
 /// Construct the `process` module.
-pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+///
+/// If `stdio` is disabled, scripts are not allowed to terminate the current
+/// process through `process::exit`.
+pub fn module(stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("process");
     module.ty::<Command>()?;
     module.ty::<Child>()?;
+    module.ty::<ChildStdin>()?;
+    module.ty::<ChildStdout>()?;
+    module.ty::<ChildStderr>()?;
     module.ty::<ExitStatus>()?;
     module.ty::<Output>()?;
+    module.ty::<Stdio>()?;
+
+    if stdio {
+        module.function(&["exit"], exit)?;
+    }
+
+    module.function(&["id"], std::process::id)?;
 
     module.function(&["Command", "new"], Command::new)?;
     module.inst_fn("spawn", Command::spawn)?;
     module.inst_fn("arg", Command::arg)?;
     module.inst_fn("args", Command::args)?;
+    module.inst_fn("env", Command::env)?;
+    module.inst_fn("env_remove", Command::env_remove)?;
+    module.inst_fn("env_clear", Command::env_clear)?;
+    module.inst_fn("current_dir", Command::current_dir)?;
+    module.inst_fn("stdin", Command::stdin)?;
+    module.inst_fn("stdout", Command::stdout)?;
+    module.inst_fn("stderr", Command::stderr)?;
+    module.async_inst_fn("status", Command::status)?;
+    module.async_inst_fn("output", Command::output)?;
+
+    module.function(&["Stdio", "piped"], Stdio::piped)?;
+    module.function(&["Stdio", "inherit"], Stdio::inherit)?;
+    module.function(&["Stdio", "null"], Stdio::null)?;
+
+    module.inst_fn("id", Child::id)?;
+    module.inst_fn("stdin", Child::stdin)?;
+    module.inst_fn("stdout", Child::stdout)?;
+    module.inst_fn("stderr", Child::stderr)?;
+    module.async_inst_fn("kill", Child::kill)?;
+    module.inst_fn("try_wait", Child::try_wait)?;
+    module.async_inst_fn("wait", Child::wait)?;
     module.async_inst_fn("wait_with_output", Child::wait_with_output)?;
+
+    module.async_inst_fn("write", ChildStdin::write)?;
+    module.async_inst_fn("flush", ChildStdin::flush)?;
+    module.async_inst_fn("close", ChildStdin::close)?;
+
+    module.async_inst_fn("next", ChildStdout::next)?;
+    module.async_inst_fn("read_to_string", ChildStdout::read_to_string)?;
+    module.async_inst_fn("next", ChildStderr::next)?;
+    module.async_inst_fn("read_to_string", ChildStderr::read_to_string)?;
+
     module.inst_fn(Protocol::STRING_DISPLAY, ExitStatus::display)?;
     module.inst_fn("code", ExitStatus::code)?;
+    module.inst_fn("success", ExitStatus::success)?;
     Ok(module)
 }
 
+/// Terminate the current process with the given exit code.
+fn exit(code: i32) {
+    std::process::exit(code)
+}
+
 #[derive(Any)]
 struct Command {
     inner: process::Command,
 }
 
 impl Command {
-    /// Construct a new command.
-    fn new(command: &str) -> Self {
+    /// Construct a new command from a string or a path.
+    fn new(command: PathArg) -> Self {
         Self {
-            inner: process::Command::new(command),
+            inner: process::Command::new(command.0),
         }
     }
 
-    /// Add arguments.
+    /// Add arguments, which are either strings or paths.
     fn args(&mut self, args: &[Value]) -> Result<(), VmError> {
         for arg in args {
-            match arg {
-                Value::String(s) => {
-                    self.inner.arg(&*s.borrow_ref()?);
-                }
-                Value::StaticString(s) => {
-                    self.inner.arg(&***s);
-                }
-                actual => {
-                    return Err(VmError::expected::<String>(actual.type_info()?));
-                }
-            }
+            self.inner
+                .arg(PathArg::from_value(arg.clone())?.into_os_string());
         }
 
         Ok(())
     }
 
-    /// Add an argument.
-    fn arg(&mut self, arg: &str) {
-        self.inner.arg(arg);
+    /// Add an argument, which is either a string or a path.
+    fn arg(&mut self, arg: PathArg) {
+        self.inner.arg(arg.into_os_string());
+    }
+
+    /// Set an environment variable for the command.
+    fn env(&mut self, key: &str, value: PathArg) {
+        self.inner.env(key, value.into_os_string());
+    }
+
+    /// Remove an environment variable which would otherwise be inherited.
+    fn env_remove(&mut self, key: &str) {
+        self.inner.env_remove(key);
+    }
+
+    /// Clear all environment variables, so that none are inherited.
+    fn env_clear(&mut self) {
+        self.inner.env_clear();
+    }
+
+    /// Set the working directory of the command.
+    fn current_dir(&mut self, dir: PathArg) {
+        self.inner.current_dir(dir.0);
+    }
+
+    fn stdin(&mut self, stdio: &Stdio) {
+        self.inner.stdin(stdio.build());
+    }
+
+    fn stdout(&mut self, stdio: &Stdio) {
+        self.inner.stdout(stdio.build());
+    }
+
+    fn stderr(&mut self, stdio: &Stdio) {
+        self.inner.stderr(stdio.build());
     }
 
     /// Spawn the command.
@@ -98,6 +189,51 @@ impl Command {
             inner: Some(self.inner.spawn()?),
         })
     }
+
+    /// Run the command to completion, returning its exit status.
+    async fn status(&mut self) -> io::Result<ExitStatus> {
+        let status = self.inner.status().await?;
+        Ok(ExitStatus { status })
+    }
+
+    /// Run the command to completion, collecting all of its output.
+    async fn output(&mut self) -> io::Result<Output> {
+        Ok(Output::from(self.inner.output().await?))
+    }
+}
+
+/// How to configure one of the standard streams of a command.
+#[derive(Debug, Clone, Copy, Any)]
+enum Stdio {
+    Piped,
+    Inherit,
+    Null,
+}
+
+impl Stdio {
+    /// A new pipe should be arranged to connect the parent and child
+    /// processes.
+    fn piped() -> Self {
+        Self::Piped
+    }
+
+    /// The child inherits from the corresponding parent descriptor.
+    fn inherit() -> Self {
+        Self::Inherit
+    }
+
+    /// The stream will be ignored.
+    fn null() -> Self {
+        Self::Null
+    }
+
+    fn build(&self) -> std::process::Stdio {
+        match self {
+            Self::Piped => std::process::Stdio::piped(),
+            Self::Inherit => std::process::Stdio::inherit(),
+            Self::Null => std::process::Stdio::null(),
+        }
+    }
 }
 
 #[derive(Any)]
@@ -110,6 +246,67 @@ struct Child {
 }
 
 impl Child {
+    fn inner(&mut self) -> Result<&mut process::Child, VmError> {
+        match &mut self.inner {
+            Some(inner) => Ok(inner),
+            None => Err(VmError::panic("already completed")),
+        }
+    }
+
+    /// The OS-assigned process identifier of the child, or `None` if it has
+    /// already been waited on.
+    fn id(&mut self) -> Result<Option<u32>, VmError> {
+        Ok(self.inner()?.id())
+    }
+
+    /// Take the handle for writing to the standard input of the child, if it
+    /// was configured with `Stdio::piped()`.
+    fn stdin(&mut self) -> Result<Option<ChildStdin>, VmError> {
+        Ok(self
+            .inner()?
+            .stdin
+            .take()
+            .map(|inner| ChildStdin { inner: Some(inner) }))
+    }
+
+    /// Take the handle for reading the standard output of the child, if it
+    /// was configured with `Stdio::piped()`.
+    fn stdout(&mut self) -> Result<Option<ChildStdout>, VmError> {
+        Ok(self.inner()?.stdout.take().map(|inner| ChildStdout {
+            inner: BufReader::new(inner),
+        }))
+    }
+
+    /// Take the handle for reading the standard error of the child, if it was
+    /// configured with `Stdio::piped()`.
+    fn stderr(&mut self) -> Result<Option<ChildStderr>, VmError> {
+        Ok(self.inner()?.stderr.take().map(|inner| ChildStderr {
+            inner: BufReader::new(inner),
+        }))
+    }
+
+    /// Forcefully kill the child and wait for it to exit.
+    async fn kill(&mut self) -> Result<io::Result<()>, VmError> {
+        Ok(self.inner()?.kill().await)
+    }
+
+    /// Check if the child has exited without waiting for it.
+    fn try_wait(&mut self) -> Result<io::Result<Option<ExitStatus>>, VmError> {
+        Ok(self
+            .inner()?
+            .try_wait()
+            .map(|status| status.map(|status| ExitStatus { status })))
+    }
+
+    /// Wait for the child to exit, returning its exit status.
+    async fn wait(&mut self) -> Result<io::Result<ExitStatus>, VmError> {
+        Ok(self
+            .inner()?
+            .wait()
+            .await
+            .map(|status| ExitStatus { status }))
+    }
+
     // Returns a future that will resolve to an Output, containing the exit
     // status, stdout, and stderr of the child process.
     async fn wait_with_output(self) -> Result<io::Result<Output>, VmError> {
@@ -125,14 +322,116 @@ impl Child {
             Err(error) => return Ok(Err(error)),
         };
 
-        Ok(Ok(Output {
-            status: ExitStatus { status: output.status },
-            stdout: Shared::new(Bytes::from_vec(output.stdout)),
-            stderr: Shared::new(Bytes::from_vec(output.stderr)),
-        }))
+        Ok(Ok(Output::from(output)))
+    }
+}
+
+/// The standard input of a child process.
+#[derive(Any)]
+struct ChildStdin {
+    // closing the handle takes it, so that the child sees the end of its
+    // input.
+    inner: Option<process::ChildStdin>,
+}
+
+impl ChildStdin {
+    fn inner(&mut self) -> Result<&mut process::ChildStdin, VmError> {
+        match &mut self.inner {
+            Some(inner) => Ok(inner),
+            None => Err(VmError::panic("already closed")),
+        }
+    }
+
+    /// Write a string or bytes to the child.
+    async fn write(&mut self, contents: Value) -> Result<io::Result<()>, VmError> {
+        let contents = match contents {
+            Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+            value => String::from_value(value)?.into_bytes(),
+        };
+
+        Ok(self.inner()?.write_all(&contents).await)
+    }
+
+    async fn flush(&mut self) -> Result<io::Result<()>, VmError> {
+        Ok(self.inner()?.flush().await)
+    }
+
+    /// Flush and close the standard input, signalling the end of input to the
+    /// child.
+    async fn close(&mut self) -> Result<io::Result<()>, VmError> {
+        let result = self.inner()?.shutdown().await;
+        self.inner = None;
+        Ok(result)
+    }
+}
+
+/// The standard output of a child process.
+///
+/// This is a stream of lines, which are read through its `next` function.
+#[derive(Any)]
+struct ChildStdout {
+    inner: BufReader<process::ChildStdout>,
+}
+
+impl ChildStdout {
+    /// Read the next line without its line ending, returning `None` once the
+    /// output is closed.
+    async fn next(&mut self) -> io::Result<Option<String>> {
+        next_line(&mut self.inner).await
+    }
+
+    /// Read all remaining output into a string.
+    async fn read_to_string(&mut self) -> io::Result<String> {
+        let mut string = String::new();
+        self.inner.read_to_string(&mut string).await?;
+        Ok(string)
     }
 }
 
+/// The standard error of a child process.
+///
+/// This is a stream of lines, which are read through its `next` function.
+#[derive(Any)]
+struct ChildStderr {
+    inner: BufReader<process::ChildStderr>,
+}
+
+impl ChildStderr {
+    /// Read the next line without its line ending, returning `None` once the
+    /// output is closed.
+    async fn next(&mut self) -> io::Result<Option<String>> {
+        next_line(&mut self.inner).await
+    }
+
+    /// Read all remaining output into a string.
+    async fn read_to_string(&mut self) -> io::Result<String> {
+        let mut string = String::new();
+        self.inner.read_to_string(&mut string).await?;
+        Ok(string)
+    }
+}
+
+async fn next_line<R>(reader: &mut R) -> io::Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    if line.ends_with('\n') {
+        line.pop();
+
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+
+    Ok(Some(line))
+}
+
 #[derive(Any)]
 struct Output {
     #[rune(get)]
@@ -143,6 +442,18 @@ struct Output {
     stderr: Shared<Bytes>,
 }
 
+impl From<std::process::Output> for Output {
+    fn from(output: std::process::Output) -> Self {
+        Self {
+            status: ExitStatus {
+                status: output.status,
+            },
+            stdout: Shared::new(Bytes::from_vec(output.stdout)),
+            stderr: Shared::new(Bytes::from_vec(output.stderr)),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Any)]
 struct ExitStatus {
     status: std::process::ExitStatus,
@@ -157,4 +468,9 @@ impl ExitStatus {
     fn code(&self) -> Option<i32> {
         self.status.code()
     }
+
+    /// Test if the process exited successfully.
+    fn success(&self) -> bool {
+        self.status.success()
+    }
 }
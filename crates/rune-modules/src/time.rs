@@ -30,32 +30,509 @@
 //!     println("Message after 10 seconds!");
 //! }
 //! ```
+//!
+//! Measure how long something takes, and give up on it if it takes too long:
+//!
+//! ```rust,ignore
+//! use time::{Duration, Instant};
+//!
+//! async fn main() {
+//!     let start = Instant::now();
+//!
+//!     match time::timeout(Duration::from_millis(500), fetch()).await {
+//!         Ok(value) => println(`done in ${start.elapsed().as_millis()}ms`),
+//!         Err(error) => println(`gave up: ${error}`),
+//!     }
+//! }
+//! ```
+//!
+//! Run something at a fixed rate:
+//!
+//! ```rust,ignore
+//! use time::Duration;
+//!
+//! async fn main() {
+//!     let interval = time::interval(Duration::from_secs(1));
+//!
+//!     while let Some(instant) = interval.next().await {
+//!         poll().await;
+//!     }
+//! }
+//! ```
 
+use rune::runtime::{Future, Protocol, Value, VmError, VmErrorKind};
 use rune::{Any, ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
 
 /// Construct the `time` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("time");
+    module.ty::<Duration>()?;
+    module.ty::<Instant>()?;
+    module.ty::<SystemTime>()?;
+    module.ty::<SystemTimeError>()?;
+    module.ty::<Interval>()?;
+    module.ty::<Elapsed>()?;
+
     module.function(&["Duration", "from_secs"], Duration::from_secs)?;
+    module.function(&["Duration", "from_millis"], Duration::from_millis)?;
+    module.function(&["Duration", "from_micros"], Duration::from_micros)?;
+    module.function(&["Duration", "from_nanos"], Duration::from_nanos)?;
+    module.function(&["Duration", "from_secs_f64"], Duration::from_secs_f64)?;
+    module.inst_fn("as_secs", Duration::as_secs)?;
+    module.inst_fn("as_millis", Duration::as_millis)?;
+    module.inst_fn("as_micros", Duration::as_micros)?;
+    module.inst_fn("as_nanos", Duration::as_nanos)?;
+    module.inst_fn("as_secs_f64", Duration::as_secs_f64)?;
+    module.inst_fn("subsec_millis", Duration::subsec_millis)?;
+    module.inst_fn("subsec_nanos", Duration::subsec_nanos)?;
+    module.inst_fn("is_zero", Duration::is_zero)?;
+    module.inst_fn(Protocol::ADD, Duration::add)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, Duration::add_assign)?;
+    module.inst_fn(Protocol::SUB, Duration::sub)?;
+    module.inst_fn(Protocol::SUB_ASSIGN, Duration::sub_assign)?;
+    module.inst_fn(Protocol::MUL, Duration::mul)?;
+    module.inst_fn(Protocol::MUL_ASSIGN, Duration::mul_assign)?;
+    module.inst_fn(Protocol::DIV, Duration::div)?;
+    module.inst_fn(Protocol::DIV_ASSIGN, Duration::div_assign)?;
+    module.inst_fn(Protocol::STRING_DEBUG, Duration::string_debug)?;
+
+    module.function(&["Instant", "now"], Instant::now)?;
+    module.inst_fn("elapsed", Instant::elapsed)?;
+    module.inst_fn("duration_since", Instant::duration_since)?;
+    module.inst_fn(Protocol::ADD, Instant::add)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, Instant::add_assign)?;
+    module.inst_fn(Protocol::SUB, Instant::sub)?;
+    module.inst_fn(Protocol::SUB_ASSIGN, Instant::sub_assign)?;
+    module.inst_fn(Protocol::STRING_DEBUG, Instant::string_debug)?;
+
+    module.function(&["SystemTime", "now"], SystemTime::now)?;
+    module.function(&["SystemTime", "UNIX_EPOCH"], SystemTime::unix_epoch)?;
+    module.inst_fn("elapsed", SystemTime::elapsed)?;
+    module.inst_fn("duration_since", SystemTime::duration_since)?;
+    module.inst_fn(Protocol::ADD, SystemTime::add)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, SystemTime::add_assign)?;
+    module.inst_fn(Protocol::SUB, SystemTime::sub)?;
+    module.inst_fn(Protocol::SUB_ASSIGN, SystemTime::sub_assign)?;
+    module.inst_fn(Protocol::STRING_DEBUG, SystemTime::string_debug)?;
+
+    module.inst_fn("duration", SystemTimeError::duration)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, SystemTimeError::display)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Elapsed::display)?;
+
     module.async_function(&["sleep"], sleep)?;
+    module.async_function(&["timeout"], timeout)?;
+    module.function(&["interval"], interval)?;
+    module.async_inst_fn("tick", Interval::tick)?;
+    module.async_inst_fn("next", Interval::next)?;
+    module.inst_fn("reset", Interval::reset)?;
+    module.inst_fn("period", Interval::period)?;
     Ok(module)
 }
 
-#[derive(Debug, Clone, Copy, Any)]
-struct Duration {
-    inner: tokio::time::Duration,
+/// A span of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Any)]
+#[rune(clone, eq, partial_cmp, cmp, hash)]
+pub struct Duration {
+    inner: std::time::Duration,
 }
 
 impl Duration {
     /// Construct a duration from seconds.
-    fn from_secs(secs: u64) -> Self {
+    fn from_secs(secs: i64) -> Result<Self, VmError> {
+        Ok(Self::from(std::time::Duration::from_secs(unsigned(secs)?)))
+    }
+
+    fn from_millis(millis: i64) -> Result<Self, VmError> {
+        Ok(Self::from(std::time::Duration::from_millis(unsigned(
+            millis,
+        )?)))
+    }
+
+    fn from_micros(micros: i64) -> Result<Self, VmError> {
+        Ok(Self::from(std::time::Duration::from_micros(unsigned(
+            micros,
+        )?)))
+    }
+
+    fn from_nanos(nanos: i64) -> Result<Self, VmError> {
+        Ok(Self::from(std::time::Duration::from_nanos(unsigned(
+            nanos,
+        )?)))
+    }
+
+    /// Construct a duration from fractional seconds, which errors if the
+    /// number is negative, not finite or too large.
+    fn from_secs_f64(secs: f64) -> Result<Self, VmError> {
+        match std::time::Duration::try_from_secs_f64(secs) {
+            Ok(inner) => Ok(Self::from(inner)),
+            Err(..) if secs < 0.0 => Err(VmError::from(VmErrorKind::Underflow)),
+            Err(..) => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    /// Access the underlying duration.
+    pub fn as_duration(&self) -> std::time::Duration {
+        self.inner
+    }
+
+    fn as_secs(&self) -> Result<i64, VmError> {
+        signed(self.inner.as_secs())
+    }
+
+    fn as_millis(&self) -> Result<i64, VmError> {
+        signed(self.inner.as_millis())
+    }
+
+    fn as_micros(&self) -> Result<i64, VmError> {
+        signed(self.inner.as_micros())
+    }
+
+    fn as_nanos(&self) -> Result<i64, VmError> {
+        signed(self.inner.as_nanos())
+    }
+
+    fn as_secs_f64(&self) -> f64 {
+        self.inner.as_secs_f64()
+    }
+
+    fn subsec_millis(&self) -> i64 {
+        self.inner.subsec_millis() as i64
+    }
+
+    fn subsec_nanos(&self) -> i64 {
+        self.inner.subsec_nanos() as i64
+    }
+
+    fn is_zero(&self) -> bool {
+        self.inner.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Result<Self, VmError> {
+        match self.inner.checked_add(other.inner) {
+            Some(inner) => Ok(Self::from(inner)),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    fn add_assign(&mut self, other: &Self) -> Result<(), VmError> {
+        *self = self.add(other)?;
+        Ok(())
+    }
+
+    /// Subtract another duration, which errors instead of producing a
+    /// negative duration.
+    fn sub(&self, other: &Self) -> Result<Self, VmError> {
+        match self.inner.checked_sub(other.inner) {
+            Some(inner) => Ok(Self::from(inner)),
+            None => Err(VmError::from(VmErrorKind::Underflow)),
+        }
+    }
+
+    fn sub_assign(&mut self, other: &Self) -> Result<(), VmError> {
+        *self = self.sub(other)?;
+        Ok(())
+    }
+
+    fn mul(&self, n: i64) -> Result<Self, VmError> {
+        let n = u32::try_from(n).map_err(|_| out_of_range(n))?;
+
+        match self.inner.checked_mul(n) {
+            Some(inner) => Ok(Self::from(inner)),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    fn mul_assign(&mut self, n: i64) -> Result<(), VmError> {
+        *self = self.mul(n)?;
+        Ok(())
+    }
+
+    fn div(&self, n: i64) -> Result<Self, VmError> {
+        let n = u32::try_from(n).map_err(|_| out_of_range(n))?;
+
+        match self.inner.checked_div(n) {
+            Some(inner) => Ok(Self::from(inner)),
+            None => Err(VmError::from(VmErrorKind::DivideByZero)),
+        }
+    }
+
+    fn div_assign(&mut self, n: i64) -> Result<(), VmError> {
+        *self = self.div(n)?;
+        Ok(())
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{:?}", self.inner)
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(inner: std::time::Duration) -> Self {
+        Self { inner }
+    }
+}
+
+/// A measurement of a monotonically nondecreasing clock, for measuring how
+/// long something takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Any)]
+#[rune(clone, eq, partial_cmp, cmp, hash)]
+pub struct Instant {
+    inner: tokio::time::Instant,
+}
+
+impl Instant {
+    fn now() -> Self {
         Self {
-            inner: tokio::time::Duration::from_secs(secs),
+            inner: tokio::time::Instant::now(),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::from(self.inner.elapsed())
+    }
+
+    /// The amount of time elapsed from an earlier instant to this one, or
+    /// zero if that instant is later than this one.
+    fn duration_since(&self, earlier: &Self) -> Duration {
+        Duration::from(self.inner.saturating_duration_since(earlier.inner))
+    }
+
+    fn add(&self, duration: &Duration) -> Result<Self, VmError> {
+        match self.inner.checked_add(duration.inner) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    fn add_assign(&mut self, duration: &Duration) -> Result<(), VmError> {
+        *self = self.add(duration)?;
+        Ok(())
+    }
+
+    fn sub(&self, duration: &Duration) -> Result<Self, VmError> {
+        match self.inner.checked_sub(duration.inner) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Underflow)),
+        }
+    }
+
+    fn sub_assign(&mut self, duration: &Duration) -> Result<(), VmError> {
+        *self = self.sub(duration)?;
+        Ok(())
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{:?}", self.inner)
+    }
+}
+
+/// A measurement of the system clock, which unlike an [Instant] can be
+/// related to wall-clock time but isn't guaranteed to be monotonic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Any)]
+#[rune(clone, eq, partial_cmp, cmp, hash)]
+pub struct SystemTime {
+    inner: std::time::SystemTime,
+}
+
+impl SystemTime {
+    fn now() -> Self {
+        Self::from(std::time::SystemTime::now())
+    }
+
+    /// The anchor of the system clock, `1970-01-01 00:00:00 UTC`.
+    ///
+    /// This is a function rather than a constant, since constants can't hold
+    /// native types.
+    fn unix_epoch() -> Self {
+        Self::from(std::time::UNIX_EPOCH)
+    }
+
+    /// Access the underlying system time.
+    pub fn as_system_time(&self) -> std::time::SystemTime {
+        self.inner
+    }
+
+    /// The amount of time elapsed since this time, which errors if the system
+    /// clock has gone backwards past it.
+    fn elapsed(&self) -> Result<Duration, SystemTimeError> {
+        Ok(Duration::from(self.inner.elapsed()?))
+    }
+
+    /// The amount of time elapsed from an earlier time to this one, which
+    /// errors if that time is later than this one.
+    fn duration_since(&self, earlier: &Self) -> Result<Duration, SystemTimeError> {
+        Ok(Duration::from(self.inner.duration_since(earlier.inner)?))
+    }
+
+    fn add(&self, duration: &Duration) -> Result<Self, VmError> {
+        match self.inner.checked_add(duration.inner) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    fn add_assign(&mut self, duration: &Duration) -> Result<(), VmError> {
+        *self = self.add(duration)?;
+        Ok(())
+    }
+
+    fn sub(&self, duration: &Duration) -> Result<Self, VmError> {
+        match self.inner.checked_sub(duration.inner) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Underflow)),
         }
     }
+
+    fn sub_assign(&mut self, duration: &Duration) -> Result<(), VmError> {
+        *self = self.sub(duration)?;
+        Ok(())
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{:?}", self.inner)
+    }
+}
+
+impl From<std::time::SystemTime> for SystemTime {
+    fn from(inner: std::time::SystemTime) -> Self {
+        Self { inner }
+    }
 }
 
-/// Convert any value to a json string.
+/// The error raised when a [SystemTime] is later than the one it's being
+/// compared against.
+#[derive(Debug, Any)]
+pub struct SystemTimeError {
+    inner: std::time::SystemTimeError,
+}
+
+impl SystemTimeError {
+    /// How far the time was ahead of the one it was compared against.
+    fn duration(&self) -> Duration {
+        Duration::from(self.inner.duration())
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
+
+impl From<std::time::SystemTimeError> for SystemTimeError {
+    fn from(inner: std::time::SystemTimeError) -> Self {
+        Self { inner }
+    }
+}
+
+/// The error raised when a [timeout] elapses.
+#[derive(Debug, Any)]
+pub struct Elapsed {
+    inner: tokio::time::error::Elapsed,
+}
+
+impl Elapsed {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
+
+/// A stream of instants which are spaced out by a period.
+///
+/// The underlying tokio interval is constructed on the first tick, since
+/// doing so requires a runtime which isn't necessarily available when the
+/// interval itself is constructed. Missed ticks are delayed rather than
+/// completed in a burst, so an interval which isn't polled for a while, or
+/// until long after it was constructed, doesn't catch up on the ticks it
+/// missed.
+#[derive(Debug, Any)]
+struct Interval {
+    start: tokio::time::Instant,
+    period: std::time::Duration,
+    inner: Option<tokio::time::Interval>,
+}
+
+impl Interval {
+    /// Wait until the next tick, where the first tick completes immediately.
+    async fn tick(&mut self) -> Instant {
+        let (start, period) = (self.start, self.period);
+
+        let inner = self.inner.get_or_insert_with(|| {
+            let mut inner = tokio::time::interval_at(start, period);
+            inner.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            inner
+        });
+
+        Instant {
+            inner: inner.tick().await,
+        }
+    }
+
+    /// Wait until the next tick, which makes an interval usable as a stream
+    /// which never ends.
+    async fn next(&mut self) -> Option<Instant> {
+        Some(self.tick().await)
+    }
+
+    /// Reset the interval so that the next tick happens one period from now.
+    fn reset(&mut self) {
+        match &mut self.inner {
+            Some(inner) => inner.reset(),
+            None => self.start = tokio::time::Instant::now() + self.period,
+        }
+    }
+
+    fn period(&self) -> Duration {
+        Duration::from(self.period)
+    }
+}
+
+/// Sleep for the given duration.
 async fn sleep(duration: &Duration) {
     tokio::time::sleep(duration.inner).await;
 }
+
+/// Wait for a future to complete, unless it takes longer than the given
+/// duration.
+async fn timeout(duration: &Duration, future: Future) -> Result<Result<Value, Elapsed>, VmError> {
+    match tokio::time::timeout(duration.inner, future).await {
+        Ok(value) => Ok(Ok(value?)),
+        Err(inner) => Ok(Err(Elapsed { inner })),
+    }
+}
+
+/// Construct an interval which ticks every period, which errors if the period
+/// is zero.
+fn interval(period: &Duration) -> Result<Interval, VmError> {
+    if period.inner.is_zero() {
+        return Err(VmError::panic("interval period must be non-zero"));
+    }
+
+    Ok(Interval {
+        start: tokio::time::Instant::now(),
+        period: period.inner,
+        inner: None,
+    })
+}
+
+/// Convert an integer from a script into an unsigned one.
+fn unsigned(n: i64) -> Result<u64, VmError> {
+    u64::try_from(n).map_err(|_| VmError::from(VmErrorKind::Underflow))
+}
+
+/// Convert an unsigned integer into one a script can use.
+fn signed<T>(n: T) -> Result<i64, VmError>
+where
+    i64: TryFrom<T>,
+{
+    i64::try_from(n).map_err(|_| VmError::from(VmErrorKind::Overflow))
+}
+
+/// The error raised when an integer operand is out of range.
+fn out_of_range(n: i64) -> VmError {
+    if n < 0 {
+        VmError::from(VmErrorKind::Underflow)
+    } else {
+        VmError::from(VmErrorKind::Overflow)
+    }
+}
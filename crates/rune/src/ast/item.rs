@@ -15,6 +15,8 @@ pub enum Item {
     Struct(ast::ItemStruct),
     /// An impl declaration.
     Impl(ast::ItemImpl),
+    /// A trait declaration.
+    Trait(ast::ItemTrait),
     /// A module declaration.
     Mod(ast::ItemMod),
     /// A const declaration.
@@ -32,6 +34,7 @@ impl Item {
             Self::Enum(item) => &item.attributes,
             Self::Struct(item) => &item.attributes,
             Self::Impl(item) => &item.attributes,
+            Self::Trait(item) => &item.attributes,
             Self::Mod(item) => &item.attributes,
             Self::Const(item) => &item.attributes,
             Self::MacroCall(item) => &item.attributes,
@@ -55,6 +58,7 @@ impl Item {
             K![enum] => true,
             K![struct] => true,
             K![impl] => true,
+            K![trait] => true,
             K![async] => matches!(p.nth(1), K![fn]),
             K![fn] => true,
             K![mod] => true,
@@ -102,6 +106,11 @@ impl Item {
                     p,
                     take(&mut attributes),
                 )?),
+                K![trait] => Self::Trait(ast::ItemTrait::parse_with_meta(
+                    p,
+                    take(&mut attributes),
+                    take(&mut visibility),
+                )?),
                 K![fn] => Self::Fn(ast::ItemFn::parse_with_meta(
                     p,
                     take(&mut attributes),
@@ -129,7 +138,7 @@ impl Item {
                 _ => {
                     return Err(ParseError::expected(
                         p.tok_at(0)?,
-                        "`fn`, `mod`, `struct`, `enum`, `use`, or macro call",
+                        "`fn`, `mod`, `struct`, `enum`, `trait`, `use`, or macro call",
                     ))
                 }
             };
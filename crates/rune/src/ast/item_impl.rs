@@ -11,6 +11,9 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ItemImpl>("impl Foo { fn test(self) { } }");
 /// testing::roundtrip::<ast::ItemImpl>("#[variant(enum_= \"SuperHero\", x = \"1\")] impl Foo { fn test(self) { } }");
 /// testing::roundtrip::<ast::ItemImpl>("#[xyz] impl Foo { #[jit] fn test(self) { } }");
+///
+/// let item = testing::roundtrip::<ast::ItemImpl>("impl Display for Foo { fn fmt(self) { } }");
+/// assert!(item.trait_.is_some());
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
@@ -20,6 +23,9 @@ pub struct ItemImpl {
     pub attributes: Vec<ast::Attribute>,
     /// The `impl` keyword.
     pub impl_: T![impl],
+    /// The trait being implemented, if this is an `impl Trait for Type` block.
+    #[rune(iter)]
+    pub trait_: Option<(ast::Path, T![for])>,
     /// Path of the implementation.
     pub path: ast::Path,
     /// The open brace.
@@ -37,7 +43,13 @@ impl ItemImpl {
         attributes: Vec<ast::Attribute>,
     ) -> Result<Self, ParseError> {
         let impl_ = parser.parse()?;
-        let path = parser.parse()?;
+        let mut path = parser.parse()?;
+
+        let trait_ = match parser.parse::<Option<T![for]>>()? {
+            Some(for_) => Some((std::mem::replace(&mut path, parser.parse()?), for_)),
+            None => None,
+        };
+
         let open = parser.parse()?;
 
         let mut functions = vec![];
@@ -51,6 +63,7 @@ impl ItemImpl {
         Ok(Self {
             attributes,
             impl_,
+            trait_,
             path,
             open,
             functions,
@@ -0,0 +1,147 @@
+use crate::ast::prelude::*;
+
+/// A trait item.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{ast, testing};
+///
+/// testing::roundtrip::<ast::ItemTrait>("trait Foo {}");
+/// testing::roundtrip::<ast::ItemTrait>("pub trait Foo { fn test(self); }");
+/// testing::roundtrip::<ast::ItemTrait>("trait Foo { fn test(self); fn other(self) { self.test() } }");
+/// testing::roundtrip::<ast::ItemTrait>("trait Foo { #[doc = \"Test.\"] async fn test(self, a); }");
+///
+/// let item = testing::roundtrip::<ast::ItemTrait>("trait Foo { fn a(self); fn b(self) {} }");
+/// assert_eq!(item.items.len(), 2);
+/// assert!(matches!(item.items[0], ast::TraitItem::Decl(..)));
+/// assert!(matches!(item.items[1], ast::TraitItem::Fn(..)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[rune(parse = "meta_only")]
+#[non_exhaustive]
+pub struct ItemTrait {
+    /// Opaque identifier for the trait.
+    #[rune(id)]
+    pub(crate) id: Id,
+    /// The attributes of the trait.
+    #[rune(iter, meta)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the trait.
+    #[rune(optional, meta)]
+    pub visibility: ast::Visibility,
+    /// The `trait` keyword.
+    pub trait_token: T![trait],
+    /// The name of the trait.
+    pub name: ast::Ident,
+    /// The open brace.
+    pub open: T!['{'],
+    /// The functions declared in the trait.
+    #[rune(iter)]
+    pub items: Vec<TraitItem>,
+    /// The close brace.
+    pub close: T!['}'],
+}
+
+item_parse!(Trait, ItemTrait, "trait item");
+
+/// A function inside of a trait.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub enum TraitItem {
+    /// A function with a default implementation.
+    Fn(ast::ItemFn),
+    /// A function which has to be provided by implementors.
+    Decl(TraitFnDecl),
+}
+
+impl TraitItem {
+    /// The name of the trait function.
+    pub fn name(&self) -> &ast::Ident {
+        match self {
+            Self::Fn(item) => &item.name,
+            Self::Decl(item) => &item.name,
+        }
+    }
+}
+
+impl Parse for TraitItem {
+    fn parse(p: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let attributes = p.parse()?;
+        let visibility = p.parse::<ast::Visibility>()?;
+        let const_token = p.parse::<Option<T![const]>>()?;
+        let async_token = p.parse()?;
+        let fn_token = p.parse()?;
+        let name = p.parse()?;
+        let args = p.parse()?;
+
+        if let Some(semi) = p.parse::<Option<T![;]>>()? {
+            if let Some(span) = visibility.option_span() {
+                return Err(ParseError::unsupported(
+                    span,
+                    "visibility on trait function",
+                ));
+            }
+
+            if let Some(span) = const_token.option_span() {
+                return Err(ParseError::unsupported(span, "const trait function"));
+            }
+
+            return Ok(Self::Decl(TraitFnDecl {
+                attributes,
+                async_token,
+                fn_token,
+                name,
+                args,
+                semi,
+            }));
+        }
+
+        Ok(Self::Fn(ast::ItemFn {
+            id: Default::default(),
+            attributes,
+            visibility,
+            const_token,
+            async_token,
+            fn_token,
+            name,
+            args,
+            body: p.parse()?,
+        }))
+    }
+}
+
+impl Peek for TraitItem {
+    fn peek(p: &mut Peeker<'_>) -> bool {
+        matches!(p.nth(0), K![#] | K![pub] | K![const] | K![async] | K![fn])
+    }
+}
+
+/// A function declared in a trait without a default implementation.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{ast, testing};
+///
+/// testing::roundtrip::<ast::TraitItem>("fn test(self);");
+/// testing::roundtrip::<ast::TraitItem>("async fn test(self, a, b);");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct TraitFnDecl {
+    /// The attributes of the function.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The optional `async` keyword.
+    #[rune(iter)]
+    pub async_token: Option<T![async]>,
+    /// The `fn` token.
+    pub fn_token: T![fn],
+    /// The name of the function.
+    pub name: ast::Ident,
+    /// The arguments of the function.
+    pub args: ast::Parenthesized<ast::FnArg, T![,]>,
+    /// The terminating semi-colon.
+    pub semi: T![;],
+}
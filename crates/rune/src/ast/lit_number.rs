@@ -24,6 +24,9 @@ pub struct LitNumber {
 /// testing::roundtrip::<ast::LitNumber>("42.42");
 /// testing::roundtrip::<ast::LitNumber>("0.42");
 /// testing::roundtrip::<ast::LitNumber>("0.42e10");
+/// testing::roundtrip::<ast::LitNumber>("42u64");
+/// testing::roundtrip::<ast::LitNumber>("0xffi128");
+/// testing::roundtrip::<ast::LitNumber>("42n");
 /// ```
 impl Parse for LitNumber {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -78,8 +81,28 @@ impl<'a> Resolve<'a> for LitNumber {
             ast::NumberBase::Decimal => (0, 10),
         };
 
-        let number = num::BigInt::from_str_radix(&string[s..], radix).map_err(err_span(span))?;
-        return Ok(ast::Number::Integer(number));
+        let string = &string[s..];
+
+        // NB: a type suffix starts with the first letter which isn't a digit
+        // in the given radix, like the `u64` in `0xffu64`.
+        let suffix = string.find(|c: char| c.is_alphabetic() && !c.is_digit(radix));
+
+        let (string, suffix) = match suffix {
+            Some(n) => {
+                let suffix = ast::NumberSuffix::parse(&string[n..])
+                    .ok_or_else(|| ResolveError::new(span, ResolveErrorKind::BadNumberLiteral))?;
+
+                (&string[..n], Some(suffix))
+            }
+            None => (string, None),
+        };
+
+        let number = num::BigInt::from_str_radix(string, radix).map_err(err_span(span))?;
+
+        return Ok(match suffix {
+            Some(suffix) => ast::Number::Suffixed(number, suffix),
+            None => ast::Number::Integer(number),
+        });
 
         fn err_span<E>(span: Span) -> impl Fn(E) -> ResolveError {
             move |_| ResolveError::new(span, ResolveErrorKind::BadNumberLiteral)
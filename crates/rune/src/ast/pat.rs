@@ -20,6 +20,12 @@ pub enum Pat {
     PatBinding(PatBinding),
     /// The rest pattern `..`.
     PatRest(PatRest),
+    /// An or-pattern `a | b`.
+    PatOr(PatOr),
+    /// A range pattern `a..b` or `a..=b`.
+    PatRange(PatRange),
+    /// A binding pattern `name @ pattern`.
+    PatAt(PatAt),
 }
 
 /// Parsing a block expression.
@@ -41,23 +47,53 @@ pub enum Pat {
 /// testing::roundtrip::<ast::Pat>("var");
 /// testing::roundtrip::<ast::Pat>("_");
 /// testing::roundtrip::<ast::Pat>("Foo(n)");
+/// testing::roundtrip::<ast::Pat>("1 | 2 | 3");
+/// testing::roundtrip::<ast::Pat>("'a'..='z'");
+/// testing::roundtrip::<ast::Pat>("-10..10");
+/// testing::roundtrip::<ast::Pat>("b'0'..");
+/// testing::roundtrip::<ast::Pat>("n @ 1..=9");
+/// testing::roundtrip::<ast::Pat>("(n @ (1 | 2), _)");
+///
+/// let pat = testing::roundtrip::<ast::Pat>("a @ 1 | 2");
+/// assert!(matches!(pat, ast::Pat::PatOr(..)));
 /// ```
 impl Parse for Pat {
     fn parse(p: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let first = Self::parse_without_or(p)?;
+
+        if !p.peek::<T![|]>()? {
+            return Ok(first);
+        }
+
+        let mut rest = Vec::new();
+
+        while let Some(pipe) = p.parse::<Option<T![|]>>()? {
+            rest.push((pipe, Self::parse_without_or(p)?));
+        }
+
+        Ok(Self::PatOr(PatOr {
+            first: Box::new(first),
+            rest,
+        }))
+    }
+}
+
+impl Pat {
+    /// Parse a pattern which is not an or-pattern.
+    ///
+    /// This is used where a `|` can't be part of the pattern, like in the
+    /// arguments of a closure.
+    pub(crate) fn parse_without_or(p: &mut Parser<'_>) -> Result<Self, ParseError> {
         let attributes = p.parse::<Vec<ast::Attribute>>()?;
 
         match p.nth(0)? {
             K![byte] => {
-                return Ok(Self::PatLit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Byte(p.parse()?))),
-                }));
+                let expr = ast::Expr::from_lit(ast::Lit::Byte(p.parse()?));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![char] => {
-                return Ok(Self::PatLit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Char(p.parse()?))),
-                }));
+                let expr = ast::Expr::from_lit(ast::Lit::Char(p.parse()?));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![bytestr] => {
                 return Ok(Self::PatLit(PatLit {
@@ -86,10 +122,8 @@ impl Parse for Pat {
                 });
             }
             K![number] => {
-                return Ok(Self::PatLit(PatLit {
-                    attributes,
-                    expr: Box::new(ast::Expr::from_lit(ast::Lit::Number(p.parse()?))),
-                }));
+                let expr = ast::Expr::from_lit(ast::Lit::Number(p.parse()?));
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![..] => {
                 return Ok(Self::PatRest(PatRest {
@@ -121,15 +155,9 @@ impl Parse for Pat {
                     items: p.parse()?,
                 }))
             }
-            K![-] => {
-                let expr: ast::Expr = p.parse()?;
-
-                if expr.is_lit() {
-                    return Ok(Self::PatLit(PatLit {
-                        attributes,
-                        expr: Box::new(expr),
-                    }));
-                }
+            K![-] if matches!(p.nth(1)?, K![number]) => {
+                let expr = Self::parse_neg_number(p)?;
+                return Self::parse_lit_or_range(p, attributes, expr);
             }
             K![_] => {
                 return Ok(Self::PatIgnore(PatIgnore {
@@ -157,6 +185,17 @@ impl Parse for Pat {
                         colon: p.parse()?,
                         pat: p.parse()?,
                     }),
+                    K![@] => match path.try_as_ident() {
+                        Some(ident) => Self::PatAt(PatAt {
+                            attributes,
+                            ident: *ident,
+                            at: p.parse()?,
+                            pat: Box::new(Self::parse_without_or(p)?),
+                        }),
+                        None => {
+                            return Err(ParseError::expected(&path, "identifier"));
+                        }
+                    },
                     _ => Self::PatPath(PatPath { attributes, path }),
                 });
             }
@@ -165,6 +204,50 @@ impl Parse for Pat {
 
         Err(ParseError::expected(p.tok_at(0)?, "pattern"))
     }
+
+    /// Parse a literal pattern, or a range pattern if the literal is followed
+    /// by range limits.
+    fn parse_lit_or_range(
+        p: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+        start: ast::Expr,
+    ) -> Result<Self, ParseError> {
+        if !matches!(p.nth(0)?, K![..] | K![..=]) {
+            return Ok(Self::PatLit(PatLit {
+                attributes,
+                expr: Box::new(start),
+            }));
+        }
+
+        let limits = p.parse::<ast::ExprRangeLimits>()?;
+
+        let end = match p.nth(0)? {
+            K![byte] => Some(ast::Expr::from_lit(ast::Lit::Byte(p.parse()?))),
+            K![char] => Some(ast::Expr::from_lit(ast::Lit::Char(p.parse()?))),
+            K![number] => Some(ast::Expr::from_lit(ast::Lit::Number(p.parse()?))),
+            K![-] if matches!(p.nth(1)?, K![number]) => Some(Self::parse_neg_number(p)?),
+            _ if matches!(limits, ast::ExprRangeLimits::Closed(..)) => {
+                return Err(ParseError::expected(p.tok_at(0)?, "end of range pattern"));
+            }
+            _ => None,
+        };
+
+        Ok(Self::PatRange(PatRange {
+            attributes,
+            start: Box::new(start),
+            limits,
+            end: end.map(Box::new),
+        }))
+    }
+
+    /// Parse a negative number literal `-<number>`.
+    fn parse_neg_number(p: &mut Parser<'_>) -> Result<ast::Expr, ParseError> {
+        Ok(ast::Expr::Unary(ast::ExprUnary {
+            attributes: Vec::new(),
+            op: ast::UnOp::Neg(p.parse()?),
+            expr: Box::new(ast::Expr::from_lit(ast::Lit::Number(p.parse()?))),
+        }))
+    }
 }
 
 impl Peek for Pat {
@@ -183,6 +266,70 @@ impl Peek for Pat {
     }
 }
 
+/// An or-pattern `a | b`, which matches if any of its alternatives match.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{ast, testing};
+///
+/// let pat = testing::roundtrip::<ast::Pat>("1 | 2 | (3, _)");
+///
+/// if let ast::Pat::PatOr(pat) = pat {
+///     assert_eq!(pat.rest.len(), 2);
+/// } else {
+///     panic!("expected or-pattern");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatOr {
+    /// The first alternative.
+    pub first: Box<ast::Pat>,
+    /// The remaining alternatives, each preceded by a `|`.
+    #[rune(iter)]
+    pub rest: Vec<(T![|], ast::Pat)>,
+}
+
+impl PatOr {
+    /// Iterate over all alternatives of the pattern.
+    pub fn alternatives(&self) -> impl Iterator<Item = &ast::Pat> + '_ {
+        std::iter::once(&*self.first).chain(self.rest.iter().map(|(_, pat)| pat))
+    }
+}
+
+/// A range pattern `a..b`, `a..=b` or `a..`.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatRange {
+    /// Attributes associated with the pattern.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The start of the range. This is represented as an expression.
+    pub start: Box<ast::Expr>,
+    /// The range limits.
+    pub limits: ast::ExprRangeLimits,
+    /// The end of the range, if it's bounded.
+    #[rune(iter)]
+    pub end: Option<Box<ast::Expr>>,
+}
+
+/// A binding pattern `name @ pattern`, which binds the matched value to
+/// `name` if `pattern` matches.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct PatAt {
+    /// Attributes associated with the pattern.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The name being bound.
+    pub ident: ast::Ident,
+    /// The `@` token.
+    pub at: T![@],
+    /// The pattern the value has to match.
+    pub pat: Box<ast::Pat>,
+}
+
 /// A literal pattern.
 #[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
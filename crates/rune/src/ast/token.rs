@@ -166,6 +166,8 @@ pub enum Number {
     Float(f64),
     /// An integer literal number.
     Integer(num::BigInt),
+    /// An integer literal number with a type suffix, like `10u64`.
+    Suffixed(num::BigInt, NumberSuffix),
 }
 
 impl Number {
@@ -203,7 +205,9 @@ impl Number {
         use std::ops::Neg;
 
         let number = match self {
-            Number::Float(_) => return Err(ParseError::new(span, ParseErrorKind::BadNumber)),
+            Number::Float(_) | Number::Suffixed(..) => {
+                return Err(ParseError::new(span, ParseErrorKind::BadNumber))
+            }
             Number::Integer(n) => {
                 if neg {
                     to(&n.clone().neg())
@@ -258,6 +262,62 @@ impl fmt::Display for Number {
         match self {
             Self::Float(n) => write!(f, "{}", n),
             Self::Integer(n) => write!(f, "{}", n),
+            Self::Suffixed(n, suffix) => write!(f, "{}{}", n, suffix),
+        }
+    }
+}
+
+/// The type suffix of an integer literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum NumberSuffix {
+    /// An unsigned 64-bit integer, like `10u64`.
+    U64,
+    /// A signed 128-bit integer, like `10i128`.
+    I128,
+    /// An arbitrary precision integer, like `10n`.
+    BigInt,
+}
+
+impl NumberSuffix {
+    /// Parse a number suffix, like `u64`.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "u64" => Some(Self::U64),
+            "i128" => Some(Self::I128),
+            "n" => Some(Self::BigInt),
+            _ => None,
+        }
+    }
+
+    /// The name of the type in `std::num` which the suffix constructs.
+    pub(crate) fn type_name(self) -> &'static str {
+        match self {
+            Self::U64 => "U64",
+            Self::I128 => "I128",
+            Self::BigInt => "BigInt",
+        }
+    }
+
+    /// Convert the given number into the suffixed type, returning its little
+    /// endian bytes or `None` if it doesn't fit.
+    pub(crate) fn to_le_bytes(self, number: &num::BigInt) -> Option<Vec<u8>> {
+        use num::ToPrimitive;
+
+        Some(match self {
+            Self::U64 => number.to_u64()?.to_le_bytes().to_vec(),
+            Self::I128 => number.to_i128()?.to_le_bytes().to_vec(),
+            Self::BigInt => number.to_signed_bytes_le(),
+        })
+    }
+}
+
+impl fmt::Display for NumberSuffix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::U64 => write!(f, "u64"),
+            Self::I128 => write!(f, "i128"),
+            Self::BigInt => write!(f, "n"),
         }
     }
 }
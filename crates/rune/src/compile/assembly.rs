@@ -18,6 +18,20 @@ pub(crate) enum AssemblyInst {
     Raw { raw: Inst },
 }
 
+/// A named variable declared in an assembly.
+#[derive(Debug, Clone)]
+pub(crate) struct AssemblyVar {
+    /// The name of the variable.
+    pub(crate) name: Box<str>,
+    /// The stack offset of the variable relative to its call frame.
+    pub(crate) offset: usize,
+    /// Where the variable was declared.
+    pub(crate) span: Span,
+    /// The span of the scope the variable is visible in, or `None` if it's
+    /// visible in the whole function.
+    pub(crate) scope: Option<Span>,
+}
+
 /// Helper structure to build instructions and maintain certain invariants.
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Assembly {
@@ -35,6 +49,8 @@ pub(crate) struct Assembly {
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
     pub(crate) required_functions: HashMap<Hash, Vec<(Span, SourceId)>>,
+    /// Named variables declared in the assembly.
+    pub(crate) variables: Vec<AssemblyVar>,
 }
 
 impl Assembly {
@@ -48,6 +64,7 @@ impl Assembly {
             comments: Default::default(),
             label_count,
             required_functions: Default::default(),
+            variables: Default::default(),
         }
     }
 
@@ -170,6 +170,12 @@ pub enum CompileErrorKind {
     UnsupportedBinding,
     #[error("floating point numbers cannot be used in patterns")]
     MatchFloatInPattern,
+    #[error("range pattern does not match any values")]
+    EmptyPatternRange,
+    #[error("float ranges are not supported in patterns")]
+    FloatRangeInPattern,
+    #[error("variable `{name}` is not bound in all alternatives of the pattern")]
+    OrPatternMissingBinding { name: Box<str> },
     #[error("duplicate key in literal object")]
     DuplicateObjectKey { existing: Span, object: Span },
     #[error("`yield` must be used in function or closure")]
@@ -178,6 +184,10 @@ pub enum CompileErrorKind {
     AwaitOutsideFunction,
     #[error("instance function declared outside of `impl` block")]
     InstanceFunctionOutsideImpl,
+    #[error("missing function `{name}` required by trait `{item}`")]
+    MissingTraitFunction { item: ItemBuf, name: Box<str> },
+    #[error("function `{name}` is not a member of trait `{item}`")]
+    NotTraitFunction { item: ItemBuf, name: Box<str> },
     #[error("import `{item}` (imported in prelude) does not exist")]
     MissingPreludeModule { item: ItemBuf },
     #[error("unsupported tuple index `{number}`")]
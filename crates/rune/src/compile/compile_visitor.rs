@@ -1,6 +1,6 @@
 use crate::ast::Span;
 use crate::compile::{Item, Location, MetaRef};
-use crate::SourceId;
+use crate::{Hash, SourceId};
 
 /// A visitor that will be called for every language item compiled.
 pub trait CompileVisitor {
@@ -13,6 +13,13 @@ pub trait CompileVisitor {
     /// Visit a variable use.
     fn visit_variable_use(&mut self, _source_id: SourceId, _var_span: Span, _span: Span) {}
 
+    /// Visit the type of a variable declared through `let`, which is reported
+    /// when it's evident from the expression the variable is initialized
+    /// with, like a literal or a constructor.
+    ///
+    /// The location points to the name of the variable.
+    fn visit_variable_type(&mut self, _location: Location, _type_hash: Hash) {}
+
     /// Visit something that is a module.
     fn visit_mod(&mut self, _source_id: SourceId, _span: Span) {}
 
@@ -39,6 +46,19 @@ pub trait CompileVisitor {
         _docstr: &str,
     ) {
     }
+
+    /// Visit a field declared in a struct / enum variant struct.
+    ///
+    /// The location points to the name of the field.
+    fn visit_field(&mut self, _location: Location, _item: &Item, _field: &str) {}
+
+    /// Visit a use of a named field, like in `value.field` or `Struct { field
+    /// }`.
+    ///
+    /// The location points to the name of the field. The item is the struct or
+    /// variant the field belongs to if it is statically known, which is the
+    /// case for struct literals and struct patterns.
+    fn visit_field_use(&mut self, _location: Location, _item: Option<&Item>, _field: &str) {}
 }
 
 /// A [CompileVisitor] which does nothing.
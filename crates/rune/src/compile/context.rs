@@ -5,16 +5,16 @@ use thiserror::Error;
 
 use crate::collections::{hash_map, HashMap, HashSet};
 use crate::compile::module::{
-    AssocFn, AssocKey, AssocKind, Function, InternalEnum, Macro, Module, ModuleFn, Type,
-    TypeSpecification, UnitType, VariantKind,
+    AssocFn, AssocKey, AssocKind, Function, InternalEnum, Macro, Module, ModuleFn, ModuleTrait,
+    ModuleTraitFn, TraitImpl, Type, TypeSpecification, UnitType, VariantKind,
 };
 use crate::compile::{
     ComponentRef, ContextMeta, ContextMetaKind, IntoComponent, Item, ItemBuf, Meta, Names,
     PrivStructMeta, PrivTupleMeta, PrivVariantMeta,
 };
 use crate::runtime::{
-    ConstValue, FunctionHandler, MacroHandler, Protocol, RuntimeContext, StaticType, TypeCheck,
-    TypeInfo, TypeOf, VariantRtti, VmError,
+    ConstValue, DeserializeHandler, FunctionHandler, KeyHandler, MacroHandler, NumberHandler,
+    Protocol, RuntimeContext, StaticType, TypeCheck, TypeInfo, TypeOf, VariantRtti, VmError,
 };
 use crate::{Hash, InstFnKind};
 
@@ -38,6 +38,18 @@ pub enum ContextError {
     ConflictingFunctionName { name: ItemBuf },
     #[error("constant with name `{name}` already exists")]
     ConflictingConstantName { name: ItemBuf },
+    #[error("trait with name `{name}` already exists")]
+    ConflictingTraitName { name: ItemBuf },
+    #[error("trait `{item}` does not exist")]
+    MissingTrait { item: ItemBuf },
+    #[error("trait `{item}` does not declare function `{name}`")]
+    NotTraitFunction { item: ItemBuf, name: Box<str> },
+    #[error("type `{type_info}` is missing function `{name}` required by trait `{item}`")]
+    MissingTraitFunction {
+        item: ItemBuf,
+        type_info: TypeInfo,
+        name: Box<str>,
+    },
     #[error("instance function `{name}` for type `{type_info}` already exists")]
     ConflictingInstanceFunction { type_info: TypeInfo, name: Box<str> },
     #[error("protocol function `{name}` for type `{type_info}` already exists")]
@@ -54,6 +66,11 @@ pub enum ContextError {
     MissingType { item: ItemBuf, type_info: TypeInfo },
     #[error("type `{item}` with info `{type_info}` is registered but is not an enum")]
     MissingEnum { item: ItemBuf, type_info: TypeInfo },
+    #[error("type `{type_info}` is deserialized from the tag `{tag}`, which is already in use")]
+    ConflictingDeserializeTag {
+        tag: &'static str,
+        type_info: TypeInfo,
+    },
     #[error("tried to insert conflicting hash `{hash}` for `{existing}`")]
     ConflictingTypeHash { hash: Hash, existing: Hash },
     #[error("variant with `{item}` already exists")]
@@ -199,6 +216,12 @@ pub struct Context {
     crates: HashSet<Box<str>>,
     /// Constants visible in this context
     constants: HashMap<Hash, ConstValue>,
+    /// Handlers for native types which can be used as keys.
+    keys: HashMap<Hash, Arc<KeyHandler>>,
+    /// Handlers for native number types.
+    numbers: HashMap<Hash, Arc<NumberHandler>>,
+    /// Handlers for native types which are deserialized from tagged maps.
+    deserializers: HashMap<Box<str>, Arc<DeserializeHandler>>,
 }
 
 impl Context {
@@ -215,7 +238,10 @@ impl Context {
     /// `false` all the corresponding low-level I/O functions have to be
     /// provided through a different module.
     ///
-    /// These are:
+    /// The `std::decimal` module is only included if the `decimal` feature is
+    /// enabled.
+    ///
+    /// The low-level I/O functions are:
     ///
     /// * `::std::io::dbg`
     /// * `::std::io::print`
@@ -225,9 +251,12 @@ impl Context {
         this.install(&crate::modules::any::module()?)?;
         this.install(&crate::modules::bytes::module()?)?;
         this.install(&crate::modules::char::module()?)?;
+        this.install(&crate::modules::clone::module()?)?;
         this.install(&crate::modules::cmp::module()?)?;
         this.install(&crate::modules::collections::module()?)?;
         this.install(&crate::modules::core::module()?)?;
+        #[cfg(feature = "decimal")]
+        this.install(&crate::modules::decimal::module()?)?;
         this.install(&crate::modules::float::module()?)?;
         this.install(&crate::modules::fmt::module()?)?;
         this.install(&crate::modules::future::module()?)?;
@@ -236,6 +265,7 @@ impl Context {
         this.install(&crate::modules::io::module(stdio)?)?;
         this.install(&crate::modules::iter::module()?)?;
         this.install(&crate::modules::mem::module()?)?;
+        this.install(&crate::modules::num::module()?)?;
         this.install(&crate::modules::object::module()?)?;
         this.install(&crate::modules::ops::module()?)?;
         this.install(&crate::modules::option::module()?)?;
@@ -271,7 +301,13 @@ impl Context {
     /// # Ok(()) }
     /// ```
     pub fn runtime(&self) -> RuntimeContext {
-        RuntimeContext::new(self.functions.clone(), self.constants.clone())
+        RuntimeContext::new(
+            self.functions.clone(),
+            self.constants.clone(),
+            self.keys.clone(),
+            self.numbers.clone(),
+            self.deserializers.clone(),
+        )
     }
 
     /// Install the specified module.
@@ -312,6 +348,14 @@ impl Context {
             self.install_associated_function(key, inst)?;
         }
 
+        for (name, t) in &module.traits {
+            self.install_trait(module, name, t)?;
+        }
+
+        for trait_impl in &module.trait_impls {
+            self.install_trait_impl(trait_impl)?;
+        }
+
         Ok(())
     }
 
@@ -335,6 +379,31 @@ impl Context {
         })
     }
 
+    /// Iterate over the items directly beneath the given path, as it would be
+    /// written in a script. So `["std", "io"]` lists the contents of the
+    /// `std::io` module, and an empty path lists the crates in the context.
+    ///
+    /// This is intended for tooling like completions in an editor.
+    pub fn iter_path_children(&self, path: &[&str]) -> impl Iterator<Item = ItemBuf> + '_ {
+        let item = match path.split_first() {
+            Some((first, rest)) if self.contains_crate(first) => {
+                ItemBuf::with_crate_item(first, rest)
+            }
+            _ => ItemBuf::with_item(path),
+        };
+
+        let children = self
+            .iter_components(&item)
+            .filter_map(|c| match c {
+                ComponentRef::Crate(name) => Some(ItemBuf::with_crate(name)),
+                ComponentRef::Str(name) => Some(item.extended(name)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        children.into_iter()
+    }
+
     /// Iterate over known child components of the given name.
     pub(crate) fn iter_components<'a, I: 'a>(
         &'a self,
@@ -413,6 +482,26 @@ impl Context {
         let item = module.item.extended(&*ty.name);
         let hash = Hash::type_hash(&item);
 
+        if let Some(key) = &ty.key {
+            self.keys.insert(type_hash, key.clone());
+        }
+
+        if let Some(number) = &ty.number {
+            self.numbers.insert(type_hash, number.clone());
+        }
+
+        if let Some((tag, deserialize)) = &ty.deserialize {
+            if self.deserializers.contains_key(*tag) {
+                return Err(ContextError::ConflictingDeserializeTag {
+                    tag,
+                    type_info: ty.type_info.clone(),
+                });
+            }
+
+            self.deserializers
+                .insert((*tag).into(), deserialize.clone());
+        }
+
         self.install_type_info(
             hash,
             ContextTypeInfo {
@@ -613,6 +702,98 @@ impl Context {
         Ok(())
     }
 
+    /// Install a trait.
+    fn install_trait(
+        &mut self,
+        module: &Module,
+        item: &Item,
+        t: &ModuleTrait,
+    ) -> Result<(), ContextError> {
+        let item = module.item.join(item);
+
+        self.names.insert(&item);
+
+        self.install_meta(ContextMeta {
+            item,
+            kind: ContextMetaKind::Trait {
+                functions: t.functions.iter().cloned().collect(),
+            },
+        })?;
+
+        Ok(())
+    }
+
+    /// Check that a native type provides all functions required by a trait,
+    /// and install the default implementation of the ones it doesn't provide.
+    fn install_trait_impl(&mut self, trait_impl: &TraitImpl) -> Result<(), ContextError> {
+        let functions = match self.meta.get(&trait_impl.trait_item) {
+            Some(ContextMeta {
+                kind: ContextMetaKind::Trait { functions },
+                ..
+            }) => functions.clone(),
+            _ => {
+                return Err(ContextError::MissingTrait {
+                    item: trait_impl.trait_item.clone(),
+                });
+            }
+        };
+
+        for ModuleTraitFn { name, default } in functions.iter() {
+            let hash = Hash::instance_function(trait_impl.type_hash, name.as_ref());
+
+            if self.functions.contains_key(&hash) {
+                continue;
+            }
+
+            let handler = match default {
+                Some(..) => self
+                    .functions
+                    .get(&Hash::type_hash(
+                        &trait_impl.trait_item.extended(name.as_ref()),
+                    ))
+                    .cloned(),
+                None => None,
+            };
+
+            let handler = match handler {
+                Some(handler) => handler,
+                None => {
+                    return Err(ContextError::MissingTraitFunction {
+                        item: trait_impl.trait_item.clone(),
+                        type_info: trait_impl.type_info.clone(),
+                        name: name.clone(),
+                    });
+                }
+            };
+
+            let item = match self
+                .types_rev
+                .get(&trait_impl.type_hash)
+                .and_then(|hash| self.types.get(hash))
+            {
+                Some(info) => info.item.clone(),
+                None => {
+                    return Err(ContextError::MissingInstance {
+                        instance_type: trait_impl.type_info.clone(),
+                    });
+                }
+            };
+
+            let signature = ContextSignature::Instance {
+                type_hash: trait_impl.type_hash,
+                item,
+                name: InstFnKind::Instance(name.clone()),
+                args: *default,
+                self_type_info: trait_impl.type_info.clone(),
+            };
+
+            self.functions_info.insert(hash, signature);
+            self.functions.insert(hash, handler);
+        }
+
+        Ok(())
+    }
+
     fn install_associated_function(
         &mut self,
         key: &AssocKey,
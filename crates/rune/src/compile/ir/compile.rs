@@ -1,5 +1,5 @@
 use crate::ast::{self, Span, Spanned};
-use crate::compile::ir::{self, IrError, IrValue};
+use crate::compile::ir::{self, IrError, IrErrorKind, IrValue};
 use crate::hir;
 use crate::parse::Resolve;
 use crate::query::Query;
@@ -105,6 +105,12 @@ pub(crate) fn expr(hir: &hir::Expr<'_>, c: &mut IrCompiler<'_>) -> Result<ir::Ir
                 let const_value = match n {
                     ast::Number::Integer(n) => IrValue::Integer(n),
                     ast::Number::Float(n) => IrValue::Float(n),
+                    ast::Number::Suffixed(_, suffix) => {
+                        return Err(IrError::new(
+                            line.span,
+                            IrErrorKind::ConstSuffixedNumber { suffix },
+                        ))
+                    }
                 };
 
                 ir::Ir::new(line.span, const_value)
@@ -237,6 +243,12 @@ fn lit(hir: &ast::Lit, c: &mut IrCompiler<'_>) -> Result<ir::Ir, IrError> {
             let const_value = match n {
                 ast::Number::Integer(n) => IrValue::Integer(n),
                 ast::Number::Float(n) => IrValue::Float(n),
+                ast::Number::Suffixed(_, suffix) => {
+                    return Err(IrError::new(
+                        span,
+                        IrErrorKind::ConstSuffixedNumber { suffix },
+                    ))
+                }
             };
 
             ir::Ir::new(span, const_value)
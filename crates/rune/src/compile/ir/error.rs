@@ -1,4 +1,4 @@
-use crate::ast::{Spanned, SpannedError};
+use crate::ast::{NumberSuffix, Spanned, SpannedError};
 use crate::compile::{IrValue, Meta};
 use crate::hir::{HirError, HirErrorKind};
 use crate::parse::{ResolveError, ResolveErrorKind};
@@ -100,6 +100,13 @@ pub enum IrErrorKind {
     /// expression.
     #[error("expected a constant expression")]
     NotConst,
+    /// Encountered a number literal with a type suffix, like `10u64`, which
+    /// constant expressions don't support.
+    #[error("number literals with a type suffix like `{suffix}` are not supported in constant expressions")]
+    ConstSuffixedNumber {
+        /// The suffix of the number literal.
+        suffix: NumberSuffix,
+    },
     /// Trying to process a cycle of constants.
     #[error("constant cycle detected")]
     ConstCycle,
@@ -7,9 +7,10 @@ use std::sync::Arc;
 use crate::ast::{LitStr, Span};
 use crate::collections::HashSet;
 use crate::compile::attrs::Attributes;
+use crate::compile::module::ModuleTraitFn;
 use crate::compile::{Item, ItemBuf, ItemId, Location, ModId, Pool, Visibility};
 use crate::parse::{Id, ParseError, ResolveContext};
-use crate::query::ImportEntry;
+use crate::query::{Function, ImportEntry};
 use crate::runtime::ConstValue;
 use crate::Hash;
 
@@ -77,6 +78,8 @@ pub enum MetaKind {
     Import,
     /// Item describes a module.
     Module,
+    /// Item describes a trait.
+    Trait,
 }
 
 impl fmt::Display for Meta {
@@ -127,6 +130,9 @@ impl fmt::Display for Meta {
             MetaKind::Module => {
                 write!(fmt, "module {}", self.item)?;
             }
+            MetaKind::Trait => {
+                write!(fmt, "trait {}", self.item)?;
+            }
         }
 
         Ok(())
@@ -236,6 +242,12 @@ pub(crate) enum ContextMetaKind {
         /// The evaluated constant value.
         const_value: ConstValue,
     },
+    /// A trait declared by a native module.
+    Trait {
+        /// The functions implementors have to provide, unless they have a
+        /// default implementation.
+        functions: Arc<[ModuleTraitFn]>,
+    },
 }
 
 impl ContextMetaKind {
@@ -274,6 +286,7 @@ impl ContextMetaKind {
                 is_test: false,
             },
             ContextMetaKind::Const { .. } => MetaKind::Const,
+            ContextMetaKind::Trait { .. } => MetaKind::Trait,
         }
     }
 }
@@ -326,6 +339,7 @@ impl PrivMeta {
             PrivMetaKind::ConstFn { .. } => None,
             PrivMetaKind::Import { .. } => None,
             PrivMetaKind::Module => None,
+            PrivMetaKind::Trait { .. } => None,
         }
     }
 }
@@ -415,6 +429,11 @@ pub(crate) enum PrivMetaKind {
     },
     /// A module.
     Module,
+    /// A trait.
+    Trait {
+        /// The functions declared in the trait.
+        functions: Arc<[PrivTraitFn]>,
+    },
 }
 
 impl PrivMetaKind {
@@ -463,10 +482,40 @@ impl PrivMetaKind {
             PrivMetaKind::ConstFn { .. } => MetaKind::ConstFn,
             PrivMetaKind::Import { .. } => MetaKind::Import,
             PrivMetaKind::Module => MetaKind::Module,
+            PrivMetaKind::Trait { .. } => MetaKind::Trait,
         }
     }
 }
 
+/// A function declared in a trait.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub(crate) struct PrivTraitFn {
+    /// The name of the function.
+    pub(crate) name: Box<str>,
+    /// The default implementation of the function, if one is provided.
+    pub(crate) default: Option<PrivTraitDefault>,
+}
+
+/// The default implementation of a trait function.
+#[derive(Debug, Clone)]
+pub(crate) enum PrivTraitDefault {
+    /// A default implementation declared in a script trait.
+    Script {
+        /// Where the default implementation is declared.
+        location: Location,
+        /// The function to compile for each type which relies on the default.
+        function: Function,
+    },
+    /// A default implementation provided by a native module.
+    Native {
+        /// The hash of the native function implementing the default.
+        hash: Hash,
+        /// The number of arguments the native function takes.
+        args: usize,
+    },
+}
+
 /// The metadata about a struct.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
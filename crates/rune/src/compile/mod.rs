@@ -9,12 +9,13 @@ use crate::hir;
 use crate::macros::Storage;
 use crate::parse::Resolve;
 use crate::query::{Build, BuildEntry, Query};
+use crate::runtime::{Call, Inst, InstAddress};
 use crate::shared::{Consts, Gen};
 use crate::worker::{LoadFileKind, Task, Worker};
 use crate::{Diagnostics, Sources};
 
 mod assembly;
-pub(crate) use self::assembly::{Assembly, AssemblyInst};
+pub(crate) use self::assembly::{Assembly, AssemblyInst, AssemblyVar};
 
 pub(crate) mod attrs;
 
@@ -56,7 +57,7 @@ pub use self::location::Location;
 mod meta;
 pub(crate) use self::meta::{
     CaptureMeta, ContextMeta, ContextMetaKind, Doc, ItemMeta, PrivMeta, PrivMetaKind,
-    PrivStructMeta, PrivTupleMeta, PrivVariantMeta,
+    PrivStructMeta, PrivTraitDefault, PrivTraitFn, PrivTupleMeta, PrivVariantMeta,
 };
 pub use self::meta::{Meta, MetaKind, MetaRef, SourceMeta};
 
@@ -404,6 +405,171 @@ impl CompileBuildEntry<'_> {
                     ));
                 }
             }
+            Build::TraitImpl(t) => {
+                tracing::trace!("trait impl: {}", self.q.pool.item(item_meta.item));
+
+                use self::v1::assemble;
+
+                let span = t.path.span();
+
+                let (type_hash, trait_id, trait_item, functions) = {
+                    let mut c = self.compiler1(location, span, &mut asm);
+                    let meta = c.lookup_meta(location.span, item_meta.item)?;
+
+                    let type_hash = meta.type_hash_of().ok_or_else(|| {
+                        CompileError::expected_meta(
+                            location.span,
+                            meta.info(c.q.pool),
+                            "type to implement trait for",
+                        )
+                    })?;
+
+                    let arena = hir::Arena::new();
+                    let ctx = hir::lowering::Ctx::new(&arena, c.q.borrow());
+                    let path = hir::lowering::path(&ctx, &t.path)?;
+                    let named = c.convert_path(&path)?;
+                    let meta = c.lookup_meta(span, named.item)?;
+
+                    let functions = match &meta.kind {
+                        PrivMetaKind::Trait { functions } => functions.clone(),
+                        _ => {
+                            return Err(CompileError::expected_meta(
+                                span,
+                                meta.info(c.q.pool),
+                                "trait",
+                            ));
+                        }
+                    };
+
+                    let trait_item = c.q.pool.item(named.item).to_owned();
+                    (type_hash, named.item, trait_item, functions)
+                };
+
+                for (name, span) in &t.provided {
+                    if !functions.iter().any(|f| f.name == *name) {
+                        return Err(CompileError::new(
+                            *span,
+                            CompileErrorKind::NotTraitFunction {
+                                item: trait_item,
+                                name: name.clone(),
+                            },
+                        ));
+                    }
+                }
+
+                // Every function which isn't provided by the impl block falls
+                // back to the default implementation in the trait, which is
+                // compiled separately for each implementing type.
+                for f in functions.iter() {
+                    if t.provided.iter().any(|(name, _)| *name == f.name) {
+                        continue;
+                    }
+
+                    let default = match &f.default {
+                        Some(default) => default,
+                        None => {
+                            return Err(CompileError::new(
+                                span,
+                                CompileErrorKind::MissingTraitFunction {
+                                    item: trait_item,
+                                    name: f.name.clone(),
+                                },
+                            ));
+                        }
+                    };
+
+                    let item = self.q.pool.item(item_meta.item).extended(&*f.name);
+
+                    match default {
+                        PrivTraitDefault::Script { location, function } => {
+                            let location = *location;
+                            let ast = &function.ast;
+
+                            let args = format_fn_args(
+                                self.q.sources,
+                                location,
+                                ast.args.iter().map(|(a, _)| a),
+                            )?;
+
+                            let count = ast.args.len();
+
+                            let mut asm = self.q.unit.new_assembly(location);
+
+                            // NB: `Self` in the default implementation refers
+                            // to the type it's being compiled for.
+                            let old = self.q.replace_trait_impl(Some((trait_id, item_meta.item)));
+
+                            let result = (|| {
+                                let mut c = self.compiler1(location, ast.span(), &mut asm);
+                                let arena = hir::Arena::new();
+                                let ctx = hir::lowering::Ctx::new(&arena, c.q.borrow());
+                                let hir = hir::lowering::item_fn(&ctx, ast)?;
+                                assemble::fn_from_item_fn(&hir, &mut c, true)
+                            })();
+
+                            self.q.replace_trait_impl(old);
+                            result?;
+
+                            self.q.unit.new_instance_function(
+                                location,
+                                &item,
+                                type_hash,
+                                &f.name,
+                                count,
+                                asm,
+                                function.call,
+                                args,
+                            )?;
+                        }
+                        PrivTraitDefault::Native { hash, args } => {
+                            // Forward the call to the native default
+                            // implementation.
+                            let mut asm = self.q.unit.new_assembly(location);
+
+                            for offset in 0..*args {
+                                asm.push(Inst::Copy { offset }, span);
+                            }
+
+                            asm.push(
+                                Inst::Call {
+                                    hash: *hash,
+                                    args: *args,
+                                },
+                                span,
+                            );
+
+                            asm.push(
+                                Inst::Return {
+                                    address: InstAddress::Top,
+                                    clean: *args,
+                                },
+                                span,
+                            );
+
+                            let debug_args = (0..*args)
+                                .map(|n| {
+                                    if n == 0 {
+                                        "self".into()
+                                    } else {
+                                        format!("#{}", n).into()
+                                    }
+                                })
+                                .collect();
+
+                            self.q.unit.new_instance_function(
+                                location,
+                                &item,
+                                type_hash,
+                                &f.name,
+                                *args,
+                                asm,
+                                Call::Immediate,
+                                debug_args,
+                            )?;
+                        }
+                    }
+                }
+            }
             Build::ReExport => {
                 tracing::trace!("re-export: {}", self.q.pool.item(item_meta.item));
 
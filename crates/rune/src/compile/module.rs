@@ -7,10 +7,11 @@ use crate::collections::{HashMap, HashSet};
 use crate::compile::{ContextError, IntoComponent, ItemBuf, Named};
 use crate::macros::{MacroContext, TokenStream};
 use crate::runtime::{
-    ConstValue, FromValue, FunctionHandler, Future, GeneratorState, MacroHandler, Protocol, Stack,
-    StaticType, ToValue, TypeCheck, TypeInfo, TypeOf, UnsafeFromValue, Value, VmError, VmErrorKind,
+    ConstValue, DeserializeHandler, FromValue, FunctionHandler, Future, GeneratorState, KeyHandler,
+    MacroHandler, NumberHandler, Protocol, Stack, StaticType, ToValue, TypeCheck, TypeInfo, TypeOf,
+    UnsafeFromValue, Value, VmError, VmErrorKind,
 };
-use crate::{Hash, InstFnInfo, InstFnKind, InstFnName};
+use crate::{Any, Hash, InstFnInfo, InstFnKind, InstFnName};
 use std::fmt;
 use std::future;
 use std::sync::Arc;
@@ -106,6 +107,15 @@ pub(crate) struct Type {
     pub(crate) type_info: TypeInfo,
     /// The specification for the type.
     pub(crate) spec: Option<TypeSpecification>,
+    /// Handler which copies values of the type into keys, if they can be used
+    /// as keys.
+    pub(crate) key: Option<Arc<KeyHandler>>,
+    /// Handler for operations where an `int` is on the left-hand side and a
+    /// value of the type is on the right-hand side, if it's a number.
+    pub(crate) number: Option<Arc<NumberHandler>>,
+    /// The tag and handler which deserializes values of the type from tagged
+    /// maps, if they round-trip through serde.
+    pub(crate) deserialize: Option<(&'static str, Arc<DeserializeHandler>)>,
 }
 
 /// Metadata about a variant.
@@ -226,6 +236,25 @@ pub(crate) struct AssocKey {
     pub(crate) kind: AssocKind,
 }
 
+pub(crate) struct ModuleTrait {
+    pub(crate) functions: Vec<ModuleTraitFn>,
+}
+
+/// A function declared by a native trait.
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleTraitFn {
+    pub(crate) name: Box<str>,
+    /// The number of arguments taken by the default implementation, if there
+    /// is one. It's registered as the free function `<trait>::<name>`.
+    pub(crate) default: Option<usize>,
+}
+
+pub(crate) struct TraitImpl {
+    pub(crate) type_hash: Hash,
+    pub(crate) type_info: TypeInfo,
+    pub(crate) trait_item: ItemBuf,
+}
+
 pub(crate) struct ModuleFn {
     pub(crate) handler: Arc<FunctionHandler>,
     pub(crate) args: Option<usize>,
@@ -257,6 +286,10 @@ pub struct Module {
     pub(crate) unit_type: Option<UnitType>,
     /// Registered generator state type.
     pub(crate) internal_enums: Vec<InternalEnum>,
+    /// Declared traits.
+    pub(crate) traits: HashMap<ItemBuf, ModuleTrait>,
+    /// Traits implemented by native types.
+    pub(crate) trait_impls: Vec<TraitImpl>,
 }
 
 impl Module {
@@ -298,6 +331,8 @@ impl Module {
             unit_type: None,
             internal_enums: Vec::new(),
             constants: Default::default(),
+            traits: Default::default(),
+            trait_impls: Vec::new(),
         }
     }
 
@@ -353,6 +388,9 @@ impl Module {
             name: T::full_name(),
             type_info,
             spec: None,
+            key: None,
+            number: None,
+            deserialize: None,
         };
 
         if let Some(old) = self.types.insert(type_hash, ty) {
@@ -366,6 +404,108 @@ impl Module {
         Ok(())
     }
 
+    /// Register that values of the given type can be used as keys, like in a
+    /// `HashMap`.
+    ///
+    /// Keys hold on to their own copy of the value, so that it can't be changed
+    /// behind the back of a collection. They're hashed through the
+    /// [Protocol::HASH] protocol and compared through [Protocol::EQ] and
+    /// [Protocol::CMP], which need to be registered separately.
+    ///
+    /// This is typically not used directly, but is used automatically with the
+    /// [Any][crate::Any] derive and `#[rune(hash)]`.
+    pub fn key<T>(&mut self) -> Result<(), ContextError>
+    where
+        T: Named + TypeOf + Any + Clone + Send + Sync,
+    {
+        let type_hash = <T as TypeOf>::type_hash();
+
+        let ty = match self.types.get_mut(&type_hash) {
+            Some(ty) => ty,
+            None => {
+                return Err(ContextError::MissingType {
+                    item: ItemBuf::with_item(&[T::full_name()]),
+                    type_info: T::type_info(),
+                });
+            }
+        };
+
+        let key: Arc<KeyHandler> = Arc::new(|value: &Value| {
+            let value = value.clone().into_any()?;
+            let value = value.downcast_borrow_ref::<T>()?;
+            Ok(Arc::new(value.clone()))
+        });
+
+        ty.key = Some(key);
+        Ok(())
+    }
+
+    /// Register that the given type is a number, which can be on the
+    /// right-hand side of an operation where an `int` is on the left-hand side,
+    /// like `2 * 1.5d`.
+    ///
+    /// Only one native function can be registered for each protocol of `int`,
+    /// so the ones in the `std::int` module dispatch to the given function when
+    /// the right-hand side is of the given type. The function is called with
+    /// the protocol of the operation, the `int` and the right-hand side.
+    pub fn number<T>(
+        &mut self,
+        integer_lhs: fn(Protocol, i64, &Value) -> Result<Value, VmError>,
+    ) -> Result<(), ContextError>
+    where
+        T: Named + TypeOf,
+    {
+        let type_hash = <T as TypeOf>::type_hash();
+
+        let ty = match self.types.get_mut(&type_hash) {
+            Some(ty) => ty,
+            None => {
+                return Err(ContextError::MissingType {
+                    item: ItemBuf::with_item(&[T::full_name()]),
+                    type_info: T::type_info(),
+                });
+            }
+        };
+
+        ty.number = Some(Arc::new(integer_lhs));
+        Ok(())
+    }
+
+    /// Register that values of the given type round-trip through serde as a
+    /// map with a single entry keyed by the given tag, like
+    /// `{"$decimal": "1.5"}`.
+    ///
+    /// Values are serialized into such a map through the [Protocol::SERIALIZE]
+    /// protocol, which needs to be registered separately. When a map like that
+    /// is deserialized inside of a virtual machine, the value of its entry is
+    /// passed to `deserialize` instead.
+    pub fn deserialize_tagged<T>(
+        &mut self,
+        tag: &'static str,
+        deserialize: fn(Value) -> Result<T, VmError>,
+    ) -> Result<(), ContextError>
+    where
+        T: 'static + Named + TypeOf + ToValue,
+    {
+        let type_hash = <T as TypeOf>::type_hash();
+
+        let ty = match self.types.get_mut(&type_hash) {
+            Some(ty) => ty,
+            None => {
+                return Err(ContextError::MissingType {
+                    item: ItemBuf::with_item(&[T::full_name()]),
+                    type_info: T::type_info(),
+                });
+            }
+        };
+
+        let handler: Arc<DeserializeHandler> =
+            Arc::new(move |value: Value| deserialize(value)?.to_value());
+
+        ty.deserialize = Some((tag, handler));
+        Ok(())
+    }
+
     /// Register that the given type is a struct, and that it has the given
     /// compile-time metadata. This implies that each field has a
     /// [Protocol::GET] field function.
@@ -718,6 +858,158 @@ impl Module {
         Ok(())
     }
 
+    /// Declare a trait which both native and script types can implement.
+    ///
+    /// Implementors have to provide an instance function for each of the
+    /// given function names. Scripts implement the trait with an
+    /// `impl Trait for Type` block, which is checked when it's compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::compile::ItemBuf;
+    /// use rune::Any;
+    ///
+    /// #[derive(Any)]
+    /// struct Request;
+    ///
+    /// impl Request {
+    ///     fn handle(&self) -> i64 {
+    ///         42
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = rune::Module::with_crate("http");
+    ///
+    /// module.trait_(&["Handler"], ["handle"])?;
+    /// module.ty::<Request>()?;
+    /// module.inst_fn("handle", Request::handle)?;
+    /// module.impl_trait::<Request>(ItemBuf::with_crate_item("http", &["Handler"]))?;
+    ///
+    /// let mut context = rune::Context::new();
+    /// context.install(&module)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn trait_<N, const M: usize>(
+        &mut self,
+        name: N,
+        functions: [&'static str; M],
+    ) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: IntoComponent,
+    {
+        let name = ItemBuf::with_item(name);
+
+        if self.traits.contains_key(&name) {
+            return Err(ContextError::ConflictingTraitName { name });
+        }
+
+        let functions = functions
+            .iter()
+            .map(|&name| ModuleTraitFn {
+                name: name.into(),
+                default: None,
+            })
+            .collect();
+
+        self.traits.insert(name, ModuleTrait { functions });
+        Ok(())
+    }
+
+    /// Provide a default implementation for a function declared by a trait in
+    /// this module with [Module::trait_].
+    ///
+    /// The default takes the implementor as its first argument and is used by
+    /// every native or script type implementing the trait which doesn't
+    /// provide the function itself. It's also available as the free function
+    /// `<trait>::<name>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::compile::ItemBuf;
+    /// use rune::runtime::Value;
+    /// use rune::Any;
+    ///
+    /// #[derive(Any)]
+    /// struct Request;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = rune::Module::with_crate("http");
+    ///
+    /// module.trait_(&["Handler"], ["handle"])?;
+    /// module.trait_default(&["Handler"], "handle", |_: Value| 404)?;
+    /// module.ty::<Request>()?;
+    /// module.impl_trait::<Request>(ItemBuf::with_crate_item("http", &["Handler"]))?;
+    ///
+    /// let mut context = rune::Context::new();
+    /// context.install(&module)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn trait_default<N, Func, Args>(
+        &mut self,
+        trait_name: N,
+        name: &'static str,
+        f: Func,
+    ) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: IntoComponent,
+        Func: Function<Args>,
+    {
+        let trait_name = ItemBuf::with_item(trait_name);
+
+        let t = match self.traits.get(&trait_name) {
+            Some(t) => t,
+            None => {
+                return Err(ContextError::MissingTrait {
+                    item: self.item.join(&trait_name),
+                });
+            }
+        };
+
+        let index = match t.functions.iter().position(|f| *f.name == *name) {
+            Some(index) => index,
+            None => {
+                return Err(ContextError::NotTraitFunction {
+                    item: self.item.join(&trait_name),
+                    name: name.into(),
+                });
+            }
+        };
+
+        self.function(trait_name.extended(name), f)?;
+
+        if let Some(t) = self.traits.get_mut(&trait_name) {
+            t.functions[index].default = Some(Func::args());
+        }
+
+        Ok(())
+    }
+
+    /// Register that the native type `T` implements the trait with the given
+    /// item.
+    ///
+    /// The trait is checked when the module is installed into a
+    /// [Context][crate::Context], so it has to be declared either in this
+    /// module or in a module which has already been installed. Installation
+    /// fails if `T` is missing any of the instance functions the trait
+    /// requires.
+    pub fn impl_trait<T>(&mut self, trait_item: ItemBuf) -> Result<(), ContextError>
+    where
+        T: TypeOf,
+    {
+        self.trait_impls.push(TraitImpl {
+            type_hash: T::type_hash(),
+            type_info: T::type_info(),
+            trait_item,
+        });
+
+        Ok(())
+    }
+
     /// Register a native macro handler.
     pub fn macro_<N, M>(&mut self, name: N, f: M) -> Result<(), ContextError>
     where
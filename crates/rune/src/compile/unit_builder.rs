@@ -10,7 +10,7 @@ use crate::compile::{
     PrivMeta, PrivMetaKind, PrivVariantMeta,
 };
 use crate::query::{QueryError, QueryErrorKind};
-use crate::runtime::debug::{DebugArgs, DebugSignature};
+use crate::runtime::debug::{DebugArgs, DebugSignature, DebugVariable};
 use crate::runtime::{
     Call, ConstValue, DebugInfo, DebugInst, Inst, Label, Protocol, Rtti, StaticString, Unit,
     UnitFn, VariantRtti,
@@ -524,6 +524,7 @@ impl UnitBuilder {
             PrivMetaKind::ConstFn { .. } => (),
             PrivMetaKind::Import { .. } => (),
             PrivMetaKind::Module { .. } => (),
+            PrivMetaKind::Trait { .. } => (),
         }
 
         Ok(())
@@ -630,6 +631,15 @@ impl UnitBuilder {
             ));
         }
 
+        // NB: script types implement the iteration protocols through instance
+        // functions with the same name.
+        for protocol in [Protocol::INTO_ITER, Protocol::NEXT] {
+            if name == protocol.name {
+                self.functions
+                    .insert(Hash::instance_function(type_hash, protocol), info);
+            }
+        }
+
         self.constants.insert(
             Hash::instance_function(hash, Protocol::INTO_TYPE_NAME),
             ConstValue::String(signature.path.to_string()),
@@ -672,6 +682,8 @@ impl UnitBuilder {
 
         self.required_functions.extend(assembly.required_functions);
 
+        let start = self.instructions.len();
+
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = None::<Box<str>>;
             let label = assembly.labels_rev.get(&pos).copied();
@@ -739,6 +751,20 @@ impl UnitBuilder {
             ));
         }
 
+        let instructions = start..self.instructions.len();
+        let debug = self.debug.get_or_insert_with(Default::default);
+
+        for var in assembly.variables {
+            debug.variables.push(DebugVariable {
+                name: var.name,
+                offset: var.offset,
+                source_id: location.source_id,
+                span: var.span,
+                scope: var.scope,
+                instructions: instructions.clone(),
+            });
+        }
+
         return Ok(());
 
         fn translate_offset(
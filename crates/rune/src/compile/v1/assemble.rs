@@ -9,8 +9,8 @@ use crate::ast::{Span, Spanned};
 use crate::collections::{HashMap, HashSet};
 use crate::compile::v1::{Assembler, Loop, Needs, Scope, Var};
 use crate::compile::{
-    CaptureMeta, CompileError, CompileErrorKind, CompileResult, Item, PrivMeta, PrivMetaKind,
-    PrivStructMeta, PrivVariantMeta,
+    CaptureMeta, CompileError, CompileErrorKind, CompileResult, Item, ItemBuf, ItemId, Location,
+    PrivMeta, PrivMetaKind, PrivStructMeta, PrivVariantMeta,
 };
 use crate::hash::ParametersBuilder;
 use crate::hir;
@@ -18,7 +18,8 @@ use crate::parse::{Id, ParseErrorKind, Resolve};
 use crate::query::Named;
 use crate::runtime::{
     ConstValue, Inst, InstAddress, InstAssignOp, InstOp, InstRangeLimits, InstTarget, InstValue,
-    InstVariant, Label, PanicReason, Protocol, TypeCheck,
+    InstVariant, Label, PanicReason, Protocol, TypeCheck, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE,
+    CHAR_TYPE, FLOAT_TYPE, INTEGER_TYPE, OBJECT_TYPE, STRING_TYPE, TUPLE_TYPE, VEC_TYPE,
 };
 use crate::Hash;
 
@@ -316,6 +317,26 @@ fn pat(
             pat_object(span, c, hir, false_label, &load)?;
             Ok(true)
         }
+        hir::PatKind::PatOr(hir) => {
+            pat_or(span, c, hir, false_label, &load)?;
+            Ok(true)
+        }
+        hir::PatKind::PatRange(hir) => Ok(pat_range(span, c, hir, false_label, load)?),
+        hir::PatKind::PatAt(hir) => {
+            load(c, Needs::Value)?;
+            let ident = hir.ident.resolve(resolve_context!(c.q))?.to_owned();
+            let offset = c.scopes.decl_var(&ident, span)?;
+
+            let load = move |c: &mut Assembler<'_>, needs: Needs| {
+                if needs.value() {
+                    c.asm.push(Inst::Copy { offset }, span);
+                }
+
+                Ok(())
+            };
+
+            pat(hir.pat, c, false_label, &load)
+        }
         _ => Err(CompileError::new(
             hir,
             CompileErrorKind::UnsupportedPatternExpr,
@@ -402,6 +423,304 @@ fn pat_lit_inst(
     Ok(None)
 }
 
+/// Test if the given expression is a, possibly negated, float literal.
+fn is_float_lit(c: &mut Assembler<'_>, hir: &hir::Expr<'_>) -> CompileResult<bool> {
+    let lit = match hir.kind {
+        hir::ExprKind::Lit(ast::Lit::Number(lit)) => lit,
+        hir::ExprKind::Unary(hir::ExprUnary {
+            op: ast::UnOp::Neg(..),
+            expr:
+                hir::Expr {
+                    kind: hir::ExprKind::Lit(ast::Lit::Number(lit)),
+                    ..
+                },
+            ..
+        }) => lit,
+        _ => return Ok(false),
+    };
+
+    let number = lit.resolve(resolve_context!(c.q))?;
+    Ok(matches!(number, ast::Number::Float(..)))
+}
+
+/// Get the type of the given expression if it's evident from the expression
+/// itself, like for literals and constructors of structs and variants.
+fn static_type(hir: &hir::Expr<'_>, c: &mut Assembler<'_>) -> CompileResult<Option<Hash>> {
+    let type_hash = match &hir.kind {
+        hir::ExprKind::Lit(lit) => match lit {
+            ast::Lit::Bool(..) => BOOL_TYPE.hash,
+            ast::Lit::Byte(..) => BYTE_TYPE.hash,
+            ast::Lit::Str(..) => STRING_TYPE.hash,
+            ast::Lit::ByteStr(..) => BYTES_TYPE.hash,
+            ast::Lit::Char(..) => CHAR_TYPE.hash,
+            ast::Lit::Number(lit) => match lit.resolve(resolve_context!(c.q))? {
+                ast::Number::Float(..) => FLOAT_TYPE.hash,
+                ast::Number::Integer(..) => INTEGER_TYPE.hash,
+                ast::Number::Suffixed(..) => return Ok(None),
+            },
+        },
+        hir::ExprKind::Vec(..) => VEC_TYPE.hash,
+        hir::ExprKind::Tuple(..) => TUPLE_TYPE.hash,
+        hir::ExprKind::Object(hir::ExprObject { path: None, .. }) => OBJECT_TYPE.hash,
+        hir::ExprKind::Object(hir::ExprObject {
+            path: Some(path), ..
+        })
+        | hir::ExprKind::Path(path)
+        | hir::ExprKind::Call(hir::ExprCall {
+            expr:
+                hir::Expr {
+                    kind: hir::ExprKind::Path(path),
+                    ..
+                },
+            ..
+        }) => {
+            let named = c.convert_path(path)?;
+
+            // NB: the meta is looked up without visiting it, since the path
+            // is visited once it's compiled.
+            let meta = match c
+                .q
+                .query_meta(path.span(), named.item, Default::default())?
+            {
+                Some(meta) => meta,
+                None => return Ok(None),
+            };
+
+            match &meta.kind {
+                PrivMetaKind::Struct { type_hash, .. } => *type_hash,
+                PrivMetaKind::Variant { enum_hash, .. } => *enum_hash,
+                _ => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(type_hash))
+}
+
+/// Get the struct that the target of a field access is statically known to
+/// be, which is the case for `self` in the instance functions of a struct.
+fn field_owner(c: &mut Assembler<'_>, target: &hir::Expr<'_>) -> CompileResult<Option<ItemId>> {
+    let item = match target.kind {
+        hir::ExprKind::Path(path) if matches!(path.as_kind(), Some(ast::PathKind::SelfValue)) => {
+            match c.q.path_self_type(path) {
+                Some(item) => item,
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    match c.q.query_meta(target.span(), item, Default::default())? {
+        Some(PrivMeta {
+            kind: PrivMetaKind::Struct { .. },
+            ..
+        }) => Ok(Some(item)),
+        _ => Ok(None),
+    }
+}
+
+/// Assemble a range pattern.
+#[instrument]
+fn pat_range(
+    span: Span,
+    c: &mut Assembler<'_>,
+    hir: &hir::PatRange<'_>,
+    false_label: Label,
+    load: &dyn Fn(&mut Assembler<'_>, Needs) -> CompileResult<()>,
+) -> CompileResult<bool> {
+    let limits = match hir.limits {
+        hir::ExprRangeLimits::HalfOpen => InstRangeLimits::HalfOpen,
+        hir::ExprRangeLimits::Closed => InstRangeLimits::Closed,
+    };
+
+    // NB: floats are rejected up front, since they otherwise fail to resolve
+    // as integers.
+    for expr in std::iter::once(hir.start).chain(hir.end) {
+        if is_float_lit(c, expr)? {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::FloatRangeInPattern,
+            ));
+        }
+    }
+
+    let start = pat_lit_inst(span, c, hir.start)?;
+
+    let end = match hir.end {
+        Some(end) => match pat_lit_inst(span, c, end)? {
+            Some(inst) => Some(inst),
+            None => {
+                return Err(CompileError::new(
+                    end,
+                    CompileErrorKind::UnsupportedPatternExpr,
+                ));
+            }
+        },
+        None => None,
+    };
+
+    // An unbounded range extends to the largest value of its type.
+    let (inst, is_empty) = match (start, end, limits) {
+        (Some(Inst::EqByte { byte: start }), Some(Inst::EqByte { byte: end }), limits) => (
+            Inst::InRangeByte { start, end, limits },
+            !limits.contains(start, end, start),
+        ),
+        (Some(Inst::EqByte { byte: start }), None, _) => (
+            Inst::InRangeByte {
+                start,
+                end: u8::MAX,
+                limits: InstRangeLimits::Closed,
+            },
+            false,
+        ),
+        (Some(Inst::EqChar { char: start }), Some(Inst::EqChar { char: end }), limits) => (
+            Inst::InRangeChar { start, end, limits },
+            !limits.contains(start, end, start),
+        ),
+        (Some(Inst::EqChar { char: start }), None, _) => (
+            Inst::InRangeChar {
+                start,
+                end: char::MAX,
+                limits: InstRangeLimits::Closed,
+            },
+            false,
+        ),
+        (
+            Some(Inst::EqInteger { integer: start }),
+            Some(Inst::EqInteger { integer: end }),
+            limits,
+        ) => (
+            Inst::InRangeInteger { start, end, limits },
+            !limits.contains(start, end, start),
+        ),
+        (Some(Inst::EqInteger { integer: start }), None, _) => (
+            Inst::InRangeInteger {
+                start,
+                end: i64::MAX,
+                limits: InstRangeLimits::Closed,
+            },
+            false,
+        ),
+        _ => {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::UnsupportedPatternExpr,
+            ));
+        }
+    };
+
+    if is_empty {
+        return Err(CompileError::new(span, CompileErrorKind::EmptyPatternRange));
+    }
+
+    load(c, Needs::Value)?;
+    c.asm.push(inst, span);
+    c.asm
+        .pop_and_jump_if_not(c.scopes.local_var_count(span)?, false_label, span);
+    Ok(true)
+}
+
+/// Assemble an or-pattern.
+///
+/// Each alternative is matched in a scope of its own. Once an alternative
+/// matches, the variables it binds are moved into the same slots regardless
+/// of which alternative matched, so that they can be declared in the
+/// enclosing scope.
+#[instrument]
+fn pat_or(
+    span: Span,
+    c: &mut Assembler<'_>,
+    alternatives: &[hir::Pat<'_>],
+    false_label: Label,
+    load: &dyn Fn(&mut Assembler<'_>, Needs) -> CompileResult<()>,
+) -> CompileResult<()> {
+    // NB: the value is loaded once, since loading it might have side effects.
+    load(c, Needs::Value)?;
+    let offset = c.scopes.decl_anon(span)?;
+    let base = c.scopes.total_var_count(span)?;
+
+    let ok_label = c.asm.new_label("pat_or_ok");
+    let mut names = None::<Vec<String>>;
+
+    for alternative in alternatives {
+        let span = alternative.span();
+        let alternative_false = c.asm.new_label("pat_or_false");
+
+        let guard = c.scopes.push_child(span)?;
+
+        let load = move |c: &mut Assembler<'_>, needs: Needs| {
+            if needs.value() {
+                c.asm.push(Inst::Copy { offset }, span);
+            }
+
+            Ok(())
+        };
+
+        pat(alternative, c, alternative_false, &load)?;
+        let scope = c.scopes.pop(guard, span)?;
+
+        let mut vars = scope
+            .vars()
+            .map(|(name, var)| (name.to_owned(), var.offset))
+            .collect::<Vec<_>>();
+        vars.sort();
+
+        let names = names.get_or_insert_with(|| vars.iter().map(|(n, _)| n.clone()).collect());
+
+        if let Some(name) = names
+            .iter()
+            .find(|name| !vars.iter().any(|(n, _)| n == *name))
+            .or_else(|| vars.iter().map(|(n, _)| n).find(|n| !names.contains(n)))
+        {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::OrPatternMissingBinding {
+                    name: name.as_str().into(),
+                },
+            ));
+        }
+
+        // Move the bound variables into the slots directly after the ones used
+        // by the enclosing scope, in the order they are declared.
+        if vars
+            .iter()
+            .enumerate()
+            .any(|(n, (_, offset))| *offset != base + n)
+        {
+            for (_, offset) in &vars {
+                c.asm.push(Inst::Copy { offset: *offset }, span);
+            }
+
+            for n in (0..vars.len()).rev() {
+                c.asm.push(Inst::Replace { offset: base + n }, span);
+            }
+        }
+
+        let count = scope.local_var_count - vars.len();
+
+        if count > 0 {
+            c.asm.push(Inst::PopN { count }, span);
+        }
+
+        c.asm.jump(ok_label, span);
+        c.asm.label(alternative_false)?;
+    }
+
+    // None of the alternatives matched.
+    c.asm.push(Inst::bool(false), span);
+    c.asm
+        .pop_and_jump_if_not(c.scopes.local_var_count(span)?, false_label, span);
+
+    c.asm.label(ok_label)?;
+
+    for name in names.into_iter().flatten() {
+        c.scopes.decl_var(&name, span)?;
+    }
+
+    Ok(())
+}
+
 /// Assemble an [hir::Condition<'_>].
 #[instrument]
 fn condition(
@@ -676,6 +995,7 @@ fn pat_object(
     let mut string_slots = Vec::new();
     let mut keys_dup = HashMap::new();
     let mut keys = Vec::new();
+    let mut key_spans = Vec::new();
     let mut bindings = Vec::new();
 
     for pat in hir.items.iter().take(hir.count) {
@@ -685,6 +1005,7 @@ fn pat_object(
         let key = match pat.kind {
             hir::PatKind::PatBinding(binding) => {
                 cow_key = binding.key.resolve(resolve_context!(c.q))?;
+                key_spans.push(binding.key.span());
                 bindings.push(Binding::Binding(
                     pat.span(),
                     cow_key.as_ref().into(),
@@ -704,6 +1025,7 @@ fn pat_object(
                 };
 
                 let key = ident.resolve(resolve_context!(c.q))?;
+                key_spans.push(ident.span());
                 bindings.push(Binding::Ident(pat.span(), key.into()));
                 key
             }
@@ -750,6 +1072,16 @@ fn pat_object(
                 }
             };
 
+            let item = c.q.pool.item(meta.item_meta.item);
+
+            for (binding, span) in bindings.iter().zip(&key_spans) {
+                c.q.visitor.visit_field_use(
+                    Location::new(c.source_id, *span),
+                    Some(item),
+                    binding.key(),
+                );
+            }
+
             let mut fields = st.fields.clone();
 
             for binding in &bindings {
@@ -888,6 +1220,7 @@ pub(crate) fn closure_from_block(
 
     return_(c, span, hir, block)?;
     c.scopes.pop(guard, span)?;
+    c.asm.variables.append(&mut c.scopes.declared);
     Ok(())
 }
 
@@ -1243,7 +1576,13 @@ fn expr_assign(
             match field_access.expr_field {
                 hir::ExprField::Path(path) => {
                     if let Some(ident) = path.try_as_ident() {
+                        let owner = field_owner(c, field_access.expr)?;
                         let slot = ident.resolve(resolve_context!(c.q))?;
+                        c.q.visitor.visit_field_use(
+                            Location::new(c.source_id, ident.span()),
+                            owner.map(|item| c.q.pool.item(item)),
+                            slot.as_ref(),
+                        );
                         let slot = c.q.unit.new_static_string(ident.span(), slot.as_ref())?;
 
                         expr(hir.rhs, c, Needs::Value)?.apply(c)?;
@@ -1471,7 +1810,13 @@ fn expr_binary(
                 match field_access.expr_field {
                     hir::ExprField::Path(path) => {
                         if let Some(ident) = path.try_as_ident() {
+                            let owner = field_owner(c, field_access.expr)?;
                             let n = ident.resolve(resolve_context!(c.q))?;
+                            c.q.visitor.visit_field_use(
+                                Location::new(c.source_id, ident.span()),
+                                owner.map(|item| c.q.pool.item(item)),
+                                n.as_ref(),
+                            );
                             let n = c.q.unit.new_static_string(path.span(), n.as_ref())?;
 
                             Some(InstTarget::Field(n))
@@ -1985,6 +2330,7 @@ pub(crate) fn closure_from_expr_closure(
 
     return_(c, span, hir.body, expr)?;
     c.scopes.pop_last(span)?;
+    c.asm.variables.append(&mut c.scopes.declared);
     Ok(())
 }
 
@@ -2145,7 +2491,13 @@ fn expr_field_access(
         }
         hir::ExprField::Path(path) => {
             if let Some(ident) = path.try_as_ident() {
+                let owner = field_owner(c, hir.expr)?;
                 let field = ident.resolve(resolve_context!(c.q))?;
+                c.q.visitor.visit_field_use(
+                    Location::new(c.source_id, ident.span()),
+                    owner.map(|item| c.q.pool.item(item)),
+                    field.as_ref(),
+                );
                 let slot = c.q.unit.new_static_string(span, field.as_ref())?;
 
                 c.asm.push(Inst::ObjectIndexGet { slot }, span);
@@ -2478,6 +2830,15 @@ fn expr_let(hir: &hir::ExprLet<'_>, c: &mut Assembler<'_>, needs: Needs) -> Comp
 
     let false_label = c.asm.new_label("let_panic");
 
+    if let hir::PatKind::PatPath(path) = hir.pat.kind {
+        if path.try_as_ident().is_some() {
+            if let Some(type_hash) = static_type(hir.expr, c)? {
+                c.q.visitor
+                    .visit_variable_type(Location::new(c.source_id, hir.pat.span()), type_hash);
+            }
+        }
+    }
+
     if pat(hir.pat, c, false_label, &load)? {
         c.diagnostics
             .let_pattern_might_panic(c.source_id, span, c.context());
@@ -2605,7 +2966,7 @@ fn expr_object(
     let guard = c.scopes.push_child(span)?;
 
     let mut keys = Vec::<Box<str>>::new();
-    let mut check_keys = Vec::new();
+    let mut check_keys = Vec::<(Box<str>, Span)>::new();
     let mut keys_dup = HashMap::new();
 
     for assign in hir.assignments {
@@ -2652,6 +3013,14 @@ fn expr_object(
             let meta = c.lookup_meta(path.span(), named.item)?;
             let item = c.q.pool.item(meta.item_meta.item);
 
+            for (key, span) in &check_keys {
+                c.q.visitor.visit_field_use(
+                    Location::new(c.source_id, *span),
+                    Some(item),
+                    key.as_ref(),
+                );
+            }
+
             match &meta.kind {
                 PrivMetaKind::Struct {
                     variant: PrivVariantMeta::Unit,
@@ -3132,6 +3501,9 @@ fn expr_unary(
 
                 c.asm.push(Inst::integer(n), span);
             }
+            ast::Number::Suffixed(int, suffix) => {
+                lit_suffixed_number(span, c, int.neg(), suffix)?;
+            }
         }
 
         return Ok(Asm::top(span));
@@ -3303,24 +3675,30 @@ pub(crate) fn fn_from_item_fn(
         pat_with_offset(pat, c, offset)?;
     }
 
+    // NB: returning is associated with the closing brace of the function, so
+    // that it's reported on its own line by the debugger.
+    let end = hir.body.span().tail();
+
     if hir.body.statements.is_empty() {
         let total_var_count = c.scopes.total_var_count(span)?;
-        c.locals_pop(total_var_count, span);
-        c.asm.push(Inst::ReturnUnit, span);
+        c.locals_pop(total_var_count, end);
+        c.asm.push(Inst::ReturnUnit, end);
+        c.asm.variables.append(&mut c.scopes.declared);
         return Ok(());
     }
 
     if !hir.body.produces_nothing() {
-        return_(c, span, hir.body, block)?;
+        return_(c, end, hir.body, block)?;
     } else {
         block(hir.body, c, Needs::None)?.apply(c)?;
 
         let total_var_count = c.scopes.total_var_count(span)?;
-        c.locals_pop(total_var_count, span);
-        c.asm.push(Inst::ReturnUnit, span);
+        c.locals_pop(total_var_count, end);
+        c.asm.push(Inst::ReturnUnit, end);
     }
 
     c.scopes.pop_last(span)?;
+    c.asm.variables.append(&mut c.scopes.declared);
     Ok(())
 }
 
@@ -3410,11 +3788,59 @@ fn lit_number(hir: &ast::LitNumber, c: &mut Assembler<'_>, needs: Needs) -> Comp
 
             c.asm.push(Inst::integer(n), span);
         }
+        ast::Number::Suffixed(number, suffix) => {
+            lit_suffixed_number(span, c, number, suffix)?;
+        }
     }
 
     Ok(Asm::top(span))
 }
 
+/// Assemble a number literal with a type suffix, like `10u64`, by calling the
+/// constructor of the corresponding type in `std::num`.
+///
+/// Numbers which don't fit in an integer are converted into the suffixed type
+/// here, and passed as its little endian bytes to `from_le_bytes`.
+fn lit_suffixed_number(
+    span: Span,
+    c: &mut Assembler<'_>,
+    number: num::BigInt,
+    suffix: ast::NumberSuffix,
+) -> CompileResult<()> {
+    let bytes = match suffix.to_le_bytes(&number) {
+        Some(bytes) => bytes,
+        None => {
+            return Err(CompileError::new(
+                span,
+                ParseErrorKind::BadNumberOutOfBounds,
+            ));
+        }
+    };
+
+    let constructor = match number.to_i64() {
+        Some(n) => {
+            c.asm.push(Inst::integer(n), span);
+            "new"
+        }
+        None => {
+            let slot = c.q.unit.new_static_bytes(span, &bytes)?;
+            c.asm.push(Inst::Bytes { slot }, span);
+            "from_le_bytes"
+        }
+    };
+
+    let item = ItemBuf::with_crate_item("std", &["num", suffix.type_name(), constructor]);
+    let hash = Hash::type_hash(&item);
+
+    c.asm.push_with_comment(
+        Inst::Call { hash, args: 1 },
+        span,
+        format!("{}{}", number, suffix),
+    );
+
+    Ok(())
+}
+
 /// Assemble a local expression.
 #[instrument]
 fn local(hir: &hir::Local<'_>, c: &mut Assembler<'_>, needs: Needs) -> CompileResult<Asm> {
@@ -3428,6 +3854,15 @@ fn local(hir: &hir::Local<'_>, c: &mut Assembler<'_>, needs: Needs) -> CompileRe
 
     let false_label = c.asm.new_label("let_panic");
 
+    if let hir::PatKind::PatPath(path) = hir.pat.kind {
+        if path.try_as_ident().is_some() {
+            if let Some(type_hash) = static_type(hir.expr, c)? {
+                c.q.visitor
+                    .visit_variable_type(Location::new(c.source_id, hir.pat.span()), type_hash);
+            }
+        }
+    }
+
     if pat(hir.pat, c, false_label, &load)? {
         c.diagnostics
             .let_pattern_might_panic(c.source_id, span, c.context());
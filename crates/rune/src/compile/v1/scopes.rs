@@ -1,7 +1,9 @@
 use crate::ast::Span;
 use crate::collections::HashMap;
 use crate::compile::v1::Assembler;
-use crate::compile::{Assembly, CompileError, CompileErrorKind, CompileResult, CompileVisitor};
+use crate::compile::{
+    Assembly, AssemblyVar, CompileError, CompileErrorKind, CompileResult, CompileVisitor,
+};
 use crate::runtime::Inst;
 use crate::SourceId;
 
@@ -49,6 +51,8 @@ impl Var {
 
 #[derive(Debug, Clone)]
 pub(crate) struct Scope {
+    /// The span of the scope, or `None` if it's the root scope of a function.
+    span: Option<Span>,
     /// Named variables.
     locals: HashMap<String, Var>,
     /// The number of variables.
@@ -61,6 +65,7 @@ impl Scope {
     /// Construct a new locals handlers.
     fn new() -> Scope {
         Self {
+            span: None,
             locals: HashMap::new(),
             total_var_count: 0,
             local_var_count: 0,
@@ -68,14 +73,20 @@ impl Scope {
     }
 
     /// Construct a new child scope.
-    fn child(&self) -> Self {
+    fn child(&self, span: Span) -> Self {
         Self {
+            span: Some(span),
             locals: HashMap::new(),
             total_var_count: self.total_var_count,
             local_var_count: 0,
         }
     }
 
+    /// Iterate over the named variables declared in this scope.
+    pub(crate) fn vars(&self) -> impl Iterator<Item = (&str, &Var)> + '_ {
+        self.locals.iter().map(|(name, var)| (name.as_str(), var))
+    }
+
     /// Insert a new local, and return the old one if there's a conflict.
     fn new_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
         let offset = self.total_var_count;
@@ -190,6 +201,9 @@ pub(crate) struct ScopeGuard(usize);
 
 pub(crate) struct Scopes {
     scopes: Vec<Scope>,
+    /// Every named variable which has been declared, used to produce debug
+    /// information.
+    pub(crate) declared: Vec<AssemblyVar>,
 }
 
 impl Scopes {
@@ -197,6 +211,7 @@ impl Scopes {
     pub(crate) fn new() -> Self {
         Self {
             scopes: vec![Scope::new()],
+            declared: Vec::new(),
         }
     }
 
@@ -284,12 +299,30 @@ impl Scopes {
 
     /// Construct a new variable.
     pub(crate) fn new_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
-        self.last_mut(span)?.new_var(name, span)
+        let offset = self.last_mut(span)?.new_var(name, span)?;
+        self.declare(name, offset, span)?;
+        Ok(offset)
     }
 
     /// Declare the given variable.
     pub(crate) fn decl_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
-        Ok(self.last_mut(span)?.decl_var(name, span))
+        let offset = self.last_mut(span)?.decl_var(name, span);
+        self.declare(name, offset, span)?;
+        Ok(offset)
+    }
+
+    /// Record the declaration of a named variable in the current scope.
+    fn declare(&mut self, name: &str, offset: usize, span: Span) -> CompileResult<()> {
+        let scope = self.last(span)?.span;
+
+        self.declared.push(AssemblyVar {
+            name: name.into(),
+            offset,
+            span,
+            scope,
+        });
+
+        Ok(())
     }
 
     /// Declare an anonymous variable.
@@ -339,13 +372,13 @@ impl Scopes {
 
     /// Construct a new child scope and return its guard.
     pub(crate) fn push_child(&mut self, span: Span) -> CompileResult<ScopeGuard> {
-        let scope = self.last(span)?.child();
+        let scope = self.last(span)?.child(span);
         Ok(self.push(scope))
     }
 
     /// Construct a new child scope.
     pub(crate) fn child(&mut self, span: Span) -> CompileResult<Scope> {
-        Ok(self.last(span)?.child())
+        Ok(self.last(span)?.child(span))
     }
 
     /// Get the local var count of the top scope.
@@ -6,7 +6,7 @@ use crate::diagnostics::{
 };
 use crate::parse::ResolveErrorKind;
 use crate::query::QueryErrorKind;
-use crate::runtime::{Unit, VmError, VmErrorKind};
+use crate::runtime::{BacktraceFrame, Unit, VmError, VmErrorKind};
 use crate::{Source, Diagnostics, SourceId, Sources};
 use crate::ast::{Span, Spanned};
 use std::convert::TryInto;
@@ -20,9 +20,14 @@ use codespan_reporting::term;
 use codespan_reporting::term::termcolor::WriteColor;
 pub use codespan_reporting::term::termcolor;
 
-struct StackFrame {
-    source_id: SourceId,
-    span: Span,
+#[derive(PartialEq, Eq)]
+enum StackFrame {
+    /// A frame with a known location in a source.
+    Source { source_id: SourceId, span: Span },
+    /// A frame passing through native code.
+    Native,
+    /// A frame without debug information.
+    Unknown,
 }
 
 /// Errors that can be raised when formatting diagnostics.
@@ -88,8 +93,8 @@ impl VmError {
     {
         let (error, unwound) = self.as_unwound();
 
-        let (unit, ip, frames) = match unwound {
-            Some((unit, ip, frames)) => (unit, ip, frames),
+        let (unit, ip) = match unwound {
+            Some((unit, ip, _)) => (unit, ip),
             None => {
                 writeln!(
                     out,
@@ -162,6 +167,20 @@ impl VmError {
                     ],
                 )
             }
+            VmErrorKind::StackOverflow { depth, size } => {
+                labels.push(
+                    d::Label::primary(source_id, span.range())
+                        .with_message("recursion too deep".to_string()),
+                );
+
+                (
+                    "stack overflow".to_string(),
+                    vec![
+                        format!("call depth is `{}`", depth),
+                        format!("stack size is `{}`", size),
+                    ],
+                )
+            }
             e => {
                 labels.push(
                     d::Label::primary(source_id, span.range())
@@ -171,26 +190,17 @@ impl VmError {
             }
         };
 
-        let mut backtrace = vec![StackFrame { source_id, span }];
-
-        for ip in frames.iter().map(|v| v.ip()) {
-            let debug_inst = match debug_info.instruction_at(ip) {
-                Some(debug_inst) => debug_inst,
-                None => {
-                    writeln!(
-                        out,
-                        "virtual machine error: {} (no debug instruction)",
-                        error
-                    )?;
-
-                    return Ok(());
-                }
-            };
-
-            let source_id = debug_inst.source_id;
-            let span = debug_inst.span;
-
-            backtrace.push(StackFrame { source_id, span });
+        let mut backtrace = Vec::new();
+
+        for frame in self.backtrace() {
+            backtrace.push(match (frame, frame.debug_inst()) {
+                (BacktraceFrame::Native, _) => StackFrame::Native,
+                (_, Some(debug_inst)) => StackFrame::Source {
+                    source_id: debug_inst.source_id,
+                    span: debug_inst.span,
+                },
+                (_, None) => StackFrame::Unknown,
+            });
         }
 
         let diagnostic = d::Diagnostic::error()
@@ -203,13 +213,35 @@ impl VmError {
         if !backtrace.is_empty() {
             writeln!(out, "backtrace:")?;
 
-            for frame in &backtrace {
-                let source = match sources.get(frame.source_id) {
+            let mut frames = backtrace.iter().peekable();
+
+            while let Some(frame) = frames.next() {
+                // NB: collapse identical frames, which are common when
+                // recursing.
+                let mut repeated = 0usize;
+
+                while frames.next_if_eq(&frame).is_some() {
+                    repeated += 1;
+                }
+
+                let (source_id, span) = match frame {
+                    StackFrame::Source { source_id, span } => (*source_id, *span),
+                    StackFrame::Native => {
+                        writeln!(out, "<native>")?;
+                        continue;
+                    }
+                    StackFrame::Unknown => {
+                        writeln!(out, "<unknown>")?;
+                        continue;
+                    }
+                };
+
+                let source = match sources.get(source_id) {
                     Some(source) => source,
                     None => continue,
                 };
 
-                let (line, line_count, text) = match source.line(frame.span) {
+                let (line, line_count, text) = match source.line(span) {
                     Some((line, line_count, text)) => (
                         line.saturating_add(1),
                         line_count.saturating_add(1),
@@ -219,6 +251,10 @@ impl VmError {
                 };
 
                 writeln!(out, "{}:{}:{}: {}", source.name(), line, line_count, text)?;
+
+                if repeated > 0 {
+                    writeln!(out, "  (repeated {} more times)", repeated)?;
+                }
             }
         }
 
@@ -0,0 +1,55 @@
+//! The canonical source code formatter for Rune.
+//!
+//! The formatter parses the source into an [ast::File][crate::ast::File] and
+//! prints it back out with normalized whitespace and indentation. Comments are
+//! preserved, and so are single blank lines separating items and statements.
+//!
+//! Whether a delimited group such as the arguments to a function call is laid
+//! out on one line or one element per line is decided by the input: if there
+//! is a line break after the opening delimiter the group is expanded, otherwise
+//! it is kept on a single line.
+//!
+//! ```
+//! let source = "fn main(){let a=[1,2,3];// the answer\nfoo( a )}";
+//! let formatted = rune::fmt::layout_source(source)?;
+//!
+//! assert_eq!(formatted, "fn main() {\n    let a = [1, 2, 3]; // the answer\n    foo(a)\n}\n");
+//! # Ok::<_, rune::parse::ParseError>(())
+//! ```
+
+mod printer;
+
+use crate::ast;
+use crate::parse::{self, Lexer, ParseError};
+use crate::SourceId;
+
+use self::printer::Printer;
+
+/// Format the given source, returning the formatted source code.
+///
+/// Returns an error if the source cannot be parsed.
+pub fn layout_source(source: &str) -> Result<String, ParseError> {
+    let file = parse::parse_all::<ast::File>(source, SourceId::empty(), true)?;
+
+    let comments = comments(source)?;
+    let mut printer = Printer::new(source, comments);
+    printer.file(&file);
+    Ok(printer.finish())
+}
+
+/// Collect the spans of all regular comments in the source.
+///
+/// The parser skips over these, so they need to be recovered separately to be
+/// put back into the formatted output.
+fn comments(source: &str) -> Result<Vec<ast::Span>, ParseError> {
+    let mut lexer = Lexer::new(source, SourceId::empty(), true);
+    let mut comments = Vec::new();
+
+    while let Some(token) = lexer.next()? {
+        if let ast::Kind::Comment | ast::Kind::MultilineComment(..) = token.kind {
+            comments.push(token.span);
+        }
+    }
+
+    Ok(comments)
+}
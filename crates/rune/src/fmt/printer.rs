@@ -0,0 +1,1278 @@
+use crate::ast;
+use crate::ast::{Span, Spanned};
+use std::collections::VecDeque;
+
+/// The string used for one level of indentation.
+const INDENT: &str = "    ";
+
+/// Pretty-printer which writes an AST back out as formatted source.
+///
+/// Leaf tokens such as identifiers and literals are copied verbatim from the
+/// original source, everything else is reconstructed from the structure of
+/// the AST.
+pub(super) struct Printer<'a> {
+    /// The source being formatted.
+    source: &'a str,
+    /// Comments which have not yet been written to the output.
+    comments: VecDeque<Span>,
+    /// The output being built.
+    out: String,
+    /// The current indentation level.
+    indent: usize,
+    /// If nothing has been written to the current line.
+    line_start: bool,
+}
+
+impl<'a> Printer<'a> {
+    /// Construct a new printer over the given source and the comments it
+    /// contains.
+    pub(super) fn new(source: &'a str, comments: Vec<Span>) -> Self {
+        Self {
+            source,
+            comments: comments.into(),
+            out: String::new(),
+            indent: 0,
+            line_start: true,
+        }
+    }
+
+    /// Finish printing, returning the formatted source.
+    pub(super) fn finish(mut self) -> String {
+        self.comments_before(self.source.len());
+
+        let len = self.out.trim_end().len();
+        self.out.truncate(len);
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+
+        self.out
+    }
+
+    /// Print a whole file.
+    pub(super) fn file(&mut self, file: &ast::File) {
+        if let Some(shebang) = &file.shebang {
+            self.verbatim(shebang.span());
+            self.nl();
+        }
+
+        for attribute in &file.attributes {
+            self.line(attribute.span());
+            self.verbatim(attribute.span());
+            self.end_line(attribute.span());
+        }
+
+        self.items(&file.items);
+    }
+
+    /// Print a sequence of items, one per line.
+    fn items(&mut self, items: &[(ast::Item, Option<T![;]>)]) {
+        for (item, semi) in items {
+            self.line(item.span());
+            self.item(item);
+
+            if semi.is_some() {
+                self.lit(";");
+            }
+
+            self.end_line(with_trailing(item, semi.as_ref()));
+        }
+    }
+
+    fn item(&mut self, item: &ast::Item) {
+        if let ast::Item::MacroCall(macro_call) = item {
+            self.verbatim(macro_call.span());
+            return;
+        }
+
+        self.outer_attributes(item.attributes());
+
+        match item {
+            ast::Item::Use(item) => {
+                self.visibility(&item.visibility);
+                self.lit("use ");
+                self.use_path(&item.path);
+            }
+            ast::Item::Fn(item) => {
+                self.item_fn(item);
+            }
+            ast::Item::Enum(item) => {
+                self.visibility(&item.visibility);
+                self.lit("enum ");
+                self.verbatim(item.name.span());
+                self.lit(" ");
+
+                self.group(
+                    "{",
+                    "}",
+                    Group::Spaced,
+                    item.variants.span(),
+                    &item.variants.braced,
+                    |p, variant| p.variant(variant),
+                );
+            }
+            ast::Item::Struct(item) => {
+                self.visibility(&item.visibility);
+                self.lit("struct ");
+                self.verbatim(item.ident.span());
+                self.struct_body(&item.body);
+            }
+            ast::Item::Impl(item) => {
+                self.lit("impl ");
+
+                if let Some((path, _)) = &item.trait_ {
+                    self.path(path);
+                    self.lit(" for ");
+                }
+
+                self.path(&item.path);
+                self.lit(" ");
+
+                let span = item.open.span().join(item.close.span());
+
+                if item.functions.is_empty() && !self.has_comments(span) {
+                    self.lit("{}");
+                    return;
+                }
+
+                self.lit("{");
+                self.nl();
+                self.indent += 1;
+
+                for function in &item.functions {
+                    self.line(function.span());
+                    self.outer_attributes(&function.attributes);
+                    self.item_fn(function);
+                    self.end_line(function.span());
+                }
+
+                self.close(item.close.span().start.into_usize(), "}");
+            }
+            ast::Item::Trait(item) => {
+                self.visibility(&item.visibility);
+                self.lit("trait ");
+                self.verbatim(item.name.span());
+                self.lit(" ");
+
+                let span = item.open.span().join(item.close.span());
+
+                if item.items.is_empty() && !self.has_comments(span) {
+                    self.lit("{}");
+                    return;
+                }
+
+                self.lit("{");
+                self.nl();
+                self.indent += 1;
+
+                for trait_item in &item.items {
+                    self.line(trait_item.span());
+
+                    match trait_item {
+                        ast::TraitItem::Fn(function) => {
+                            self.outer_attributes(&function.attributes);
+                            self.item_fn(function);
+                        }
+                        ast::TraitItem::Decl(decl) => {
+                            self.outer_attributes(&decl.attributes);
+
+                            if decl.async_token.is_some() {
+                                self.lit("async ");
+                            }
+
+                            self.lit("fn ");
+                            self.verbatim(decl.name.span());
+                            self.fn_args(&decl.args);
+                            self.lit(";");
+                        }
+                    }
+
+                    self.end_line(trait_item.span());
+                }
+
+                self.close(item.close.span().start.into_usize(), "}");
+            }
+            ast::Item::Mod(item) => {
+                self.visibility(&item.visibility);
+                self.lit("mod ");
+                self.verbatim(item.name.span());
+
+                match &item.body {
+                    ast::ItemModBody::EmptyBody(..) => {
+                        self.lit(";");
+                    }
+                    ast::ItemModBody::InlineBody(body) => {
+                        self.lit(" ");
+
+                        let span = body.open.span().join(body.close.span());
+
+                        if body.file.items.is_empty()
+                            && body.file.attributes.is_empty()
+                            && !self.has_comments(span)
+                        {
+                            self.lit("{}");
+                            return;
+                        }
+
+                        self.lit("{");
+                        self.nl();
+                        self.indent += 1;
+                        self.file(&body.file);
+                        self.close(body.close.span().start.into_usize(), "}");
+                    }
+                }
+            }
+            ast::Item::Const(item) => {
+                self.visibility(&item.visibility);
+                self.lit("const ");
+                self.verbatim(item.name.span());
+                self.lit(" = ");
+                self.expr(&item.expr);
+            }
+            ast::Item::MacroCall(..) => {}
+        }
+    }
+
+    fn item_fn(&mut self, item: &ast::ItemFn) {
+        self.visibility(&item.visibility);
+
+        if item.const_token.is_some() {
+            self.lit("const ");
+        }
+
+        if item.async_token.is_some() {
+            self.lit("async ");
+        }
+
+        self.lit("fn ");
+        self.verbatim(item.name.span());
+
+        self.fn_args(&item.args);
+        self.lit(" ");
+        self.block(&item.body);
+    }
+
+    fn fn_args(&mut self, args: &ast::Parenthesized<ast::FnArg, ast::Comma>) {
+        self.group(
+            "(",
+            ")",
+            Group::Tight,
+            args.span(),
+            &args.parenthesized,
+            |p, arg| p.fn_arg(arg),
+        );
+    }
+
+    fn fn_arg(&mut self, arg: &ast::FnArg) {
+        match arg {
+            ast::FnArg::SelfValue(..) => self.lit("self"),
+            ast::FnArg::Pat(pat) => self.pat(pat),
+        }
+    }
+
+    fn variant(&mut self, variant: &ast::ItemVariant) {
+        self.inline_attributes(&variant.attributes);
+        self.verbatim(variant.name.span());
+
+        match &variant.body {
+            ast::ItemVariantBody::UnitBody => {}
+            ast::ItemVariantBody::TupleBody(body) => {
+                self.group(
+                    "(",
+                    ")",
+                    Group::Tight,
+                    body.span(),
+                    &body.parenthesized,
+                    |p, field| p.field(field),
+                );
+            }
+            ast::ItemVariantBody::StructBody(body) => {
+                self.lit(" ");
+
+                self.group(
+                    "{",
+                    "}",
+                    Group::Spaced,
+                    body.span(),
+                    &body.braced,
+                    |p, field| p.field(field),
+                );
+            }
+        }
+    }
+
+    fn struct_body(&mut self, body: &ast::ItemStructBody) {
+        match body {
+            ast::ItemStructBody::UnitBody => {}
+            ast::ItemStructBody::TupleBody(body) => {
+                self.group(
+                    "(",
+                    ")",
+                    Group::Tight,
+                    body.span(),
+                    &body.parenthesized,
+                    |p, field| p.field(field),
+                );
+            }
+            ast::ItemStructBody::StructBody(body) => {
+                self.lit(" ");
+
+                self.group(
+                    "{",
+                    "}",
+                    Group::Spaced,
+                    body.span(),
+                    &body.braced,
+                    |p, field| p.field(field),
+                );
+            }
+        }
+    }
+
+    fn field(&mut self, field: &ast::Field) {
+        self.inline_attributes(&field.attributes);
+        self.visibility(&field.visibility);
+        self.verbatim(field.name.span());
+    }
+
+    fn visibility(&mut self, visibility: &ast::Visibility) {
+        match visibility {
+            ast::Visibility::Inherited => return,
+            ast::Visibility::Public(..) => self.lit("pub"),
+            ast::Visibility::Crate(..) => self.lit("pub(crate)"),
+            ast::Visibility::Super(..) => self.lit("pub(super)"),
+            ast::Visibility::SelfValue(..) => self.lit("pub(self)"),
+            ast::Visibility::In(restrict) => {
+                self.lit("pub(in ");
+                self.path(&restrict.restriction.path);
+                self.lit(")");
+            }
+        }
+
+        self.lit(" ");
+    }
+
+    fn use_path(&mut self, path: &ast::ItemUsePath) {
+        if path.global.is_some() {
+            self.lit("::");
+        }
+
+        self.use_segment(&path.first);
+
+        for (_, segment) in &path.segments {
+            self.lit("::");
+            self.use_segment(segment);
+        }
+
+        if let Some((_, alias)) = &path.alias {
+            self.lit(" as ");
+            self.verbatim(alias.span());
+        }
+    }
+
+    fn use_segment(&mut self, segment: &ast::ItemUseSegment) {
+        match segment {
+            ast::ItemUseSegment::PathSegment(segment) => self.path_segment(segment),
+            ast::ItemUseSegment::Wildcard(..) => self.lit("*"),
+            ast::ItemUseSegment::Group(group) => {
+                self.group(
+                    "{",
+                    "}",
+                    Group::Tight,
+                    group.span(),
+                    &group.braced,
+                    |p, path| p.use_path(path),
+                );
+            }
+        }
+    }
+
+    fn path(&mut self, path: &ast::Path) {
+        if path.global.is_some() {
+            self.lit("::");
+        }
+
+        self.path_segment(&path.first);
+
+        for (_, segment) in &path.rest {
+            self.lit("::");
+            self.path_segment(segment);
+        }
+
+        if path.trailing.is_some() {
+            self.lit("::");
+        }
+    }
+
+    fn path_segment(&mut self, segment: &ast::PathSegment) {
+        match segment {
+            ast::PathSegment::Generics(generics) => {
+                self.lit("<");
+
+                for (index, (generic, _)) in generics.angle_bracketed.iter().enumerate() {
+                    if index > 0 {
+                        self.lit(", ");
+                    }
+
+                    self.expr(&generic.expr);
+                }
+
+                self.lit(">");
+            }
+            segment => self.verbatim(segment.span()),
+        }
+    }
+
+    fn block(&mut self, block: &ast::Block) {
+        if block.statements.is_empty() && !self.has_comments(block.span()) {
+            self.lit("{}");
+            return;
+        }
+
+        self.lit("{");
+        self.nl();
+        self.indent += 1;
+
+        for stmt in &block.statements {
+            self.line(stmt.span());
+            self.stmt(stmt);
+            self.end_line(stmt.span());
+        }
+
+        self.close(block.close.span().start.into_usize(), "}");
+    }
+
+    fn stmt(&mut self, stmt: &ast::Stmt) {
+        match stmt {
+            ast::Stmt::Local(local) => {
+                self.outer_attributes(&local.attributes);
+                self.lit("let ");
+                self.pat(&local.pat);
+                self.lit(" = ");
+                self.expr(&local.expr);
+                self.lit(";");
+            }
+            ast::Stmt::Item(item, semi) => {
+                self.item(item);
+
+                if semi.is_some() {
+                    self.lit(";");
+                }
+            }
+            ast::Stmt::Expr(expr) => {
+                self.expr(expr);
+            }
+            ast::Stmt::Semi(semi) => {
+                self.expr(&semi.expr);
+                self.lit(";");
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &ast::Expr) {
+        if let ast::Expr::MacroCall(macro_call) = expr {
+            self.verbatim(macro_call.span());
+            return;
+        }
+
+        self.inline_attributes(expr.attributes());
+
+        match expr {
+            ast::Expr::Path(path) => {
+                self.path(path);
+            }
+            ast::Expr::Assign(expr) => {
+                self.expr(&expr.lhs);
+                self.lit(" = ");
+                self.expr(&expr.rhs);
+            }
+            ast::Expr::While(expr) => {
+                self.label(&expr.label);
+                self.lit("while ");
+                self.condition(&expr.condition);
+                self.lit(" ");
+                self.block(&expr.body);
+            }
+            ast::Expr::Loop(expr) => {
+                self.label(&expr.label);
+                self.lit("loop ");
+                self.block(&expr.body);
+            }
+            ast::Expr::For(expr) => {
+                self.label(&expr.label);
+                self.lit("for ");
+                self.pat(&expr.binding);
+                self.lit(" in ");
+                self.expr(&expr.iter);
+                self.lit(" ");
+                self.block(&expr.body);
+            }
+            ast::Expr::Let(expr) => {
+                self.expr_let(expr);
+            }
+            ast::Expr::If(expr) => {
+                self.lit("if ");
+                self.condition(&expr.condition);
+                self.lit(" ");
+                self.block(&expr.block);
+
+                for expr_else_if in &expr.expr_else_ifs {
+                    self.lit(" else if ");
+                    self.condition(&expr_else_if.condition);
+                    self.lit(" ");
+                    self.block(&expr_else_if.block);
+                }
+
+                if let Some(expr_else) = &expr.expr_else {
+                    self.lit(" else ");
+                    self.block(&expr_else.block);
+                }
+            }
+            ast::Expr::Match(expr) => {
+                self.lit("match ");
+                self.expr(&expr.expr);
+                self.lit(" ");
+
+                let span = expr.open.span().join(expr.close.span());
+
+                self.branches(span, &expr.branches, |p, branch| {
+                    p.pat(&branch.pat);
+
+                    if let Some((_, condition)) = &branch.condition {
+                        p.lit(" if ");
+                        p.expr(condition);
+                    }
+
+                    p.lit(" => ");
+                    p.expr(&branch.body);
+                    &branch.body
+                });
+            }
+            ast::Expr::Call(expr) => {
+                self.expr(&expr.expr);
+
+                self.group(
+                    "(",
+                    ")",
+                    Group::Tight,
+                    expr.args.span(),
+                    &expr.args.parenthesized,
+                    |p, arg| p.expr(arg),
+                );
+            }
+            ast::Expr::FieldAccess(expr) => {
+                self.expr(&expr.expr);
+                self.dot(&expr.expr, expr.dot.span());
+
+                match &expr.expr_field {
+                    ast::ExprField::Path(path) => self.path(path),
+                    ast::ExprField::LitNumber(number) => self.verbatim(number.span()),
+                }
+            }
+            ast::Expr::Binary(expr) => {
+                self.expr(&expr.lhs);
+                self.lit(" ");
+                self.lit(&expr.op.to_string());
+                self.lit(" ");
+                self.expr(&expr.rhs);
+            }
+            ast::Expr::Unary(expr) => {
+                self.lit(&expr.op.to_string());
+                self.expr(&expr.expr);
+            }
+            ast::Expr::Index(expr) => {
+                self.expr(&expr.target);
+                self.lit("[");
+                self.expr(&expr.index);
+                self.lit("]");
+            }
+            ast::Expr::Break(expr) => {
+                self.lit("break");
+
+                if let Some(value) = &expr.expr {
+                    self.lit(" ");
+
+                    match &**value {
+                        ast::ExprBreakValue::Expr(expr) => self.expr(expr),
+                        ast::ExprBreakValue::Label(label) => self.verbatim(label.span()),
+                    }
+                }
+            }
+            ast::Expr::Continue(expr) => {
+                self.lit("continue");
+
+                if let Some(label) = &expr.label {
+                    self.lit(" ");
+                    self.verbatim(label.span());
+                }
+            }
+            ast::Expr::Yield(expr) => {
+                self.lit("yield");
+
+                if let Some(expr) = &expr.expr {
+                    self.lit(" ");
+                    self.expr(expr);
+                }
+            }
+            ast::Expr::Block(expr) => {
+                if expr.async_token.is_some() {
+                    self.lit("async ");
+                }
+
+                if expr.const_token.is_some() {
+                    self.lit("const ");
+                }
+
+                if expr.move_token.is_some() {
+                    self.lit("move ");
+                }
+
+                self.block(&expr.block);
+            }
+            ast::Expr::Return(expr) => {
+                self.lit("return");
+
+                if let Some(expr) = &expr.expr {
+                    self.lit(" ");
+                    self.expr(expr);
+                }
+            }
+            ast::Expr::Await(expr) => {
+                self.expr(&expr.expr);
+                self.dot(&expr.expr, expr.dot.span());
+                self.lit("await");
+            }
+            ast::Expr::Try(expr) => {
+                self.expr(&expr.expr);
+                self.lit("?");
+            }
+            ast::Expr::Select(expr) => {
+                self.lit("select ");
+
+                let span = expr.open.span().join(expr.close.span());
+
+                self.branches(span, &expr.branches, |p, branch| match branch {
+                    ast::ExprSelectBranch::Pat(branch) => {
+                        p.pat(&branch.pat);
+                        p.lit(" = ");
+                        p.expr(&branch.expr);
+                        p.lit(" => ");
+                        p.expr(&branch.body);
+                        &branch.body
+                    }
+                    ast::ExprSelectBranch::Default(branch) => {
+                        p.lit("default => ");
+                        p.expr(&branch.body);
+                        &branch.body
+                    }
+                });
+            }
+            ast::Expr::Closure(expr) => {
+                if expr.async_token.is_some() {
+                    self.lit("async ");
+                }
+
+                if expr.move_token.is_some() {
+                    self.lit("move ");
+                }
+
+                match &expr.args {
+                    ast::ExprClosureArgs::Empty { .. } => {
+                        self.lit("||");
+                    }
+                    ast::ExprClosureArgs::List { args, .. } => {
+                        self.lit("|");
+
+                        for (index, (arg, _)) in args.iter().enumerate() {
+                            if index > 0 {
+                                self.lit(", ");
+                            }
+
+                            self.fn_arg(arg);
+                        }
+
+                        self.lit("|");
+                    }
+                }
+
+                self.lit(" ");
+                self.expr(&expr.body);
+            }
+            ast::Expr::Lit(expr) => {
+                self.verbatim(expr.lit.span());
+            }
+            ast::Expr::Object(expr) => {
+                let group = self.object_ident(&expr.ident);
+
+                self.group(
+                    "{",
+                    "}",
+                    group,
+                    expr.assignments.span(),
+                    &expr.assignments.braced,
+                    |p, assign| {
+                        p.object_key(&assign.key);
+
+                        if let Some((_, expr)) = &assign.assign {
+                            p.lit(": ");
+                            p.expr(expr);
+                        }
+                    },
+                );
+            }
+            ast::Expr::Tuple(expr) => {
+                self.group(
+                    "(",
+                    ")",
+                    Group::Tuple,
+                    expr.items.span(),
+                    &expr.items.parenthesized,
+                    |p, expr| p.expr(expr),
+                );
+            }
+            ast::Expr::Vec(expr) => {
+                self.group(
+                    "[",
+                    "]",
+                    Group::Tight,
+                    expr.items.span(),
+                    &expr.items.bracketed,
+                    |p, expr| p.expr(expr),
+                );
+            }
+            ast::Expr::Range(expr) => {
+                if let Some(from) = &expr.from {
+                    self.expr(from);
+                }
+
+                match expr.limits {
+                    ast::ExprRangeLimits::HalfOpen(..) => self.lit(".."),
+                    ast::ExprRangeLimits::Closed(..) => self.lit("..="),
+                }
+
+                if let Some(to) = &expr.to {
+                    self.expr(to);
+                }
+            }
+            ast::Expr::Empty(expr) => {
+                self.expr(&expr.expr);
+            }
+            ast::Expr::Group(expr) => {
+                self.lit("(");
+                self.expr(&expr.expr);
+                self.lit(")");
+            }
+            ast::Expr::MacroCall(..) => {}
+        }
+    }
+
+    /// Print the `.` of a field access or await, breaking the line before it
+    /// if the source does so that method chains can be spread out.
+    fn dot(&mut self, expr: &ast::Expr, dot: Span) {
+        let between = &self.source[expr.span().end.into_usize()..dot.start.into_usize()];
+
+        if between.contains('\n') {
+            self.trailing_comments(expr.span().end.into_usize());
+            self.nl();
+            self.indent += 1;
+            self.lit(".");
+            self.indent -= 1;
+        } else {
+            self.lit(".");
+        }
+    }
+
+    fn expr_let(&mut self, expr: &ast::ExprLet) {
+        self.lit("let ");
+        self.pat(&expr.pat);
+        self.lit(" = ");
+        self.expr(&expr.expr);
+    }
+
+    fn condition(&mut self, condition: &ast::Condition) {
+        match condition {
+            ast::Condition::Expr(expr) => self.expr(expr),
+            ast::Condition::ExprLet(expr) => {
+                self.inline_attributes(&expr.attributes);
+                self.expr_let(expr);
+            }
+        }
+    }
+
+    fn label(&mut self, label: &Option<(ast::Label, T![:])>) {
+        if let Some((label, _)) = label {
+            self.verbatim(label.span());
+            self.lit(": ");
+        }
+    }
+
+    /// Print the branches of a `match` or `select` expression. The closure
+    /// prints a single branch and returns its body, which determines whether
+    /// the branch needs a trailing comma.
+    fn branches<'b, T>(
+        &mut self,
+        span: Span,
+        branches: &'b [(T, Option<T![,]>)],
+        mut f: impl FnMut(&mut Self, &'b T) -> &'b ast::Expr,
+    ) where
+        T: Spanned,
+    {
+        if branches.is_empty() && !self.has_comments(span) {
+            self.lit("{}");
+            return;
+        }
+
+        self.lit("{");
+        self.nl();
+        self.indent += 1;
+
+        for (branch, comma) in branches {
+            self.line(branch.span());
+            let body = f(self, branch);
+
+            if !matches!(body, ast::Expr::Block(..)) {
+                self.lit(",");
+            }
+
+            self.end_line(with_trailing(branch, comma.as_ref()));
+        }
+
+        self.close(span.end.into_usize(), "}");
+    }
+
+    fn pat(&mut self, pat: &ast::Pat) {
+        match pat {
+            ast::Pat::PatIgnore(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.lit("_");
+            }
+            ast::Pat::PatPath(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.path(&pat.path);
+            }
+            ast::Pat::PatLit(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.expr(&pat.expr);
+            }
+            ast::Pat::PatVec(pat) => {
+                self.inline_attributes(&pat.attributes);
+
+                self.group(
+                    "[",
+                    "]",
+                    Group::Tight,
+                    pat.items.span(),
+                    &pat.items.bracketed,
+                    |p, pat| p.pat(pat),
+                );
+            }
+            ast::Pat::PatTuple(pat) => {
+                self.inline_attributes(&pat.attributes);
+
+                let group = if let Some(path) = &pat.path {
+                    self.path(path);
+                    Group::Tight
+                } else {
+                    Group::Tuple
+                };
+
+                self.group(
+                    "(",
+                    ")",
+                    group,
+                    pat.items.span(),
+                    &pat.items.parenthesized,
+                    |p, pat| p.pat(pat),
+                );
+            }
+            ast::Pat::PatObject(pat) => {
+                self.inline_attributes(&pat.attributes);
+                let group = self.object_ident(&pat.ident);
+
+                self.group(
+                    "{",
+                    "}",
+                    group,
+                    pat.items.span(),
+                    &pat.items.braced,
+                    |p, pat| p.pat(pat),
+                );
+            }
+            ast::Pat::PatBinding(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.object_key(&pat.key);
+                self.lit(": ");
+                self.pat(&pat.pat);
+            }
+            ast::Pat::PatRest(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.lit("..");
+            }
+            ast::Pat::PatOr(pat) => {
+                self.pat(&pat.first);
+
+                for (_, pat) in &pat.rest {
+                    self.lit(" | ");
+                    self.pat(pat);
+                }
+            }
+            ast::Pat::PatRange(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.expr(&pat.start);
+
+                match pat.limits {
+                    ast::ExprRangeLimits::HalfOpen(..) => self.lit(".."),
+                    ast::ExprRangeLimits::Closed(..) => self.lit("..="),
+                }
+
+                if let Some(end) = &pat.end {
+                    self.expr(end);
+                }
+            }
+            ast::Pat::PatAt(pat) => {
+                self.inline_attributes(&pat.attributes);
+                self.verbatim(pat.ident.span());
+                self.lit(" @ ");
+                self.pat(&pat.pat);
+            }
+        }
+    }
+
+    /// Print the identifier of an object expression or pattern, returning how
+    /// the braced group following it should be laid out.
+    fn object_ident(&mut self, ident: &ast::ObjectIdent) -> Group {
+        match ident {
+            ast::ObjectIdent::Anonymous(..) => {
+                self.lit("#");
+                Group::Tight
+            }
+            ast::ObjectIdent::Named(path) => {
+                self.path(path);
+                self.lit(" ");
+                Group::Spaced
+            }
+        }
+    }
+
+    fn object_key(&mut self, key: &ast::ObjectKey) {
+        match key {
+            ast::ObjectKey::LitStr(lit) => self.verbatim(lit.span()),
+            ast::ObjectKey::Path(path) => self.path(path),
+        }
+    }
+
+    /// Print attributes which are each placed on a line of their own.
+    fn outer_attributes(&mut self, attributes: &[ast::Attribute]) {
+        for attribute in attributes {
+            self.comments_before(attribute.span().start.into_usize());
+            self.verbatim(attribute.span());
+            self.nl();
+        }
+    }
+
+    /// Print attributes which are placed on the same line as what they are
+    /// attached to.
+    ///
+    /// Line doc comments still have to be followed by a line break.
+    fn inline_attributes(&mut self, attributes: &[ast::Attribute]) {
+        for attribute in attributes {
+            self.verbatim(attribute.span());
+
+            if self.text(attribute.span()).starts_with("//") {
+                self.nl();
+            } else {
+                self.lit(" ");
+            }
+        }
+    }
+
+    /// Print a delimited, comma-separated group of elements.
+    ///
+    /// The group is laid out with one element per line if the original source
+    /// breaks the line after the opening delimiter or if it contains comments,
+    /// otherwise it is kept on a single line.
+    fn group<T, S>(
+        &mut self,
+        open: &str,
+        close: &str,
+        group: Group,
+        span: Span,
+        items: &[(T, Option<S>)],
+        mut f: impl FnMut(&mut Self, &T),
+    ) where
+        T: Spanned,
+        S: Spanned,
+    {
+        let has_comments = self.has_line_comments(span);
+
+        if items.is_empty() && !self.has_comments(span) {
+            self.lit(open);
+            self.lit(close);
+            return;
+        }
+
+        let first = match items.first() {
+            Some((item, _)) => item.span().start.into_usize(),
+            None => span.end.into_usize(),
+        };
+
+        let broken = self.source[span.start.into_usize()..first].contains('\n');
+
+        self.lit(open);
+
+        if !broken && !has_comments {
+            if let Group::Spaced = group {
+                self.lit(" ");
+            }
+
+            for (index, (item, _)) in items.iter().enumerate() {
+                if index > 0 {
+                    self.lit(", ");
+                }
+
+                f(self, item);
+            }
+
+            if let (Group::Tuple, [_]) = (group, items) {
+                self.lit(",");
+            }
+
+            self.comments_inline(span.end.into_usize());
+
+            if let Group::Spaced = group {
+                self.lit(" ");
+            }
+
+            self.lit(close);
+            return;
+        }
+
+        self.nl();
+        self.indent += 1;
+
+        for (item, comma) in items {
+            self.line(item.span());
+            f(self, item);
+            self.lit(",");
+            self.end_line(with_trailing(item, comma.as_ref()));
+        }
+
+        self.close(span.end.into_usize(), close);
+    }
+
+    /// Close an indented section with the given token, which is located at
+    /// the given position in the source.
+    fn close(&mut self, pos: usize, token: &str) {
+        self.comments_before(pos);
+        self.indent -= 1;
+        self.lit(token);
+    }
+
+    /// Start a new line for the node at the given span, preceded by any
+    /// pending comments and a blank line if there was one in the source.
+    fn line(&mut self, span: Span) {
+        let start = span.start.into_usize();
+        self.comments_before(start);
+
+        if self.blank_before(start) {
+            self.blank_line();
+        }
+    }
+
+    /// End the line of a node which ends at the given span, including any
+    /// trailing comments.
+    fn end_line(&mut self, span: Span) {
+        self.trailing_comments(span.end.into_usize());
+        self.nl();
+    }
+
+    /// Write all pending comments which start before the given position, each
+    /// on a line of their own.
+    fn comments_before(&mut self, pos: usize) {
+        while let Some(comment) = self.comments.front().copied() {
+            if comment.start.into_usize() >= pos {
+                break;
+            }
+
+            self.comments.pop_front();
+
+            if !self.line_start {
+                self.nl();
+            }
+
+            if self.blank_before(comment.start.into_usize()) {
+                self.blank_line();
+            }
+
+            let text = self.text(comment).trim_end();
+            self.lit(text);
+            self.nl();
+        }
+    }
+
+    /// Write pending comments which start before the given position on the
+    /// current line, so that they stay in front of the token at that position.
+    ///
+    /// Line comments end the current line. Returns `true` if a comment was
+    /// written on the current line.
+    fn comments_inline(&mut self, pos: usize) -> bool {
+        let mut written = false;
+
+        while let Some(comment) = self.comments.front().copied() {
+            if comment.start.into_usize() >= pos {
+                break;
+            }
+
+            self.comments.pop_front();
+            let text = self.text(comment).trim_end();
+
+            if text.starts_with("//") {
+                if !self.line_start {
+                    let len = self.out.trim_end_matches(' ').len();
+                    self.out.truncate(len);
+                    self.lit(" ");
+                }
+
+                self.lit(text);
+                self.nl();
+                written = false;
+                continue;
+            }
+
+            if !self.line_start && !self.out.ends_with([' ', '(', '[']) {
+                self.lit(" ");
+            }
+
+            self.lit(text);
+            written = true;
+        }
+
+        written
+    }
+
+    /// Write pending comments which belong to the current line, either because
+    /// they are inside of the node that was just printed or because they follow
+    /// it on the same line in the source.
+    fn trailing_comments(&mut self, end: usize) {
+        while let Some(comment) = self.comments.front().copied() {
+            let start = comment.start.into_usize();
+
+            if start >= end && !self.source[end..start].trim().is_empty() {
+                break;
+            }
+
+            if start >= end && self.source[end..start].contains('\n') {
+                break;
+            }
+
+            self.comments.pop_front();
+            let text = self.text(comment).trim_end();
+            self.lit(" ");
+            self.lit(text);
+        }
+    }
+
+    /// Test if there are pending comments inside of the given span.
+    fn has_comments(&self, span: Span) -> bool {
+        self.comments
+            .iter()
+            .any(|c| c.start >= span.start && c.end <= span.end)
+    }
+
+    /// Test if there are pending line comments inside of the given span, which
+    /// can't be placed in the middle of a line.
+    fn has_line_comments(&self, span: Span) -> bool {
+        self.comments
+            .iter()
+            .any(|c| c.start >= span.start && c.end <= span.end && self.text(*c).starts_with("//"))
+    }
+
+    /// Test if the source has a blank line immediately before the given
+    /// position.
+    fn blank_before(&self, pos: usize) -> bool {
+        let before = &self.source[..pos];
+        let ws = &before[before.trim_end().len()..];
+        ws.matches('\n').count() > 1
+    }
+
+    /// Write a blank line, unless it would be at the start of the output or
+    /// directly after an opening delimiter.
+    fn blank_line(&mut self) {
+        if self.out.ends_with("\n\n") {
+            return;
+        }
+
+        match self.out.trim_end().chars().last() {
+            None | Some('{' | '(' | '[') => return,
+            _ => (),
+        }
+
+        if !self.line_start {
+            self.nl();
+        }
+
+        self.nl();
+    }
+
+    /// Copy the given span verbatim from the source. Any comments inside of it
+    /// are copied along with it.
+    fn verbatim(&mut self, span: Span) {
+        if self.comments_inline(span.start.into_usize()) {
+            self.lit(" ");
+        }
+
+        self.comments
+            .retain(|c| c.start < span.start || c.end > span.end);
+
+        let text = self.text(span).trim_end();
+        self.lit(text);
+    }
+
+    /// Write a literal string.
+    fn lit(&mut self, s: &str) {
+        if self.line_start {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+
+            self.line_start = false;
+        }
+
+        self.out.push_str(s);
+    }
+
+    /// Write a line break.
+    fn nl(&mut self) {
+        self.out.push('\n');
+        self.line_start = true;
+    }
+
+    fn text(&self, span: Span) -> &'a str {
+        &self.source[span.range()]
+    }
+}
+
+/// Join the span of a node with the span of an optional token following it.
+fn with_trailing<T, S>(node: &T, token: Option<&S>) -> Span
+where
+    T: Spanned,
+    S: Spanned,
+{
+    match token {
+        Some(token) => node.span().join(token.span()),
+        None => node.span(),
+    }
+}
+
+/// How a delimited group is laid out when it fits on a single line.
+#[derive(Clone, Copy)]
+enum Group {
+    /// No spaces inside of the delimiters, like `[1, 2]`.
+    Tight,
+    /// Spaces inside of the delimiters, like `Foo { a, b }`.
+    Spaced,
+    /// Like [Group::Tight], but a single element gets a trailing comma so it is
+    /// not confused with a parenthesized expression, like `(1,)`.
+    Tuple,
+}
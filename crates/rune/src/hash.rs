@@ -5,7 +5,6 @@ use serde::{Deserialize, Serialize};
 use std::any;
 use std::fmt;
 use std::hash::{self, BuildHasher, BuildHasherDefault, Hash as _, Hasher};
-use std::mem;
 use twox_hash::XxHash64;
 
 const SEP: u64 = 0x4bc94d6bd06053ad;
@@ -62,10 +61,13 @@ impl Hash {
     }
 
     /// Construct a hash from a type id.
-    pub const fn from_type_id(type_id: any::TypeId) -> Self {
-        // Safety: a type id is exactly a 64-bit unsigned integer.
-        // And has an identical bit pattern to `Hash`.
-        unsafe { mem::transmute(type_id) }
+    ///
+    /// A type id is wider than a hash on current toolchains and its layout is
+    /// unspecified, so it can't be transmuted into one in a `const fn` like it
+    /// used to be. Instead it's hashed, which gives the same value for the same
+    /// type for as long as the process runs.
+    pub fn from_type_id(type_id: any::TypeId) -> Self {
+        Self::of(type_id)
     }
 
     /// Construct a hash to an instance function, where the instance is a
@@ -55,6 +55,12 @@ pub enum PatKind<'hir> {
     PatObject(&'hir PatItems<'hir>),
     /// A binding `a: pattern` or `"foo": pattern`.
     PatBinding(&'hir PatBinding<'hir>),
+    /// An or-pattern `a | b`.
+    PatOr(&'hir [Pat<'hir>]),
+    /// A range pattern `a..b` or `a..=b`.
+    PatRange(&'hir PatRange<'hir>),
+    /// A binding pattern `name @ pattern`.
+    PatAt(&'hir PatAt<'hir>),
 }
 
 /// A tuple pattern.
@@ -81,6 +87,28 @@ pub struct PatBinding<'hir> {
     pub pat: &'hir Pat<'hir>,
 }
 
+/// A range pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PatRange<'hir> {
+    /// The start of the range.
+    pub start: &'hir Expr<'hir>,
+    /// The range limits.
+    pub limits: ExprRangeLimits,
+    /// The end of the range, if it's bounded.
+    pub end: Option<&'hir Expr<'hir>>,
+}
+
+/// A binding pattern `name @ pattern`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PatAt<'hir> {
+    /// The name being bound.
+    pub ident: &'hir ast::Ident,
+    /// The pattern the value has to match.
+    pub pat: &'hir Pat<'hir>,
+}
+
 /// An expression.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Spanned)]
 #[non_exhaustive]
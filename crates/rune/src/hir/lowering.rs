@@ -370,6 +370,13 @@ fn pat<'hir>(ctx: &Ctx<'hir, '_>, ast: &ast::Pat) -> Result<hir::Pat<'hir>, HirE
                     count,
                 }))
             }
+            // NB: a parenthesized or-pattern without a trailing comma like
+            // `(1 | 2)` is a group rather than a tuple with one element.
+            ast::Pat::PatTuple(ast::PatTuple {
+                path: None, items, ..
+            }) if matches!(&items.parenthesized[..], [(ast::Pat::PatOr(..), None)]) => {
+                return pat(ctx, &items.parenthesized[0].0);
+            }
             ast::Pat::PatTuple(ast) => {
                 let items = iter!(ctx, ast; &ast.items, |(ast, _)| pat(ctx, ast)?);
                 let (is_open, count) = pat_items_count(items)?;
@@ -398,6 +405,22 @@ fn pat<'hir>(ctx: &Ctx<'hir, '_>, ast: &ast::Pat) -> Result<hir::Pat<'hir>, HirE
                     pat: alloc!(ctx, ast; pat(ctx, &ast.pat)?),
                 }))
             }
+            ast::Pat::PatOr(ast) => {
+                let alternatives = ast.alternatives().collect::<Vec<_>>();
+                hir::PatKind::PatOr(iter!(ctx, ast; alternatives, |ast| pat(ctx, ast)?))
+            }
+            ast::Pat::PatRange(ast) => hir::PatKind::PatRange(alloc!(ctx, ast; hir::PatRange {
+                start: alloc!(ctx, ast; expr(ctx, &ast.start)?),
+                limits: match ast.limits {
+                    ast::ExprRangeLimits::HalfOpen(_) => hir::ExprRangeLimits::HalfOpen,
+                    ast::ExprRangeLimits::Closed(_) => hir::ExprRangeLimits::Closed,
+                },
+                end: option!(ctx, ast; &ast.end, |ast| expr(ctx, ast)?),
+            })),
+            ast::Pat::PatAt(ast) => hir::PatKind::PatAt(alloc!(ctx, ast; hir::PatAt {
+                ident: alloc!(ctx, ast; ast.ident),
+                pat: alloc!(ctx, ast; pat(ctx, &ast.pat)?),
+            })),
         },
     })
 }
@@ -3,8 +3,8 @@ use crate::ast::{OptionSpanned, Span, Spanned};
 use crate::collections::HashMap;
 use crate::compile::attrs::Attributes;
 use crate::compile::{
-    attrs, ir, CompileError, CompileErrorKind, CompileResult, Doc, ItemId, Location, ModId,
-    Options, SourceLoader, Visibility,
+    attrs, ir, CompileError, CompileErrorKind, CompileResult, Doc, ItemId, ItemMeta, Location,
+    ModId, Options, PrivTraitDefault, PrivTraitFn, SourceLoader, Visibility,
 };
 use crate::indexing::locals;
 use crate::indexing::{IndexFnKind, IndexScopes};
@@ -12,7 +12,7 @@ use crate::macros::MacroCompiler;
 use crate::parse::{Parse, ParseError, ParseErrorKind, Parser, Resolve};
 use crate::query::{
     BuiltInFile, BuiltInFormat, BuiltInLine, BuiltInMacro, BuiltInTemplate, Function, Indexed,
-    IndexedEntry, IndexedFunction, InstanceFunction, Query,
+    IndexedEntry, IndexedFunction, InstanceFunction, Query, TraitImpl,
 };
 use crate::runtime::format;
 use crate::runtime::Call;
@@ -641,58 +641,13 @@ fn item_fn(ast: &mut ast::ItemFn, idx: &mut Indexer<'_>) -> CompileResult<()> {
         &docs,
     )?;
 
-    let kind = match (ast.const_token, ast.async_token) {
-        (Some(const_token), Some(async_token)) => {
-            return Err(CompileError::new(
-                const_token.span().join(async_token.span()),
-                CompileErrorKind::FnConstAsyncConflict,
-            ));
-        }
-        (Some(..), _) => IndexFnKind::Const,
-        (_, Some(..)) => IndexFnKind::Async,
-        _ => IndexFnKind::None,
-    };
-
-    if let (Some(const_token), Some(async_token)) = (ast.const_token, ast.async_token) {
-        return Err(CompileError::new(
-            const_token.span().join(async_token.span()),
-            CompileErrorKind::FnConstAsyncConflict,
-        ));
-    }
-
-    let guard = idx.scopes.push_function(kind);
-
-    for (arg, _) in &mut ast.args {
-        match arg {
-            ast::FnArg::SelfValue(s) => {
-                let span = s.span();
-                idx.scopes.declare(SELF, span)?;
-            }
-            ast::FnArg::Pat(p) => {
-                locals::pat(p, idx)?;
-            }
-        }
-    }
-
-    // Take and restore item nesting.
-    let last = idx.nested_item.replace(ast.descriptive_span());
-    block(&mut ast.body, idx)?;
-    idx.nested_item = last;
-
-    let f = guard.into_function(span)?;
+    let call = fn_body(ast, idx)?;
     ast.id = item_meta.id;
 
-    let call = match Indexer::call(f.generator, f.kind) {
+    let call = match call {
         Some(call) => call,
         // const function.
         None => {
-            if f.generator {
-                return Err(CompileError::new(
-                    span,
-                    CompileErrorKind::FnConstNotGenerator,
-                ));
-            }
-
             idx.q.index_const_fn(item_meta, Box::new(ast.clone()))?;
             return Ok(());
         }
@@ -790,6 +745,54 @@ fn item_fn(ast: &mut ast::ItemFn, idx: &mut Indexer<'_>) -> CompileResult<()> {
     Ok(())
 }
 
+/// Index the arguments and body of a function, returning its calling
+/// convention or `None` if it's a `const fn`.
+fn fn_body(ast: &mut ast::ItemFn, idx: &mut Indexer<'_>) -> CompileResult<Option<Call>> {
+    let span = ast.span();
+
+    let kind = match (ast.const_token, ast.async_token) {
+        (Some(const_token), Some(async_token)) => {
+            return Err(CompileError::new(
+                const_token.span().join(async_token.span()),
+                CompileErrorKind::FnConstAsyncConflict,
+            ));
+        }
+        (Some(..), _) => IndexFnKind::Const,
+        (_, Some(..)) => IndexFnKind::Async,
+        _ => IndexFnKind::None,
+    };
+
+    let guard = idx.scopes.push_function(kind);
+
+    for (arg, _) in &mut ast.args {
+        match arg {
+            ast::FnArg::SelfValue(s) => {
+                let span = s.span();
+                idx.scopes.declare(SELF, span)?;
+            }
+            ast::FnArg::Pat(p) => {
+                locals::pat(p, idx)?;
+            }
+        }
+    }
+
+    // Take and restore item nesting.
+    let last = idx.nested_item.replace(ast.descriptive_span());
+    block(&mut ast.body, idx)?;
+    idx.nested_item = last;
+
+    let f = guard.into_function(span)?;
+
+    match Indexer::call(f.generator, f.kind) {
+        Some(call) => Ok(Some(call)),
+        None if f.generator => Err(CompileError::new(
+            span,
+            CompileErrorKind::FnConstNotGenerator,
+        )),
+        None => Ok(None),
+    }
+}
+
 #[instrument]
 fn expr_block(ast: &mut ast::ExprBlock, idx: &mut Indexer<'_>) -> CompileResult<()> {
     let span = ast.span();
@@ -981,14 +984,39 @@ fn pat(ast: &mut ast::Pat, idx: &mut Indexer<'_>, is_used: IsUsed) -> CompileRes
         ast::Pat::PatBinding(pat) => {
             pat_binding(pat, idx)?;
         }
+        ast::Pat::PatOr(pat) => {
+            pat_or(pat, idx, is_used)?;
+        }
+        ast::Pat::PatAt(pat) => {
+            pat_at(pat, idx)?;
+        }
         ast::Pat::PatIgnore(..) => (),
         ast::Pat::PatLit(..) => (),
+        ast::Pat::PatRange(..) => (),
         ast::Pat::PatRest(..) => (),
     }
 
     Ok(())
 }
 
+#[instrument]
+fn pat_or(ast: &mut ast::PatOr, idx: &mut Indexer<'_>, is_used: IsUsed) -> CompileResult<()> {
+    pat(&mut ast.first, idx, is_used)?;
+
+    for (_, p) in &mut ast.rest {
+        pat(p, idx, is_used)?;
+    }
+
+    Ok(())
+}
+
+#[instrument]
+fn pat_at(ast: &mut ast::PatAt, idx: &mut Indexer<'_>) -> CompileResult<()> {
+    declare(&mut ast.ident, idx)?;
+    pat(&mut ast.pat, idx, NOT_USED)?;
+    Ok(())
+}
+
 #[instrument]
 fn pat_tuple(ast: &mut ast::PatTuple, idx: &mut Indexer<'_>) -> CompileResult<()> {
     if let Some(p) = &mut ast.path {
@@ -1269,6 +1297,10 @@ fn item_enum(ast: &mut ast::ItemEnum, idx: &mut Indexer<'_>) -> CompileResult<()
             let name = field.name.resolve(ctx)?;
             let item = idx.items.item();
 
+            idx.q
+                .visitor
+                .visit_field(Location::new(idx.source_id, field.name.span()), &item, name);
+
             for doc in docs {
                 idx.q.visitor.visit_field_doc_comment(
                     Location::new(idx.source_id, doc.span),
@@ -1326,6 +1358,10 @@ fn item_struct(ast: &mut ast::ItemStruct, idx: &mut Indexer<'_>) -> CompileResul
         let name = field.name.resolve(ctx)?;
         let item = idx.items.item();
 
+        idx.q
+            .visitor
+            .visit_field(Location::new(idx.source_id, field.name.span()), &item, name);
+
         for doc in docs {
             idx.q.visitor.visit_field_doc_comment(
                 Location::new(idx.source_id, doc.span),
@@ -1371,6 +1407,12 @@ fn item_impl(ast: &mut ast::ItemImpl, idx: &mut Indexer<'_>) -> CompileResult<()
         ));
     }
 
+    // NB: the trait is resolved like any other path from the scope the impl
+    // block is declared in.
+    if let Some((path_ast, _)) = &mut ast.trait_ {
+        path(path_ast, idx, NOT_USED)?;
+    }
+
     let mut guards = Vec::new();
 
     if let Some(global) = &ast.path.global {
@@ -1396,6 +1438,141 @@ fn item_impl(ast: &mut ast::ItemImpl, idx: &mut Indexer<'_>) -> CompileResult<()
     }
 
     idx.impl_item = old;
+
+    if let Some((path, _)) = &ast.trait_ {
+        let mut provided = Vec::new();
+
+        for f in &ast.functions {
+            let name = f.name.resolve(resolve_context!(idx.q))?;
+            provided.push((name.into(), f.name.span()));
+        }
+
+        let item_meta = ItemMeta {
+            location: Location::new(idx.source_id, ast.span()),
+            item: new,
+            module: idx.mod_item,
+            ..Default::default()
+        };
+
+        idx.q.queue_trait_impl(
+            item_meta,
+            TraitImpl {
+                path: Box::new(path.clone()),
+                provided,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[instrument]
+fn item_trait(ast: &mut ast::ItemTrait, idx: &mut Indexer<'_>) -> CompileResult<()> {
+    let mut attrs = Attributes::new(ast.attributes.to_vec());
+    let docs = Doc::collect_from(resolve_context!(idx.q), &mut attrs)?;
+
+    if let Some(first) = attrs.remaining() {
+        return Err(CompileError::msg(
+            first,
+            "trait attributes are not supported",
+        ));
+    }
+
+    let name = ast.name.resolve(resolve_context!(idx.q))?;
+    let _guard = idx.items.push_name(name.as_ref());
+
+    let visibility = ast_to_visibility(&ast.visibility)?;
+    let item_meta = idx.q.insert_new_item(
+        &idx.items,
+        Location::new(idx.source_id, ast.span()),
+        idx.mod_item,
+        visibility,
+        &docs,
+    )?;
+    ast.id = item_meta.id;
+
+    // NB: `Self` in default implementations is indexed as the trait, and
+    // resolved to the implementing type when a default is compiled for it.
+    let old = idx.impl_item.replace(item_meta.item);
+    let mut functions = Vec::<PrivTraitFn>::new();
+
+    for trait_item in &mut ast.items {
+        let name: Box<str> = trait_item.name().resolve(resolve_context!(idx.q))?.into();
+
+        if functions.iter().any(|f| f.name == name) {
+            return Err(CompileError::msg(
+                trait_item.name(),
+                "function is already declared in this trait",
+            ));
+        }
+
+        let default = match trait_item {
+            ast::TraitItem::Decl(decl) => {
+                let mut attrs = Attributes::new(decl.attributes.to_vec());
+                Doc::collect_from(resolve_context!(idx.q), &mut attrs)?;
+
+                if let Some(first) = attrs.remaining() {
+                    return Err(CompileError::msg(
+                        first,
+                        "trait function attributes are not supported",
+                    ));
+                }
+
+                None
+            }
+            ast::TraitItem::Fn(f) => {
+                let span = f.span();
+
+                if !f.is_instance() {
+                    return Err(CompileError::msg(
+                        f.descriptive_span(),
+                        "default implementations are only supported for instance functions",
+                    ));
+                }
+                let mut attrs = Attributes::new(f.attributes.to_vec());
+                let docs = Doc::collect_from(resolve_context!(idx.q), &mut attrs)?;
+
+                if let Some(first) = attrs.remaining() {
+                    return Err(CompileError::msg(
+                        first,
+                        "trait function attributes are not supported",
+                    ));
+                }
+
+                let _guard = idx.items.push_name(name.as_ref());
+
+                let fn_meta = idx.q.insert_new_item(
+                    &idx.items,
+                    Location::new(idx.source_id, span),
+                    idx.mod_item,
+                    visibility,
+                    &docs,
+                )?;
+
+                let call = fn_body(f, idx)?.ok_or_else(|| {
+                    CompileError::msg(
+                        f.descriptive_span(),
+                        "const trait functions are not supported",
+                    )
+                })?;
+
+                f.id = fn_meta.id;
+
+                Some(PrivTraitDefault::Script {
+                    location: fn_meta.location,
+                    function: Function {
+                        ast: Box::new(f.clone()),
+                        call,
+                    },
+                })
+            }
+        };
+
+        functions.push(PrivTraitFn { name, default });
+    }
+
+    idx.impl_item = old;
+    idx.q.index_trait(item_meta, functions)?;
     Ok(())
 }
 
@@ -1501,6 +1678,9 @@ fn item(ast: &mut ast::Item, idx: &mut Indexer<'_>) -> CompileResult<()> {
         ast::Item::Impl(item) => {
             item_impl(item, idx)?;
         }
+        ast::Item::Trait(item) => {
+            item_trait(item, idx)?;
+        }
         ast::Item::Mod(item) => {
             item_mod(item, idx)?;
         }
@@ -24,8 +24,15 @@ pub(crate) fn pat(ast: &mut ast::Pat, idx: &mut Indexer<'_>) -> CompileResult<()
         ast::Pat::PatBinding(p) => {
             pat_binding(p, idx)?;
         }
+        ast::Pat::PatOr(p) => {
+            pat_or(p, idx)?;
+        }
+        ast::Pat::PatAt(p) => {
+            pat_at(p, idx)?;
+        }
         ast::Pat::PatIgnore(..) => (),
         ast::Pat::PatLit(..) => (),
+        ast::Pat::PatRange(..) => (),
         ast::Pat::PatRest(..) => (),
     }
 
@@ -103,3 +110,21 @@ fn pat_binding(ast: &mut ast::PatBinding, idx: &mut Indexer<'_>) -> CompileResul
     pat(&mut ast.pat, idx)?;
     Ok(())
 }
+
+#[instrument]
+fn pat_or(ast: &mut ast::PatOr, idx: &mut Indexer<'_>) -> CompileResult<()> {
+    pat(&mut ast.first, idx)?;
+
+    for (_, p) in &mut ast.rest {
+        pat(p, idx)?;
+    }
+
+    Ok(())
+}
+
+#[instrument]
+fn pat_at(ast: &mut ast::PatAt, idx: &mut Indexer<'_>) -> CompileResult<()> {
+    ident(&mut ast.ident, idx)?;
+    pat(&mut ast.pat, idx)?;
+    Ok(())
+}
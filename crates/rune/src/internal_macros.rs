@@ -165,6 +165,16 @@ macro_rules! cfg_emit {
     }
 }
 
+macro_rules! cfg_decimal {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "decimal")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "decimal")))]
+            $item
+        )*
+    }
+}
+
 macro_rules! cfg_workspace {
     ($($item:item)*) => {
         $(
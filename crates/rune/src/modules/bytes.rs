@@ -1,6 +1,6 @@
 //! `std::bytes` module.
 
-use crate::runtime::Bytes;
+use crate::runtime::{memory, Bytes, VmError};
 use crate::{ContextError, Module};
 
 /// Construct the `std::bytes` module.
@@ -9,7 +9,7 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.ty::<Bytes>()?;
     module.function(&["Bytes", "new"], Bytes::new)?;
-    module.function(&["Bytes", "with_capacity"], Bytes::with_capacity)?;
+    module.function(&["Bytes", "with_capacity"], with_capacity)?;
     module.function(&["Bytes", "from_vec"], Bytes::from_vec)?;
 
     module.inst_fn("into_vec", Bytes::into_vec)?;
@@ -21,9 +21,26 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("len", Bytes::len)?;
     module.inst_fn("capacity", Bytes::capacity)?;
     module.inst_fn("clear", Bytes::clear)?;
-    module.inst_fn("reserve", Bytes::reserve)?;
-    module.inst_fn("reserve_exact", Bytes::reserve_exact)?;
+    module.inst_fn("reserve", reserve)?;
+    module.inst_fn("reserve_exact", reserve_exact)?;
     module.inst_fn("clone", Bytes::clone)?;
     module.inst_fn("shrink_to_fit", Bytes::shrink_to_fit)?;
     Ok(module)
 }
+
+fn with_capacity(cap: usize) -> Result<Bytes, VmError> {
+    memory::ensure(cap)?;
+    Ok(Bytes::with_capacity(cap))
+}
+
+fn reserve(bytes: &mut Bytes, additional: usize) -> Result<(), VmError> {
+    memory::ensure(additional)?;
+    bytes.reserve(additional);
+    Ok(())
+}
+
+fn reserve_exact(bytes: &mut Bytes, additional: usize) -> Result<(), VmError> {
+    memory::ensure(additional)?;
+    bytes.reserve_exact(additional);
+    Ok(())
+}
@@ -0,0 +1,16 @@
+//! The `std::clone` module.
+
+use crate::runtime::{Value, VmError};
+use crate::{ContextError, Module};
+
+/// Construct the `std::clone` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["clone"]);
+    module.function(&["clone"], clone)?;
+    Ok(module)
+}
+
+/// Clone the given value, deeply unless it implements the clone protocol.
+fn clone(value: Value) -> Result<Value, VmError> {
+    Value::value_ptr_clone(&value)
+}
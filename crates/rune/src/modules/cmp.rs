@@ -1,12 +1,52 @@
 //! The `std::cmp` module.
 
+use crate::compile::Variant;
+use crate::runtime::{Protocol, Value, VmError};
 use crate::{ContextError, Module};
+use std::cmp::Ordering;
 
 /// Construct the `std::cmp` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["cmp"]);
 
-    module.ty::<std::cmp::Ordering>()?;
+    module.ty::<Ordering>()?;
+    module.enum_meta::<Ordering, 3>([
+        ("Less", Variant::unit()),
+        ("Equal", Variant::unit()),
+        ("Greater", Variant::unit()),
+    ])?;
+    module.variant_constructor(0, || Ordering::Less)?;
+    module.variant_constructor(1, || Ordering::Equal)?;
+    module.variant_constructor(2, || Ordering::Greater)?;
+    module.inst_fn(Protocol::IS_VARIANT, ordering_is_variant)?;
+    module.inst_fn(Protocol::EQ, |a: &Ordering, b: &Ordering| a == b)?;
+    module.inst_fn("reverse", Ordering::reverse)?;
+    module.inst_fn("then", Ordering::then)?;
 
+    module.function(&["max"], max)?;
+    module.function(&["min"], min)?;
     Ok(module)
 }
+
+fn ordering_is_variant(ordering: &Ordering, index: usize) -> bool {
+    matches!(
+        (ordering, index),
+        (Ordering::Less, 0) | (Ordering::Equal, 1) | (Ordering::Greater, 2)
+    )
+}
+
+/// Return the greater of two values, or the second one if they are equal.
+fn max(a: Value, b: Value) -> Result<Value, VmError> {
+    Ok(match Value::value_ptr_cmp(&a, &b)? {
+        Ordering::Greater => a,
+        _ => b,
+    })
+}
+
+/// Return the lesser of two values, or the first one if they are equal.
+fn min(a: Value, b: Value) -> Result<Value, VmError> {
+    Ok(match Value::value_ptr_cmp(&a, &b)? {
+        Ordering::Greater => b,
+        _ => a,
+    })
+}
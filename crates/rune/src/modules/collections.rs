@@ -1,20 +1,104 @@
 //! `std::collections` module.
 
-use crate::runtime::{Iterator, IteratorTrait, Key, Protocol, Ref, Value, VmError, VmErrorKind};
+use crate::runtime::{
+    memory, Iterator, IteratorTrait, Key, Protocol, Range, RangeLimits, Ref, Value, VmError,
+    VmErrorKind,
+};
 use crate::{Any, ContextError, Module};
+use hashbrown::hash_map::{DefaultHashBuilder, RawEntryMut};
+use std::cmp::Ordering;
 use std::fmt;
+use std::mem;
+use std::ops::Bound;
+
+/// A hash table of keys, which are told apart through [Key::value_ptr_eq].
+///
+/// Since that can fail, entries are looked up through the raw entry API rather
+/// than through the standard [Eq] implementation of keys.
+type Table<V> = crate::collections::HashMap<Key, V>;
+
+/// Calculate the hash of a key with the hasher of the given table.
+fn table_hash<V>(table: &Table<V>, key: &Key) -> u64 {
+    use std::hash::BuildHasher;
+    table.hasher().hash_one(key)
+}
+
+/// Get the entry for the given key.
+fn table_get<'a, V>(table: &'a Table<V>, key: &Key) -> Result<Option<&'a V>, VmError> {
+    let hash = table_hash(table, key);
+    let mut error = None;
+
+    let entry = table.raw_entry().from_hash(hash, |k| {
+        // NB: stop searching at the first error, which is raised below.
+        Key::value_ptr_eq(k, key).unwrap_or_else(|e| {
+            error = Some(e);
+            true
+        })
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(entry.map(|(_, value)| value)),
+    }
+}
+
+/// Get the mutable entry for the given key, along with the hash of the key.
+fn table_entry<'a, V>(
+    table: &'a mut Table<V>,
+    key: &Key,
+) -> Result<(u64, RawEntryMut<'a, Key, V, DefaultHashBuilder>), VmError> {
+    let hash = table_hash(table, key);
+    let mut error = None;
+
+    let entry = table.raw_entry_mut().from_hash(hash, |k| {
+        // NB: stop searching at the first error, which is raised below.
+        Key::value_ptr_eq(k, key).unwrap_or_else(|e| {
+            error = Some(e);
+            true
+        })
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok((hash, entry)),
+    }
+}
 
 #[derive(Any, Clone)]
 #[rune(module = "crate")]
-struct HashMap {
-    map: crate::collections::HashMap<Key, Value>,
+pub(crate) struct HashMap {
+    map: Table<Value>,
 }
 
 impl HashMap {
     fn new() -> Self {
-        Self {
-            map: crate::collections::HashMap::new(),
+        Self { map: Table::new() }
+    }
+
+    /// Clone the map along with the values in it.
+    fn deep_clone(&self) -> Result<Self, VmError> {
+        let mut map = Table::with_capacity(self.map.len());
+
+        for (key, value) in &self.map {
+            let value = Value::value_ptr_clone(value)?;
+            Key::checked(|| map.insert(key.clone(), value))?;
         }
+
+        Ok(Self { map })
+    }
+
+    /// Construct a hashmap from an iterator of key-value pairs.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        use crate::runtime::FromValue;
+
+        let mut map = Self::new();
+
+        while let Some(value) = it.next()? {
+            let (key, value) = <(Key, Value)>::from_value(value)?;
+            map.insert(key, value)?;
+        }
+
+        Ok(map)
     }
 
     /// Extend this hashmap from an iterator.
@@ -26,7 +110,7 @@ impl HashMap {
 
         while let Some(value) = it.next()? {
             let (key, value) = <(Key, Value)>::from_value(value)?;
-            self.map.insert(key, value);
+            self.insert(key, value)?;
         }
 
         Ok(())
@@ -51,37 +135,43 @@ impl HashMap {
     }
 
     #[inline]
-    fn contains_key(&self, key: Key) -> bool {
-        self.map.contains_key(&key)
+    fn contains_key(&self, key: Key) -> Result<bool, VmError> {
+        Ok(table_get(&self.map, &key)?.is_some())
     }
 
     #[inline]
-    fn index_set(&mut self, key: Key, value: Value) {
-        let _ = self.map.insert(key, value);
+    fn index_set(&mut self, key: Key, value: Value) -> Result<(), VmError> {
+        let _ = self.insert(key, value)?;
+        Ok(())
     }
 
     #[inline]
-    fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
-        self.map.insert(key, value)
+    fn insert(&mut self, key: Key, value: Value) -> Result<Option<Value>, VmError> {
+        Ok(match table_entry(&mut self.map, &key)? {
+            (_, RawEntryMut::Occupied(mut entry)) => Some(mem::replace(entry.get_mut(), value)),
+            (hash, RawEntryMut::Vacant(entry)) => {
+                entry.insert_hashed_nocheck(hash, key, value);
+                None
+            }
+        })
     }
 
     #[inline]
-    fn get(&self, key: Key) -> Option<Value> {
-        self.map.get(&key).cloned()
+    fn get(&self, key: Key) -> Result<Option<Value>, VmError> {
+        Ok(table_get(&self.map, &key)?.cloned())
     }
 
     #[inline]
     fn index_get(&self, key: Key) -> Result<Value, VmError> {
         use crate::runtime::TypeOf;
 
-        let value = self.map.get(&key).ok_or_else(|| {
-            VmError::from(VmErrorKind::MissingIndexKey {
+        match table_get(&self.map, &key)? {
+            Some(value) => Ok(value.clone()),
+            None => Err(VmError::from(VmErrorKind::MissingIndexKey {
                 target: Self::type_info(),
                 index: key,
-            })
-        })?;
-
-        Ok(value.clone())
+            })),
+        }
     }
 
     #[inline]
@@ -100,8 +190,12 @@ impl HashMap {
     }
 
     #[inline]
-    fn remove(&mut self, key: Key) {
-        self.map.remove(&key);
+    fn remove(&mut self, key: Key) -> Result<(), VmError> {
+        if let (_, RawEntryMut::Occupied(entry)) = table_entry(&mut self.map, &key)? {
+            entry.remove();
+        }
+
+        Ok(())
     }
 
     #[inline]
@@ -111,17 +205,30 @@ impl HashMap {
     }
 }
 
+/// A hash set of keys.
+///
+/// Keys are copies of the values inserted into the set, so cloning the set
+/// copies everything in it like cloning any other collection does.
 #[derive(Any, Clone)]
 #[rune(module = "crate")]
-struct HashSet {
-    set: crate::collections::HashSet<Key>,
+pub(crate) struct HashSet {
+    set: Table<()>,
 }
 
 impl HashSet {
     fn new() -> Self {
-        Self {
-            set: crate::collections::HashSet::new(),
+        Self { set: Table::new() }
+    }
+
+    /// Construct a set from an iterator of keys.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        let mut set = Self::new();
+
+        while let Some(value) = it.next()? {
+            set.insert(Key::from_value(&value)?)?;
         }
+
+        Ok(set)
     }
 
     /// Extend this set from an iterator.
@@ -131,26 +238,36 @@ impl HashSet {
 
         while let Some(value) = it.next()? {
             let key = Key::from_value(&value)?;
-            self.set.insert(key);
+            self.insert(key)?;
         }
 
         Ok(())
     }
 
+    /// Get the keys of the set.
+    fn keys(&self) -> std::vec::IntoIter<Key> {
+        self.set.keys().cloned().collect::<Vec<_>>().into_iter()
+    }
+
     #[inline]
     fn iter(&self) -> Iterator {
-        let iter = self.set.clone().into_iter();
-        Iterator::from("std::collections::set::Iter", iter)
+        Iterator::from("std::collections::set::Iter", self.keys())
     }
 
     #[inline]
-    fn insert(&mut self, key: Key) -> bool {
-        self.set.insert(key)
+    fn insert(&mut self, key: Key) -> Result<bool, VmError> {
+        Ok(match table_entry(&mut self.set, &key)? {
+            (_, RawEntryMut::Occupied(..)) => false,
+            (hash, RawEntryMut::Vacant(entry)) => {
+                entry.insert_hashed_nocheck(hash, key, ());
+                true
+            }
+        })
     }
 
     #[inline]
-    fn contains(&self, key: Key) -> bool {
-        self.set.contains(&key)
+    fn contains(&self, key: Key) -> Result<bool, VmError> {
+        Ok(table_get(&self.set, &key)?.is_some())
     }
 
     #[inline]
@@ -169,8 +286,12 @@ impl HashSet {
     }
 
     #[inline]
-    fn remove(&mut self, key: Key) {
-        self.set.remove(&key);
+    fn remove(&mut self, key: Key) -> Result<(), VmError> {
+        if let (_, RawEntryMut::Occupied(entry)) = table_entry(&mut self.set, &key)? {
+            entry.remove();
+        }
+
+        Ok(())
     }
 
     #[inline]
@@ -178,7 +299,7 @@ impl HashSet {
         Iterator::from(
             "std::collections::set::Difference",
             Difference {
-                this: self.set.clone().into_iter(),
+                this: self.keys(),
                 other: Some(other),
             },
         )
@@ -189,12 +310,12 @@ impl HashSet {
         // use shortest iterator as driver for intersections
         let intersection = if zelf.len() <= other.len() {
             Intersection {
-                this: zelf.set.clone().into_iter(),
+                this: zelf.keys(),
                 other: Some(other),
             }
         } else {
             Intersection {
-                this: other.set.clone().into_iter(),
+                this: other.keys(),
                 other: Some(zelf),
             }
         };
@@ -218,79 +339,91 @@ impl HashSet {
     #[inline]
     fn string_debug(&self, s: &mut String) -> fmt::Result {
         use std::fmt::Write;
-        write!(s, "{:?}", self.set)
+
+        write!(s, "{{")?;
+
+        let mut it = self.set.keys().peekable();
+
+        while let Some(key) = it.next() {
+            write!(s, "{:?}", key)?;
+
+            if it.peek().is_some() {
+                write!(s, ", ")?;
+            }
+        }
+
+        write!(s, "}}")
     }
 
     #[inline]
-    fn eq(&self, other: &Self) -> bool {
-        self.set == other.set
+    fn eq(&self, other: &Self) -> Result<bool, VmError> {
+        if self.set.len() != other.set.len() {
+            return Ok(false);
+        }
+
+        for key in self.set.keys() {
+            if table_get(&other.set, key)?.is_none() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
     }
 }
 
-struct Intersection<I>
-where
-    I: std::iter::Iterator<Item = Key>,
-{
-    this: I,
+struct Intersection {
+    this: std::vec::IntoIter<Key>,
     other: Option<Ref<HashSet>>,
 }
 
-impl<I> std::iter::Iterator for Intersection<I>
-where
-    I: std::iter::Iterator<Item = Key>,
-{
-    type Item = Key;
-    fn next(&mut self) -> Option<Self::Item> {
-        let other = self.other.take()?;
-
-        loop {
-            let item = self.this.next()?;
+impl IteratorTrait for Intersection {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let other = match self.other.take() {
+            Some(other) => other,
+            None => return Ok(None),
+        };
 
-            if other.set.contains(&item) {
+        for item in self.this.by_ref() {
+            if table_get(&other.set, &item)?.is_some() {
                 self.other = Some(other);
-                return Some(item);
+                return Ok(Some(item.into_value()));
             }
         }
+
+        Ok(None)
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let (_, upper) = self.this.size_hint();
-        (0, upper)
+        (0, Some(self.this.len()))
     }
 }
 
-struct Difference<I>
-where
-    I: std::iter::Iterator<Item = Key>,
-{
-    this: I,
+struct Difference {
+    this: std::vec::IntoIter<Key>,
     other: Option<Ref<HashSet>>,
 }
 
-impl<I> std::iter::Iterator for Difference<I>
-where
-    I: std::iter::Iterator<Item = Key>,
-{
-    type Item = Key;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let other = self.other.take()?;
-
-        loop {
-            let item = self.this.next()?;
+impl IteratorTrait for Difference {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let other = match self.other.take() {
+            Some(other) => other,
+            None => return Ok(None),
+        };
 
-            if !other.set.contains(&item) {
+        for item in self.this.by_ref() {
+            if table_get(&other.set, &item)?.is_none() {
                 self.other = Some(other);
-                return Some(item);
+                return Ok(Some(item.into_value()));
             }
         }
+
+        Ok(None)
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let (_, upper) = self.this.size_hint();
-        (0, upper)
+        (0, Some(self.this.len()))
     }
 }
 
@@ -310,7 +443,7 @@ impl IteratorTrait for Union {
 
 #[derive(Any, Clone, Default)]
 #[rune(module = "crate")]
-struct VecDeque {
+pub(crate) struct VecDeque {
     inner: std::collections::VecDeque<Value>,
 }
 
@@ -319,10 +452,34 @@ impl VecDeque {
         Default::default()
     }
 
-    fn with_capacity(count: usize) -> VecDeque {
-        Self {
+    /// Clone the deque along with the values in it.
+    fn deep_clone(&self) -> Result<Self, VmError> {
+        let inner = self
+            .inner
+            .iter()
+            .map(Value::value_ptr_clone)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { inner })
+    }
+
+    fn with_capacity(count: usize) -> Result<VecDeque, VmError> {
+        memory::ensure(count.saturating_mul(mem::size_of::<Value>()))?;
+
+        Ok(Self {
             inner: std::collections::VecDeque::with_capacity(count),
+        })
+    }
+
+    /// Construct a VecDeque from an iterator.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        let mut cont = Self::with_capacity(it.capacity_hint())?;
+
+        while let Some(value) = it.next()? {
+            cont.push_back(value);
         }
+
+        Ok(cont)
     }
 
     /// Extend this VecDeque with something that implements the into_iter
@@ -411,6 +568,580 @@ impl VecDeque {
     }
 }
 
+/// An ordered map of keys.
+///
+/// Keys are ordered through [Key::value_ptr_cmp], so script and native types
+/// take part through the [Protocol::CMP] protocol. Since that can fail, every
+/// operation which compares keys does so inside of [Key::checked], and only
+/// modifies the map once its keys have been compared without errors.
+#[derive(Any, Clone, Default)]
+#[rune(module = "crate")]
+pub(crate) struct BTreeMap {
+    map: std::collections::BTreeMap<Key, Value>,
+}
+
+impl BTreeMap {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    /// Clone the map along with the values in it.
+    fn deep_clone(&self) -> Result<Self, VmError> {
+        let mut map = std::collections::BTreeMap::new();
+
+        for (key, value) in &self.map {
+            let value = Value::value_ptr_clone(value)?;
+            Key::checked(|| map.insert(key.clone(), value))?;
+        }
+
+        Ok(Self { map })
+    }
+
+    /// Construct a btree map from an iterator of key-value pairs.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        use crate::runtime::FromValue;
+
+        let mut map = Self::new();
+
+        while let Some(value) = it.next()? {
+            let (key, value) = <(Key, Value)>::from_value(value)?;
+            map.insert(key, value)?;
+        }
+
+        Ok(map)
+    }
+
+    /// Extend this btree map from an iterator.
+    #[inline]
+    fn extend(&mut self, value: Value) -> Result<(), VmError> {
+        use crate::runtime::FromValue;
+
+        let mut it = value.into_iter()?;
+
+        while let Some(value) = it.next()? {
+            let (key, value) = <(Key, Value)>::from_value(value)?;
+            self.insert(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn iter(&self) -> Iterator {
+        let iter = self
+            .map
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>()
+            .into_iter();
+        Iterator::from_double_ended("std::collections::btree_map::Iter", iter)
+    }
+
+    #[inline]
+    fn keys(&self) -> Iterator {
+        let iter = self.map.keys().cloned().collect::<Vec<_>>().into_iter();
+        Iterator::from_double_ended("std::collections::btree_map::Keys", iter)
+    }
+
+    #[inline]
+    fn values(&self) -> Iterator {
+        let iter = self.map.values().cloned().collect::<Vec<_>>().into_iter();
+        Iterator::from_double_ended("std::collections::btree_map::Values", iter)
+    }
+
+    /// Iterate over the key-value pairs whose keys fall inside of the given
+    /// range, like `map.range(1..10)`.
+    fn range(&self, range: &Range) -> Result<Iterator, VmError> {
+        let entries = match key_bounds(range)? {
+            Some(bounds) => Key::checked(|| {
+                self.map
+                    .range(bounds)
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })?,
+            None => Vec::new(),
+        };
+
+        Ok(Iterator::from_double_ended(
+            "std::collections::btree_map::Range",
+            entries.into_iter(),
+        ))
+    }
+
+    #[inline]
+    fn contains_key(&self, key: Key) -> Result<bool, VmError> {
+        Key::checked(|| self.map.contains_key(&key))
+    }
+
+    #[inline]
+    fn index_set(&mut self, key: Key, value: Value) -> Result<(), VmError> {
+        let _ = self.insert(key, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Key, value: Value) -> Result<Option<Value>, VmError> {
+        use std::collections::btree_map::Entry;
+
+        // NB: an entry found through a failed comparison is dropped unused.
+        Ok(match Key::checked(|| self.map.entry(key))? {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        })
+    }
+
+    #[inline]
+    fn get(&self, key: Key) -> Result<Option<Value>, VmError> {
+        Key::checked(|| self.map.get(&key).cloned())
+    }
+
+    #[inline]
+    fn index_get(&self, key: Key) -> Result<Value, VmError> {
+        use crate::runtime::TypeOf;
+
+        match self.get(key.clone())? {
+            Some(value) => Ok(value),
+            None => Err(VmError::from(VmErrorKind::MissingIndexKey {
+                target: Self::type_info(),
+                index: key,
+            })),
+        }
+    }
+
+    #[inline]
+    fn first_key_value(&self) -> Option<(Key, Value)> {
+        let (key, value) = self.map.first_key_value()?;
+        Some((key.clone(), value.clone()))
+    }
+
+    #[inline]
+    fn last_key_value(&self) -> Option<(Key, Value)> {
+        let (key, value) = self.map.last_key_value()?;
+        Some((key.clone(), value.clone()))
+    }
+
+    #[inline]
+    fn pop_first(&mut self) -> Option<(Key, Value)> {
+        self.map.pop_first()
+    }
+
+    #[inline]
+    fn pop_last(&mut self) -> Option<(Key, Value)> {
+        self.map.pop_last()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    #[inline]
+    fn remove(&mut self, key: Key) -> Result<Option<Value>, VmError> {
+        // NB: a failed comparison is treated as equal keys, so the key is
+        // looked up before anything is removed.
+        match Key::checked(|| self.map.contains_key(&key))? {
+            true => Ok(self.map.remove(&key)),
+            false => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write;
+
+        write!(s, "{{")?;
+
+        let mut it = self.map.iter().peekable();
+
+        while let Some((key, value)) = it.next() {
+            write!(s, "{:?}: {:?}", key, value)?;
+
+            if it.peek().is_some() {
+                write!(s, ", ")?;
+            }
+        }
+
+        write!(s, "}}")
+    }
+
+    /// Test if two maps are equal, comparing their values through the
+    /// [Protocol::EQ] protocol.
+    #[inline]
+    fn eq(&self, other: &Self) -> Result<bool, VmError> {
+        if self.map.len() != other.map.len() {
+            return Ok(false);
+        }
+
+        for ((a, x), (b, y)) in self.map.iter().zip(other.map.iter()) {
+            if !Key::value_ptr_eq(a, b)? || !Value::value_ptr_eq_env(x, y)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// An ordered set of keys.
+///
+/// Keys are ordered like the keys of a [BTreeMap]. Like for a [HashSet],
+/// cloning the set copies everything in it.
+#[derive(Any, Clone, Default)]
+#[rune(module = "crate")]
+pub(crate) struct BTreeSet {
+    set: std::collections::BTreeSet<Key>,
+}
+
+impl BTreeSet {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    /// Construct a btree set from an iterator of keys.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        let mut set = Self::new();
+
+        while let Some(value) = it.next()? {
+            set.insert(Key::from_value(&value)?)?;
+        }
+
+        Ok(set)
+    }
+
+    /// Extend this btree set from an iterator.
+    #[inline]
+    fn extend(&mut self, value: Value) -> Result<(), VmError> {
+        let mut it = value.into_iter()?;
+
+        while let Some(value) = it.next()? {
+            let key = Key::from_value(&value)?;
+            self.insert(key)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn iter(&self) -> Iterator {
+        let iter = self.set.iter().cloned().collect::<Vec<_>>().into_iter();
+        Iterator::from_double_ended("std::collections::btree_set::Iter", iter)
+    }
+
+    /// Iterate over the keys which fall inside of the given range, like
+    /// `set.range(1..10)`.
+    fn range(&self, range: &Range) -> Result<Iterator, VmError> {
+        let keys = match key_bounds(range)? {
+            Some(bounds) => Key::checked(|| self.set.range(bounds).cloned().collect::<Vec<_>>())?,
+            None => Vec::new(),
+        };
+
+        Ok(Iterator::from_double_ended(
+            "std::collections::btree_set::Range",
+            keys.into_iter(),
+        ))
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Key) -> Result<bool, VmError> {
+        // NB: a failed comparison is treated as equal keys, so the key is
+        // looked up before it's inserted.
+        match Key::checked(|| self.set.contains(&key))? {
+            true => Ok(false),
+            false => Ok(self.set.insert(key)),
+        }
+    }
+
+    #[inline]
+    fn contains(&self, key: Key) -> Result<bool, VmError> {
+        Key::checked(|| self.set.contains(&key))
+    }
+
+    #[inline]
+    fn first(&self) -> Option<Key> {
+        self.set.first().cloned()
+    }
+
+    #[inline]
+    fn last(&self) -> Option<Key> {
+        self.set.last().cloned()
+    }
+
+    #[inline]
+    fn pop_first(&mut self) -> Option<Key> {
+        self.set.pop_first()
+    }
+
+    #[inline]
+    fn pop_last(&mut self) -> Option<Key> {
+        self.set.pop_last()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.set.clear()
+    }
+
+    #[inline]
+    fn remove(&mut self, key: Key) -> Result<bool, VmError> {
+        match Key::checked(|| self.set.contains(&key))? {
+            true => Ok(self.set.remove(&key)),
+            false => Ok(false),
+        }
+    }
+
+    #[inline]
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write;
+
+        write!(s, "{{")?;
+
+        let mut it = self.set.iter().peekable();
+
+        while let Some(key) = it.next() {
+            write!(s, "{:?}", key)?;
+
+            if it.peek().is_some() {
+                write!(s, ", ")?;
+            }
+        }
+
+        write!(s, "}}")
+    }
+
+    #[inline]
+    fn eq(&self, other: &Self) -> Result<bool, VmError> {
+        if self.set.len() != other.set.len() {
+            return Ok(false);
+        }
+
+        for (a, b) in self.set.iter().zip(other.set.iter()) {
+            if Key::value_ptr_cmp(a, b)? != Ordering::Equal {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Convert a range into the bounds of the keys that fall inside of it.
+///
+/// Returns `None` if the start of the range comes after its end, where the
+/// standard btree collections would panic.
+fn key_bounds(range: &Range) -> Result<Option<(Bound<Key>, Bound<Key>)>, VmError> {
+    let start = match &range.start {
+        Some(start) => Some(Key::from_value(start)?),
+        None => None,
+    };
+
+    let end = match &range.end {
+        Some(end) => Some(Key::from_value(end)?),
+        None => None,
+    };
+
+    if let (Some(start), Some(end)) = (&start, &end) {
+        if Key::value_ptr_cmp(start, end)? == Ordering::Greater {
+            return Ok(None);
+        }
+    }
+
+    let end = match (end, range.limits) {
+        (Some(end), RangeLimits::Closed) => Bound::Included(end),
+        (Some(end), RangeLimits::HalfOpen) => Bound::Excluded(end),
+        (None, _) => Bound::Unbounded,
+    };
+
+    Ok(Some((start.map_or(Bound::Unbounded, Bound::Included), end)))
+}
+
+/// A priority queue implemented as a max-heap, where the greatest value is
+/// popped first.
+///
+/// Values are ordered like when sorting, so native and script types take part
+/// through the [Protocol::CMP] protocol.
+#[derive(Any, Clone, Default)]
+#[rune(module = "crate")]
+pub(crate) struct BinaryHeap {
+    heap: Vec<Value>,
+}
+
+impl BinaryHeap {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    /// Clone the heap along with the values in it.
+    fn deep_clone(&self) -> Result<Self, VmError> {
+        let heap = self
+            .heap
+            .iter()
+            .map(Value::value_ptr_clone)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { heap })
+    }
+
+    fn with_capacity(count: usize) -> Result<Self, VmError> {
+        memory::ensure(count.saturating_mul(mem::size_of::<Value>()))?;
+
+        Ok(Self {
+            heap: Vec::with_capacity(count),
+        })
+    }
+
+    /// Construct a binary heap from an iterator.
+    pub(crate) fn from_iter(mut it: Iterator) -> Result<Self, VmError> {
+        let mut heap = Self::with_capacity(it.capacity_hint())?;
+
+        while let Some(value) = it.next()? {
+            heap.push(value)?;
+        }
+
+        Ok(heap)
+    }
+
+    /// Extend this binary heap with something that implements the into_iter
+    /// protocol.
+    fn extend(&mut self, value: Value) -> Result<(), VmError> {
+        let mut it = value.into_iter()?;
+
+        while let Some(value) = it.next()? {
+            self.push(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, value: Value) -> Result<(), VmError> {
+        memory::ensure_push::<Value>(self.heap.len(), self.heap.capacity())?;
+        self.heap.push(value);
+        self.sift_up(self.heap.len() - 1)
+    }
+
+    fn pop(&mut self) -> Result<Option<Value>, VmError> {
+        let mut value = match self.heap.pop() {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        if !self.heap.is_empty() {
+            std::mem::swap(&mut value, &mut self.heap[0]);
+            self.sift_down(0)?;
+        }
+
+        Ok(Some(value))
+    }
+
+    fn peek(&self) -> Option<Value> {
+        self.heap.first().cloned()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Iterate over the values of the heap in an arbitrary order.
+    #[inline]
+    fn iter(&self) -> Iterator {
+        let iter = self.heap.clone().into_iter();
+        Iterator::from("std::collections::binary_heap::Iter", iter)
+    }
+
+    /// Get the values of the heap sorted in ascending order.
+    fn into_sorted_vec(mut self) -> Result<crate::runtime::Vec, VmError> {
+        let mut values = Vec::with_capacity(self.len());
+
+        while let Some(value) = self.pop()? {
+            values.push(value);
+        }
+
+        values.reverse();
+        Ok(crate::runtime::Vec::from(values))
+    }
+
+    #[inline]
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write;
+        write!(s, "{:?}", self.heap)
+    }
+
+    /// Move the value at the given position up until its parent is greater
+    /// than or equal to it.
+    fn sift_up(&mut self, mut pos: usize) -> Result<(), VmError> {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+
+            if Value::value_ptr_cmp(&self.heap[pos], &self.heap[parent])? != Ordering::Greater {
+                break;
+            }
+
+            self.heap.swap(pos, parent);
+            pos = parent;
+        }
+
+        Ok(())
+    }
+
+    /// Move the value at the given position down until both of its children
+    /// are less than or equal to it.
+    fn sift_down(&mut self, mut pos: usize) -> Result<(), VmError> {
+        loop {
+            let mut child = 2 * pos + 1;
+
+            if child >= self.heap.len() {
+                break;
+            }
+
+            if child + 1 < self.heap.len()
+                && Value::value_ptr_cmp(&self.heap[child + 1], &self.heap[child])?
+                    == Ordering::Greater
+            {
+                child += 1;
+            }
+
+            if Value::value_ptr_cmp(&self.heap[child], &self.heap[pos])? != Ordering::Greater {
+                break;
+            }
+
+            self.heap.swap(pos, child);
+            pos = child;
+        }
+
+        Ok(())
+    }
+}
+
 /// The `std::collections` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["collections"]);
@@ -418,7 +1149,7 @@ pub fn module() -> Result<Module, ContextError> {
     module.function(&["HashMap", "new"], HashMap::new)?;
     module.function(&["HashMap", "from"], hashmap_from)?;
     module.inst_fn("clear", HashMap::clear)?;
-    module.inst_fn("clone", HashMap::clone)?;
+    module.inst_fn(Protocol::CLONE, HashMap::deep_clone)?;
     module.inst_fn("contains_key", HashMap::contains_key)?;
     module.inst_fn("extend", HashMap::extend)?;
     module.inst_fn("get", HashMap::get)?;
@@ -438,7 +1169,7 @@ pub fn module() -> Result<Module, ContextError> {
     module.function(&["HashSet", "new"], HashSet::new)?;
     module.function(&["HashSet", "from"], hashset_from)?;
     module.inst_fn("clear", HashSet::clear)?;
-    module.inst_fn("clone", HashSet::clone)?;
+    module.inst_fn(Protocol::CLONE, HashSet::clone)?;
     module.inst_fn("contains", HashSet::contains)?;
     module.inst_fn("difference", HashSet::difference)?;
     module.inst_fn("extend", HashSet::extend)?;
@@ -470,46 +1201,99 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("reserve", VecDeque::reserve)?;
     module.inst_fn("rotate_left", VecDeque::rotate_left)?;
     module.inst_fn("rotate_right", VecDeque::rotate_right)?;
+    module.inst_fn(Protocol::CLONE, VecDeque::deep_clone)?;
     module.inst_fn(Protocol::INDEX_GET, VecDeque::get)?;
     module.inst_fn(Protocol::INDEX_SET, VecDeque::set)?;
     module.inst_fn(Protocol::INTO_ITER, VecDeque::iter)?;
     module.inst_fn(Protocol::STRING_DEBUG, VecDeque::string_debug)?;
 
+    module.ty::<BTreeMap>()?;
+    module.function(&["BTreeMap", "new"], BTreeMap::new)?;
+    module.function(&["BTreeMap", "from"], btreemap_from)?;
+    module.inst_fn("clear", BTreeMap::clear)?;
+    module.inst_fn(Protocol::CLONE, BTreeMap::deep_clone)?;
+    module.inst_fn("contains_key", BTreeMap::contains_key)?;
+    module.inst_fn("extend", BTreeMap::extend)?;
+    module.inst_fn("first_key_value", BTreeMap::first_key_value)?;
+    module.inst_fn("get", BTreeMap::get)?;
+    module.inst_fn("insert", BTreeMap::insert)?;
+    module.inst_fn("is_empty", BTreeMap::is_empty)?;
+    module.inst_fn("iter", BTreeMap::iter)?;
+    module.inst_fn("keys", BTreeMap::keys)?;
+    module.inst_fn("last_key_value", BTreeMap::last_key_value)?;
+    module.inst_fn("len", BTreeMap::len)?;
+    module.inst_fn("pop_first", BTreeMap::pop_first)?;
+    module.inst_fn("pop_last", BTreeMap::pop_last)?;
+    module.inst_fn("range", BTreeMap::range)?;
+    module.inst_fn("remove", BTreeMap::remove)?;
+    module.inst_fn("values", BTreeMap::values)?;
+    module.inst_fn(Protocol::INTO_ITER, BTreeMap::iter)?;
+    module.inst_fn(Protocol::INDEX_SET, BTreeMap::index_set)?;
+    module.inst_fn(Protocol::INDEX_GET, BTreeMap::index_get)?;
+    module.inst_fn(Protocol::STRING_DEBUG, BTreeMap::string_debug)?;
+    module.inst_fn(Protocol::EQ, BTreeMap::eq)?;
+
+    module.ty::<BTreeSet>()?;
+    module.function(&["BTreeSet", "new"], BTreeSet::new)?;
+    module.function(&["BTreeSet", "from"], btreeset_from)?;
+    module.inst_fn("clear", BTreeSet::clear)?;
+    module.inst_fn(Protocol::CLONE, BTreeSet::clone)?;
+    module.inst_fn("contains", BTreeSet::contains)?;
+    module.inst_fn("extend", BTreeSet::extend)?;
+    module.inst_fn("first", BTreeSet::first)?;
+    module.inst_fn("insert", BTreeSet::insert)?;
+    module.inst_fn("is_empty", BTreeSet::is_empty)?;
+    module.inst_fn("iter", BTreeSet::iter)?;
+    module.inst_fn("last", BTreeSet::last)?;
+    module.inst_fn("len", BTreeSet::len)?;
+    module.inst_fn("pop_first", BTreeSet::pop_first)?;
+    module.inst_fn("pop_last", BTreeSet::pop_last)?;
+    module.inst_fn("range", BTreeSet::range)?;
+    module.inst_fn("remove", BTreeSet::remove)?;
+    module.inst_fn(Protocol::INTO_ITER, BTreeSet::iter)?;
+    module.inst_fn(Protocol::STRING_DEBUG, BTreeSet::string_debug)?;
+    module.inst_fn(Protocol::EQ, BTreeSet::eq)?;
+
+    module.ty::<BinaryHeap>()?;
+    module.function(&["BinaryHeap", "new"], BinaryHeap::new)?;
+    module.function(&["BinaryHeap", "with_capacity"], BinaryHeap::with_capacity)?;
+    module.function(&["BinaryHeap", "from"], binaryheap_from)?;
+    module.inst_fn("clear", BinaryHeap::clear)?;
+    module.inst_fn(Protocol::CLONE, BinaryHeap::deep_clone)?;
+    module.inst_fn("extend", BinaryHeap::extend)?;
+    module.inst_fn("into_sorted_vec", BinaryHeap::into_sorted_vec)?;
+    module.inst_fn("is_empty", BinaryHeap::is_empty)?;
+    module.inst_fn("iter", BinaryHeap::iter)?;
+    module.inst_fn("len", BinaryHeap::len)?;
+    module.inst_fn("peek", BinaryHeap::peek)?;
+    module.inst_fn("pop", BinaryHeap::pop)?;
+    module.inst_fn("push", BinaryHeap::push)?;
+    module.inst_fn(Protocol::INTO_ITER, BinaryHeap::iter)?;
+    module.inst_fn(Protocol::STRING_DEBUG, BinaryHeap::string_debug)?;
+
     Ok(module)
 }
 
 fn hashmap_from(value: Value) -> Result<HashMap, VmError> {
-    use crate::runtime::FromValue;
-
-    let mut map = HashMap::new();
-    let mut it = value.into_iter()?;
-
-    while let Some(value) = it.next()? {
-        let (key, value) = <(Key, Value)>::from_value(value)?;
-        map.insert(key, value);
-    }
-
-    Ok(map)
+    HashMap::from_iter(value.into_iter()?)
 }
 
 fn vecdeque_from(value: Value) -> Result<VecDeque, VmError> {
-    let mut cont = VecDeque::new();
-    let mut it = value.into_iter()?;
-
-    while let Some(value) = it.next()? {
-        cont.push_back(value);
-    }
-
-    Ok(cont)
+    VecDeque::from_iter(value.into_iter()?)
 }
 
 fn hashset_from(value: Value) -> Result<HashSet, VmError> {
-    let mut set = HashSet::new();
-    let mut it = value.into_iter()?;
+    HashSet::from_iter(value.into_iter()?)
+}
 
-    while let Some(value) = it.next()? {
-        set.insert(Key::from_value(&value)?);
-    }
+fn btreemap_from(value: Value) -> Result<BTreeMap, VmError> {
+    BTreeMap::from_iter(value.into_iter()?)
+}
+
+fn btreeset_from(value: Value) -> Result<BTreeSet, VmError> {
+    BTreeSet::from_iter(value.into_iter()?)
+}
 
-    Ok(set)
+fn binaryheap_from(value: Value) -> Result<BinaryHeap, VmError> {
+    BinaryHeap::from_iter(value.into_iter()?)
 }
@@ -0,0 +1,319 @@
+//! The `std::decimal` module.
+//!
+//! Decimals are serialized as a map with a single entry holding the decimal as
+//! a string, like `{"$decimal": "1.5"}`, so that they don't lose any precision
+//! and are turned back into decimals when deserialized.
+
+use crate::compile::Variant;
+use crate::runtime::{Object, Protocol, ToValue, TypeOf, Value, VmError, VmErrorKind};
+use crate::{ContextError, Module};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Write};
+use std::hash::{Hash as _, Hasher as _};
+
+/// The tag of the map which decimals are serialized as.
+const TAG: &str = "$decimal";
+
+/// Construct the `std::decimal` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["decimal"]);
+
+    module.ty::<rust_decimal::Error>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, format_decimal_error)?;
+
+    module.ty::<RoundingStrategy>()?;
+    module.enum_meta::<RoundingStrategy, 7>([
+        ("MidpointNearestEven", Variant::unit()),
+        ("MidpointAwayFromZero", Variant::unit()),
+        ("MidpointTowardZero", Variant::unit()),
+        ("ToZero", Variant::unit()),
+        ("AwayFromZero", Variant::unit()),
+        ("ToNegativeInfinity", Variant::unit()),
+        ("ToPositiveInfinity", Variant::unit()),
+    ])?;
+    module.variant_constructor(0, || RoundingStrategy::MidpointNearestEven)?;
+    module.variant_constructor(1, || RoundingStrategy::MidpointAwayFromZero)?;
+    module.variant_constructor(2, || RoundingStrategy::MidpointTowardZero)?;
+    module.variant_constructor(3, || RoundingStrategy::ToZero)?;
+    module.variant_constructor(4, || RoundingStrategy::AwayFromZero)?;
+    module.variant_constructor(5, || RoundingStrategy::ToNegativeInfinity)?;
+    module.variant_constructor(6, || RoundingStrategy::ToPositiveInfinity)?;
+    module.inst_fn(Protocol::IS_VARIANT, rounding_strategy_is_variant)?;
+    module.inst_fn(
+        Protocol::EQ,
+        |a: &RoundingStrategy, b: &RoundingStrategy| a == b,
+    )?;
+
+    module.ty::<Decimal>()?;
+    module.function(&["Decimal", "new"], new)?;
+    module.function(&["Decimal", "parse"], parse)?;
+    module.function(&["Decimal", "from_int"], |n: i64| Decimal::from(n))?;
+    module.function(&["Decimal", "from_float"], Decimal::from_f64)?;
+
+    module.inst_fn("to_int", |d: &Decimal| d.to_i64())?;
+    module.inst_fn("to_float", |d: &Decimal| d.to_f64())?;
+    module.inst_fn("to_string", Decimal::to_string)?;
+    module.inst_fn("mantissa", Decimal::mantissa)?;
+    module.inst_fn("scale", Decimal::scale)?;
+    module.inst_fn("rescale", rescale)?;
+    module.inst_fn("normalize", Decimal::normalize)?;
+    module.inst_fn("round", Decimal::round)?;
+    module.inst_fn("round_dp", Decimal::round_dp)?;
+    module.inst_fn("round_dp_with_strategy", round_dp_with_strategy)?;
+    module.inst_fn("trunc", Decimal::trunc)?;
+    module.inst_fn("fract", Decimal::fract)?;
+    module.inst_fn("floor", Decimal::floor)?;
+    module.inst_fn("ceil", Decimal::ceil)?;
+    module.inst_fn("abs", Decimal::abs)?;
+    module.inst_fn("is_zero", Decimal::is_zero)?;
+    module.inst_fn("is_sign_negative", Decimal::is_sign_negative)?;
+    module.inst_fn("is_sign_positive", Decimal::is_sign_positive)?;
+
+    module.inst_fn(Protocol::ADD, add)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, add_assign)?;
+    module.inst_fn(Protocol::SUB, sub)?;
+    module.inst_fn(Protocol::SUB_ASSIGN, sub_assign)?;
+    module.inst_fn(Protocol::MUL, mul)?;
+    module.inst_fn(Protocol::MUL_ASSIGN, mul_assign)?;
+    module.inst_fn(Protocol::DIV, div)?;
+    module.inst_fn(Protocol::DIV_ASSIGN, div_assign)?;
+    module.inst_fn(Protocol::REM, rem)?;
+    module.inst_fn(Protocol::REM_ASSIGN, rem_assign)?;
+
+    module.inst_fn(Protocol::EQ, eq)?;
+    module.inst_fn(Protocol::PARTIAL_CMP, partial_cmp)?;
+    module.inst_fn(Protocol::CMP, cmp)?;
+    module.inst_fn(Protocol::HASH, hash)?;
+    module.key::<Decimal>()?;
+    module.number::<Decimal>(integer_lhs)?;
+    module.inst_fn(Protocol::SERIALIZE, serialize)?;
+    module.deserialize_tagged::<Decimal>(TAG, deserialize)?;
+    module.inst_fn(Protocol::CLONE, |d: &Decimal| *d)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, format_decimal)?;
+    module.inst_fn(Protocol::STRING_DEBUG, format_decimal)?;
+    Ok(module)
+}
+
+/// Construct a decimal from a mantissa and a scale, so that `new(12345, 2)`
+/// is `123.45`.
+fn new(num: i64, scale: u32) -> Result<Decimal, rust_decimal::Error> {
+    Decimal::try_new(num, scale)
+}
+
+/// Parse a decimal from a string, like `"123.45"` or `"1e-3"`.
+fn parse(s: &str) -> Result<Decimal, rust_decimal::Error> {
+    match s.parse() {
+        Ok(d) => Ok(d),
+        Err(error) => match Decimal::from_scientific(s) {
+            Ok(d) => Ok(d),
+            Err(..) => Err(error),
+        },
+    }
+}
+
+/// Change the scale of the decimal, rounding midpoints away from zero if the
+/// scale is reduced.
+fn rescale(d: &Decimal, scale: u32) -> Decimal {
+    let mut d = *d;
+    d.rescale(scale);
+    d
+}
+
+fn round_dp_with_strategy(d: &Decimal, dp: u32, strategy: &RoundingStrategy) -> Decimal {
+    d.round_dp_with_strategy(dp, *strategy)
+}
+
+fn rounding_strategy_is_variant(strategy: &RoundingStrategy, index: usize) -> bool {
+    matches!(
+        (strategy, index),
+        (RoundingStrategy::MidpointNearestEven, 0)
+            | (RoundingStrategy::MidpointAwayFromZero, 1)
+            | (RoundingStrategy::MidpointTowardZero, 2)
+            | (RoundingStrategy::ToZero, 3)
+            | (RoundingStrategy::AwayFromZero, 4)
+            | (RoundingStrategy::ToNegativeInfinity, 5)
+            | (RoundingStrategy::ToPositiveInfinity, 6)
+    )
+}
+
+/// Coerce the right-hand side of an operation into a decimal, which is either
+/// a decimal or an integer.
+fn operand(value: &Value) -> Result<Option<Decimal>, VmError> {
+    Ok(match value {
+        Value::Integer(n) => Some(Decimal::from(*n)),
+        Value::Any(any) => any.borrow_ref()?.downcast_borrow_ref::<Decimal>().copied(),
+        _ => None,
+    })
+}
+
+/// A checked arithmetic operation.
+type Checked = fn(Decimal, Decimal) -> Option<Decimal>;
+
+/// Perform a checked arithmetic operation.
+fn arith(
+    op: Protocol,
+    a: &Decimal,
+    b: &Value,
+    checked: Checked,
+    error: fn(&Decimal) -> VmErrorKind,
+) -> Result<Decimal, VmError> {
+    let b = match operand(b)? {
+        Some(b) => b,
+        None => {
+            return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                op: op.name,
+                lhs: Decimal::type_info(),
+                rhs: b.type_info()?,
+            }))
+        }
+    };
+
+    match checked(*a, b) {
+        Some(d) => Ok(d),
+        None => Err(VmError::from(error(&b))),
+    }
+}
+
+/// The error raised when an addition, subtraction or multiplication is out of
+/// range.
+fn out_of_range(_: &Decimal) -> VmErrorKind {
+    VmErrorKind::Overflow
+}
+
+/// The error raised when a division or remainder fails, which is either a
+/// division by zero or out of range.
+fn division_error(b: &Decimal) -> VmErrorKind {
+    if b.is_zero() {
+        VmErrorKind::DivideByZero
+    } else {
+        VmErrorKind::Overflow
+    }
+}
+
+macro_rules! arith_fns {
+    ($($protocol:ident, $name:ident, $assign:ident, $checked:ident, $error:ident;)*) => {
+        $(
+            fn $name(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+                arith(Protocol::$protocol, a, &b, Decimal::$checked, $error)
+            }
+
+            fn $assign(a: &mut Decimal, b: Value) -> Result<(), VmError> {
+                *a = arith(Protocol::$protocol, a, &b, Decimal::$checked, $error)?;
+                Ok(())
+            }
+        )*
+    };
+}
+
+arith_fns! {
+    ADD, add, add_assign, checked_add, out_of_range;
+    SUB, sub, sub_assign, checked_sub, out_of_range;
+    MUL, mul, mul_assign, checked_mul, out_of_range;
+    DIV, div, div_assign, checked_div, division_error;
+    REM, rem, rem_assign, checked_rem, division_error;
+}
+
+/// Apply a protocol where an `int` is on the left-hand side and a decimal is on
+/// the right-hand side, like `2 * 1.5d`.
+fn integer_lhs(protocol: Protocol, a: i64, b: &Value) -> Result<Value, VmError> {
+    const ARITH: [(Protocol, Checked, fn(&Decimal) -> VmErrorKind); 5] = [
+        (Protocol::ADD, Decimal::checked_add, out_of_range),
+        (Protocol::SUB, Decimal::checked_sub, out_of_range),
+        (Protocol::MUL, Decimal::checked_mul, out_of_range),
+        (Protocol::DIV, Decimal::checked_div, division_error),
+        (Protocol::REM, Decimal::checked_rem, division_error),
+    ];
+
+    let a = Decimal::from(a);
+
+    if let Some((_, checked, error)) = ARITH.into_iter().find(|(p, ..)| *p == protocol) {
+        return arith(protocol, &a, b, checked, error)?.to_value();
+    }
+
+    if let Some(b) = operand(b)? {
+        if protocol == Protocol::EQ {
+            return (a == b).to_value();
+        }
+
+        if protocol == Protocol::PARTIAL_CMP {
+            return a.partial_cmp(&b).to_value();
+        }
+
+        if protocol == Protocol::CMP {
+            return a.cmp(&b).to_value();
+        }
+    }
+
+    Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+        op: protocol.name,
+        lhs: i64::type_info(),
+        rhs: b.type_info()?,
+    }))
+}
+
+/// Test if a decimal is equal to another decimal or an integer, regardless of
+/// scale.
+fn eq(a: &Decimal, b: Value) -> Result<bool, VmError> {
+    Ok(operand(&b)?.map(|b| *a == b).unwrap_or_default())
+}
+
+/// Compare a decimal to another decimal or an integer.
+fn partial_cmp(a: &Decimal, b: Value) -> Result<Option<Ordering>, VmError> {
+    Ok(Some(compare(Protocol::PARTIAL_CMP, a, &b)?))
+}
+
+/// Compare a decimal to another decimal or an integer.
+fn cmp(a: &Decimal, b: Value) -> Result<Ordering, VmError> {
+    compare(Protocol::CMP, a, &b)
+}
+
+fn compare(op: Protocol, a: &Decimal, b: &Value) -> Result<Ordering, VmError> {
+    match operand(b)? {
+        Some(b) => Ok(a.cmp(&b)),
+        None => Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op: op.name,
+            lhs: Decimal::type_info(),
+            rhs: b.type_info()?,
+        })),
+    }
+}
+
+/// Serialize a decimal as a map which is tagged so that it can be told apart
+/// when deserializing.
+fn serialize(d: &Decimal) -> Result<Object, VmError> {
+    let mut object = Object::new();
+    object.insert(String::from(TAG), d.to_string().to_value()?);
+    Ok(object)
+}
+
+/// Deserialize a decimal from the value of its tagged map.
+fn deserialize(value: Value) -> Result<Decimal, VmError> {
+    let string = value.into_string()?;
+    let string = string.borrow_ref()?;
+
+    match parse(&string) {
+        Ok(d) => Ok(d),
+        Err(error) => Err(VmError::panic(error.to_string())),
+    }
+}
+
+fn hash(d: &Decimal) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    d.hash(&mut hasher);
+    hasher.finish() as i64
+}
+
+fn format_decimal(d: &Decimal, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", d)
+}
+
+fn format_decimal_error(error: &rust_decimal::Error, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+crate::__internal_impl_any!(Decimal);
+crate::__internal_impl_any!(RoundingStrategy);
+crate::__internal_impl_any!(rust_decimal::Error);
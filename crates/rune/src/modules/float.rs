@@ -21,6 +21,8 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.ty::<ParseFloatError>()?;
     module.function(&["parse"], parse)?;
+    module.inst_fn("partial_cmp", |a: f64, b: f64| a.partial_cmp(&b))?;
+    module.inst_fn("total_cmp", |a: f64, b: f64| a.total_cmp(&b))?;
     module.inst_fn("max", f64::max)?;
     module.inst_fn("min", f64::min)?;
     module.inst_fn("abs", f64::abs)?;
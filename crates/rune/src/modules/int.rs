@@ -1,8 +1,26 @@
 //! The `std::int` module.
 
+use crate::runtime::{Number, Protocol, ToValue, TypeOf, Value, VmError, VmErrorKind};
 use crate::{ContextError, Module};
 use std::num::ParseIntError;
 
+/// Protocols where an `int` on the left-hand side is dispatched to a native
+/// number on the right-hand side.
+const INTEGER_LHS: [Protocol; 12] = [
+    Protocol::ADD,
+    Protocol::SUB,
+    Protocol::MUL,
+    Protocol::DIV,
+    Protocol::REM,
+    Protocol::BIT_AND,
+    Protocol::BIT_OR,
+    Protocol::BIT_XOR,
+    Protocol::SHL,
+    Protocol::SHR,
+    Protocol::EQ,
+    Protocol::PARTIAL_CMP,
+];
+
 /// Construct the `std::int` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["int"]);
@@ -12,6 +30,15 @@ pub fn module() -> Result<Module, ContextError> {
     module.function(&["parse"], parse)?;
     module.inst_fn("to_float", to_float)?;
 
+    module.inst_fn(Protocol::CMP, cmp)?;
+
+    // NB: allows for an `int` to be on the left-hand side of an operation with
+    // a native number, like `1 + 2u64` or `2 * 1.5d`.
+    for protocol in INTEGER_LHS {
+        module.inst_fn(protocol, move |a: i64, b: Value| {
+            integer_lhs(protocol, a, &b)
+        })?;
+    }
     module.inst_fn("max", i64::max)?;
     module.inst_fn("min", i64::min)?;
     module.inst_fn("abs", i64::abs)?;
@@ -38,6 +65,35 @@ pub fn module() -> Result<Module, ContextError> {
     Ok(module)
 }
 
+/// Compare an integer to another integer or a native number.
+fn cmp(a: i64, b: Value) -> Result<Value, VmError> {
+    match b {
+        Value::Integer(b) => a.cmp(&b).to_value(),
+        b => integer_lhs(Protocol::CMP, a, &b),
+    }
+}
+
+/// Dispatch an operation with an `int` on the left-hand side to the native
+/// number on the right-hand side.
+fn integer_lhs(protocol: Protocol, a: i64, b: &Value) -> Result<Value, VmError> {
+    if let Some(number) = Number::of(b)? {
+        return number(protocol, a, b);
+    }
+
+    // NB: this is reached when comparing an `int` to anything else.
+    let op = if protocol == Protocol::EQ {
+        "=="
+    } else {
+        protocol.name
+    };
+
+    Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+        op,
+        lhs: i64::type_info(),
+        rhs: b.type_info()?,
+    }))
+}
+
 /// Parse an integer.
 fn parse(s: &str) -> Result<i64, ParseIntError> {
     str::parse::<i64>(s)
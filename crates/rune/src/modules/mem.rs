@@ -1,6 +1,6 @@
 //! The `std::mem` module.
 
-use crate::runtime::{Value, VmError};
+use crate::runtime::{EnvProtocolCaller, Protocol, Value, VmError};
 use crate::{ContextError, Module};
 
 /// Construct the `std` module.
@@ -10,7 +10,15 @@ pub fn module() -> Result<Module, ContextError> {
     Ok(module)
 }
 
+/// Drop the given value, calling its [Protocol::DROP] implementation if it has
+/// one.
+///
+/// The value is taken, so any other references to it can no longer access
+/// it, and native values are dropped immediately.
 fn drop_impl(value: Value) -> Result<(), VmError> {
+    // NB: implementing the protocol is optional.
+    EnvProtocolCaller.try_call_protocol_fn(Protocol::DROP, value.clone(), ())?;
+
     value.take()?;
     Ok(())
 }
@@ -4,9 +4,13 @@
 pub mod any;
 pub mod bytes;
 pub mod char;
+pub mod clone;
 pub mod cmp;
 pub mod collections;
 pub mod core;
+cfg_decimal! {
+    pub mod decimal;
+}
 pub mod float;
 pub mod fmt;
 pub mod future;
@@ -15,6 +19,7 @@ pub mod int;
 pub mod io;
 pub mod iter;
 pub mod mem;
+pub mod num;
 pub mod object;
 pub mod ops;
 pub mod option;
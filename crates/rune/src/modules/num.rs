@@ -0,0 +1,364 @@
+//! The `std::num` module.
+
+use crate::runtime::{
+    memory, BigInt, Protocol, ToValue, TypeInfo, TypeOf, Value, VmError, VmErrorKind, Wide, I128,
+    U64,
+};
+use crate::{ContextError, Module};
+use num::bigint::ParseBigIntError;
+use num::{Signed, ToPrimitive, Zero};
+use std::fmt::Write;
+use std::num::ParseIntError;
+
+/// Install a wide integer type along with its constructors, conversions and
+/// operator protocols.
+macro_rules! install_wide {
+    ($module:ident, $ty:ident) => {{
+        $module.ty::<$ty>()?;
+        $module.number::<$ty>(integer_lhs)?;
+
+        $module.function(&[stringify!($ty), "new"], |value: Value| {
+            <$ty as WideType>::from_wide(Wide::expect(&value)?)
+        })?;
+        $module.function(
+            &[stringify!($ty), "from_le_bytes"],
+            <$ty as WideType>::from_le_bytes,
+        )?;
+        $module.function(&[stringify!($ty), "parse"], <$ty as WideType>::parse)?;
+
+        $module.inst_fn("to_int", |a: &$ty| a.wide().to_primitive::<i64>().ok())?;
+        $module.inst_fn("to_float", |a: &$ty| {
+            a.wide().to_bigint().to_f64().unwrap_or(f64::NAN)
+        })?;
+
+        for op in Op::ALL {
+            $module.inst_fn(op.protocol(), move |a: &$ty, b: Value| {
+                let b = operand(op.protocol(), $ty::type_info(), &b)?;
+                op.apply(a.wide(), b)?.to_value()
+            })?;
+
+            $module.inst_fn(op.assign_protocol(), move |a: &mut $ty, b: Value| {
+                let b = operand(op.assign_protocol(), $ty::type_info(), &b)?;
+                *a = <$ty as WideType>::from_wide(op.apply(a.wide(), b)?)?;
+                Ok::<_, VmError>(())
+            })?;
+        }
+
+        $module.inst_fn(Protocol::PARTIAL_CMP, |a: &$ty, b: Value| {
+            let b = operand(Protocol::PARTIAL_CMP, $ty::type_info(), &b)?;
+            Ok::<_, VmError>(Some(a.wide().cmp(&b)))
+        })?;
+
+        $module.inst_fn(Protocol::CMP, |a: &$ty, b: Value| {
+            let b = operand(Protocol::CMP, $ty::type_info(), &b)?;
+            Ok::<_, VmError>(a.wide().cmp(&b))
+        })?;
+
+        $module.inst_fn(Protocol::EQ, |a: &$ty, b: Value| {
+            Ok::<_, VmError>(match Wide::from_value(&b)? {
+                Some(b) => a.wide().cmp(&b).is_eq(),
+                None => false,
+            })
+        })?;
+
+        $module.inst_fn(Protocol::STRING_DISPLAY, |a: &$ty, s: &mut String| {
+            write!(s, "{}", a.0)
+        })?;
+
+        $module.inst_fn(Protocol::STRING_DEBUG, |a: &$ty, s: &mut String| {
+            write!(s, "{:?}", a)
+        })?;
+    }};
+}
+
+/// Construct the `std::num` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["num"]);
+
+    module.ty::<ParseBigIntError>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, format_parse_big_int_error)?;
+
+    install_wide!(module, U64);
+    module.inst_fn("pow", |a: &U64, exp: u32| match a.0.checked_pow(exp) {
+        Some(n) => Ok(U64(n)),
+        None => Err(VmError::from(VmErrorKind::Overflow)),
+    })?;
+
+    install_wide!(module, I128);
+    module.inst_fn("pow", |a: &I128, exp: u32| match a.0.checked_pow(exp) {
+        Some(n) => Ok(I128(n)),
+        None => Err(VmError::from(VmErrorKind::Overflow)),
+    })?;
+    module.inst_fn("abs", |a: &I128| match a.0.checked_abs() {
+        Some(n) => Ok(I128(n)),
+        None => Err(VmError::from(VmErrorKind::Overflow)),
+    })?;
+
+    install_wide!(module, BigInt);
+    module.inst_fn("pow", |a: &BigInt, exp: u32| {
+        let bits = a.0.bits().saturating_mul(u64::from(exp));
+        memory::ensure(usize::try_from(bits / 8).unwrap_or(usize::MAX))?;
+        Ok::<_, VmError>(BigInt(num::pow::pow(a.0.clone(), exp as usize)))
+    })?;
+    module.inst_fn("abs", |a: &BigInt| BigInt(a.0.abs()))?;
+
+    Ok(module)
+}
+
+/// Apply a protocol where an `int` is on the left-hand side and a wide integer
+/// is on the right-hand side, like `1 + 2u64` or `1 < 2u64`.
+fn integer_lhs(protocol: Protocol, a: i64, b: &Value) -> Result<Value, VmError> {
+    let a = Wide::Integer(a);
+    let b = operand(protocol, i64::type_info(), b)?;
+
+    if protocol == Protocol::EQ {
+        return a.cmp(&b).is_eq().to_value();
+    }
+
+    if protocol == Protocol::PARTIAL_CMP {
+        return Some(a.cmp(&b)).to_value();
+    }
+
+    if protocol == Protocol::CMP {
+        return a.cmp(&b).to_value();
+    }
+
+    match Op::ALL.into_iter().find(|op| op.protocol() == protocol) {
+        Some(op) => op.apply(a, b)?.to_value(),
+        None => Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op: protocol.name,
+            lhs: i64::type_info(),
+            rhs: b.to_value()?.type_info()?,
+        })),
+    }
+}
+
+/// Coerce the right-hand side of an operation into a wide integer, erroring
+/// if it isn't an integer.
+fn operand(protocol: Protocol, lhs: TypeInfo, rhs: &Value) -> Result<Wide, VmError> {
+    match Wide::from_value(rhs)? {
+        Some(wide) => Ok(wide),
+        None => Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op: protocol.name,
+            lhs,
+            rhs: rhs.type_info()?,
+        })),
+    }
+}
+
+/// Conversions between a wide integer type and the operands of operations.
+trait WideType: Sized {
+    /// The error raised when parsing fails.
+    type ParseError;
+
+    /// The operand corresponding to the value.
+    fn wide(&self) -> Wide;
+
+    /// Convert the result of an operation back into this type, erroring if it
+    /// doesn't fit.
+    fn from_wide(wide: Wide) -> Result<Self, VmError>;
+
+    /// Parse a decimal string into this type.
+    fn parse(s: &str) -> Result<Self, Self::ParseError>;
+
+    /// Construct this type from its little endian bytes, like the ones of
+    /// number literals which don't fit in an integer.
+    fn from_le_bytes(bytes: &[u8]) -> Result<Self, VmError>;
+}
+
+impl WideType for U64 {
+    type ParseError = ParseIntError;
+
+    fn wide(&self) -> Wide {
+        Wide::U64(self.0)
+    }
+
+    fn from_wide(wide: Wide) -> Result<Self, VmError> {
+        Ok(Self(wide.to_primitive()?))
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        Ok(Self(s.parse()?))
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Result<Self, VmError> {
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self(u64::from_le_bytes(bytes))),
+            Err(..) => Err(VmError::panic("expected 8 bytes")),
+        }
+    }
+}
+
+impl WideType for I128 {
+    type ParseError = ParseIntError;
+
+    fn wide(&self) -> Wide {
+        Wide::I128(self.0)
+    }
+
+    fn from_wide(wide: Wide) -> Result<Self, VmError> {
+        Ok(Self(wide.to_primitive()?))
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        Ok(Self(s.parse()?))
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Result<Self, VmError> {
+        match bytes.try_into() {
+            Ok(bytes) => Ok(Self(i128::from_le_bytes(bytes))),
+            Err(..) => Err(VmError::panic("expected 16 bytes")),
+        }
+    }
+}
+
+impl WideType for BigInt {
+    type ParseError = ParseBigIntError;
+
+    fn wide(&self) -> Wide {
+        Wide::BigInt(self.0.clone())
+    }
+
+    fn from_wide(wide: Wide) -> Result<Self, VmError> {
+        Ok(Self(wide.to_bigint()))
+    }
+
+    fn parse(s: &str) -> Result<Self, Self::ParseError> {
+        Ok(Self(s.parse()?))
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Result<Self, VmError> {
+        memory::ensure(bytes.len())?;
+        Ok(Self(num::BigInt::from_signed_bytes_le(bytes)))
+    }
+}
+
+/// An operator implemented for wide integers.
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+impl Op {
+    const ALL: [Op; 10] = [
+        Op::Add,
+        Op::Sub,
+        Op::Mul,
+        Op::Div,
+        Op::Rem,
+        Op::BitAnd,
+        Op::BitOr,
+        Op::BitXor,
+        Op::Shl,
+        Op::Shr,
+    ];
+
+    /// The protocol implementing the operator.
+    fn protocol(self) -> Protocol {
+        match self {
+            Op::Add => Protocol::ADD,
+            Op::Sub => Protocol::SUB,
+            Op::Mul => Protocol::MUL,
+            Op::Div => Protocol::DIV,
+            Op::Rem => Protocol::REM,
+            Op::BitAnd => Protocol::BIT_AND,
+            Op::BitOr => Protocol::BIT_OR,
+            Op::BitXor => Protocol::BIT_XOR,
+            Op::Shl => Protocol::SHL,
+            Op::Shr => Protocol::SHR,
+        }
+    }
+
+    /// The protocol implementing the assign variant of the operator.
+    fn assign_protocol(self) -> Protocol {
+        match self {
+            Op::Add => Protocol::ADD_ASSIGN,
+            Op::Sub => Protocol::SUB_ASSIGN,
+            Op::Mul => Protocol::MUL_ASSIGN,
+            Op::Div => Protocol::DIV_ASSIGN,
+            Op::Rem => Protocol::REM_ASSIGN,
+            Op::BitAnd => Protocol::BIT_AND_ASSIGN,
+            Op::BitOr => Protocol::BIT_OR_ASSIGN,
+            Op::BitXor => Protocol::BIT_XOR_ASSIGN,
+            Op::Shl => Protocol::SHL_ASSIGN,
+            Op::Shr => Protocol::SHR_ASSIGN,
+        }
+    }
+
+    /// Apply the operator.
+    fn apply(self, a: Wide, b: Wide) -> Result<Wide, VmError> {
+        match self {
+            Op::Add => a.arith(
+                b,
+                || VmErrorKind::Overflow,
+                i128::checked_add,
+                |a, b| Some(a + b),
+            ),
+            Op::Sub => a.arith(
+                b,
+                || VmErrorKind::Underflow,
+                i128::checked_sub,
+                |a, b| Some(a - b),
+            ),
+            Op::Mul => {
+                // NB: a product needs at most as many bits as its operands combined.
+                let bits = a.bits().saturating_add(b.bits());
+                memory::ensure(usize::try_from(bits / 8).unwrap_or(usize::MAX))?;
+
+                a.arith(
+                    b,
+                    || VmErrorKind::Overflow,
+                    i128::checked_mul,
+                    |a, b| Some(a * b),
+                )
+            }
+            Op::Div => a.arith(
+                b,
+                || VmErrorKind::DivideByZero,
+                i128::checked_div,
+                |a, b| (!b.is_zero()).then(|| a / b),
+            ),
+            Op::Rem => a.arith(
+                b,
+                || VmErrorKind::DivideByZero,
+                i128::checked_rem,
+                |a, b| (!b.is_zero()).then(|| a % b),
+            ),
+            Op::BitAnd => a.arith(
+                b,
+                || VmErrorKind::Overflow,
+                |a, b| Some(a & b),
+                |a, b| Some(a & b),
+            ),
+            Op::BitOr => a.arith(
+                b,
+                || VmErrorKind::Overflow,
+                |a, b| Some(a | b),
+                |a, b| Some(a | b),
+            ),
+            Op::BitXor => a.arith(
+                b,
+                || VmErrorKind::Overflow,
+                |a, b| Some(a ^ b),
+                |a, b| Some(a ^ b),
+            ),
+            Op::Shl => a.shift(b, true),
+            Op::Shr => a.shift(b, false),
+        }
+    }
+}
+
+fn format_parse_big_int_error(error: &ParseBigIntError, buf: &mut String) -> std::fmt::Result {
+    write!(buf, "{}", error)
+}
+
+crate::__internal_impl_any!(ParseBigIntError);
@@ -1,7 +1,11 @@
 //! The `std::string` module.
 
-use crate::runtime::{Bytes, Iterator, Protocol, Value, VmError, VmErrorKind};
+use crate::runtime::{
+    memory, Bytes, FromValue, Iterator, Protocol, Range, RangeLimits, TypeOf, Value, VmError,
+    VmErrorKind,
+};
 use crate::{Any, ContextError, Module};
+use std::fmt;
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -11,37 +15,53 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.function(&["String", "from_str"], <String as From<&str>>::from)?;
     module.function(&["String", "new"], String::new)?;
-    module.function(&["String", "with_capacity"], String::with_capacity)?;
+    module.function(&["String", "with_capacity"], string_with_capacity)?;
 
     module.inst_fn("cmp", str::cmp)?;
     module.inst_fn("len", String::len)?;
     module.inst_fn("starts_with", str::starts_with::<&str>)?;
     module.inst_fn("ends_with", str::ends_with::<&str>)?;
+    module.inst_fn("contains", string_contains)?;
+    module.inst_fn("find", string_find)?;
+    module.inst_fn("rfind", string_rfind)?;
+    module.inst_fn("strip_prefix", string_strip_prefix)?;
+    module.inst_fn("strip_suffix", string_strip_suffix)?;
+    module.inst_fn("to_lowercase", str::to_lowercase)?;
+    module.inst_fn("to_uppercase", str::to_uppercase)?;
+    module.inst_fn("repeat", string_repeat)?;
     module.inst_fn("capacity", String::capacity)?;
     module.inst_fn("clear", String::clear)?;
-    module.inst_fn("push", String::push)?;
-    module.inst_fn("push_str", String::push_str)?;
-    module.inst_fn("reserve", String::reserve)?;
-    module.inst_fn("reserve_exact", String::reserve_exact)?;
+    module.inst_fn("push", string_push)?;
+    module.inst_fn("push_str", string_push_str)?;
+    module.inst_fn("reserve", string_reserve)?;
+    module.inst_fn("reserve_exact", string_reserve_exact)?;
     module.inst_fn("into_bytes", into_bytes)?;
     module.inst_fn("clone", String::clone)?;
     module.inst_fn("shrink_to_fit", String::shrink_to_fit)?;
     module.inst_fn("char_at", char_at)?;
     module.inst_fn("split", string_split)?;
+    module.inst_fn("splitn", string_splitn)?;
+    module.inst_fn("rsplit", string_rsplit)?;
+    module.inst_fn("split_whitespace", string_split_whitespace)?;
+    module.inst_fn("lines", string_lines)?;
     module.inst_fn("trim", string_trim)?;
+    module.inst_fn("trim_start", string_trim_start)?;
     module.inst_fn("trim_end", string_trim_end)?;
     module.inst_fn("replace", str::replace::<&str>)?;
     // TODO: deprecate this variant.
     module.inst_fn("split_str", string_split)?;
     module.inst_fn("is_empty", str::is_empty)?;
     module.inst_fn("chars", string_chars)?;
+    module.inst_fn("char_indices", string_char_indices)?;
+    module.inst_fn("bytes", string_bytes)?;
     module.inst_fn(Protocol::ADD, add)?;
-    module.inst_fn(Protocol::ADD_ASSIGN, String::push_str)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, string_push_str)?;
     module.inst_fn(Protocol::INDEX_GET, string_index_get)?;
     module.inst_fn("get", string_get)?;
 
     // TODO: parameterize once generics are available.
     module.function(&["parse_int"], parse_int)?;
+    module.function(&["parse_float"], parse_float)?;
     module.function(&["parse_char"], parse_char)?;
 
     Ok(module)
@@ -52,9 +72,9 @@ pub fn module() -> Result<Module, ContextError> {
 struct NotCharBoundary(());
 
 impl NotCharBoundary {
-    fn string_display(&self, s: &mut String) -> std::fmt::Result {
+    fn string_display(&self, s: &mut String) -> fmt::Result {
         use std::fmt::Write as _;
-        write!(s, "index outside of character boundary")
+        write!(s, "{}", self)
     }
 
     fn install(m: &mut Module) -> Result<(), ContextError> {
@@ -63,6 +83,46 @@ impl NotCharBoundary {
     }
 }
 
+impl fmt::Display for NotCharBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "index outside of character boundary")
+    }
+}
+
+/// A pattern used to search strings, which is either a string or a character.
+enum Pattern {
+    String(String),
+    Char(char),
+}
+
+impl Pattern {
+    /// Coerce the argument at the given position into a pattern.
+    fn from_arg(arg: usize, value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::String(s) => Self::String(s.borrow_ref()?.clone()),
+            Value::StaticString(s) => Self::String(s.as_str().to_owned()),
+            Value::Char(c) => Self::Char(c),
+            value => return Err(VmError::bad_argument::<String>(arg, &value)?),
+        })
+    }
+}
+
+/// Evaluate the given expression with `$pat` bound to the concrete pattern.
+macro_rules! with_pattern {
+    ($pattern:expr, |$pat:ident| $expr:expr) => {
+        match $pattern {
+            Pattern::String(string) => {
+                let $pat = string.as_str();
+                $expr
+            }
+            Pattern::Char(c) => {
+                let $pat = c;
+                $expr
+            }
+        }
+    };
+}
+
 /// into_bytes shim for strings.
 fn into_bytes(s: String) -> Bytes {
     Bytes::from_vec(s.into_bytes())
@@ -76,30 +136,104 @@ fn char_at(s: &str, index: usize) -> Option<char> {
     s[index..].chars().next()
 }
 
-fn string_split(this: &str, value: Value) -> Result<Iterator, VmError> {
-    let lines = match value {
-        Value::String(s) => this
-            .split(s.borrow_ref()?.as_str())
-            .map(String::from)
-            .collect::<Vec<String>>(),
-        Value::StaticString(s) => this
-            .split(s.as_str())
-            .map(String::from)
-            .collect::<Vec<String>>(),
-        Value::Char(pat) => this.split(pat).map(String::from).collect::<Vec<String>>(),
-        value => return Err(VmError::bad_argument::<String>(0, &value)?),
+fn string_contains(this: &str, pattern: Value) -> Result<bool, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    Ok(with_pattern!(pattern, |pat| this.contains(pat)))
+}
+
+fn string_find(this: &str, pattern: Value) -> Result<Option<usize>, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    Ok(with_pattern!(pattern, |pat| this.find(pat)))
+}
+
+fn string_rfind(this: &str, pattern: Value) -> Result<Option<usize>, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    Ok(with_pattern!(pattern, |pat| this.rfind(pat)))
+}
+
+fn string_strip_prefix(this: &str, pattern: Value) -> Result<Option<String>, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    let out = with_pattern!(pattern, |pat| this.strip_prefix(pat));
+    Ok(out.map(String::from))
+}
+
+fn string_strip_suffix(this: &str, pattern: Value) -> Result<Option<String>, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    let out = with_pattern!(pattern, |pat| this.strip_suffix(pat));
+    Ok(out.map(String::from))
+}
+
+fn string_repeat(this: &str, n: usize) -> Result<String, VmError> {
+    let len = match this.len().checked_mul(n) {
+        Some(len) => len,
+        None => return Err(VmError::panic("capacity overflow")),
     };
 
+    memory::ensure(len)?;
+    Ok(this.repeat(n))
+}
+
+fn string_split(this: &str, pattern: Value) -> Result<Iterator, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    let parts = with_pattern!(pattern, |pat| this
+        .split(pat)
+        .map(String::from)
+        .collect::<Vec<String>>());
+
     Ok(Iterator::from_double_ended(
         "std::str::Split",
-        lines.into_iter(),
+        parts.into_iter(),
+    ))
+}
+
+fn string_splitn(this: &str, n: usize, pattern: Value) -> Result<Iterator, VmError> {
+    let pattern = Pattern::from_arg(1, pattern)?;
+    let parts = with_pattern!(pattern, |pat| this
+        .splitn(n, pat)
+        .map(String::from)
+        .collect::<Vec<String>>());
+
+    Ok(Iterator::from_double_ended(
+        "std::str::SplitN",
+        parts.into_iter(),
     ))
 }
 
+fn string_rsplit(this: &str, pattern: Value) -> Result<Iterator, VmError> {
+    let pattern = Pattern::from_arg(0, pattern)?;
+    let parts = with_pattern!(pattern, |pat| this
+        .rsplit(pat)
+        .map(String::from)
+        .collect::<Vec<String>>());
+
+    Ok(Iterator::from_double_ended(
+        "std::str::RSplit",
+        parts.into_iter(),
+    ))
+}
+
+fn string_split_whitespace(this: &str) -> Iterator {
+    let parts = this
+        .split_whitespace()
+        .map(String::from)
+        .collect::<Vec<String>>();
+
+    Iterator::from_double_ended("std::str::SplitWhitespace", parts.into_iter())
+}
+
+fn string_lines(this: &str) -> Iterator {
+    let lines = this.lines().map(String::from).collect::<Vec<String>>();
+    Iterator::from_double_ended("std::str::Lines", lines.into_iter())
+}
+
 fn string_trim(this: &str) -> String {
     this.trim().to_owned()
 }
 
+fn string_trim_start(this: &str) -> String {
+    this.trim_start().to_owned()
+}
+
 fn string_trim_end(this: &str) -> String {
     this.trim_end().to_owned()
 }
@@ -108,10 +242,47 @@ fn parse_int(s: &str) -> Result<i64, std::num::ParseIntError> {
     str::parse::<i64>(s)
 }
 
+fn parse_float(s: &str) -> Result<f64, std::num::ParseFloatError> {
+    str::parse::<f64>(s)
+}
+
 fn parse_char(s: &str) -> Result<char, std::char::ParseCharError> {
     str::parse::<char>(s)
 }
 
+fn string_with_capacity(cap: usize) -> Result<String, VmError> {
+    memory::ensure(cap)?;
+    Ok(String::with_capacity(cap))
+}
+
+fn string_push(s: &mut String, c: char) {
+    let capacity = s.capacity();
+    s.push(c);
+    memory::resize::<u8>(capacity, s.capacity());
+}
+
+fn string_push_str(s: &mut String, other: &str) {
+    let capacity = s.capacity();
+    s.push_str(other);
+    memory::resize::<u8>(capacity, s.capacity());
+}
+
+fn string_reserve(s: &mut String, additional: usize) -> Result<(), VmError> {
+    memory::ensure(additional)?;
+    let capacity = s.capacity();
+    s.reserve(additional);
+    memory::resize::<u8>(capacity, s.capacity());
+    Ok(())
+}
+
+fn string_reserve_exact(s: &mut String, additional: usize) -> Result<(), VmError> {
+    memory::ensure(additional)?;
+    let capacity = s.capacity();
+    s.reserve_exact(additional);
+    memory::resize::<u8>(capacity, s.capacity());
+    Ok(())
+}
+
 /// The add operation for strings.
 fn add(a: &str, b: &str) -> String {
     let mut string = String::with_capacity(a.len() + b.len());
@@ -125,39 +296,42 @@ fn string_chars(s: &str) -> Iterator {
     Iterator::from_double_ended("std::str::Chars", iter)
 }
 
+fn string_char_indices(s: &str) -> Iterator {
+    let iter = s.char_indices().collect::<Vec<_>>().into_iter();
+    Iterator::from_double_ended("std::str::CharIndices", iter)
+}
+
+fn string_bytes(s: &str) -> Iterator {
+    let iter = s.bytes().collect::<Vec<_>>().into_iter();
+    Iterator::from_double_ended("std::str::Bytes", iter)
+}
+
+/// Resolve a range into the byte offsets of the string it covers, as a
+/// half-open `(start, end)` pair.
+fn string_range(s: &str, range: &Range) -> Result<(usize, usize), VmError> {
+    let start = match range.start.clone() {
+        Some(value) => <usize>::from_value(value)?,
+        None => 0,
+    };
+
+    let end = match (range.end.clone(), range.limits) {
+        (Some(value), RangeLimits::HalfOpen) => <usize>::from_value(value)?,
+        (Some(value), RangeLimits::Closed) => <usize>::from_value(value)?
+            .checked_add(1)
+            .ok_or(VmErrorKind::UnsupportedRange)?,
+        (None, RangeLimits::HalfOpen) => s.len(),
+        (None, RangeLimits::Closed) => return Err(VmError::from(VmErrorKind::UnsupportedRange)),
+    };
+
+    Ok((start, end))
+}
+
 /// Get a specific string index.
 fn string_get(s: &str, key: Value) -> Result<Option<String>, VmError> {
-    use crate::runtime::{FromValue, RangeLimits, TypeOf};
-
     match key {
         Value::Range(range) => {
-            let range = range.borrow_ref()?;
-
-            let start = match range.start.clone() {
-                Some(value) => Some(<usize>::from_value(value)?),
-                None => None,
-            };
-
-            let end = match range.end.clone() {
-                Some(value) => Some(<usize>::from_value(value)?),
-                None => None,
-            };
-
-            let out = match range.limits {
-                RangeLimits::HalfOpen => match (start, end) {
-                    (Some(start), Some(end)) => s.get(start..end),
-                    (Some(start), None) => s.get(start..),
-                    (None, Some(end)) => s.get(..end),
-                    (None, None) => s.get(..),
-                },
-                RangeLimits::Closed => match (start, end) {
-                    (Some(start), Some(end)) => s.get(start..=end),
-                    (None, Some(end)) => s.get(..=end),
-                    _ => return Err(VmError::from(VmErrorKind::UnsupportedRange)),
-                },
-            };
-
-            Ok(out.map(|out| out.to_owned()))
+            let (start, end) = string_range(s, &*range.borrow_ref()?)?;
+            Ok(s.get(start..end).map(|out| out.to_owned()))
         }
         index => Err(VmError::from(VmErrorKind::UnsupportedIndexGet {
             target: String::type_info(),
@@ -168,5 +342,30 @@ fn string_get(s: &str, key: Value) -> Result<Option<String>, VmError> {
 
 /// Get a specific string index.
 fn string_index_get(s: &str, key: Value) -> Result<String, VmError> {
-    string_get(s, key)?.ok_or_else(|| VmError::panic("missing string slice"))
+    match key {
+        Value::Range(range) => {
+            let (start, end) = string_range(s, &*range.borrow_ref()?)?;
+
+            if end > s.len() {
+                return Err(VmError::from(VmErrorKind::OutOfRange {
+                    index: end.into(),
+                    len: s.len().into(),
+                }));
+            }
+
+            if start > end {
+                return Err(VmError::from(VmErrorKind::UnsupportedRange));
+            }
+
+            if !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+                return Err(VmError::panic(NotCharBoundary(())));
+            }
+
+            Ok(s[start..end].to_owned())
+        }
+        index => Err(VmError::from(VmErrorKind::UnsupportedIndexGet {
+            target: String::type_info(),
+            index: index.type_info()?,
+        })),
+    }
 }
@@ -1,7 +1,10 @@
 //! The `std::vec` module.
 
-use crate::runtime::{Function, Protocol, TypeOf, Value, Vec};
+use crate::runtime::{
+    Function, Protocol, TypeInfo, TypeOf, Value, Vec, VmError, VmErrorKind, VEC_TYPE,
+};
 use crate::{ContextError, Module, Params};
+use std::cmp::Ordering;
 
 /// Construct the `std::vec` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -10,42 +13,85 @@ pub fn module() -> Result<Module, ContextError> {
     module.ty::<Vec>()?;
 
     module.function(&["Vec", "new"], Vec::new)?;
+    module.inst_fn("binary_search", binary_search)?;
+    module.inst_fn("chunks", Vec::chunks)?;
     module.inst_fn("clear", Vec::clear)?;
-    module.inst_fn("clone", Vec::clone)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("dedup", Vec::dedup)?;
     module.inst_fn("extend", Vec::extend)?;
+    module.inst_fn("first", first)?;
     module.inst_fn("get", vec_get)?;
+    module.inst_fn("is_empty", Vec::is_empty)?;
     module.inst_fn("iter", Vec::into_iterator)?;
+    module.inst_fn("join", Vec::join)?;
+    module.inst_fn("last", last)?;
     module.inst_fn("len", Vec::len)?;
     module.inst_fn("pop", Vec::pop)?;
     module.inst_fn("push", Vec::push)?;
     module.inst_fn("remove", Vec::remove)?;
+    module.inst_fn("retain", retain)?;
+    module.inst_fn("reverse", reverse)?;
+    module.inst_fn("sort", sort)?;
     module.inst_fn("sort_by", sort_by)?;
+    module.inst_fn("split_off", Vec::split_off)?;
+    module.inst_fn("swap", Vec::swap)?;
+    module.inst_fn("truncate", Vec::truncate)?;
     module.inst_fn("insert", Vec::insert)?;
+    module.inst_fn("windows", Vec::windows)?;
     module.inst_fn(Protocol::INTO_ITER, Vec::into_iterator)?;
+    module.inst_fn(Protocol::INDEX_GET, index_get)?;
     module.inst_fn(Protocol::INDEX_SET, Vec::set)?;
 
-    // TODO: parameterize with generics.
-    module.inst_fn(Params("sort", [i64::type_hash()]), sort_int)?;
+    // NB: kept so that explicitly parameterized calls like `sort::<int>`
+    // continue to work.
+    module.inst_fn(Params("sort", [i64::type_hash()]), sort)?;
     Ok(module)
 }
 
-/// Sort a vector of integers.
-fn sort_int(vec: &mut Vec) {
-    vec.sort_by(|a, b| match (a, b) {
-        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
-        // NB: fall back to sorting by address.
-        _ => (a as *const _ as usize).cmp(&(b as *const _ as usize)),
-    });
+/// Sort a vector using the [Protocol::CMP] ordering of its values.
+fn sort(vec: &mut Vec) -> Result<(), VmError> {
+    vec.try_sort_by(Value::value_ptr_cmp)
 }
 
 fn vec_get(vec: &Vec, index: usize) -> Option<Value> {
     vec.get(index).cloned()
 }
 
-fn sort_by(vec: &mut Vec, comparator: &Function) {
-    vec.sort_by(|a, b| {
-        comparator
-            .call::<_, std::cmp::Ordering>((a, b))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    })
+fn first(vec: &Vec) -> Option<Value> {
+    vec.first().cloned()
+}
+
+fn last(vec: &Vec) -> Option<Value> {
+    vec.last().cloned()
+}
+
+fn contains(vec: &Vec, value: Value) -> Result<bool, VmError> {
+    vec.contains(&value)
+}
+
+fn binary_search(vec: &Vec, value: Value) -> Result<Result<usize, usize>, VmError> {
+    vec.binary_search(&value)
+}
+
+fn reverse(vec: &mut Vec) {
+    vec.reverse();
+}
+
+fn retain(vec: &mut Vec, predicate: &Function) -> Result<(), VmError> {
+    vec.retain(|value| predicate.call::<_, bool>((value,)))
+}
+
+/// Slice the vector with a range, like `vec[1..3]`.
+fn index_get(vec: &Vec, index: Value) -> Result<Vec, VmError> {
+    match index {
+        Value::Range(range) => vec.get_range(&*range.borrow_ref()?),
+        index => Err(VmError::from(VmErrorKind::UnsupportedIndexGet {
+            target: TypeInfo::StaticType(VEC_TYPE),
+            index: index.type_info()?,
+        })),
+    }
+}
+
+fn sort_by(vec: &mut Vec, comparator: &Function) -> Result<(), VmError> {
+    vec.try_sort_by(|a, b| comparator.call::<_, Ordering>((a, b)))
 }
@@ -14,8 +14,8 @@ use crate::compile::{
     ir, CaptureMeta, CompileError, CompileErrorKind, CompileVisitor, ComponentRef, ContextMeta,
     ContextMetaKind, Doc, ImportStep, IntoComponent, IrBudget, IrCompiler, IrInterpreter, Item,
     ItemBuf, ItemId, ItemMeta, Location, ModId, ModMeta, Names, Pool, Prelude, PrivMeta,
-    PrivMetaKind, PrivStructMeta, PrivTupleMeta, PrivVariantMeta, SourceMeta, UnitBuilder,
-    Visibility,
+    PrivMetaKind, PrivStructMeta, PrivTraitDefault, PrivTraitFn, PrivTupleMeta, PrivVariantMeta,
+    SourceMeta, UnitBuilder, Visibility,
 };
 use crate::hir;
 use crate::macros::Storage;
@@ -117,6 +117,9 @@ pub(crate) struct QueryInner {
     items: HashMap<NonZeroId, ItemMeta>,
     /// All available names in the context.
     names: Names,
+    /// The trait whose default implementations are being compiled, and the
+    /// implementing type which `Self` refers to in them.
+    trait_impl: Option<(ItemId, ItemId)>,
 }
 
 /// Query system of the rune compiler.
@@ -518,6 +521,58 @@ impl<'a> Query<'a> {
         Ok(())
     }
 
+    /// Add a new trait item that can be queried.
+    #[tracing::instrument(skip_all)]
+    pub(crate) fn index_trait(
+        &mut self,
+        item_meta: ItemMeta,
+        functions: Vec<PrivTraitFn>,
+    ) -> Result<(), QueryError> {
+        tracing::trace!(item = ?self.pool.item(item_meta.item));
+
+        self.index(IndexedEntry {
+            item_meta,
+            indexed: Indexed::Trait(Trait {
+                functions: functions.into(),
+            }),
+        });
+
+        Ok(())
+    }
+
+    /// Set the trait whose default implementations are being compiled for the
+    /// given type, returning the previous one.
+    pub(crate) fn replace_trait_impl(
+        &mut self,
+        trait_impl: Option<(ItemId, ItemId)>,
+    ) -> Option<(ItemId, ItemId)> {
+        std::mem::replace(&mut self.inner.trait_impl, trait_impl)
+    }
+
+    /// Test if the given item is a trait declared in a script.
+    fn is_trait(&self, item: ItemId) -> bool {
+        if let Some(meta) = self.inner.meta.get(&item) {
+            return matches!(meta.kind, PrivMetaKind::Trait { .. });
+        }
+
+        self.inner
+            .indexed
+            .get(&item)
+            .into_iter()
+            .flatten()
+            .any(|e| matches!(e.indexed, Indexed::Trait(..)))
+    }
+
+    /// Queue up an `impl Trait for Type` block to be checked against its
+    /// trait once everything has been indexed.
+    pub(crate) fn queue_trait_impl(&mut self, item_meta: ItemMeta, trait_impl: TraitImpl) {
+        self.inner.queue.push_back(BuildEntry {
+            item_meta,
+            used: Used::Used,
+            build: Build::TraitImpl(trait_impl),
+        });
+    }
+
     /// Add a new variant item that can be queried.
     #[tracing::instrument(skip_all)]
     pub(crate) fn index_variant(
@@ -652,6 +707,18 @@ impl<'a> Query<'a> {
             ContextMetaKind::Const { ref const_value } => PrivMetaKind::Const {
                 const_value: const_value.clone(),
             },
+            ContextMetaKind::Trait { ref functions } => PrivMetaKind::Trait {
+                functions: functions
+                    .iter()
+                    .map(|f| PrivTraitFn {
+                        name: f.name.clone(),
+                        default: f.default.map(|args| PrivTraitDefault::Native {
+                            hash: Hash::type_hash(&context_meta.item.extended(&*f.name)),
+                            args,
+                        }),
+                    })
+                    .collect(),
+            },
         };
 
         let meta = PrivMeta {
@@ -709,6 +776,28 @@ impl<'a> Query<'a> {
         Ok(None)
     }
 
+    /// Get the item that `Self` refers to where the given path is used, if
+    /// any.
+    pub(crate) fn path_self_type(&self, path: &hir::Path<'_>) -> Option<ItemId> {
+        let id = path.id();
+        let qp = id.as_ref().and_then(|id| self.inner.query_paths.get(id))?;
+        self.self_type(qp)
+    }
+
+    /// Get the item that `Self` refers to in the given query path.
+    fn self_type(&self, qp: &QueryPath) -> Option<ItemId> {
+        let impl_item = qp.impl_item?;
+
+        match self.inner.trait_impl {
+            Some((trait_item, type_item)) if trait_item == impl_item => Some(type_item),
+            // NB: a trait is only the `Self` of its default implementations
+            // while they're compiled for a type, which isn't the case for
+            // closures declared in them.
+            _ if self.is_trait(impl_item) => None,
+            _ => Some(impl_item),
+        }
+    }
+
     /// Perform a path lookup on the current state of the unit.
     #[tracing::instrument(skip_all)]
     pub(crate) fn convert_path<'hir>(
@@ -753,12 +842,11 @@ impl<'a> Query<'a> {
                     .try_map_alloc(self.pool.module(qp.module).item, Item::parent)
                     .ok_or_else(CompileError::unsupported_super(segment.span()))?,
                 hir::PathSegmentKind::SelfType => {
-                    let impl_item = qp.impl_item.ok_or_else(|| {
-                        CompileError::new(segment.span(), CompileErrorKind::UnsupportedSelfType)
-                    })?;
-
                     in_self_type = true;
-                    impl_item
+
+                    self.self_type(&qp).ok_or_else(|| {
+                        CompileError::new(segment.span(), CompileErrorKind::UnsupportedSelfType)
+                    })?
                 }
                 hir::PathSegmentKind::SelfValue => self.pool.module(qp.module).item,
                 hir::PathSegmentKind::Crate => ItemId::default(),
@@ -1183,6 +1271,9 @@ impl<'a> Query<'a> {
                 }
             }
             Indexed::Module => PrivMetaKind::Module,
+            Indexed::Trait(t) => PrivMetaKind::Trait {
+                functions: t.functions,
+            },
         };
 
         let source = SourceMeta {
@@ -1445,6 +1536,8 @@ pub(crate) enum Indexed {
     Import(Import),
     /// An indexed module.
     Module,
+    /// A trait.
+    Trait(Trait),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1519,6 +1612,21 @@ pub(crate) struct InstanceFunction {
     pub(crate) instance_span: Span,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct Trait {
+    /// The functions declared in the trait.
+    pub(crate) functions: Arc<[PrivTraitFn]>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TraitImpl {
+    /// The path to the trait being implemented.
+    pub(crate) path: Box<ast::Path>,
+    /// The names of the functions provided by the impl block, and where they
+    /// are declared.
+    pub(crate) provided: Vec<(Box<str>, Span)>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Closure {
     /// Ast for closure.
@@ -1568,6 +1676,8 @@ pub(crate) enum Build {
     AsyncBlock(AsyncBlock),
     Unused,
     Import(Import),
+    /// An `impl Trait for Type` block to check against its trait.
+    TraitImpl(TraitImpl),
     /// A public re-export.
     ReExport,
     /// A build which simply queries for the item.
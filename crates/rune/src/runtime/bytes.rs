@@ -4,15 +4,19 @@
 
 use crate::compile::{InstallWith, Named};
 use crate::runtime::{
-    FromValue, Mut, RawMut, RawRef, RawStr, Ref, UnsafeFromValue, Value, VmError,
+    memory, FromValue, Mut, RawMut, RawRef, RawStr, Ref, UnsafeFromValue, Value, VmError,
 };
 use serde::{Deserialize, Serialize};
 use std::cmp;
 use std::fmt;
+use std::mem;
 use std::ops;
 
 /// A vector of bytes.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+///
+/// The memory it allocates is accounted for in the [memory quota][crate::runtime::memory],
+/// and is returned to it when it's dropped.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct Bytes {
     #[serde(with = "serde_bytes")]
@@ -27,29 +31,36 @@ impl Bytes {
 
     /// Construct a new bytes container with the specified capacity.
     pub fn with_capacity(cap: usize) -> Self {
-        Bytes {
-            bytes: Vec::with_capacity(cap),
-        }
+        let bytes = Vec::with_capacity(cap);
+        memory::resize::<u8>(0, bytes.capacity());
+        Bytes { bytes }
     }
 
     /// Convert into vector.
-    pub fn into_vec(self) -> Vec<u8> {
-        self.bytes
+    pub fn into_vec(mut self) -> Vec<u8> {
+        let bytes = mem::take(&mut self.bytes);
+        memory::resize::<u8>(bytes.capacity(), 0);
+        bytes
     }
 
     /// Construct from a byte vector.
     pub fn from_vec(bytes: Vec<u8>) -> Self {
+        memory::resize::<u8>(0, bytes.capacity());
         Self { bytes }
     }
 
     /// Do something with the bytes.
     pub fn extend(&mut self, other: &Self) {
+        let capacity = self.bytes.capacity();
         self.bytes.extend(other.bytes.iter().copied());
+        memory::resize::<u8>(capacity, self.bytes.capacity());
     }
 
     /// Do something with the bytes.
     pub fn extend_str(&mut self, s: &str) {
+        let capacity = self.bytes.capacity();
         self.bytes.extend(s.as_bytes());
+        memory::resize::<u8>(capacity, self.bytes.capacity());
     }
 
     /// Test if the collection is empty.
@@ -76,17 +87,23 @@ impl Bytes {
     ///
     /// The exact amount is unspecified.
     pub fn reserve(&mut self, additional: usize) {
+        let capacity = self.bytes.capacity();
         self.bytes.reserve(additional);
+        memory::resize::<u8>(capacity, self.bytes.capacity());
     }
 
     /// Resever additional space to the exact amount specified.
     pub fn reserve_exact(&mut self, additional: usize) {
+        let capacity = self.bytes.capacity();
         self.bytes.reserve_exact(additional);
+        memory::resize::<u8>(capacity, self.bytes.capacity());
     }
 
     /// Shrink to fit the amount of bytes in the container.
     pub fn shrink_to_fit(&mut self) {
+        let capacity = self.bytes.capacity();
         self.bytes.shrink_to_fit();
+        memory::resize::<u8>(capacity, self.bytes.capacity());
     }
 
     /// Pop the last byte.
@@ -102,7 +119,19 @@ impl Bytes {
 
 impl From<Vec<u8>> for Bytes {
     fn from(bytes: Vec<u8>) -> Self {
-        Self { bytes }
+        Self::from_vec(bytes)
+    }
+}
+
+impl Clone for Bytes {
+    fn clone(&self) -> Self {
+        Self::from_vec(self.bytes.clone())
+    }
+}
+
+impl Drop for Bytes {
+    fn drop(&mut self) {
+        memory::resize::<u8>(self.bytes.capacity(), 0);
     }
 }
 
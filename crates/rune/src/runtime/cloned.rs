@@ -0,0 +1,137 @@
+//! Thread-local state of a deep clone in progress.
+//!
+//! [Value::value_ptr_clone] calls back into scripts through the
+//! [Protocol::CLONE][crate::runtime::Protocol::CLONE] protocol, which might in
+//! turn clone values. Keeping track of what has been cloned here lets every
+//! level of such a clone see the same values.
+
+use crate::collections::{HashMap, HashSet};
+use crate::runtime::{Shared, Value, VmError, VmErrorKind};
+use std::cell::RefCell;
+use std::mem;
+
+/// The maximum depth of values which can be cloned.
+const MAX_DEPTH: usize = 256;
+
+thread_local!(static CLONED: RefCell<Cloned> = RefCell::new(Cloned::default()));
+
+#[derive(Default)]
+struct Cloned {
+    /// The depth of the clone in progress.
+    depth: usize,
+    /// Clones by the address of the value they were cloned from. The original
+    /// is kept alive so that its address can't be reused while cloning.
+    seen: HashMap<*const (), (Value, Value)>,
+    /// Addresses of values being cloned through the clone protocol.
+    protocol: HashSet<*const ()>,
+}
+
+/// Get the clone of the given value, if it has already been cloned.
+pub(crate) fn get(value: &Value) -> Option<Value> {
+    let ptr = address(value)?;
+    CLONED.with(|tls| Some(tls.borrow().seen.get(&ptr)?.1.clone()))
+}
+
+/// Register the clone of the given value.
+pub(crate) fn insert(value: Value, clone: Value) {
+    if let Some(ptr) = address(&value) {
+        CLONED.with(|tls| tls.borrow_mut().seen.insert(ptr, (value, clone)));
+    }
+}
+
+/// A guard for one level of a deep clone.
+///
+/// Everything registered is released once the outermost guard is dropped.
+pub(crate) struct Guard(());
+
+impl Guard {
+    /// Enter one level of a deep clone.
+    pub(crate) fn enter() -> Result<Self, VmError> {
+        CLONED.with(|tls| {
+            let mut cloned = tls.borrow_mut();
+
+            if cloned.depth == MAX_DEPTH {
+                return Err(VmError::from(VmErrorKind::CloneDepthExceeded {
+                    depth: MAX_DEPTH,
+                }));
+            }
+
+            cloned.depth += 1;
+            Ok(Self(()))
+        })
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let seen = CLONED.with(|tls| {
+            let mut cloned = tls.borrow_mut();
+            cloned.depth -= 1;
+
+            if cloned.depth == 0 {
+                cloned.protocol.clear();
+                mem::take(&mut cloned.seen)
+            } else {
+                HashMap::new()
+            }
+        });
+
+        // NB: values are dropped outside of the borrow, since dropping them
+        // might call into scripts which clone values.
+        drop(seen);
+    }
+}
+
+/// A guard for a value being cloned through the clone protocol.
+pub(crate) struct Protocol(Option<*const ()>);
+
+impl Protocol {
+    /// Enter the clone protocol for the given value, erroring if it's already
+    /// being cloned through it.
+    pub(crate) fn enter(value: &Value) -> Result<Self, VmError> {
+        let ptr = match address(value) {
+            Some(ptr) => ptr,
+            None => return Ok(Self(None)),
+        };
+
+        if !CLONED.with(|tls| tls.borrow_mut().protocol.insert(ptr)) {
+            return Err(VmError::from(VmErrorKind::CloneCycle));
+        }
+
+        Ok(Self(Some(ptr)))
+    }
+}
+
+impl Drop for Protocol {
+    fn drop(&mut self) {
+        if let Some(ptr) = self.0 {
+            CLONED.with(|tls| tls.borrow_mut().protocol.remove(&ptr));
+        }
+    }
+}
+
+/// The address of a shared value.
+fn address(value: &Value) -> Option<*const ()> {
+    Some(match value {
+        Value::String(value) => Shared::as_ptr(value),
+        Value::Bytes(value) => Shared::as_ptr(value),
+        Value::Vec(value) => Shared::as_ptr(value),
+        Value::Tuple(value) => Shared::as_ptr(value),
+        Value::Object(value) => Shared::as_ptr(value),
+        Value::Range(value) => Shared::as_ptr(value),
+        Value::Future(value) => Shared::as_ptr(value),
+        Value::Stream(value) => Shared::as_ptr(value),
+        Value::Generator(value) => Shared::as_ptr(value),
+        Value::GeneratorState(value) => Shared::as_ptr(value),
+        Value::Option(value) => Shared::as_ptr(value),
+        Value::Result(value) => Shared::as_ptr(value),
+        Value::UnitStruct(value) => Shared::as_ptr(value),
+        Value::TupleStruct(value) => Shared::as_ptr(value),
+        Value::Struct(value) => Shared::as_ptr(value),
+        Value::Variant(value) => Shared::as_ptr(value),
+        Value::Function(value) => Shared::as_ptr(value),
+        Value::Iterator(value) => Shared::as_ptr(value),
+        Value::Any(value) => Shared::as_ptr(value),
+        _ => return None,
+    })
+}
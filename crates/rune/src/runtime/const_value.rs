@@ -52,7 +52,7 @@ impl ConstValue {
             Self::Bool(b) => Value::Bool(b),
             Self::Integer(n) => Value::Integer(n),
             Self::Float(n) => Value::Float(n),
-            Self::String(s) => Value::String(Shared::new(s)),
+            Self::String(s) => Value::from(s),
             Self::StaticString(s) => Value::StaticString(s),
             Self::Bytes(b) => Value::Bytes(Shared::new(b)),
             Self::Option(option) => {
@@ -7,6 +7,7 @@ use crate::runtime::DebugLabel;
 use crate::{Hash, SourceId};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops;
 
 /// Debug information about a unit.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -18,6 +19,9 @@ pub struct DebugInfo {
     pub functions: HashMap<Hash, DebugSignature>,
     /// Reverse lookup of a function.
     pub functions_rev: HashMap<usize, Hash>,
+    /// Named variables declared in functions.
+    #[serde(default)]
+    pub variables: Vec<DebugVariable>,
 }
 
 impl DebugInfo {
@@ -32,6 +36,37 @@ impl DebugInfo {
         let signature = self.functions.get(&hash)?;
         Some((hash, signature))
     }
+
+    /// Get the named variables which are visible at the given instruction
+    /// pointer, ordered by their stack offset.
+    ///
+    /// If a variable is shadowed, only the most recent declaration is
+    /// returned.
+    pub fn variables_at(&self, ip: usize) -> Vec<&DebugVariable> {
+        let inst = match self.instruction_at(ip) {
+            Some(inst) => inst,
+            None => return Vec::new(),
+        };
+
+        let mut visible = HashMap::<&str, &DebugVariable>::new();
+
+        for var in &self.variables {
+            if !var.is_visible_at(ip, inst) {
+                continue;
+            }
+
+            match visible.get(&*var.name) {
+                Some(existing) if existing.span.start > var.span.start => (),
+                _ => {
+                    visible.insert(&var.name, var);
+                }
+            }
+        }
+
+        let mut visible = visible.into_iter().map(|(_, var)| var).collect::<Vec<_>>();
+        visible.sort_by_key(|var| var.offset);
+        visible
+    }
 }
 
 /// Debug information for every instruction.
@@ -65,6 +100,43 @@ impl DebugInst {
     }
 }
 
+/// Debug information on a named variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DebugVariable {
+    /// The name of the variable.
+    pub name: Box<str>,
+    /// The stack offset of the variable relative to the bottom of its call
+    /// frame.
+    pub offset: usize,
+    /// The file by id the variable was declared in.
+    pub source_id: SourceId,
+    /// The span of the declaration.
+    pub span: Span,
+    /// The span of the scope the variable is declared in, or `None` if it's
+    /// declared in the top-level scope of a function.
+    pub scope: Option<Span>,
+    /// The instructions of the function the variable belongs to.
+    pub instructions: ops::Range<usize>,
+}
+
+impl DebugVariable {
+    /// Test if the variable is visible at the given instruction.
+    fn is_visible_at(&self, ip: usize, inst: &DebugInst) -> bool {
+        if !self.instructions.contains(&ip) || self.source_id != inst.source_id {
+            return false;
+        }
+
+        if let Some(scope) = self.scope {
+            if inst.span.start < scope.start || inst.span.end > scope.end {
+                return false;
+            }
+        }
+
+        self.span.end <= inst.span.start
+    }
+}
+
 /// Debug information on function arguments.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DebugArgs {
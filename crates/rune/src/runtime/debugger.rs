@@ -0,0 +1,374 @@
+//! A step debugger for virtual machines.
+
+use crate::collections::HashSet;
+use crate::runtime::{Unit, Value, Vm, VmError, VmExecution};
+use crate::{Hash, SourceId, Sources};
+use std::ops;
+use std::sync::Arc;
+
+/// How execution should proceed when a [Debugger] is resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DebugStep {
+    /// Run until a breakpoint is hit or execution completes.
+    Continue,
+    /// Stop at the next line, entering any function which is called.
+    Into,
+    /// Stop at the next line of the current function or one of its callers.
+    Over,
+    /// Stop at the next line once the current function has returned.
+    Out,
+}
+
+/// The reason why a [Debugger] stopped.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DebugStop {
+    /// A breakpoint was hit.
+    Breakpoint,
+    /// A step was completed.
+    Step,
+    /// Execution completed with the given value.
+    Complete(Value),
+}
+
+/// A line in a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DebugLocation {
+    /// The source the line belongs to.
+    pub source_id: SourceId,
+    /// The zero-based line number.
+    pub line: usize,
+}
+
+/// A call frame as seen by the debugger.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DebugFrame {
+    /// The instruction the frame is currently at.
+    pub ip: usize,
+    /// The function the frame is executing.
+    pub function: Option<Hash>,
+    /// The line the frame is currently at.
+    pub location: Option<DebugLocation>,
+    /// The part of the stack which belongs to the frame.
+    stack: ops::Range<usize>,
+}
+
+/// A step debugger which drives a [VmExecution], stopping at breakpoints and
+/// after steps.
+///
+/// Breakpoints and steps operate on source lines, which are resolved using
+/// the debug information of the unit being debugged.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, Diagnostics, FromValue, Source, Sources, Vm};
+/// use rune::runtime::debugger::{DebugStep, DebugStop, Debugger};
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let context = Context::with_default_modules()?;
+///
+/// let mut sources = Sources::new();
+/// let source_id = sources.insert(Source::new("test", r#"
+/// pub fn main() {
+///     let a = 1;
+///     let b = a + 2;
+///     b
+/// }
+/// "#));
+///
+/// let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+/// let mut vm = Vm::new(Arc::new(context.runtime()), unit.clone());
+/// let mut execution = vm.execute(&["main"], ())?;
+///
+/// let mut debugger = Debugger::new(unit, &sources);
+/// assert_eq!(debugger.set_breakpoint(source_id, 3), Some(3));
+///
+/// let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+/// assert!(matches!(stop, DebugStop::Breakpoint));
+///
+/// let locals = debugger.locals(execution.vm(), &debugger.backtrace(execution.vm())[0]);
+/// assert_eq!(locals.len(), 1);
+/// assert_eq!(locals[0].0, "a");
+/// assert_eq!(i64::from_value(locals[0].1.clone())?, 1);
+///
+/// let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+/// assert!(matches!(stop, DebugStop::Complete(..)));
+/// # Ok(()) }
+/// ```
+pub struct Debugger {
+    /// The unit being debugged.
+    unit: Arc<Unit>,
+    /// The line of every instruction in the unit.
+    locations: Vec<Option<DebugLocation>>,
+    /// Sorted entrypoints of functions.
+    functions: Vec<(usize, Hash)>,
+    /// Lines on which to stop.
+    breakpoints: HashSet<DebugLocation>,
+    /// The last line executed at each call depth.
+    frames: Vec<Option<DebugLocation>>,
+}
+
+impl Debugger {
+    /// Construct a new debugger for the given unit, using the given sources to
+    /// resolve lines.
+    ///
+    /// The unit must have been compiled with debug information, or the
+    /// debugger won't be able to stop anywhere.
+    pub fn new(unit: Arc<Unit>, sources: &Sources) -> Self {
+        let mut locations = Vec::new();
+        let mut functions = Vec::new();
+
+        if let Some(debug) = unit.debug_info() {
+            for inst in &debug.instructions {
+                let location = sources.get(inst.source_id).and_then(|source| {
+                    let line = source.line_index(inst.span.start.into_usize());
+
+                    // NB: instructions which span multiple lines, like the
+                    // ones cleaning up after a block, don't belong to a line
+                    // and are skipped over.
+                    if source.line_index(inst.span.end.into_usize()) != line {
+                        return None;
+                    }
+
+                    Some(DebugLocation {
+                        source_id: inst.source_id,
+                        line,
+                    })
+                });
+
+                locations.push(location);
+            }
+
+            functions.extend(debug.functions_rev.iter().map(|(ip, hash)| (*ip, *hash)));
+            functions.sort();
+        }
+
+        Self {
+            unit,
+            locations,
+            functions,
+            breakpoints: HashSet::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Set a breakpoint on the given zero-based line.
+    ///
+    /// If there is no code on the given line, the breakpoint is moved to the
+    /// next line which has code. Returns the line the breakpoint was set on, or
+    /// `None` if there is no code on or after the given line.
+    pub fn set_breakpoint(&mut self, source_id: SourceId, line: usize) -> Option<usize> {
+        let line = self
+            .locations
+            .iter()
+            .flatten()
+            .filter(|l| l.source_id == source_id && l.line >= line)
+            .map(|l| l.line)
+            .min()?;
+
+        self.breakpoints.insert(DebugLocation { source_id, line });
+        Some(line)
+    }
+
+    /// Remove the breakpoint on the given zero-based line. Returns `true` if
+    /// there was a breakpoint on that line.
+    pub fn remove_breakpoint(&mut self, source_id: SourceId, line: usize) -> bool {
+        self.breakpoints.remove(&DebugLocation { source_id, line })
+    }
+
+    /// Remove all breakpoints in the given source.
+    pub fn clear_breakpoints(&mut self, source_id: SourceId) {
+        self.breakpoints.retain(|l| l.source_id != source_id);
+    }
+
+    /// Iterate over all breakpoints.
+    pub fn breakpoints(&self) -> impl Iterator<Item = DebugLocation> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Get the line corresponding to the given instruction pointer.
+    pub fn location_at(&self, ip: usize) -> Option<DebugLocation> {
+        self.locations.get(ip).copied().flatten()
+    }
+
+    /// Get the function which contains the given instruction pointer.
+    pub fn function_at(&self, ip: usize) -> Option<Hash> {
+        let index = match self.functions.binary_search_by(|(start, _)| start.cmp(&ip)) {
+            Ok(index) => index,
+            Err(index) => index.checked_sub(1)?,
+        };
+
+        Some(self.functions[index].1)
+    }
+
+    /// Resume the given execution until it's stopped according to `step`, a
+    /// breakpoint is hit or it completes, without support for async
+    /// instructions.
+    ///
+    /// Execution stops *before* the first instruction of a line is executed.
+    pub fn resume<T>(
+        &mut self,
+        execution: &mut VmExecution<T>,
+        step: DebugStep,
+    ) -> Result<DebugStop, VmError>
+    where
+        T: AsMut<Vm> + AsRef<Vm>,
+    {
+        let start = execution.depth();
+
+        loop {
+            if let Some(stop) = self.check(execution, step, start) {
+                return Ok(stop);
+            }
+
+            if let Some(value) = execution.step()? {
+                self.frames.clear();
+                return Ok(DebugStop::Complete(value));
+            }
+        }
+    }
+
+    /// Resume the given execution until it's stopped according to `step`, a
+    /// breakpoint is hit or it completes, with support for async
+    /// instructions.
+    ///
+    /// Execution stops *before* the first instruction of a line is executed.
+    pub async fn async_resume<T>(
+        &mut self,
+        execution: &mut VmExecution<T>,
+        step: DebugStep,
+    ) -> Result<DebugStop, VmError>
+    where
+        T: AsMut<Vm> + AsRef<Vm>,
+    {
+        let start = execution.depth();
+
+        loop {
+            if let Some(stop) = self.check(execution, step, start) {
+                return Ok(stop);
+            }
+
+            if let Some(value) = execution.async_step().await? {
+                self.frames.clear();
+                return Ok(DebugStop::Complete(value));
+            }
+        }
+    }
+
+    /// Check if execution should stop before the next instruction is
+    /// executed. `start` is the call depth the step started at.
+    fn check<T>(
+        &mut self,
+        execution: &VmExecution<T>,
+        step: DebugStep,
+        start: usize,
+    ) -> Option<DebugStop>
+    where
+        T: AsMut<Vm> + AsRef<Vm>,
+    {
+        let depth = execution.depth();
+        self.frames.resize(depth + 1, None);
+
+        let location = self.location(execution.vm())?;
+
+        // NB: a line is only entered once per call depth, so returning into
+        // the middle of a line doesn't stop on it again.
+        if self.frames[depth] == Some(location) {
+            return None;
+        }
+
+        self.frames[depth] = Some(location);
+
+        if self.breakpoints.contains(&location) {
+            return Some(DebugStop::Breakpoint);
+        }
+
+        let stop = match step {
+            DebugStep::Continue => false,
+            DebugStep::Into => true,
+            DebugStep::Over => depth <= start,
+            DebugStep::Out => depth < start,
+        };
+
+        if stop {
+            Some(DebugStop::Step)
+        } else {
+            None
+        }
+    }
+
+    /// Get the call frames of the given virtual machine, starting with the
+    /// innermost one.
+    pub fn backtrace(&self, vm: &Vm) -> Vec<DebugFrame> {
+        let mut frames = Vec::new();
+        let mut ip = vm.ip();
+        let mut stack = vm.stack().stack_bottom()..vm.stack().len();
+
+        for frame in vm.call_frames().iter().rev() {
+            frames.push(self.frame(vm, ip, stack.clone()));
+            ip = frame.ip();
+            stack = frame.stack_bottom()..stack.start;
+        }
+
+        frames.push(self.frame(vm, ip, stack));
+        frames
+    }
+
+    /// Get the named variables which are visible in the given frame, ordered
+    /// by when they were declared.
+    pub fn locals(&self, vm: &Vm, frame: &DebugFrame) -> Vec<(String, Value)> {
+        let debug = match self.unit_of(vm).and_then(|unit| unit.debug_info()) {
+            Some(debug) => debug,
+            None => return Vec::new(),
+        };
+
+        let mut locals = Vec::new();
+
+        for var in debug.variables_at(frame.ip) {
+            let index = frame.stack.start + var.offset;
+
+            if !frame.stack.contains(&index) {
+                continue;
+            }
+
+            if let Some(value) = vm.stack().get(index) {
+                locals.push((var.name.to_string(), value.clone()));
+            }
+        }
+
+        locals
+    }
+
+    fn frame(&self, vm: &Vm, ip: usize, stack: ops::Range<usize>) -> DebugFrame {
+        let known = self.unit_of(vm).is_some();
+
+        DebugFrame {
+            ip,
+            function: self.function_at(ip).filter(|_| known),
+            location: self.location_at(ip).filter(|_| known),
+            stack,
+        }
+    }
+
+    /// The line the given virtual machine is at, if it's executing the unit
+    /// being debugged.
+    fn location(&self, vm: &Vm) -> Option<DebugLocation> {
+        self.unit_of(vm)?;
+        self.location_at(vm.ip())
+    }
+
+    /// Access the unit being debugged if it's the one executed by the given
+    /// virtual machine.
+    fn unit_of(&self, vm: &Vm) -> Option<&Unit> {
+        if Arc::ptr_eq(vm.unit(), &self.unit) {
+            Some(&self.unit)
+        } else {
+            None
+        }
+    }
+}
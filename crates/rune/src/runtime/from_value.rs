@@ -1,6 +1,6 @@
 use crate::runtime::{
     AnyObj, Mut, RawMut, RawRef, Ref, Shared, StaticString, Value, VmError, VmErrorKind,
-    VmIntegerRepr,
+    VmIntegerRepr, Wide,
 };
 use crate::Any;
 use std::sync::Arc;
@@ -395,7 +395,13 @@ macro_rules! impl_number {
         impl FromValue for $ty {
             fn from_value(value: Value) -> Result<Self, VmError> {
                 use std::convert::TryInto as _;
-                let integer = value.into_integer()?;
+
+                let integer = match value {
+                    Value::Integer(integer) => integer,
+                    // NB: integers which don't fit in an `int` are stored as
+                    // one of the wide integer types.
+                    value => return Wide::expect(&value)?.to_primitive(),
+                };
 
                 match integer.try_into() {
                     Ok(number) => Ok(number),
@@ -420,6 +426,12 @@ impl_number!(i32);
 impl_number!(i128);
 impl_number!(isize);
 
+impl FromValue for num::BigInt {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(Wide::expect(&value)?.to_bigint())
+    }
+}
+
 impl FromValue for f64 {
     fn from_value(value: Value) -> Result<Self, VmError> {
         value.into_float()
@@ -832,9 +832,10 @@ impl FnOffset {
 
         let mut new_stack = vm.stack_mut().drain(args)?.collect::<Stack>();
         extra.into_stack(&mut new_stack)?;
-        let mut vm = Vm::with_stack(self.context.clone(), self.unit.clone(), new_stack);
-        vm.set_ip(self.offset);
-        Ok(Some(VmCall::new(self.call, vm)))
+        let mut new_vm = Vm::with_stack(self.context.clone(), self.unit.clone(), new_stack);
+        new_vm.inherit_limits(vm);
+        new_vm.set_ip(self.offset);
+        Ok(Some(VmCall::new(self.call, new_vm)))
     }
 }
 
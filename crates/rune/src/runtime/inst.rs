@@ -786,6 +786,54 @@ pub enum Inst {
         /// The integer to test against.
         integer: i64,
     },
+    /// Test if the top of the stack is a byte within the given range.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    InRangeByte {
+        /// The start of the range.
+        start: u8,
+        /// The end of the range.
+        end: u8,
+        /// The limits of the range.
+        limits: InstRangeLimits,
+    },
+    /// Test if the top of the stack is a character within the given range.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    InRangeChar {
+        /// The start of the range.
+        start: char,
+        /// The end of the range.
+        end: char,
+        /// The limits of the range.
+        limits: InstRangeLimits,
+    },
+    /// Test if the top of the stack is an integer within the given range.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <boolean>
+    /// ```
+    InRangeInteger {
+        /// The start of the range.
+        start: i64,
+        /// The end of the range.
+        end: i64,
+        /// The limits of the range.
+        limits: InstRangeLimits,
+    },
 
     /// Test if the top of the stack is a specific boolean.
     ///
@@ -1226,6 +1274,19 @@ impl fmt::Display for Inst {
             Self::EqInteger { integer } => {
                 write!(fmt, "eq-integer integer={}", integer)?;
             }
+            Self::InRangeByte { start, end, limits } => {
+                write!(fmt, "in-range-byte range={:?}{}{:?}", start, limits, end)?;
+            }
+            Self::InRangeChar { start, end, limits } => {
+                write!(
+                    fmt,
+                    "in-range-character range={:?}{}{:?}",
+                    start, limits, end
+                )?;
+            }
+            Self::InRangeInteger { start, end, limits } => {
+                write!(fmt, "in-range-integer range={}{}{}", start, limits, end)?;
+            }
             Self::EqBool { boolean } => {
                 write!(fmt, "eq-integer boolean={}", boolean)?;
             }
@@ -1338,6 +1399,20 @@ pub enum InstRangeLimits {
     Closed,
 }
 
+impl InstRangeLimits {
+    /// Test if `value` is within the range `start` to `end` with the current
+    /// limits.
+    pub(crate) fn contains<T>(self, start: T, end: T, value: T) -> bool
+    where
+        T: PartialOrd,
+    {
+        match self {
+            Self::HalfOpen => start <= value && value < end,
+            Self::Closed => start <= value && value <= end,
+        }
+    }
+}
+
 impl fmt::Display for InstRangeLimits {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1,11 +1,13 @@
 use crate::compile::Named;
 use crate::runtime::{
-    FromValue, Function, Mut, RawMut, RawRef, RawStr, Ref, ToValue, UnsafeFromValue, Value,
-    VmError, VmErrorKind,
+    memory, EnvProtocolCaller, FromValue, Function, Mut, Protocol, ProtocolCaller, RawMut, RawRef,
+    RawStr, Ref, ToValue, UnsafeFromValue, Value, Vec, VmError, VmErrorKind,
 };
 use crate::InstallWith;
+use std::cmp;
 use std::fmt;
 use std::iter;
+use std::mem;
 use std::vec;
 
 // Note: A fair amount of code in this module is duplicated from the Rust
@@ -15,6 +17,10 @@ use std::vec;
 //
 // Copyright 2014-2020 The Rust Project Developers
 
+/// The maximum number of values to preallocate space for when collecting an
+/// iterator.
+const MAX_CAPACITY_HINT: usize = 4096;
+
 /// Internal iterator trait used to build useful internal iterator abstractions,
 /// like [Fuse].
 trait RuneIterator: fmt::Debug {
@@ -104,6 +110,15 @@ impl Iterator {
         }
     }
 
+    /// Construct an iterator which produces values by calling the
+    /// [Protocol::NEXT] protocol on the given value, like a script type which
+    /// implements `next`.
+    ///
+    /// This must be used inside of a virtual machine.
+    pub(crate) fn from_next(value: Value) -> Self {
+        Self::from("std::iter::Next", NextProtocol { value })
+    }
+
     /// Creates an iterator that yields nothing.
     pub fn empty() -> Self {
         Self {
@@ -123,6 +138,15 @@ impl Iterator {
         self.iter.size_hint()
     }
 
+    /// Get the number of values to preallocate space for when collecting the
+    /// iterator.
+    ///
+    /// This is the lower bound of the size hint, clamped since it can be
+    /// arbitrarily large, like for `cycle`.
+    pub(crate) fn capacity_hint(&self) -> usize {
+        cmp::min(self.iter.size_hint().0, MAX_CAPACITY_HINT)
+    }
+
     /// Get the next value out of the iterator.
     pub fn next(&mut self) -> Result<Option<Value>, VmError> {
         self.iter.next()
@@ -177,6 +201,133 @@ impl Iterator {
         }
     }
 
+    /// Flatten an iterator of iterable values.
+    pub fn flatten(self) -> Self {
+        Self {
+            iter: IterRepr::Flatten(Box::new(FlatMap {
+                map: Fuse::new(self.iter),
+                frontiter: None,
+                backiter: None,
+            })),
+        }
+    }
+
+    /// Zip this iterator together with another, producing tuples of values
+    /// from both iterators until either of them is exhausted.
+    pub fn zip(self, other: Value) -> Result<Self, VmError> {
+        let other = other.into_iter()?;
+
+        Ok(Self {
+            iter: IterRepr::Zip(Box::new(Zip {
+                a: self.iter,
+                b: other.iter,
+            })),
+        })
+    }
+
+    /// Take values from the iterator while the given predicate returns `true`.
+    pub fn take_while(self, predicate: Function) -> Self {
+        Self {
+            iter: IterRepr::TakeWhile(Box::new(TakeWhile {
+                iter: self.iter,
+                predicate,
+                done: false,
+            })),
+        }
+    }
+
+    /// Skip values in the iterator while the given predicate returns `true`.
+    pub fn skip_while(self, predicate: Function) -> Self {
+        Self {
+            iter: IterRepr::SkipWhile(Box::new(SkipWhile {
+                iter: self.iter,
+                predicate,
+                done: false,
+            })),
+        }
+    }
+
+    /// Step over the iterator by the given amount, always starting with the
+    /// first element.
+    pub fn step_by(self, step: usize) -> Result<Self, VmError> {
+        if step == 0 {
+            return Err(VmError::panic("step must be non-zero"));
+        }
+
+        Ok(Self {
+            iter: IterRepr::StepBy(Box::new(StepBy {
+                iter: self.iter,
+                step,
+                first_take: true,
+            })),
+        })
+    }
+
+    /// Produce a running state over the iterator.
+    ///
+    /// The function is called with the current state and the next value. If
+    /// it returns `Some(value)`, the value becomes the new state and is
+    /// produced by the iterator. If it returns `None`, iteration stops.
+    pub fn scan(self, initial: Value, f: Function) -> Self {
+        Self {
+            iter: IterRepr::Scan(Box::new(Scan {
+                iter: self.iter,
+                state: Some(initial),
+                f,
+            })),
+        }
+    }
+
+    /// Call the given function with each value as it passes through the
+    /// iterator.
+    pub fn inspect(self, f: Function) -> Self {
+        Self {
+            iter: IterRepr::Inspect(Box::new(Inspect { iter: self.iter, f })),
+        }
+    }
+
+    /// Repeat the iterator endlessly.
+    ///
+    /// Values are buffered as they are produced by the first pass over the
+    /// iterator, and repeated from the buffer after that.
+    pub fn cycle(self) -> Self {
+        Self {
+            iter: IterRepr::Cycle(Box::new(Cycle {
+                iter: Some(self.iter),
+                buffer: vec::Vec::new(),
+                index: 0,
+            })),
+        }
+    }
+
+    /// Group the values of the iterator into vectors of the given size. The
+    /// last vector is shorter if the iterator runs out of values.
+    pub fn chunks(self, size: usize) -> Result<Self, VmError> {
+        if size == 0 {
+            return Err(VmError::panic("chunk size must be non-zero"));
+        }
+
+        Ok(Self {
+            iter: IterRepr::Chunks(Box::new(Chunks {
+                iter: self.iter,
+                size,
+            })),
+        })
+    }
+
+    /// Remove consecutive repeated values from the iterator.
+    ///
+    /// Values are compared using [Protocol::EQ][crate::runtime::Protocol::EQ]
+    /// where necessary, so this must be used inside of a virtual machine.
+    pub fn dedup(self) -> Self {
+        Self {
+            iter: IterRepr::Dedup(Box::new(Dedup {
+                iter: self.iter,
+                last: None,
+            })),
+        }
+    }
+
     /// Find the first matching value in the iterator using the given function.
     pub fn find(mut self, find: Function) -> Result<Option<Value>, VmError> {
         while let Some(value) = self.next()? {
@@ -212,6 +363,91 @@ impl Iterator {
         Ok(false)
     }
 
+    /// Find the index of the first value matching the given predicate.
+    pub fn position(mut self, predicate: Function) -> Result<Option<usize>, VmError> {
+        let mut index = 0usize;
+
+        while let Some(value) = self.next()? {
+            if predicate.call::<_, bool>((value,))? {
+                return Ok(Some(index));
+            }
+
+            index = index.saturating_add(1);
+        }
+
+        Ok(None)
+    }
+
+    /// Consume the iterator, returning the last value.
+    pub fn last(mut self) -> Result<Option<Value>, VmError> {
+        let mut last = None;
+
+        while let Some(value) = self.next()? {
+            last = Some(value);
+        }
+
+        Ok(last)
+    }
+
+    /// Get the `n`th value of the iterator, skipping over the values before
+    /// it.
+    pub fn nth(&mut self, n: usize) -> Result<Option<Value>, VmError> {
+        for _ in 0..n {
+            if self.next()?.is_none() {
+                return Ok(None);
+            }
+        }
+
+        self.next()
+    }
+
+    /// Partition the iterator into two vectors, where the first contains all
+    /// values for which the predicate returned `true` and the second all
+    /// values for which it returned `false`.
+    pub fn partition(mut self, predicate: Function) -> Result<(Vec, Vec), VmError> {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+
+        while let Some(value) = self.next()? {
+            if predicate.call::<_, bool>((value.clone(),))? {
+                left.try_push(value)?;
+            } else {
+                right.try_push(value)?;
+            }
+        }
+
+        Ok((left, right))
+    }
+
+    /// Unzip an iterator of pairs into two vectors.
+    pub fn unzip(mut self) -> Result<(Vec, Vec), VmError> {
+        let cap = self.capacity_hint();
+        let mut left = Vec::try_with_capacity(cap)?;
+        let mut right = Vec::try_with_capacity(cap)?;
+
+        while let Some(value) = self.next()? {
+            // NB: the pair is borrowed, since it might still be referenced
+            // elsewhere.
+            let pair = value.into_tuple()?;
+            let pair = pair.borrow_ref()?;
+
+            let (a, b) = match &pair[..] {
+                [a, b] => (a.clone(), b.clone()),
+                _ => {
+                    return Err(VmError::from(VmErrorKind::ExpectedTupleLength {
+                        actual: pair.len(),
+                        expected: 2,
+                    }));
+                }
+            };
+
+            left.try_push(a)?;
+            right.try_push(b)?;
+        }
+
+        Ok((left, right))
+    }
+
     /// Chain this iterator with another.
     pub fn chain(self, other: Value) -> Result<Self, VmError> {
         let other = other.into_iter()?;
@@ -304,10 +540,12 @@ impl Iterator {
     where
         T: FromValue,
     {
-        let (cap, _) = self.iter.size_hint();
+        let cap = self.capacity_hint();
+        memory::ensure(cap.saturating_mul(mem::size_of::<T>()))?;
         let mut vec = vec::Vec::with_capacity(cap);
 
         while let Some(value) = self.next()? {
+            memory::ensure_push::<T>(vec.len(), vec.capacity())?;
             vec.push(T::from_value(value)?);
         }
 
@@ -324,6 +562,59 @@ impl Iterator {
         Ok(accumulator)
     }
 
+    /// Find the maximum value in the iterator, returning the last one if
+    /// several are equally maximum.
+    pub fn max(self) -> Result<Option<Value>, VmError> {
+        self.select_by_key(None, cmp::Ordering::is_le)
+    }
+
+    /// Find the minimum value in the iterator, returning the first one if
+    /// several are equally minimum.
+    pub fn min(self) -> Result<Option<Value>, VmError> {
+        self.select_by_key(None, cmp::Ordering::is_gt)
+    }
+
+    /// Find the value which gives the maximum key according to the given
+    /// function, returning the last one if several are equally maximum.
+    pub fn max_by_key(self, f: Function) -> Result<Option<Value>, VmError> {
+        self.select_by_key(Some(f), cmp::Ordering::is_le)
+    }
+
+    /// Find the value which gives the minimum key according to the given
+    /// function, returning the first one if several are equally minimum.
+    pub fn min_by_key(self, f: Function) -> Result<Option<Value>, VmError> {
+        self.select_by_key(Some(f), cmp::Ordering::is_gt)
+    }
+
+    /// Select a value by key, where the current selection is replaced when
+    /// `replace` returns `true` for how it compares to the next key.
+    fn select_by_key(
+        mut self,
+        f: Option<Function>,
+        replace: fn(cmp::Ordering) -> bool,
+    ) -> Result<Option<Value>, VmError> {
+        let key = |value: &Value| match &f {
+            Some(f) => f.call::<_, Value>((value.clone(),)),
+            None => Ok(value.clone()),
+        };
+
+        let (mut selected_key, mut selected) = match self.next()? {
+            Some(value) => (key(&value)?, value),
+            None => return Ok(None),
+        };
+
+        while let Some(value) = self.next()? {
+            let value_key = key(&value)?;
+
+            if replace(Value::value_ptr_cmp(&selected_key, &value_key)?) {
+                selected_key = value_key;
+                selected = value;
+            }
+        }
+
+        Ok(Some(selected))
+    }
+
     /// Compute the product under the assumption of a homogeonous iterator of type T.
     pub fn product(self) -> Result<Value, VmError> {
         let product = Product { iter: self.iter };
@@ -390,7 +681,17 @@ enum IterRepr {
     DoubleEndedIterator(Box<IteratorObj<dyn DoubleEndedIteratorTrait>>),
     Map(Box<Map<Self>>),
     FlatMap(Box<FlatMap<Map<Self>>>),
+    Flatten(Box<FlatMap<Self>>),
     Filter(Box<Filter<Self>>),
+    Zip(Box<Zip<Self, Self>>),
+    TakeWhile(Box<TakeWhile<Self>>),
+    SkipWhile(Box<SkipWhile<Self>>),
+    StepBy(Box<StepBy<Self>>),
+    Scan(Box<Scan<Self>>),
+    Inspect(Box<Inspect<Self>>),
+    Cycle(Box<Cycle<Self>>),
+    Chunks(Box<Chunks<Self>>),
+    Dedup(Box<Dedup<Self>>),
     Rev(Box<Rev<Self>>),
     Chain(Box<Chain<Self, Self>>),
     Enumerate(Box<Enumerate<Self>>),
@@ -410,6 +711,16 @@ impl RuneIterator for IterRepr {
             Self::Map(iter) => iter.is_double_ended(),
             Self::FlatMap(iter) => iter.is_double_ended(),
             Self::Filter(iter) => iter.is_double_ended(),
+            Self::Flatten(iter) => iter.is_double_ended(),
+            Self::Zip(iter) => iter.is_double_ended(),
+            Self::TakeWhile(iter) => iter.is_double_ended(),
+            Self::SkipWhile(iter) => iter.is_double_ended(),
+            Self::StepBy(iter) => iter.is_double_ended(),
+            Self::Scan(iter) => iter.is_double_ended(),
+            Self::Inspect(iter) => iter.is_double_ended(),
+            Self::Cycle(iter) => iter.is_double_ended(),
+            Self::Chunks(iter) => iter.is_double_ended(),
+            Self::Dedup(iter) => iter.is_double_ended(),
             Self::Rev(..) => true,
             Self::Chain(iter) => iter.is_double_ended(),
             Self::Enumerate(iter) => iter.is_double_ended(),
@@ -429,6 +740,16 @@ impl RuneIterator for IterRepr {
             Self::Map(iter) => iter.size_hint(),
             Self::FlatMap(iter) => iter.size_hint(),
             Self::Filter(iter) => iter.size_hint(),
+            Self::Flatten(iter) => iter.size_hint(),
+            Self::Zip(iter) => iter.size_hint(),
+            Self::TakeWhile(iter) => iter.size_hint(),
+            Self::SkipWhile(iter) => iter.size_hint(),
+            Self::StepBy(iter) => iter.size_hint(),
+            Self::Scan(iter) => iter.size_hint(),
+            Self::Inspect(iter) => iter.size_hint(),
+            Self::Cycle(iter) => iter.size_hint(),
+            Self::Chunks(iter) => iter.size_hint(),
+            Self::Dedup(iter) => iter.size_hint(),
             Self::Rev(iter) => iter.size_hint(),
             Self::Chain(iter) => iter.size_hint(),
             Self::Enumerate(iter) => iter.size_hint(),
@@ -447,6 +768,16 @@ impl RuneIterator for IterRepr {
             Self::Map(iter) => iter.next(),
             Self::FlatMap(iter) => iter.next(),
             Self::Filter(iter) => iter.next(),
+            Self::Flatten(iter) => iter.next(),
+            Self::Zip(iter) => iter.next(),
+            Self::TakeWhile(iter) => iter.next(),
+            Self::SkipWhile(iter) => iter.next(),
+            Self::StepBy(iter) => iter.next(),
+            Self::Scan(iter) => iter.next(),
+            Self::Inspect(iter) => iter.next(),
+            Self::Cycle(iter) => iter.next(),
+            Self::Chunks(iter) => iter.next(),
+            Self::Dedup(iter) => iter.next(),
             Self::Rev(iter) => iter.next(),
             Self::Chain(iter) => iter.next(),
             Self::Enumerate(iter) => iter.next(),
@@ -468,6 +799,16 @@ impl RuneIterator for IterRepr {
             Self::Map(iter) => iter.next_back(),
             Self::FlatMap(iter) => iter.next_back(),
             Self::Filter(iter) => iter.next_back(),
+            Self::Flatten(iter) => iter.next_back(),
+            Self::Zip(iter) => iter.next_back(),
+            Self::TakeWhile(iter) => iter.next_back(),
+            Self::SkipWhile(iter) => iter.next_back(),
+            Self::StepBy(iter) => iter.next_back(),
+            Self::Scan(iter) => iter.next_back(),
+            Self::Inspect(iter) => iter.next_back(),
+            Self::Cycle(iter) => iter.next_back(),
+            Self::Chunks(iter) => iter.next_back(),
+            Self::Dedup(iter) => iter.next_back(),
             Self::Rev(iter) => iter.next_back(),
             Self::Chain(iter) => iter.next_back(),
             Self::Enumerate(iter) => iter.next_back(),
@@ -488,6 +829,16 @@ impl fmt::Debug for IterRepr {
             Self::Map(iter) => write!(f, "{:?}", iter),
             Self::FlatMap(iter) => write!(f, "{:?}", iter),
             Self::Filter(iter) => write!(f, "{:?}", iter),
+            Self::Flatten(iter) => write!(f, "{:?}", iter),
+            Self::Zip(iter) => write!(f, "{:?}", iter),
+            Self::TakeWhile(iter) => write!(f, "{:?}", iter),
+            Self::SkipWhile(iter) => write!(f, "{:?}", iter),
+            Self::StepBy(iter) => write!(f, "{:?}", iter),
+            Self::Scan(iter) => write!(f, "{:?}", iter),
+            Self::Inspect(iter) => write!(f, "{:?}", iter),
+            Self::Cycle(iter) => write!(f, "{:?}", iter),
+            Self::Chunks(iter) => write!(f, "{:?}", iter),
+            Self::Dedup(iter) => write!(f, "{:?}", iter),
             Self::Rev(iter) => write!(f, "{:?}", iter),
             Self::Chain(iter) => write!(f, "{:?}", iter),
             Self::Enumerate(iter) => write!(f, "{:?}", iter),
@@ -672,6 +1023,445 @@ where
     }
 }
 
+/// Construct the error raised when trying to iterate from the back of an
+/// iterator which isn't double-ended.
+fn not_double_ended<I>(iter: &I) -> VmError
+where
+    I: ?Sized + fmt::Debug,
+{
+    VmError::panic(format!("`{:?}` is not a double-ended iterator", iter))
+}
+
+#[derive(Debug)]
+struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> RuneIterator for Zip<A, B>
+where
+    A: RuneIterator,
+    B: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.a.size_hint();
+        let (b_lower, b_upper) = self.b.size_hint();
+
+        let lower = cmp::min(a_lower, b_lower);
+
+        let upper = match (a_upper, b_upper) {
+            (Some(x), Some(y)) => Some(cmp::min(x, y)),
+            (Some(x), None) => Some(x),
+            (None, Some(y)) => Some(y),
+            (None, None) => None,
+        };
+
+        (lower, upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let a = match self.a.next()? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let b = match self.b.next()? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        Ok(Some((a, b).to_value()?))
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct TakeWhile<I> {
+    iter: I,
+    predicate: Function,
+    done: bool,
+}
+
+impl<I> RuneIterator for TakeWhile<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        if let Some(value) = self.iter.next()? {
+            if self.predicate.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        self.done = true;
+        Ok(None)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct SkipWhile<I> {
+    iter: I,
+    predicate: Function,
+    done: bool,
+}
+
+impl<I> RuneIterator for SkipWhile<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+
+        if self.done {
+            return (lower, upper);
+        }
+
+        (0, upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.done {
+            return self.iter.next();
+        }
+
+        while let Some(value) = self.iter.next()? {
+            if !self.predicate.call::<_, bool>((value.clone(),))? {
+                self.done = true;
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct StepBy<I> {
+    iter: I,
+    step: usize,
+    first_take: bool,
+}
+
+impl<I> RuneIterator for StepBy<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+
+        let f = |n: usize| {
+            if self.first_take {
+                if n == 0 {
+                    0
+                } else {
+                    1 + (n - 1) / self.step
+                }
+            } else {
+                n / self.step
+            }
+        };
+
+        (f(lower), upper.map(f))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.first_take {
+            self.first_take = false;
+            return self.iter.next();
+        }
+
+        for _ in 1..self.step {
+            if self.iter.next()?.is_none() {
+                return Ok(None);
+            }
+        }
+
+        self.iter.next()
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct Scan<I> {
+    iter: I,
+    state: Option<Value>,
+    f: Function,
+}
+
+impl<I> RuneIterator for Scan<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.state.is_none() {
+            return (0, Some(0));
+        }
+
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let state = match &self.state {
+            Some(state) => state.clone(),
+            None => return Ok(None),
+        };
+
+        let value = match self.iter.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let state = self.f.call::<_, Option<Value>>((state, value))?;
+        self.state = state.clone();
+        Ok(state)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct Inspect<I> {
+    iter: I,
+    f: Function,
+}
+
+impl<I> RuneIterator for Inspect<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        self.iter.is_double_ended()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = self.iter.next()?;
+
+        if let Some(value) = &value {
+            self.f.call::<_, ()>((value.clone(),))?;
+        }
+
+        Ok(value)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        let value = self.iter.next_back()?;
+
+        if let Some(value) = &value {
+            self.f.call::<_, ()>((value.clone(),))?;
+        }
+
+        Ok(value)
+    }
+}
+
+#[derive(Debug)]
+struct Cycle<I> {
+    iter: Option<I>,
+    buffer: vec::Vec<Value>,
+    index: usize,
+}
+
+impl<I> RuneIterator for Cycle<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(iter) if self.buffer.is_empty() => match iter.size_hint() {
+                (0, Some(0)) => (0, Some(0)),
+                (0, _) => (0, None),
+                _ => (usize::MAX, None),
+            },
+            None if self.buffer.is_empty() => (0, Some(0)),
+            _ => (usize::MAX, None),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if let Some(iter) = &mut self.iter {
+            if let Some(value) = iter.next()? {
+                self.buffer.push(value.clone());
+                return Ok(Some(value));
+            }
+
+            self.iter = None;
+        }
+
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let value = self.buffer[self.index].clone();
+        self.index = (self.index + 1) % self.buffer.len();
+        Ok(Some(value))
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct Chunks<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I> RuneIterator for Chunks<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let f = |n: usize| n.div_ceil(self.size);
+        (f(lower), upper.map(f))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let mut chunk = Vec::new();
+
+        while chunk.len() < self.size {
+            match self.iter.next()? {
+                Some(value) => chunk.push(value),
+                None => break,
+            }
+        }
+
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(chunk.to_value()?))
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+#[derive(Debug)]
+struct Dedup<I> {
+    iter: I,
+    last: Option<Value>,
+}
+
+impl<I> RuneIterator for Dedup<I>
+where
+    I: RuneIterator,
+{
+    fn is_double_ended(&self) -> bool {
+        false
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let pending = usize::from(self.last.is_some());
+        let lower = usize::from(lower.saturating_add(pending) > 0);
+        (lower, upper.and_then(|upper| upper.checked_add(pending)))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let current = match self.last.take() {
+            Some(value) => value,
+            None => match self.iter.next()? {
+                Some(value) => value,
+                None => return Ok(None),
+            },
+        };
+
+        while let Some(value) = self.iter.next()? {
+            if !Value::value_ptr_eq_env(&current, &value)? {
+                self.last = Some(value);
+                break;
+            }
+        }
+
+        Ok(Some(current))
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(not_double_ended(self))
+    }
+}
+
+/// An iterator which calls the [Protocol::NEXT] protocol on a value.
+struct NextProtocol {
+    value: Value,
+}
+
+impl IteratorTrait for NextProtocol {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = EnvProtocolCaller.call_protocol_fn(Protocol::NEXT, self.value.clone(), ())?;
+        <Option<Value> as FromValue>::from_value(value)
+    }
+}
+
 /// The trait for interacting with an iterator.
 ///
 /// This has a blanket implementation, and is primarily used to restrict the
@@ -1,16 +1,31 @@
+use crate::runtime::vm::CallResult;
 use crate::runtime::{
-    Bytes, FromValue, Object, Shared, StaticString, ToValue, Tuple, TypeInfo, Value, Variant,
-    VariantData, VariantRtti, Vec, VmError, VmErrorKind,
+    AnyObj, Bytes, EnvProtocolCaller, FromValue, Object, Protocol, Rtti, Shared, StaticString,
+    Struct, ToValue, Tuple, TupleStruct, TypeInfo, UnitStruct, Value, Variant, VariantData,
+    VariantRtti, Vec, VmError, VmErrorKind, Wide,
 };
+use crate::{Any, Hash};
 use serde::{de, ser};
+use std::cell::Cell;
 use std::cmp;
 use std::fmt;
 use std::hash;
 use std::sync::Arc;
 use std::vec;
 
+thread_local! {
+    /// The first error raised by a comparison of keys through the standard
+    /// comparison traits, see [Key::checked].
+    static ERROR: Cell<Option<VmError>> = Cell::new(None);
+}
+
 /// A key that can be used as an anonymous object key.
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+///
+/// Keys are compared through [Key::value_ptr_eq] and [Key::value_ptr_cmp],
+/// which is also what the standard comparison traits use. Since those can
+/// fail, comparisons through the standard traits should be made inside of
+/// [Key::checked].
+#[derive(Clone, Hash)]
 pub enum Key {
     /// A constant unit.
     Unit,
@@ -34,10 +49,20 @@ pub enum Key {
     Option(Option<Box<Key>>),
     /// A variant.
     Variant(VariantKey),
+    /// A script struct, which is hashed structurally.
+    Struct(StructKey),
+    /// A native value, hashed through the [Protocol::HASH] protocol.
+    Any(AnyKey),
 }
 
 impl Key {
     /// Convert a value reference into a key.
+    ///
+    /// Native values can only be used as keys if their type has been registered
+    /// through [Module::key][crate::Module::key], in which case the key holds
+    /// on to its own copy of the value. Integers of any
+    /// width are stored as the narrowest of `int`, `i128` and big integers
+    /// which fits them, so that `1u64` and `1` are the same key.
     pub fn from_value(value: &Value) -> Result<Self, VmError> {
         return Ok(match value {
             Value::Unit => Self::Unit,
@@ -88,6 +113,33 @@ impl Key {
                     data,
                 })
             }
+            Value::UnitStruct(st) => Key::Struct(StructKey {
+                rtti: st.borrow_ref()?.rtti.clone(),
+                data: VariantKeyData::Unit,
+            }),
+            Value::TupleStruct(st) => {
+                let st = st.borrow_ref()?;
+
+                Key::Struct(StructKey {
+                    rtti: st.rtti.clone(),
+                    data: VariantKeyData::Tuple(tuple_from_value(&st.data)?),
+                })
+            }
+            Value::Struct(st) => {
+                let st = st.borrow_ref()?;
+
+                Key::Struct(StructKey {
+                    rtti: st.rtti.clone(),
+                    data: VariantKeyData::Struct(struct_from_value(&st.data)?),
+                })
+            }
+            // NB: integers which are numerically equal are the same key,
+            // regardless of their width.
+            Value::Any(..) => match Wide::from_value(value)?.map(Wide::normalize) {
+                Some(Wide::Integer(n)) => Self::Integer(n),
+                Some(wide) => any_from_value(wide.to_value()?)?,
+                None => any_from_value(value.clone())?,
+            },
             value => {
                 return Err(VmError::from(VmErrorKind::KeyNotSupported {
                     actual: value.type_info()?,
@@ -95,6 +147,42 @@ impl Key {
             }
         });
 
+        fn any_from_value(value: Value) -> Result<Key, VmError> {
+            let type_hash = value.type_hash()?;
+            let type_info = value.type_info()?;
+
+            let key = crate::runtime::env::with(|context, _| Ok(context.key(type_hash).cloned()))?;
+
+            let key = match key {
+                Some(key) => key,
+                None => {
+                    return Err(VmError::from(VmErrorKind::KeyNotSupported {
+                        actual: type_info,
+                    }))
+                }
+            };
+
+            // NB: the key holds on to its own copy of the native value, so
+            // that it can't be changed behind the back of a collection.
+            let copy = key(&value)?;
+
+            let hash = match EnvProtocolCaller.try_call_protocol_fn(Protocol::HASH, value, ())? {
+                CallResult::Ok(hash) => i64::from_value(hash)?,
+                CallResult::Unsupported(..) => {
+                    return Err(VmError::from(VmErrorKind::KeyNotSupported {
+                        actual: type_info,
+                    }))
+                }
+            };
+
+            Ok(Key::Any(AnyKey {
+                type_hash,
+                type_info,
+                hash,
+                value: copy,
+            }))
+        }
+
         fn tuple_from_value(tuple: &Tuple) -> Result<Box<[Key]>, VmError> {
             let mut output = vec::Vec::with_capacity(tuple.len());
 
@@ -129,7 +217,7 @@ impl Key {
             Self::Bool(b) => Value::Bool(b),
             Self::Integer(n) => Value::Integer(n),
             Self::String(s) => match s {
-                StringKey::String(s) => Value::String(Shared::new(String::from(s))),
+                StringKey::String(s) => Value::from(String::from(s)),
                 StringKey::StaticString(s) => Value::StaticString(s),
             },
             Self::Bytes(b) => Value::Bytes(Shared::new(b)),
@@ -158,6 +246,20 @@ impl Key {
                     data,
                 }))
             }
+            Self::Struct(st) => match st.data {
+                VariantKeyData::Unit => {
+                    Value::UnitStruct(Shared::new(UnitStruct { rtti: st.rtti }))
+                }
+                VariantKeyData::Tuple(tuple) => Value::TupleStruct(Shared::new(TupleStruct {
+                    rtti: st.rtti,
+                    data: tuple_into_value(tuple),
+                })),
+                VariantKeyData::Struct(data) => Value::Struct(Shared::new(Struct {
+                    rtti: st.rtti,
+                    data: struct_into_value(data),
+                })),
+            },
+            Self::Any(any) => any.to_value(),
         };
 
         fn tuple_into_value(data: Box<[Key]>) -> Tuple {
@@ -203,8 +305,215 @@ impl Key {
             Self::Tuple(..) => TypeInfo::StaticType(crate::runtime::TUPLE_TYPE),
             Self::Option(..) => TypeInfo::StaticType(crate::runtime::OPTION_TYPE),
             Self::Variant(variant) => TypeInfo::Variant(variant.rtti.clone()),
+            Self::Struct(st) => TypeInfo::Typed(st.rtti.clone()),
+            Self::Any(any) => any.type_info.clone(),
         }
     }
+
+    /// Call the given closure, which compares keys through the standard
+    /// comparison traits, like when looking them up in a
+    /// [BTreeMap][std::collections::BTreeMap].
+    ///
+    /// The standard traits can't fail, so a comparison which raises an error
+    /// is treated as unequal keys, or equal keys when ordering them. The first
+    /// such error is returned here instead of the output of the closure.
+    pub(crate) fn checked<F, T>(f: F) -> Result<T, VmError>
+    where
+        F: FnOnce() -> T,
+    {
+        let outer = ERROR.with(|error| error.take());
+        let output = f();
+
+        match ERROR.with(|error| error.replace(outer)) {
+            Some(error) => Err(error),
+            None => Ok(output),
+        }
+    }
+
+    /// Test if two keys are equal, like when looking them up in a hash map.
+    ///
+    /// Native values of the same type and hash are told apart through the
+    /// [Protocol::EQ] protocol, which raises an error if it isn't implemented.
+    pub(crate) fn value_ptr_eq(a: &Key, b: &Key) -> Result<bool, VmError> {
+        Ok(match (a, b) {
+            (Self::Unit, Self::Unit) => true,
+            (Self::Byte(x), Self::Byte(y)) => x == y,
+            (Self::Char(x), Self::Char(y)) => x == y,
+            (Self::Bool(x), Self::Bool(y)) => x == y,
+            (Self::Integer(x), Self::Integer(y)) => x == y,
+            (Self::String(x), Self::String(y)) => x == y,
+            (Self::Bytes(x), Self::Bytes(y)) => x == y,
+            (Self::Vec(x), Self::Vec(y)) => slice_eq(x, y)?,
+            (Self::Tuple(x), Self::Tuple(y)) => slice_eq(x, y)?,
+            (Self::Option(x), Self::Option(y)) => match (x, y) {
+                (Some(x), Some(y)) => Self::value_ptr_eq(x, y)?,
+                (x, y) => x.is_none() && y.is_none(),
+            },
+            (Self::Struct(x), Self::Struct(y)) => {
+                x.rtti.hash == y.rtti.hash && x.data.value_ptr_eq(&y.data)?
+            }
+            (Self::Variant(x), Self::Variant(y)) => {
+                x.rtti.hash == y.rtti.hash && x.data.value_ptr_eq(&y.data)?
+            }
+            (Self::Any(x), Self::Any(y)) => {
+                if x.type_hash != y.type_hash || x.hash != y.hash {
+                    return Ok(false);
+                }
+
+                if x.is_same(y) {
+                    return Ok(true);
+                }
+
+                Value::value_ptr_eq_env(&x.to_value(), &y.to_value())?
+            }
+            _ => false,
+        })
+    }
+
+    /// Compare two keys in the order used by ordered collections.
+    ///
+    /// Script structs and variants of the same type are ordered through the
+    /// [Protocol::CMP] protocol if they implement it, and native values of the
+    /// same type through [Protocol::CMP] or [Protocol::PARTIAL_CMP]. Native
+    /// values which can't be compared raise an error.
+    pub(crate) fn value_ptr_cmp(a: &Key, b: &Key) -> Result<cmp::Ordering, VmError> {
+        return Ok(match (a, b) {
+            (Self::Unit, Self::Unit) => cmp::Ordering::Equal,
+            (Self::Byte(x), Self::Byte(y)) => x.cmp(y),
+            (Self::Char(x), Self::Char(y)) => x.cmp(y),
+            (Self::Bool(x), Self::Bool(y)) => x.cmp(y),
+            (Self::Integer(x), Self::Integer(y)) => x.cmp(y),
+            (Self::String(x), Self::String(y)) => x.cmp(y),
+            (Self::Bytes(x), Self::Bytes(y)) => x.cmp(y),
+            (Self::Vec(x), Self::Vec(y)) => slice_cmp(x, y)?,
+            (Self::Tuple(x), Self::Tuple(y)) => slice_cmp(x, y)?,
+            (Self::Option(x), Self::Option(y)) => match (x, y) {
+                (Some(x), Some(y)) => Self::value_ptr_cmp(x, y)?,
+                (x, y) => x.is_some().cmp(&y.is_some()),
+            },
+            (Self::Struct(x), Self::Struct(y)) if x.rtti.hash == y.rtti.hash => {
+                match protocol_cmp(a, b)? {
+                    Some(ordering) => ordering,
+                    None => x.data.value_ptr_cmp(&y.data)?,
+                }
+            }
+            (Self::Struct(x), Self::Struct(y)) => x.rtti.hash.cmp(&y.rtti.hash),
+            (Self::Variant(x), Self::Variant(y)) if x.rtti.enum_hash == y.rtti.enum_hash => {
+                if let Some(ordering) = protocol_cmp(a, b)? {
+                    return Ok(ordering);
+                }
+
+                match x.rtti.hash.cmp(&y.rtti.hash) {
+                    cmp::Ordering::Equal => x.data.value_ptr_cmp(&y.data)?,
+                    ordering => ordering,
+                }
+            }
+            (Self::Variant(x), Self::Variant(y)) => x.rtti.enum_hash.cmp(&y.rtti.enum_hash),
+            (Self::Any(x), Self::Any(y)) if x.type_hash == y.type_hash => {
+                if x.is_same(y) {
+                    return Ok(cmp::Ordering::Equal);
+                }
+
+                Value::value_ptr_cmp(&x.to_value(), &y.to_value())?
+            }
+            (Self::Any(x), Self::Any(y)) => x.type_hash.cmp(&y.type_hash),
+            (a, b) => a.rank().cmp(&b.rank()),
+        });
+
+        /// Compare two keys of the same script type through the
+        /// [Protocol::CMP] protocol, if it's implemented.
+        fn protocol_cmp(a: &Key, b: &Key) -> Result<Option<cmp::Ordering>, VmError> {
+            let a = a.clone().into_value();
+            let b = b.clone().into_value();
+
+            match EnvProtocolCaller.try_call_protocol_fn(Protocol::CMP, a, (b,))? {
+                CallResult::Ok(value) => Ok(Some(cmp::Ordering::from_value(value)?)),
+                CallResult::Unsupported(..) => Ok(None),
+            }
+        }
+    }
+
+    /// The rank of the kind of key, which orders keys of different kinds.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Unit => 0,
+            Self::Byte(..) => 1,
+            Self::Char(..) => 2,
+            Self::Bool(..) => 3,
+            Self::Integer(..) => 4,
+            Self::String(..) => 5,
+            Self::Bytes(..) => 6,
+            Self::Vec(..) => 7,
+            Self::Tuple(..) => 8,
+            Self::Option(..) => 9,
+            Self::Variant(..) => 10,
+            Self::Struct(..) => 11,
+            Self::Any(..) => 12,
+        }
+    }
+}
+
+/// Unwrap the result of a comparison made through one of the standard
+/// comparison traits, recording its error for [Key::checked].
+fn record<T>(result: Result<T, VmError>, fallback: T) -> T {
+    match result {
+        Ok(output) => output,
+        Err(error) => {
+            ERROR.with(|cell| {
+                let first = cell.take().unwrap_or(error);
+                cell.set(Some(first));
+            });
+
+            fallback
+        }
+    }
+}
+
+impl cmp::PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        record(Key::value_ptr_eq(self, other), false)
+    }
+}
+
+impl cmp::Eq for Key {}
+
+impl cmp::PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::Ord for Key {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        record(Key::value_ptr_cmp(self, other), cmp::Ordering::Equal)
+    }
+}
+
+/// Test if two slices of keys are equal.
+fn slice_eq(a: &[Key], b: &[Key]) -> Result<bool, VmError> {
+    if a.len() != b.len() {
+        return Ok(false);
+    }
+
+    for (a, b) in a.iter().zip(b.iter()) {
+        if !Key::value_ptr_eq(a, b)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Lexicographically compare two slices of keys.
+fn slice_cmp(a: &[Key], b: &[Key]) -> Result<cmp::Ordering, VmError> {
+    for (a, b) in a.iter().zip(b.iter()) {
+        match Key::value_ptr_cmp(a, b)? {
+            cmp::Ordering::Equal => continue,
+            ordering => return Ok(ordering),
+        }
+    }
+
+    Ok(a.len().cmp(&b.len()))
 }
 
 impl fmt::Debug for Key {
@@ -221,6 +530,8 @@ impl fmt::Debug for Key {
             Key::Tuple(tuple) => write!(f, "{:?}", tuple),
             Key::Option(opt) => write!(f, "{:?}", opt),
             Key::Variant(variant) => write!(f, "{:?}", variant),
+            Key::Struct(st) => write!(f, "{:?}", st),
+            Key::Any(any) => write!(f, "{:?}", any),
         }
     }
 }
@@ -283,6 +594,8 @@ impl ser::Serialize for Key {
             }
             Self::Option(option) => <Option<Box<Key>>>::serialize(option, serializer),
             Self::Variant(..) => Err(ser::Error::custom("cannot serialize variants")),
+            Self::Struct(..) => Err(ser::Error::custom("cannot serialize structs")),
+            Self::Any(any) => any.to_value().serialize(serializer),
         }
     }
 }
@@ -508,7 +821,7 @@ impl cmp::Ord for StringKey {
 }
 
 /// A variant that has been serialized to a key.
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Hash)]
 pub struct VariantKey {
     rtti: Arc<VariantRtti>,
     data: VariantKeyData,
@@ -517,8 +830,147 @@ pub struct VariantKey {
 impl fmt::Debug for VariantKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.rtti.item)?;
+        self.data.fmt_fields(f)
+    }
+}
+
+/// A script struct that has been serialized to a key.
+#[derive(Clone, Hash)]
+pub struct StructKey {
+    rtti: Arc<Rtti>,
+    data: VariantKeyData,
+}
+
+impl fmt::Debug for StructKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rtti.item)?;
+        self.data.fmt_fields(f)
+    }
+}
+
+/// A native value that has been hashed through the [Protocol::HASH] protocol.
+///
+/// The key holds on to its own copy of the value, so that it can't be changed
+/// behind the back of a collection. Native values are compared by making new
+/// values out of that copy.
+#[derive(Clone)]
+pub struct AnyKey {
+    type_hash: Hash,
+    type_info: TypeInfo,
+    hash: i64,
+    value: Arc<dyn AnyKeyValue>,
+}
+
+impl AnyKey {
+    /// Construct a new native value from the key.
+    fn to_value(&self) -> Value {
+        self.value.to_value()
+    }
+
+    /// Test if two keys hold on to the same copy of a native value.
+    fn is_same(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.value) as *const () == Arc::as_ptr(&other.value) as *const ()
+    }
+}
+
+impl hash::Hash for AnyKey {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.type_hash.hash(state);
+        self.hash.hash(state);
+    }
+}
+
+impl fmt::Debug for AnyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.type_info)
+    }
+}
+
+/// A copy of a native value held by an [AnyKey].
+pub(crate) trait AnyKeyValue: Send + Sync {
+    /// Construct a new value out of the copy.
+    fn to_value(&self) -> Value;
+}
+
+impl<T> AnyKeyValue for T
+where
+    T: Any + Clone + Send + Sync,
+{
+    fn to_value(&self) -> Value {
+        Value::from(AnyObj::new(self.clone()))
+    }
+}
+
+/// Variant data that has been serialized to a key.
+#[derive(Debug, Clone, Hash)]
+pub enum VariantKeyData {
+    /// A unit variant with a specific type hash.
+    Unit,
+    /// A tuple variant with a specific type hash.
+    Tuple(Box<[Key]>),
+    /// An struct variant with a specific type hash.
+    Struct(Box<[(Box<str>, Key)]>),
+}
+
+impl VariantKeyData {
+    /// Test if the fields of two keys of the same type are equal.
+    fn value_ptr_eq(&self, other: &Self) -> Result<bool, VmError> {
+        match (self, other) {
+            (Self::Tuple(a), Self::Tuple(b)) => slice_eq(a, b),
+            (Self::Struct(a), Self::Struct(b)) => {
+                if a.len() != b.len() {
+                    return Ok(false);
+                }
+
+                for ((a_name, a), (b_name, b)) in a.iter().zip(b.iter()) {
+                    if a_name != b_name || !Key::value_ptr_eq(a, b)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            (Self::Unit, Self::Unit) => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Compare the fields of two keys of the same type, in the order used by
+    /// ordered collections.
+    fn value_ptr_cmp(&self, other: &Self) -> Result<cmp::Ordering, VmError> {
+        match (self, other) {
+            (Self::Tuple(a), Self::Tuple(b)) => slice_cmp(a, b),
+            (Self::Struct(a), Self::Struct(b)) => {
+                for ((a_name, a), (b_name, b)) in a.iter().zip(b.iter()) {
+                    match a_name.cmp(b_name) {
+                        cmp::Ordering::Equal => (),
+                        ordering => return Ok(ordering),
+                    }
+
+                    match Key::value_ptr_cmp(a, b)? {
+                        cmp::Ordering::Equal => continue,
+                        ordering => return Ok(ordering),
+                    }
+                }
+
+                Ok(a.len().cmp(&b.len()))
+            }
+            (a, b) => Ok(a.rank().cmp(&b.rank())),
+        }
+    }
 
-        match &self.data {
+    /// The rank of the kind of data, which orders data of different kinds.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Unit => 0,
+            Self::Tuple(..) => 1,
+            Self::Struct(..) => 2,
+        }
+    }
+
+    /// Debug format the fields of the key data, if any.
+    fn fmt_fields(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
             VariantKeyData::Unit => (),
             VariantKeyData::Tuple(tuple) => {
                 let mut it = tuple.iter();
@@ -545,14 +997,3 @@ impl fmt::Debug for VariantKey {
         Ok(())
     }
 }
-
-/// Variant data that has been serialized to a key.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub enum VariantKeyData {
-    /// A unit variant with a specific type hash.
-    Unit,
-    /// A tuple variant with a specific type hash.
-    Tuple(Box<[Key]>),
-    /// An struct variant with a specific type hash.
-    Struct(Box<[(Box<str>, Key)]>),
-}
@@ -0,0 +1,71 @@
+//! Thread-local tracking of the call depth and stack size of running virtual
+//! machines.
+//!
+//! Native functions can call back into scripts, which is done by constructing
+//! and running a nested virtual machine. So that limits configured through
+//! [Vm::set_max_call_depth][crate::Vm::set_max_call_depth] and
+//! [Vm::set_max_stack_size][crate::Vm::set_max_stack_size] also apply to such
+//! nested virtual machines, the running virtual machine publishes its limits
+//! and usage here.
+
+use std::cell::Cell;
+
+thread_local!(static LIMITS: Cell<Limits> = const { Cell::new(Limits::UNLIMITED) });
+
+/// Call depth and stack size limits, together with how much of them is in use
+/// by the virtual machines running on the current thread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    /// The maximum total number of call frames.
+    pub(crate) max_call_depth: usize,
+    /// The maximum total number of values on the stack.
+    pub(crate) max_stack_size: usize,
+    /// The number of call frames in use.
+    pub(crate) call_depth: usize,
+    /// The number of stack values in use.
+    pub(crate) stack_size: usize,
+}
+
+impl Limits {
+    pub(crate) const UNLIMITED: Self = Self {
+        max_call_depth: usize::MAX,
+        max_stack_size: usize::MAX,
+        call_depth: 0,
+        stack_size: 0,
+    };
+
+    /// Test if either of the limits is in place.
+    #[inline]
+    pub(crate) fn is_limited(&self) -> bool {
+        self.max_call_depth != usize::MAX || self.max_stack_size != usize::MAX
+    }
+}
+
+/// Get the limits of the current thread.
+#[inline]
+pub(crate) fn get() -> Limits {
+    LIMITS.with(|tls| tls.get())
+}
+
+/// Publish the limits of the current thread.
+#[inline]
+pub(crate) fn set(limits: Limits) {
+    LIMITS.with(|tls| tls.set(limits));
+}
+
+/// A guard which restores the limits of the current thread when dropped.
+#[repr(transparent)]
+pub(crate) struct Guard(Limits);
+
+impl Guard {
+    /// Construct a guard restoring the current limits.
+    pub(crate) fn new() -> Self {
+        Self(get())
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        set(self.0);
+    }
+}
@@ -0,0 +1,311 @@
+//! Memory limiting module for Rune.
+//!
+//! This module contains methods which allows for limiting the number of bytes
+//! the virtual machine is allowed to allocate while executing.
+//!
+//! The limit is a quota of allocations, which is consumed when shared values
+//! are constructed, when the stack grows and when containers like vectors,
+//! tuples, objects and strings are constructed or grow. The memory is returned
+//! to the quota when they are dropped.
+//!
+//! Once the quota has been exceeded the virtual machine errors with
+//! [VmErrorKind::MemoryLimitExceeded][crate::runtime::VmErrorKind::MemoryLimitExceeded]
+//! after the instruction which allocated the memory, like one which constructs
+//! a value or calls a function. Operations which allocate a known
+//! amount of memory up front, like `String::with_capacity`, and native
+//! functions which grow containers in a loop, like `Vec::extend` or
+//! `Iterator::collect`, error before the allocation is performed.
+//!
+//! By default no limit is in place, but it can be enabled by wrapping your
+//! function call or future in [with]. While no limit is in place on any thread,
+//! allocations aren't tracked at all.
+//!
+//! # Examples
+//!
+//! ```
+//! use rune::{Context, Source, Sources, Vm};
+//! use rune::runtime::{memory, VmErrorKind};
+//! use std::sync::Arc;
+//!
+//! # fn main() -> rune::Result<()> {
+//! let context = Context::with_default_modules()?;
+//!
+//! let mut sources = Sources::new();
+//! sources.insert(Source::new("test", r#"
+//! pub fn main() {
+//!     let values = [];
+//!
+//!     loop {
+//!         values.push("hello");
+//!     }
+//! }
+//! "#));
+//!
+//! let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+//! let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+//!
+//! let error = memory::with(1024 * 1024, || vm.call(&["main"], ())).call().unwrap_err();
+//! assert!(matches!(error.as_unwound().0, VmErrorKind::MemoryLimitExceeded { .. }));
+//! # Ok(()) }
+//! ```
+
+use crate::runtime::{VmError, VmErrorKind};
+use pin_project::pin_project;
+use std::cell::Cell;
+use std::cmp;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+thread_local!(static MEMORY: Cell<Quota> = const { Cell::new(Quota::UNLIMITED) });
+
+/// The number of memory limits in place across all threads. Allocations skip
+/// the thread-local quota entirely while this is zero.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Test if a memory limit might be in place on the current thread.
+#[inline(always)]
+fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed) != 0
+}
+
+/// The state of the memory quota of the current thread.
+#[derive(Debug, Clone, Copy)]
+struct Quota {
+    /// The maximum number of bytes which can be allocated.
+    limit: usize,
+    /// The number of bytes allocated so far.
+    used: usize,
+}
+
+impl Quota {
+    const UNLIMITED: Self = Self {
+        limit: usize::MAX,
+        used: 0,
+    };
+}
+
+/// Something being memory limited.
+#[pin_project]
+pub struct Memory<T> {
+    /// The maximum number of bytes which can be allocated.
+    limit: usize,
+    /// The number of bytes allocated so far.
+    used: usize,
+    /// The thing being limited.
+    #[pin]
+    value: T,
+}
+
+/// Wrap the given value with a memory limit in bytes.
+pub fn with<T>(limit: usize, value: T) -> Memory<T> {
+    Memory {
+        limit,
+        used: 0,
+        value,
+    }
+}
+
+/// Get the number of bytes which have been allocated within the current limit.
+pub fn used() -> usize {
+    MEMORY.with(|tls| tls.get().used)
+}
+
+/// Record that the given number of bytes has been allocated.
+///
+/// If this exceeds the quota, the virtual machine will error before executing
+/// its next instruction.
+#[inline]
+pub(crate) fn allocate(bytes: usize) {
+    if is_active() {
+        allocate_slow(bytes);
+    }
+}
+
+#[inline(never)]
+fn allocate_slow(bytes: usize) {
+    MEMORY.with(|tls| {
+        let mut quota = tls.get();
+
+        if quota.limit != usize::MAX {
+            quota.used = quota.used.saturating_add(bytes);
+            tls.set(quota);
+        }
+    })
+}
+
+/// Record that the given number of bytes has been freed, returning them to
+/// the quota.
+#[inline]
+pub(crate) fn release(bytes: usize) {
+    if is_active() {
+        release_slow(bytes);
+    }
+}
+
+#[inline(never)]
+fn release_slow(bytes: usize) {
+    MEMORY.with(|tls| {
+        let mut quota = tls.get();
+
+        if quota.limit != usize::MAX {
+            debug_assert!(
+                bytes <= quota.used,
+                "released {} bytes, but only {} bytes are in use",
+                bytes,
+                quota.used
+            );
+
+            quota.used = quota.used.saturating_sub(bytes);
+            tls.set(quota);
+        }
+    })
+}
+
+/// Record that a buffer of `T` was resized from one capacity to another.
+#[inline]
+pub(crate) fn resize<T>(from: usize, to: usize) {
+    if to > from {
+        allocate((to - from).saturating_mul(mem::size_of::<T>()));
+    } else {
+        release((from - to).saturating_mul(mem::size_of::<T>()));
+    }
+}
+
+/// Check that a vector with the given capacity can grow to fit one more
+/// element without exceeding the quota.
+///
+/// Native functions which push to a vector in a loop should call this before
+/// each push, since the quota is otherwise only checked between instructions.
+pub(crate) fn ensure_push<T>(len: usize, capacity: usize) -> Result<(), VmError> {
+    if len < capacity {
+        return Ok(());
+    }
+
+    // NB: vectors at least double in size when they grow.
+    ensure(cmp::max(capacity, 4).saturating_mul(mem::size_of::<T>()))
+}
+
+/// Check that the given number of bytes can be allocated without exceeding the
+/// quota.
+///
+/// This should be used before allocations with a size that is known up front,
+/// so that they can be rejected before they are performed.
+#[inline]
+pub(crate) fn ensure(bytes: usize) -> Result<(), VmError> {
+    if !is_active() {
+        return Ok(());
+    }
+
+    ensure_slow(bytes)
+}
+
+#[inline(never)]
+fn ensure_slow(bytes: usize) -> Result<(), VmError> {
+    MEMORY.with(|tls| {
+        let quota = tls.get();
+
+        match quota.used.checked_add(bytes) {
+            Some(used) if used <= quota.limit => Ok(()),
+            _ => Err(VmError::from(VmErrorKind::MemoryLimitExceeded {
+                limit: quota.limit,
+            })),
+        }
+    })
+}
+
+/// Check that the quota hasn't been exceeded by allocations which can't be
+/// rejected before they're performed, like constructing shared values.
+///
+/// This is called by the virtual machine after instructions which allocate,
+/// and by native functions which allocate in a loop.
+#[inline]
+pub(crate) fn check() -> Result<(), VmErrorKind> {
+    if !is_active() {
+        return Ok(());
+    }
+
+    check_slow()
+}
+
+#[inline(never)]
+fn check_slow() -> Result<(), VmErrorKind> {
+    MEMORY.with(|tls| {
+        let quota = tls.get();
+
+        if quota.used > quota.limit {
+            return Err(VmErrorKind::MemoryLimitExceeded { limit: quota.limit });
+        }
+
+        Ok(())
+    })
+}
+
+/// Restores the previous quota of the current thread when dropped.
+#[repr(transparent)]
+struct MemoryGuard(Quota);
+
+impl MemoryGuard {
+    /// Put the given quota in place on the current thread.
+    fn new(tls: &Cell<Quota>, quota: Quota) -> Self {
+        ACTIVE.fetch_add(1, Ordering::Relaxed);
+        Self(tls.replace(quota))
+    }
+}
+
+impl Drop for MemoryGuard {
+    fn drop(&mut self) {
+        MEMORY.with(|tls| {
+            tls.set(self.0);
+        });
+
+        ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<T, O> Memory<T>
+where
+    T: FnOnce() -> O,
+{
+    /// Call the wrapped function.
+    pub fn call(self) -> O {
+        MEMORY.with(|tls| {
+            let _guard = MemoryGuard::new(
+                tls,
+                Quota {
+                    limit: self.limit,
+                    used: self.used,
+                },
+            );
+
+            (self.value)()
+        })
+    }
+}
+
+impl<T> Future for Memory<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        MEMORY.with(|tls| {
+            let _guard = MemoryGuard::new(
+                tls,
+                Quota {
+                    limit: *this.limit,
+                    used: *this.used,
+                },
+            );
+
+            let poll = this.value.poll(cx);
+            *this.used = tls.get().used;
+            poll
+        })
+    }
+}
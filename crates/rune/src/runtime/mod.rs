@@ -7,8 +7,10 @@ mod awaited;
 pub mod budget;
 mod bytes;
 mod call;
+mod cloned;
 mod const_value;
 pub mod debug;
+pub mod debugger;
 mod env;
 pub mod format;
 mod from_value;
@@ -21,6 +23,9 @@ mod inst;
 mod iterator;
 mod key;
 mod label;
+mod limits;
+pub mod memory;
+mod num;
 mod object;
 mod panic;
 mod protocol;
@@ -72,8 +77,10 @@ pub use self::inst::{
     PanicReason, TypeCheck,
 };
 pub use self::iterator::{Iterator, IteratorTrait};
+pub(crate) use self::key::AnyKeyValue;
 pub use self::key::Key;
 pub use self::label::{DebugLabel, Label};
+pub(crate) use self::num::{BigInt, Number, Wide, I128, U64};
 pub use self::object::Object;
 pub use self::panic::Panic;
 pub use self::protocol::Protocol;
@@ -81,7 +88,9 @@ pub(crate) use self::protocol_caller::{EnvProtocolCaller, ProtocolCaller};
 pub use self::range::{Range, RangeLimits};
 pub use self::raw_str::RawStr;
 pub use self::runtime_context::RuntimeContext;
-pub(crate) use self::runtime_context::{FunctionHandler, MacroHandler};
+pub(crate) use self::runtime_context::{
+    DeserializeHandler, FunctionHandler, KeyHandler, MacroHandler, NumberHandler,
+};
 pub use self::select::Select;
 pub use self::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard};
 pub use self::stack::{Stack, StackError};
@@ -104,7 +113,7 @@ pub use self::vec::Vec;
 pub use self::vec_tuple::VecTuple;
 pub use self::vm::{CallFrame, Vm};
 pub(crate) use self::vm_call::VmCall;
-pub use self::vm_error::{VmError, VmErrorKind, VmIntegerRepr};
+pub use self::vm_error::{BacktraceFrame, VmError, VmErrorKind, VmIntegerRepr};
 pub use self::vm_execution::{ExecutionState, VmExecution, VmSendExecution};
 pub(crate) use self::vm_halt::VmHalt;
 pub use self::vm_halt::VmHaltInfo;
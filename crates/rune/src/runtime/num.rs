@@ -0,0 +1,285 @@
+//! Integer types which are wider than the `int` of a script.
+
+use crate::runtime::{
+    env, memory, NumberHandler, ToValue, Value, VmError, VmErrorKind, VmIntegerRepr,
+};
+use crate::Any;
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+
+/// An unsigned 64-bit integer, like `10u64`.
+#[derive(Any, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[rune(module = "crate", clone, hash)]
+pub(crate) struct U64(pub(crate) u64);
+
+impl fmt::Debug for U64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}u64", self.0)
+    }
+}
+
+/// A signed 128-bit integer, like `10i128`.
+#[derive(Any, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[rune(module = "crate", clone, hash)]
+pub(crate) struct I128(pub(crate) i128);
+
+impl fmt::Debug for I128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}i128", self.0)
+    }
+}
+
+/// An arbitrary precision integer, like `10n`.
+#[derive(Any, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[rune(module = "crate", clone, hash)]
+pub(crate) struct BigInt(pub(crate) num::BigInt);
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}n", self.0)
+    }
+}
+
+/// Lookup of the native number types registered through
+/// [Module::number][crate::Module::number], which can be on the right-hand side
+/// of an operation where an `int` is on the left-hand side, like `2 * 1.5d`.
+pub(crate) struct Number;
+
+impl Number {
+    /// Look up the handler of the native number type of a value, returning
+    /// `None` if it isn't one.
+    pub(crate) fn of(value: &Value) -> Result<Option<Arc<NumberHandler>>, VmError> {
+        if !matches!(value, Value::Any(..)) {
+            return Ok(None);
+        }
+
+        let type_hash = value.type_hash()?;
+        env::with(|context, _| Ok(context.number(type_hash).cloned()))
+    }
+
+    /// Test if the value is an `int` or a native number type, which can be
+    /// compared with each other.
+    pub(crate) fn is_number(value: &Value) -> Result<bool, VmError> {
+        Ok(matches!(value, Value::Integer(..)) || Self::of(value)?.is_some())
+    }
+}
+
+/// An integer of any width, used as the operand of wide integer operations.
+///
+/// Operations between integers of different widths produce the widest of the
+/// two, where `int` < `U64` < `I128` < `BigInt`.
+#[derive(Debug, Clone)]
+pub(crate) enum Wide {
+    Integer(i64),
+    U64(u64),
+    I128(i128),
+    BigInt(num::BigInt),
+}
+
+impl Wide {
+    /// Coerce a value into a wide integer, returning `None` if the value isn't
+    /// an integer.
+    pub(crate) fn from_value(value: &Value) -> Result<Option<Self>, VmError> {
+        let any = match value {
+            Value::Integer(n) => return Ok(Some(Self::Integer(*n))),
+            Value::Any(any) => any.borrow_ref()?,
+            _ => return Ok(None),
+        };
+
+        if let Some(n) = any.downcast_borrow_ref::<U64>() {
+            return Ok(Some(Self::U64(n.0)));
+        }
+
+        if let Some(n) = any.downcast_borrow_ref::<I128>() {
+            return Ok(Some(Self::I128(n.0)));
+        }
+
+        if let Some(n) = any.downcast_borrow_ref::<BigInt>() {
+            return Ok(Some(Self::BigInt(n.0.clone())));
+        }
+
+        Ok(None)
+    }
+
+    /// Coerce a value into a wide integer, erroring if it isn't an integer.
+    pub(crate) fn expect(value: &Value) -> Result<Self, VmError> {
+        match Self::from_value(value)? {
+            Some(wide) => Ok(wide),
+            None => Err(VmError::expected::<i64>(value.type_info()?)),
+        }
+    }
+
+    /// The rank of the width of the integer.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Integer(..) => 0,
+            Self::U64(..) => 1,
+            Self::I128(..) => 2,
+            Self::BigInt(..) => 3,
+        }
+    }
+
+    /// Convert into an `i128`, erroring if it doesn't fit.
+    pub(crate) fn to_i128(&self) -> Result<i128, VmError> {
+        match self {
+            Self::Integer(n) => Ok(i128::from(*n)),
+            Self::U64(n) => Ok(i128::from(*n)),
+            Self::I128(n) => Ok(*n),
+            Self::BigInt(n) => i128::try_from(n).map_err(|_| out_of_bounds(n.sign())),
+        }
+    }
+
+    /// Convert into a big integer.
+    pub(crate) fn to_bigint(&self) -> num::BigInt {
+        match self {
+            Self::Integer(n) => num::BigInt::from(*n),
+            Self::U64(n) => num::BigInt::from(*n),
+            Self::I128(n) => num::BigInt::from(*n),
+            Self::BigInt(n) => n.clone(),
+        }
+    }
+
+    /// Convert into a primitive number, erroring if it doesn't fit.
+    pub(crate) fn to_primitive<T>(&self) -> Result<T, VmError>
+    where
+        T: for<'a> TryFrom<&'a num::BigInt>,
+    {
+        let number = self.to_bigint();
+
+        if let Ok(n) = T::try_from(&number) {
+            return Ok(n);
+        }
+
+        Err(VmError::from(VmErrorKind::ValueToIntegerCoercionError {
+            from: VmIntegerRepr::from(number),
+            to: std::any::type_name::<T>(),
+        }))
+    }
+
+    /// Convert into the narrowest of `int`, `I128` and `BigInt` which fits the
+    /// integer, so that integers which are numerically equal have the same
+    /// representation.
+    pub(crate) fn normalize(self) -> Self {
+        match self.to_i128() {
+            Ok(n) => match i64::try_from(n) {
+                Ok(n) => Self::Integer(n),
+                Err(..) => Self::I128(n),
+            },
+            Err(..) => Self::BigInt(self.to_bigint()),
+        }
+    }
+
+    /// Narrow an `i128` into an integer of the given rank, erroring if it
+    /// doesn't fit.
+    fn narrow(rank: u8, n: i128) -> Result<Self, VmError> {
+        Ok(match rank {
+            0 => Self::Integer(i64::try_from(n).map_err(|_| out_of_bounds_i128(n))?),
+            1 => Self::U64(u64::try_from(n).map_err(|_| out_of_bounds_i128(n))?),
+            _ => Self::I128(n),
+        })
+    }
+
+    /// An upper bound of the number of bits needed to store the integer.
+    pub(crate) fn bits(&self) -> u64 {
+        match self {
+            Self::BigInt(n) => n.bits(),
+            _ => 128,
+        }
+    }
+
+    /// Perform an arithmetic operation, widening the operands as needed.
+    pub(crate) fn arith(
+        self,
+        rhs: Self,
+        error: fn() -> VmErrorKind,
+        wide_op: fn(i128, i128) -> Option<i128>,
+        big_op: fn(&num::BigInt, &num::BigInt) -> Option<num::BigInt>,
+    ) -> Result<Self, VmError> {
+        let rank = cmp::max(self.rank(), rhs.rank());
+
+        if rank == 3 {
+            let n = big_op(&self.to_bigint(), &rhs.to_bigint()).ok_or_else(error)?;
+            return Ok(Self::BigInt(n));
+        }
+
+        let n = wide_op(self.to_i128()?, rhs.to_i128()?).ok_or_else(error)?;
+        Self::narrow(rank, n)
+    }
+
+    /// Shift the integer, where the result has the same width as `self`.
+    pub(crate) fn shift(self, rhs: Self, left: bool) -> Result<Self, VmError> {
+        let amount = rhs
+            .to_primitive::<u32>()
+            .map_err(|_| VmError::from(VmErrorKind::Overflow))?;
+
+        let shifted = match self {
+            Self::Integer(n) => {
+                shift(n, amount, left, i64::checked_shl, i64::checked_shr).map(Self::Integer)
+            }
+            Self::U64(n) => {
+                shift(n, amount, left, u64::checked_shl, u64::checked_shr).map(Self::U64)
+            }
+            Self::I128(n) => {
+                shift(n, amount, left, i128::checked_shl, i128::checked_shr).map(Self::I128)
+            }
+            Self::BigInt(n) if left => {
+                let bits = n.bits().saturating_add(u64::from(amount));
+                memory::ensure(usize::try_from(bits / 8).unwrap_or(usize::MAX))?;
+                Some(Self::BigInt(n << amount as usize))
+            }
+            Self::BigInt(n) => Some(Self::BigInt(n >> amount as usize)),
+        };
+
+        return shifted.ok_or_else(|| VmError::from(VmErrorKind::Overflow));
+
+        fn shift<T>(
+            n: T,
+            amount: u32,
+            left: bool,
+            shl: fn(T, u32) -> Option<T>,
+            shr: fn(T, u32) -> Option<T>,
+        ) -> Option<T> {
+            if left {
+                shl(n, amount)
+            } else {
+                shr(n, amount)
+            }
+        }
+    }
+
+    /// Compare two integers of any width.
+    pub(crate) fn cmp(&self, other: &Self) -> cmp::Ordering {
+        match (self.to_i128(), other.to_i128()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => self.to_bigint().cmp(&other.to_bigint()),
+        }
+    }
+}
+
+impl ToValue for Wide {
+    fn to_value(self) -> Result<Value, VmError> {
+        match self {
+            Self::Integer(n) => Ok(Value::Integer(n)),
+            Self::U64(n) => U64(n).to_value(),
+            Self::I128(n) => I128(n).to_value(),
+            Self::BigInt(n) => BigInt(n).to_value(),
+        }
+    }
+}
+
+fn out_of_bounds(sign: num::bigint::Sign) -> VmError {
+    match sign {
+        num::bigint::Sign::Minus => VmError::from(VmErrorKind::Underflow),
+        _ => VmError::from(VmErrorKind::Overflow),
+    }
+}
+
+fn out_of_bounds_i128(n: i128) -> VmError {
+    if n < 0 {
+        VmError::from(VmErrorKind::Underflow)
+    } else {
+        VmError::from(VmErrorKind::Overflow)
+    }
+}
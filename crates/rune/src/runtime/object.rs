@@ -1,14 +1,15 @@
 use crate::collections::{btree_map, BTreeMap};
 use crate::compile::{ItemBuf, Named};
 use crate::runtime::{
-    FromValue, Iterator, Mut, RawMut, RawRef, RawStr, Ref, ToValue, UnsafeFromValue, Value, Vm,
-    VmError,
+    memory, FromValue, Iterator, Mut, ProtocolCaller, RawMut, RawRef, RawStr, Ref, ToValue,
+    UnsafeFromValue, Value, VmError,
 };
 use crate::InstallWith;
 use std::borrow;
 use std::cmp;
 use std::fmt;
 use std::hash;
+use std::mem;
 
 /// An owning iterator over the entries of a `Object`.
 ///
@@ -57,6 +58,9 @@ pub type Values<'a> = btree_map::Values<'a, String, Value>;
 
 /// Struct representing a dynamic anonymous object.
 ///
+/// The memory it allocates is accounted for in the [memory quota][crate::runtime::memory],
+/// and is returned to it when it's dropped.
+///
 /// # Examples
 ///
 /// ```
@@ -73,7 +77,7 @@ pub type Values<'a> = btree_map::Values<'a, String, Value>;
 /// assert_eq!(None::<bool>, object.get_value("baz")?);
 /// # Ok(()) }
 /// ```
-#[derive(Default, Clone)]
+#[derive(Default)]
 #[repr(transparent)]
 pub struct Object {
     inner: BTreeMap<String, Value>,
@@ -162,7 +166,9 @@ impl Object {
         String: borrow::Borrow<Q>,
         Q: hash::Hash + cmp::Eq + cmp::Ord,
     {
-        self.inner.remove(k)
+        let (key, value) = self.inner.remove_entry(k)?;
+        memory::release(entry_size(key.capacity()));
+        Some(value)
     }
 
     /// Inserts a key-value pair into the dynamic object, converting it as
@@ -172,26 +178,43 @@ impl Object {
     where
         T: ToValue,
     {
-        self.inner.insert(k, v.to_value()?);
+        self.insert(k, v.to_value()?);
         Ok(())
     }
 
     /// Inserts a key-value pair into the dynamic object.
     #[inline]
     pub fn insert(&mut self, k: String, v: Value) -> Option<Value> {
-        self.inner.insert(k, v)
+        let size = entry_size(k.capacity());
+        let old = self.inner.insert(k, v);
+
+        if old.is_none() {
+            memory::allocate(size);
+        }
+
+        old
     }
 
     /// Clears the object, removing all key-value pairs. Keeps the allocated
     /// memory for reuse.
     #[inline]
     pub fn clear(&mut self) {
+        memory::release(self.size());
         self.inner.clear();
     }
 
     /// Convert into inner.
-    pub fn into_inner(self) -> BTreeMap<String, Value> {
+    pub fn into_inner(mut self) -> BTreeMap<String, Value> {
+        memory::release(self.size());
+        mem::take(&mut self.inner)
+    }
+
+    /// The number of bytes accounted to the entries of the object.
+    fn size(&self) -> usize {
         self.inner
+            .keys()
+            .map(|key| entry_size(key.capacity()))
+            .sum()
     }
 
     /// An iterator visiting all key-value pairs in arbitrary order.
@@ -220,8 +243,12 @@ impl Object {
     }
 
     /// Value pointer equals implementation for an Object.
-    pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Self, b: &Self) -> Result<bool, VmError> {
-        map_ptr_eq(vm, &a.inner, &b.inner)
+    pub(crate) fn value_ptr_eq(
+        caller: &mut impl ProtocolCaller,
+        a: &Self,
+        b: &Self,
+    ) -> Result<bool, VmError> {
+        map_ptr_eq(caller, &a.inner, &b.inner)
     }
 
     /// Debug implementation for a struct. This assumes that all fields
@@ -262,7 +289,23 @@ impl IntoIterator for Object {
     /// pair out of the object in arbitrary order. The object cannot be used
     /// after calling this.
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
+        self.into_inner().into_iter()
+    }
+}
+
+impl Clone for Object {
+    fn clone(&self) -> Self {
+        memory::allocate(self.size());
+
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        memory::release(self.size());
     }
 }
 
@@ -274,9 +317,13 @@ impl fmt::Debug for Object {
 
 impl std::iter::FromIterator<(String, Value)> for Object {
     fn from_iter<T: IntoIterator<Item = (String, Value)>>(src: T) -> Self {
-        Self {
-            inner: src.into_iter().collect(),
+        let mut object = Self::new();
+
+        for (key, value) in src {
+            object.insert(key, value);
         }
+
+        object
     }
 }
 
@@ -355,9 +402,15 @@ impl fmt::Display for DebugStruct<'_> {
     }
 }
 
+/// The number of bytes accounted to an entry with a key of the given
+/// capacity.
+fn entry_size(key: usize) -> usize {
+    mem::size_of::<(String, Value)>() + key
+}
+
 /// Helper function two compare two hashmaps of values.
 pub(crate) fn map_ptr_eq<K>(
-    vm: &mut Vm,
+    caller: &mut impl ProtocolCaller,
     a: &BTreeMap<K, Value>,
     b: &BTreeMap<K, Value>,
 ) -> Result<bool, VmError>
@@ -375,7 +428,7 @@ where
             None => return Ok(false),
         };
 
-        if !Value::value_ptr_eq(vm, a, b)? {
+        if !Value::value_ptr_eq(caller, a, b)? {
             return Ok(false);
         }
     }
@@ -81,6 +81,66 @@ impl Protocol {
         hash: Hash::new(0x418f5becbf885806),
     };
 
+    /// Hash a value, returning an integer.
+    ///
+    /// This is used when native values are used as keys in maps and sets,
+    /// together with [Protocol::EQ] to tell keys with the same hash apart. The
+    /// hash is the same as for an instance function named `hash`.
+    pub const HASH: Protocol = Protocol {
+        name: "hash",
+        hash: Hash::new(0x3440d109c9dd0b5f),
+    };
+
+    /// Clone a value.
+    ///
+    /// Values which don't implement this are cloned deeply by the virtual
+    /// machine when `clone()` is called on them. The hash is the same as for
+    /// an instance function named `clone`.
+    pub const CLONE: Protocol = Protocol {
+        name: "clone",
+        hash: Hash::new(0xbece0d347b6ac4e9),
+    };
+
+    /// Release the resources held by a value.
+    ///
+    /// This is called by `std::mem::drop` before the value is taken. The hash
+    /// is the same as for an instance function named `drop`.
+    pub const DROP: Protocol = Protocol {
+        name: "drop",
+        hash: Hash::new(0x80bbcfdc4f9f1d43),
+    };
+
+    /// Convert a native value into a value which is serialized in its place.
+    ///
+    /// This is used when values are serialized through serde inside of a
+    /// virtual machine. The hash is the same as for an instance function named
+    /// `serialize`.
+    pub const SERIALIZE: Protocol = Protocol {
+        name: "serialize",
+        hash: Hash::new(0xbff735982631e49e),
+    };
+
+    /// Perform a partial comparison of two values, returning an
+    /// `Option<Ordering>`.
+    ///
+    /// This is used by the `<`, `<=`, `>` and `>=` operators. The hash is the
+    /// same as for an instance function named `partial_cmp`, so scripts can
+    /// implement it with a regular method.
+    pub const PARTIAL_CMP: Protocol = Protocol {
+        name: "partial_cmp",
+        hash: Hash::new(0x06870669a59e21cf),
+    };
+
+    /// Perform a total comparison of two values, returning an `Ordering`.
+    ///
+    /// This is used for sorting, and as a fallback for the comparison
+    /// operators. The hash is the same as for an instance function named
+    /// `cmp`, so scripts can implement it with a regular method.
+    pub const CMP: Protocol = Protocol {
+        name: "cmp",
+        hash: Hash::new(0x3ab799784e414506),
+    };
+
     /// The function to access a field.
     pub const GET: Protocol = Protocol {
         name: "get",
@@ -238,12 +298,19 @@ impl Protocol {
     };
 
     /// Function used to convert an argument into an iterator.
+    ///
+    /// Script types implement this by declaring an instance function named
+    /// `into_iter`.
     pub const INTO_ITER: Protocol = Protocol {
         name: "into_iter",
         hash: Hash::new(0x15a85c8d774b4065),
     };
 
-    /// The function to call to continue iteration.
+    /// The function to call to continue iteration, returning an `Option`.
+    ///
+    /// Values which implement this can be used as iterators, like in `for`
+    /// loops and with the functions of `std::iter::Iterator`. Script types
+    /// implement this by declaring an instance function named `next`.
     pub const NEXT: Protocol = Protocol {
         name: "next",
         hash: Hash::new(0xc3cde069de2ba320),
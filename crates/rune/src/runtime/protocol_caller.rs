@@ -13,6 +13,17 @@ pub(crate) trait ProtocolCaller {
     ) -> Result<Value, VmError>
     where
         A: GuardedArgs;
+
+    /// Try to call the given protocol function, returning the target as
+    /// unsupported if the protocol isn't implemented for it.
+    fn try_call_protocol_fn<A>(
+        &mut self,
+        protocol: Protocol,
+        target: Value,
+        args: A,
+    ) -> Result<CallResult<Value>, VmError>
+    where
+        A: GuardedArgs;
 }
 
 /// Use the global environment caller.
@@ -20,13 +31,27 @@ pub(crate) trait ProtocolCaller {
 /// This allocates its own stack and virtual machine for the call.
 pub(crate) struct EnvProtocolCaller;
 
-impl ProtocolCaller for EnvProtocolCaller {
-    fn call_protocol_fn<A>(
+impl EnvProtocolCaller {
+    /// Test if the given protocol function is implemented for the given type.
+    pub(crate) fn has_protocol_fn(
+        self,
+        protocol: Protocol,
+        type_hash: Hash,
+    ) -> Result<bool, VmError> {
+        crate::runtime::env::with(|context, unit| {
+            let hash = Hash::instance_function(type_hash, protocol.hash);
+            Ok(unit.function(hash).is_some() || context.function(hash).is_some())
+        })
+    }
+
+    /// Try to call the given protocol function, returning the target as
+    /// unsupported if the protocol isn't implemented for it.
+    pub(crate) fn try_call_protocol_fn<A>(
         self,
         protocol: Protocol,
         target: Value,
         args: A,
-    ) -> Result<Value, VmError>
+    ) -> Result<CallResult<Value>, VmError>
     where
         A: GuardedArgs,
     {
@@ -50,12 +75,12 @@ impl ProtocolCaller for EnvProtocolCaller {
 
                 let mut vm = Vm::with_stack(context.clone(), unit.clone(), stack);
                 vm.set_ip(offset);
-                return call.call_with_vm(vm);
+                return Ok(CallResult::Ok(call.call_with_vm(vm)?));
             }
 
             let handler = match context.function(hash) {
                 Some(handler) => handler,
-                None => return Err(VmError::from(VmErrorKind::MissingFunction { hash })),
+                None => return Ok(CallResult::Unsupported(target)),
             };
 
             let mut stack = Stack::with_capacity(count);
@@ -65,7 +90,7 @@ impl ProtocolCaller for EnvProtocolCaller {
             let _guard = unsafe { args.unsafe_into_stack(&mut stack)? };
 
             handler(&mut stack, count)?;
-            Ok(stack.pop()?)
+            Ok(CallResult::Ok(stack.pop()?))
         });
 
         /// Check that arguments matches expected or raise the appropriate error.
@@ -82,6 +107,37 @@ impl ProtocolCaller for EnvProtocolCaller {
     }
 }
 
+impl ProtocolCaller for EnvProtocolCaller {
+    fn call_protocol_fn<A>(
+        self,
+        protocol: Protocol,
+        target: Value,
+        args: A,
+    ) -> Result<Value, VmError>
+    where
+        A: GuardedArgs,
+    {
+        match self.try_call_protocol_fn(protocol, target, args)? {
+            CallResult::Ok(value) => Ok(value),
+            CallResult::Unsupported(target) => Err(VmError::from(VmErrorKind::MissingFunction {
+                hash: Hash::instance_function(target.type_hash()?, protocol.hash),
+            })),
+        }
+    }
+
+    fn try_call_protocol_fn<A>(
+        &mut self,
+        protocol: Protocol,
+        target: Value,
+        args: A,
+    ) -> Result<CallResult<Value>, VmError>
+    where
+        A: GuardedArgs,
+    {
+        EnvProtocolCaller.try_call_protocol_fn(protocol, target, args)
+    }
+}
+
 impl ProtocolCaller for &mut Vm {
     fn call_protocol_fn<A>(
         self,
@@ -100,4 +156,19 @@ impl ProtocolCaller for &mut Vm {
 
         Ok(self.stack_mut().pop()?)
     }
+
+    fn try_call_protocol_fn<A>(
+        &mut self,
+        protocol: Protocol,
+        target: Value,
+        args: A,
+    ) -> Result<CallResult<Value>, VmError>
+    where
+        A: GuardedArgs,
+    {
+        Ok(match self.call_instance_fn(target, protocol, args)? {
+            CallResult::Ok(()) => CallResult::Ok(self.stack_mut().pop()?),
+            CallResult::Unsupported(target) => CallResult::Unsupported(target),
+        })
+    }
 }
@@ -1,7 +1,7 @@
 use crate::compile::{InstallWith, Named};
 use crate::runtime::{
-    FromValue, Iterator, Mut, Panic, RawMut, RawRef, RawStr, Ref, ToValue, UnsafeFromValue, Value,
-    Vm, VmError, VmErrorKind,
+    FromValue, Iterator, Mut, Panic, ProtocolCaller, RawMut, RawRef, RawStr, Ref, ToValue,
+    UnsafeFromValue, Value, VmError, VmErrorKind,
 };
 use std::fmt;
 use std::ops;
@@ -57,20 +57,24 @@ impl Range {
     }
 
     /// Value pointer equals implementation for a range.
-    pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Self, b: &Self) -> Result<bool, VmError> {
+    pub(crate) fn value_ptr_eq(
+        caller: &mut impl ProtocolCaller,
+        a: &Self,
+        b: &Self,
+    ) -> Result<bool, VmError> {
         if a.limits != b.limits {
             return Ok(false);
         }
 
         match (&a.start, &b.start) {
             (None, None) => (),
-            (Some(a), Some(b)) if Value::value_ptr_eq(vm, a, b)? => (),
+            (Some(a), Some(b)) if Value::value_ptr_eq(caller, a, b)? => (),
             _ => return Ok(false),
         }
 
         match (&a.end, &b.end) {
             (None, None) => (),
-            (Some(a), Some(b)) if Value::value_ptr_eq(vm, a, b)? => (),
+            (Some(a), Some(b)) if Value::value_ptr_eq(caller, a, b)? => (),
             _ => return Ok(false),
         }
 
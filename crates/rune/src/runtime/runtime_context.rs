@@ -1,6 +1,6 @@
 use crate::collections::HashMap;
 use crate::macros::{MacroContext, TokenStream};
-use crate::runtime::{ConstValue, Stack, VmError};
+use crate::runtime::{AnyKeyValue, ConstValue, Protocol, Stack, Value, VmError};
 use crate::Hash;
 use std::fmt;
 use std::sync::Arc;
@@ -8,6 +8,18 @@ use std::sync::Arc;
 /// A type-reduced function handler.
 pub(crate) type FunctionHandler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync;
 
+/// A type-reduced handler which copies a native value into a key.
+pub(crate) type KeyHandler = dyn Fn(&Value) -> Result<Arc<dyn AnyKeyValue>, VmError> + Send + Sync;
+
+/// A type-reduced handler which turns the value of a tagged map back into the
+/// native value it was serialized from.
+pub(crate) type DeserializeHandler = dyn Fn(Value) -> Result<Value, VmError> + Send + Sync;
+
+/// A type-reduced handler which applies a protocol where an `int` is on the
+/// left-hand side and a native number is on the right-hand side.
+pub(crate) type NumberHandler =
+    dyn Fn(Protocol, i64, &Value) -> Result<Value, VmError> + Send + Sync;
+
 /// A (type erased) macro handler.
 pub(crate) type MacroHandler =
     dyn Fn(&mut MacroContext, &TokenStream) -> crate::Result<TokenStream> + Send + Sync;
@@ -24,16 +36,28 @@ pub struct RuntimeContext {
     functions: HashMap<Hash, Arc<FunctionHandler>>,
     /// Named constant values
     constants: HashMap<Hash, ConstValue>,
+    /// Handlers for native types which can be used as keys.
+    keys: HashMap<Hash, Arc<KeyHandler>>,
+    /// Handlers for native number types.
+    numbers: HashMap<Hash, Arc<NumberHandler>>,
+    /// Handlers for native types which are deserialized from tagged maps.
+    deserializers: HashMap<Box<str>, Arc<DeserializeHandler>>,
 }
 
 impl RuntimeContext {
     pub(crate) fn new(
         functions: HashMap<Hash, Arc<FunctionHandler>>,
         constants: HashMap<Hash, ConstValue>,
+        keys: HashMap<Hash, Arc<KeyHandler>>,
+        numbers: HashMap<Hash, Arc<NumberHandler>>,
+        deserializers: HashMap<Box<str>, Arc<DeserializeHandler>>,
     ) -> Self {
         Self {
             functions,
             constants,
+            keys,
+            numbers,
+            deserializers,
         }
     }
 
@@ -46,6 +70,24 @@ impl RuntimeContext {
     pub fn constant(&self, hash: Hash) -> Option<&ConstValue> {
         self.constants.get(&hash)
     }
+
+    /// Lookup the handler which copies native values of the given type into
+    /// keys.
+    pub(crate) fn key(&self, type_hash: Hash) -> Option<&Arc<KeyHandler>> {
+        self.keys.get(&type_hash)
+    }
+
+    /// Lookup the handler for operations where an `int` is on the left-hand
+    /// side and a native number of the given type is on the right-hand side.
+    pub(crate) fn number(&self, type_hash: Hash) -> Option<&Arc<NumberHandler>> {
+        self.numbers.get(&type_hash)
+    }
+
+    /// Lookup the handler which deserializes native values from maps with a
+    /// single entry keyed by the given tag.
+    pub(crate) fn deserializer(&self, tag: &str) -> Option<&Arc<DeserializeHandler>> {
+        self.deserializers.get(tag)
+    }
 }
 
 impl fmt::Debug for RuntimeContext {
@@ -1,5 +1,6 @@
 use crate::runtime::{
-    Access, AccessError, AccessKind, AnyObj, AnyObjError, BorrowMut, BorrowRef, RawAccessGuard,
+    memory, Access, AccessError, AccessKind, AnyObj, AnyObjError, BorrowMut, BorrowRef,
+    RawAccessGuard,
 };
 use crate::{Any, Hash};
 use std::any;
@@ -22,9 +23,15 @@ pub struct Shared<T: ?Sized> {
 impl<T> Shared<T> {
     /// Construct a new shared value.
     pub fn new(data: T) -> Self {
+        memory::allocate(mem::size_of::<SharedBox<T>>());
+        Self::with_count(data, 1)
+    }
+
+    /// Construct a new shared value with the given initial count.
+    fn with_count(data: T, count: usize) -> Self {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(false),
-            count: Cell::new(1),
+            count: Cell::new(count),
             data: data.into(),
         }));
 
@@ -103,6 +110,29 @@ impl<T> Shared<T> {
         unsafe { self.inner.as_ref().access.is_exclusive() }
     }
 
+    /// Test if two shared values refer to the same value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::runtime::Shared;
+    ///
+    /// let a = Shared::new(1u32);
+    /// let b = a.clone();
+    ///
+    /// assert!(Shared::ptr_eq(&a, &b));
+    /// assert!(!Shared::ptr_eq(&a, &Shared::new(1u32)));
+    /// ```
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.inner == other.inner
+    }
+
+    /// Get the address of the shared value, which is the same for every
+    /// shared value referring to it.
+    pub(crate) fn as_ptr(this: &Self) -> *const () {
+        this.inner.as_ptr() as *const ()
+    }
+
     /// Take the interior value, if we have exlusive access to it and there
     /// are no other live exlusive or shared references.
     ///
@@ -150,7 +180,15 @@ impl<T> Shared<T> {
             //
             // Future access is forever prevented since we never release
             // the access (see above).
-            Ok(ptr::read(inner.data.get()))
+            let data = ptr::read(inner.data.get());
+
+            if inner.count.get() & COUNTED_STRING != 0 {
+                // Safety: only shared strings are counted.
+                let string = &*ptr::addr_of!(data).cast::<String>();
+                memory::release(string.capacity());
+            }
+
+            Ok(data)
         }
     }
 
@@ -357,6 +395,15 @@ impl<T: ?Sized> Shared<T> {
     }
 }
 
+impl Shared<String> {
+    /// Construct a new shared string, whose heap memory is accounted for in
+    /// the memory quota until it's dropped or taken.
+    pub(crate) fn new_counted(string: String) -> Self {
+        memory::allocate(mem::size_of::<SharedBox<String>>() + string.capacity());
+        Self::with_count(string, 1 | COUNTED_STRING)
+    }
+}
+
 impl Shared<AnyObj> {
     /// Construct a `Shared<Any>` from a pointer, that will be "taken" once the
     /// returned guard is dropped.
@@ -439,6 +486,8 @@ impl Shared<AnyObj> {
     ///
     /// The reference must be valid for the duration of the guard.
     unsafe fn unsafe_from_any_pointer(any: AnyObj) -> (Self, SharedPointerGuard) {
+        memory::allocate(mem::size_of::<SharedBox<AnyObj>>());
+
         let inner = ptr::NonNull::from(Box::leak(Box::new(SharedBox {
             access: Access::new(true),
             count: Cell::new(2),
@@ -728,7 +777,7 @@ where
             let mut debug = fmt.debug_struct("Shared");
 
             debug.field("access", &inner.access);
-            debug.field("count", &inner.count.get());
+            debug.field("count", &(inner.count.get() & !COUNTED_STRING));
 
             if !inner.access.is_shared() {
                 debug.field("data", &any::type_name::<T>());
@@ -741,6 +790,12 @@ where
     }
 }
 
+/// Set in the count of a shared string whose heap memory is accounted for in
+/// the memory quota, so that it can be returned to the quota when the string
+/// is dropped. This is kept in the count so that it doesn't make every other
+/// shared value larger.
+const COUNTED_STRING: usize = 1 << (usize::BITS - 1);
+
 /// The boxed internals of [Shared].
 #[repr(C)]
 struct SharedBox<T: ?Sized> {
@@ -757,8 +812,9 @@ impl<T: ?Sized> SharedBox<T> {
     /// Increment the reference count of the inner value.
     unsafe fn inc(this: *const Self) {
         let count = (*this).count.get();
+        let strong = count & !COUNTED_STRING;
 
-        if count == 0 || count == usize::max_value() {
+        if strong == 0 || strong == !COUNTED_STRING {
             process::abort();
         }
 
@@ -774,18 +830,19 @@ impl<T: ?Sized> SharedBox<T> {
     unsafe fn dec(this: *mut Self) -> bool {
         let count = (*this).count.get();
 
-        if count == 0 {
+        if count & !COUNTED_STRING == 0 {
             process::abort();
         }
 
         let count = count - 1;
         (*this).count.set(count);
 
-        if count != 0 {
+        if count & !COUNTED_STRING != 0 {
             return false;
         }
 
         let this = Box::from_raw(this);
+        memory::release(mem::size_of_val(&*this));
 
         if this.access.is_taken() {
             // NB: This prevents the inner `T` from being dropped in case it
@@ -803,6 +860,12 @@ impl<T: ?Sized> SharedBox<T> {
                 "expected exclusive, but was: {:?}",
                 this.access
             );
+
+            if count & COUNTED_STRING != 0 {
+                // Safety: only shared strings are counted.
+                let string = &*this.data.get().cast::<String>();
+                memory::release(string.capacity());
+            }
         }
 
         true
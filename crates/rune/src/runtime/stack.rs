@@ -1,4 +1,4 @@
-use crate::runtime::{InstAddress, Value};
+use crate::runtime::{memory, InstAddress, Value};
 use std::borrow::Cow;
 use std::iter;
 use std::mem;
@@ -11,7 +11,10 @@ use thiserror::Error;
 pub struct StackError(());
 
 /// The stack of the virtual machine, where all values are stored.
-#[derive(Default, Debug, Clone)]
+///
+/// The memory it allocates is accounted for in the [memory quota][crate::runtime::memory],
+/// and is returned to it when it's dropped.
+#[derive(Default, Debug)]
 pub struct Stack {
     /// The current stack of values.
     stack: Vec<Value>,
@@ -56,10 +59,7 @@ impl Stack {
     /// # Ok(()) }
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            stack: Vec::with_capacity(capacity),
-            stack_bottom: 0,
-        }
+        Self::from(Vec::with_capacity(capacity))
     }
 
     /// Check if the stack is empty.
@@ -130,7 +130,9 @@ impl Stack {
     where
         Value: From<T>,
     {
+        let capacity = self.stack.capacity();
         self.stack.push(Value::from(value));
+        memory::resize::<Value>(capacity, self.stack.capacity());
     }
 
     /// Pop a value from the stack.
@@ -207,7 +209,9 @@ impl Stack {
     where
         I: IntoIterator<Item = Value>,
     {
+        let capacity = self.stack.capacity();
         self.stack.extend(iter);
+        memory::resize::<Value>(capacity, self.stack.capacity());
     }
 
     /// Clear the current stack.
@@ -264,6 +268,22 @@ impl Stack {
         }
     }
 
+    /// Get the value at the given offset from the top mutably.
+    pub(crate) fn at_offset_from_top_mut(
+        &mut self,
+        offset: usize,
+    ) -> Result<&mut Value, StackError> {
+        match self
+            .stack
+            .len()
+            .checked_sub(offset)
+            .filter(|n| *n >= self.stack_bottom)
+        {
+            Some(n) => self.stack.get_mut(n).ok_or(StackError(())),
+            None => Err(StackError(())),
+        }
+    }
+
     /// Get the offset at the given location.
     pub(crate) fn at_offset_mut(&mut self, offset: usize) -> Result<&mut Value, StackError> {
         let n = match self.stack_bottom.checked_add(offset) {
@@ -347,17 +367,30 @@ impl Stack {
     }
 }
 
+impl Clone for Stack {
+    fn clone(&self) -> Self {
+        let mut stack = Self::from(self.stack.clone());
+        stack.stack_bottom = self.stack_bottom;
+        stack
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        memory::resize::<Value>(self.stack.capacity(), 0);
+    }
+}
+
 impl iter::FromIterator<Value> for Stack {
     fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
-        Self {
-            stack: iter.into_iter().collect(),
-            stack_bottom: 0,
-        }
+        Self::from(iter.into_iter().collect::<Vec<_>>())
     }
 }
 
 impl From<Vec<Value>> for Stack {
     fn from(stack: Vec<Value>) -> Self {
+        memory::resize::<Value>(0, stack.capacity());
+
         Self {
             stack,
             stack_bottom: 0,
@@ -178,15 +178,61 @@ macro_rules! number_value_trait {
 
 number_value_trait!(u16);
 number_value_trait!(u32);
-number_value_trait!(u64);
-number_value_trait!(u128);
-number_value_trait!(usize);
 number_value_trait!(i8);
 number_value_trait!(i16);
 number_value_trait!(i32);
-number_value_trait!(i128);
 number_value_trait!(isize);
 
+/// Numbers which might not fit in an `int` are converted into the given wide
+/// integer type instead.
+macro_rules! wide_number_value_trait {
+    ($ty:ty, $wide:ident, $repr:ty) => {
+        impl ToValue for $ty {
+            fn to_value(self) -> Result<Value, VmError> {
+                use std::convert::TryInto as _;
+
+                if let Ok(number) = self.try_into() {
+                    return Ok(Value::Integer(number));
+                }
+
+                match self.try_into() {
+                    Ok(number) => crate::runtime::$wide(number).to_value(),
+                    Err(..) => Err(VmError::from(VmErrorKind::IntegerToValueCoercionError {
+                        from: VmIntegerRepr::from(self),
+                        to: std::any::type_name::<$repr>(),
+                    })),
+                }
+            }
+        }
+    };
+}
+
+wide_number_value_trait!(u64, U64, u64);
+wide_number_value_trait!(usize, U64, u64);
+wide_number_value_trait!(i128, I128, i128);
+
+impl ToValue for u128 {
+    fn to_value(self) -> Result<Value, VmError> {
+        use std::convert::TryFrom as _;
+
+        if let Ok(number) = i64::try_from(self) {
+            return Ok(Value::Integer(number));
+        }
+
+        if let Ok(number) = i128::try_from(self) {
+            return crate::runtime::I128(number).to_value();
+        }
+
+        crate::runtime::BigInt(num::BigInt::from(self)).to_value()
+    }
+}
+
+impl ToValue for num::BigInt {
+    fn to_value(self) -> Result<Value, VmError> {
+        crate::runtime::BigInt(self).to_value()
+    }
+}
+
 impl ToValue for f32 {
     fn to_value(self) -> Result<Value, VmError> {
         Ok(Value::Float(self as f64))
@@ -1,12 +1,16 @@
 use crate::runtime::{
-    ConstValue, FromValue, Mut, Ref, ToValue, Value, Vm, VmError, VmErrorKind, TUPLE_TYPE,
+    memory, ConstValue, FromValue, Mut, ProtocolCaller, Ref, ToValue, Value, VmError, VmErrorKind,
+    TUPLE_TYPE,
 };
 use std::fmt;
+use std::mem;
 use std::ops;
 use std::slice;
 
 /// Struct representing a dynamic anonymous object.
-#[derive(Clone)]
+///
+/// The memory it allocates is accounted for in the [memory quota][crate::runtime::memory],
+/// and is returned to it when it's dropped.
 #[repr(transparent)]
 pub struct Tuple {
     inner: Box<[Value]>,
@@ -20,8 +24,10 @@ impl Tuple {
     }
 
     /// Convert into inner std boxed slice.
-    pub fn into_inner(self) -> Box<[Value]> {
-        self.inner
+    pub fn into_inner(mut self) -> Box<[Value]> {
+        let inner = mem::take(&mut self.inner);
+        memory::resize::<Value>(inner.len(), 0);
+        inner
     }
 
     /// Returns `true` if the dynamic tuple contains no elements.
@@ -59,13 +65,17 @@ impl Tuple {
     }
 
     /// Value pointer equals implementation for a Tuple.
-    pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Self, b: &Self) -> Result<bool, VmError> {
+    pub(crate) fn value_ptr_eq(
+        caller: &mut impl ProtocolCaller,
+        a: &Self,
+        b: &Self,
+    ) -> Result<bool, VmError> {
         if a.len() != b.len() {
             return Ok(false);
         }
 
         for (a, b) in a.iter().zip(b.iter()) {
-            if !Value::value_ptr_eq(vm, a, b)? {
+            if !Value::value_ptr_eq(caller, a, b)? {
                 return Ok(false);
             }
         }
@@ -94,6 +104,18 @@ impl fmt::Debug for Tuple {
     }
 }
 
+impl Clone for Tuple {
+    fn clone(&self) -> Self {
+        Self::from(self.inner.clone())
+    }
+}
+
+impl Drop for Tuple {
+    fn drop(&mut self) {
+        memory::resize::<Value>(self.inner.len(), 0);
+    }
+}
+
 impl ops::Deref for Tuple {
     type Target = [Value];
 
@@ -128,14 +150,13 @@ impl<'a> IntoIterator for &'a mut Tuple {
 
 impl From<Vec<Value>> for Tuple {
     fn from(vec: Vec<Value>) -> Self {
-        Self {
-            inner: vec.into_boxed_slice(),
-        }
+        Self::from(vec.into_boxed_slice())
     }
 }
 
 impl From<Box<[Value]>> for Tuple {
     fn from(inner: Box<[Value]>) -> Self {
+        memory::resize::<Value>(0, inner.len());
         Self { inner }
     }
 }
@@ -148,9 +169,7 @@ impl From<Box<[ConstValue]>> for Tuple {
             out.push(value.into_value());
         }
 
-        Self {
-            inner: out.into_boxed_slice(),
-        }
+        Self::from(out)
     }
 }
 
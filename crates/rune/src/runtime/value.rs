@@ -1,10 +1,11 @@
 use crate::compile::ItemBuf;
+use crate::runtime::cloned;
 use crate::runtime::vm::CallResult;
 use crate::runtime::{
     AccessKind, AnyObj, Bytes, ConstValue, EnvProtocolCaller, Format, FromValue, Function, Future,
-    Generator, GeneratorState, Iterator, Mut, Object, Protocol, ProtocolCaller, Range, RawMut,
-    RawRef, Ref, Shared, StaticString, Stream, ToValue, Tuple, TypeInfo, Variant, Vec, Vm, VmError,
-    VmErrorKind,
+    Generator, GeneratorState, Iterator, Mut, Number, Object, Protocol, ProtocolCaller, Range,
+    RawMut, RawRef, Ref, Shared, StaticString, Stream, ToValue, Tuple, TypeInfo, Variant,
+    VariantData, Vec, Vm, VmError, VmErrorKind,
 };
 use crate::{Any, Hash};
 use serde::{de, ser, Deserialize, Serialize};
@@ -492,8 +493,21 @@ impl Value {
             target => target,
         };
 
-        let value = EnvProtocolCaller.call_protocol_fn(Protocol::INTO_ITER, target, ())?;
-        Iterator::from_value(value)
+        let type_hash = target.type_hash()?;
+
+        match EnvProtocolCaller.try_call_protocol_fn(Protocol::INTO_ITER, target, ())? {
+            CallResult::Ok(value) => Iterator::from_value(value),
+            // NB: values which implement the next protocol are their own
+            // iterators.
+            CallResult::Unsupported(target)
+                if EnvProtocolCaller.has_protocol_fn(Protocol::NEXT, type_hash)? =>
+            {
+                Ok(Iterator::from_next(target))
+            }
+            CallResult::Unsupported(..) => Err(VmError::from(VmErrorKind::MissingFunction {
+                hash: Hash::instance_function(type_hash, Protocol::INTO_ITER.hash),
+            })),
+        }
     }
 
     /// Coerce into future, or convert into a future using the
@@ -611,7 +625,7 @@ impl Value {
             Self::Float(value) => Self::Float(value),
             Self::Type(value) => Self::Type(value),
             Self::StaticString(value) => Self::StaticString(value),
-            Self::String(value) => Self::String(Shared::new(value.take()?)),
+            Self::String(value) => Self::from(value.take()?),
             Self::Bytes(value) => Self::Bytes(Shared::new(value.take()?)),
             Self::Vec(value) => Self::Vec(Shared::new(value.take()?)),
             Self::Tuple(value) => Self::Tuple(Shared::new(value.take()?)),
@@ -634,6 +648,147 @@ impl Value {
         })
     }
 
+    /// Clone the value through the [Protocol::CLONE] protocol.
+    ///
+    /// Types which don't implement the protocol are cloned deeply, so that
+    /// strings, collections, script structs and variants are copied along
+    /// with everything they contain. Functions, formats and iterators are
+    /// immutable and are shared instead. Native types, futures, streams and
+    /// generators can't be cloned unless they implement the protocol.
+    ///
+    /// Values which contain themselves are cloned into values which contain
+    /// their clone. Cloning errors if values are nested too deeply, or if a
+    /// value contains itself through the clone protocol.
+    ///
+    /// This must be called inside of a virtual machine, see [Vm::with].
+    pub(crate) fn value_ptr_clone(value: &Value) -> Result<Value, VmError> {
+        let _guard = cloned::Guard::enter()?;
+
+        Ok(match value {
+            Self::Unit => Self::Unit,
+            Self::Bool(value) => Self::Bool(*value),
+            Self::Byte(value) => Self::Byte(*value),
+            Self::Char(value) => Self::Char(*value),
+            Self::Integer(value) => Self::Integer(*value),
+            Self::Float(value) => Self::Float(*value),
+            Self::Type(value) => Self::Type(*value),
+            Self::StaticString(value) => Self::StaticString(value.clone()),
+            Self::Format(value) => Self::Format(value.clone()),
+            Self::Iterator(value) => Self::Iterator(value.clone()),
+            Self::Function(value) => Self::Function(value.clone()),
+            Self::String(value) => Self::from(value.borrow_ref()?.clone()),
+            Self::Bytes(value) => Self::Bytes(Shared::new(value.borrow_ref()?.clone())),
+            Self::Vec(value) => clone_shared(value, Self::Vec, Vec::new(), |value| {
+                Ok(Vec::from(clone_values(value)?))
+            })?,
+            Self::Tuple(value) => {
+                clone_shared(value, Self::Tuple, Tuple::from(vec::Vec::new()), |value| {
+                    Ok(Tuple::from(clone_values(value)?))
+                })?
+            }
+            Self::Object(value) => clone_shared(value, Self::Object, Object::new(), clone_object)?,
+            Self::Range(value) => {
+                let empty = Range::new(None, None, value.borrow_ref()?.limits);
+
+                clone_shared(value, Self::Range, empty, |value| {
+                    Ok(Range {
+                        start: value
+                            .start
+                            .as_ref()
+                            .map(Self::value_ptr_clone)
+                            .transpose()?,
+                        end: value.end.as_ref().map(Self::value_ptr_clone).transpose()?,
+                        limits: value.limits,
+                    })
+                })?
+            }
+            Self::Option(value) => clone_shared(value, Self::Option, None, |value| {
+                value.as_ref().map(Self::value_ptr_clone).transpose()
+            })?,
+            Self::Result(value) => clone_shared(value, Self::Result, Ok(Self::Unit), |value| {
+                Ok(match value {
+                    Ok(value) => Ok(Self::value_ptr_clone(value)?),
+                    Err(value) => Err(Self::value_ptr_clone(value)?),
+                })
+            })?,
+            value => {
+                if let Some(value) = cloned::get(value) {
+                    return Ok(value);
+                }
+
+                let result = {
+                    let _protocol = cloned::Protocol::enter(value)?;
+                    EnvProtocolCaller.try_call_protocol_fn(Protocol::CLONE, value.clone(), ())?
+                };
+
+                if let CallResult::Ok(value) = result {
+                    return Ok(value);
+                }
+
+                match value {
+                    Self::UnitStruct(value) => Self::UnitStruct(Shared::new(UnitStruct {
+                        rtti: value.borrow_ref()?.rtti.clone(),
+                    })),
+                    Self::TupleStruct(value) => {
+                        let empty = TupleStruct {
+                            rtti: value.borrow_ref()?.rtti.clone(),
+                            data: Tuple::from(vec::Vec::new()),
+                        };
+
+                        clone_shared(value, Self::TupleStruct, empty, |value| {
+                            Ok(TupleStruct {
+                                rtti: value.rtti.clone(),
+                                data: Tuple::from(clone_values(&value.data)?),
+                            })
+                        })?
+                    }
+                    Self::Struct(value) => {
+                        let empty = Struct {
+                            rtti: value.borrow_ref()?.rtti.clone(),
+                            data: Object::new(),
+                        };
+
+                        clone_shared(value, Self::Struct, empty, |value| {
+                            Ok(Struct {
+                                rtti: value.rtti.clone(),
+                                data: clone_object(&value.data)?,
+                            })
+                        })?
+                    }
+                    Self::Variant(value) => {
+                        let empty = Variant {
+                            rtti: value.borrow_ref()?.rtti.clone(),
+                            data: VariantData::Unit,
+                        };
+
+                        clone_shared(value, Self::Variant, empty, |value| {
+                            let data = match &value.data {
+                                VariantData::Unit => VariantData::Unit,
+                                VariantData::Tuple(tuple) => {
+                                    VariantData::Tuple(Tuple::from(clone_values(tuple)?))
+                                }
+                                VariantData::Struct(object) => {
+                                    VariantData::Struct(clone_object(object)?)
+                                }
+                            };
+
+                            Ok(Variant {
+                                rtti: value.rtti.clone(),
+                                data,
+                            })
+                        })?
+                    }
+                    value => {
+                        return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
+                            instance: value.type_info()?,
+                            hash: Hash::instance_function(value.type_hash()?, Protocol::CLONE),
+                        }));
+                    }
+                }
+            }
+        })
+    }
+
     /// Try to coerce value into a unit.
     #[inline]
     pub fn into_unit(self) -> Result<(), VmError> {
@@ -957,7 +1112,11 @@ impl Value {
     /// each other.
     ///
     /// This is the basis for the eq operation (`==`).
-    pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Value, b: &Value) -> Result<bool, VmError> {
+    pub(crate) fn value_ptr_eq(
+        caller: &mut impl ProtocolCaller,
+        a: &Value,
+        b: &Value,
+    ) -> Result<bool, VmError> {
         match (a, b) {
             (Self::Unit, Self::Unit) => return Ok(true),
             (Self::Bool(a), Self::Bool(b)) => return Ok(a == b),
@@ -968,22 +1127,22 @@ impl Value {
             (Self::Vec(a), Self::Vec(b)) => {
                 let a = a.borrow_ref()?;
                 let b = b.borrow_ref()?;
-                return Vec::value_ptr_eq(vm, &*a, &*b);
+                return Vec::value_ptr_eq(caller, &*a, &*b);
             }
             (Self::Tuple(a), Self::Tuple(b)) => {
                 let a = a.borrow_ref()?;
                 let b = b.borrow_ref()?;
-                return Tuple::value_ptr_eq(vm, &*a, &*b);
+                return Tuple::value_ptr_eq(caller, &*a, &*b);
             }
             (Self::Object(a), Self::Object(b)) => {
                 let a = a.borrow_ref()?;
                 let b = b.borrow_ref()?;
-                return Object::value_ptr_eq(vm, &*a, &*b);
+                return Object::value_ptr_eq(caller, &*a, &*b);
             }
             (Self::Range(a), Self::Range(b)) => {
                 let a = a.borrow_ref()?;
                 let b = b.borrow_ref()?;
-                return Range::value_ptr_eq(vm, &*a, &*b);
+                return Range::value_ptr_eq(caller, &*a, &*b);
             }
             (Self::UnitStruct(a), Self::UnitStruct(b)) => {
                 if a.borrow_ref()?.rtti.hash == b.borrow_ref()?.rtti.hash {
@@ -1000,7 +1159,7 @@ impl Value {
                 let b = b.borrow_ref()?;
 
                 if a.rtti.hash == b.rtti.hash {
-                    return Tuple::value_ptr_eq(vm, &a.data, &b.data);
+                    return Tuple::value_ptr_eq(caller, &a.data, &b.data);
                 }
             }
             (Self::Struct(a), Self::Struct(b)) => {
@@ -1008,7 +1167,7 @@ impl Value {
                 let b = b.borrow_ref()?;
 
                 if a.rtti.hash == b.rtti.hash {
-                    return Object::value_ptr_eq(vm, &a.data, &b.data);
+                    return Object::value_ptr_eq(caller, &a.data, &b.data);
                 }
             }
             (Self::Variant(a), Self::Variant(b)) => {
@@ -1016,7 +1175,7 @@ impl Value {
                 let b = b.borrow_ref()?;
 
                 if a.rtti().enum_hash == b.rtti().enum_hash {
-                    return Variant::value_ptr_eq(vm, &*a, &*b);
+                    return Variant::value_ptr_eq(caller, &*a, &*b);
                 }
             }
             (Self::String(a), Self::String(b)) => {
@@ -1035,17 +1194,17 @@ impl Value {
                 return Ok(***a == ***b);
             }
             (Self::Option(a), Self::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
-                (Some(a), Some(b)) => return Self::value_ptr_eq(vm, a, b),
+                (Some(a), Some(b)) => return Self::value_ptr_eq(caller, a, b),
                 (None, None) => return Ok(true),
                 _ => return Ok(false),
             },
             (Self::Result(a), Self::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
-                (Ok(a), Ok(b)) => return Self::value_ptr_eq(vm, a, b),
-                (Err(a), Err(b)) => return Self::value_ptr_eq(vm, a, b),
+                (Ok(a), Ok(b)) => return Self::value_ptr_eq(caller, a, b),
+                (Err(a), Err(b)) => return Self::value_ptr_eq(caller, a, b),
                 _ => return Ok(false),
             },
-            (a, b) => match vm.call_instance_fn(a.clone(), Protocol::EQ, (b.clone(),))? {
-                CallResult::Ok(()) => return bool::from_value(vm.stack_mut().pop()?),
+            (a, b) => match caller.try_call_protocol_fn(Protocol::EQ, a.clone(), (b.clone(),))? {
+                CallResult::Ok(value) => return bool::from_value(value),
                 CallResult::Unsupported(..) => {}
             },
         }
@@ -1056,6 +1215,179 @@ impl Value {
             rhs: b.type_info()?,
         }))
     }
+
+    /// Test if two values are equal like [Value::value_ptr_eq], calling any
+    /// protocol functions through the current environment.
+    ///
+    /// This must be called inside of a virtual machine, see [Vm::with].
+    pub(crate) fn value_ptr_eq_env(a: &Value, b: &Value) -> Result<bool, VmError> {
+        Self::value_ptr_eq(&mut EnvProtocolCaller, a, b)
+    }
+
+    /// Perform a partial comparison of two values, as used by the `<`, `<=`,
+    /// `>` and `>=` operators.
+    ///
+    /// Built-in types are compared by value, where vectors and tuples are
+    /// compared lexicographically. Any other type is compared through the
+    /// [Protocol::PARTIAL_CMP] protocol, falling back to [Protocol::CMP].
+    ///
+    /// The `op` is the operation reported if the values can't be compared.
+    /// This must be called inside of a virtual machine, see [Vm::with].
+    pub(crate) fn value_ptr_partial_cmp(
+        op: &'static str,
+        a: &Value,
+        b: &Value,
+    ) -> Result<Option<cmp::Ordering>, VmError> {
+        match (a, b) {
+            (Self::Unit, Self::Unit) => return Ok(Some(cmp::Ordering::Equal)),
+            (Self::Bool(a), Self::Bool(b)) => return Ok(a.partial_cmp(b)),
+            (Self::Byte(a), Self::Byte(b)) => return Ok(a.partial_cmp(b)),
+            (Self::Char(a), Self::Char(b)) => return Ok(a.partial_cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => return Ok(a.partial_cmp(b)),
+            (Self::Float(a), Self::Float(b)) => return Ok(a.partial_cmp(b)),
+            (Self::Bytes(a), Self::Bytes(b)) => {
+                return Ok((**a.borrow_ref()?).partial_cmp(&**b.borrow_ref()?));
+            }
+            (Self::Vec(a), Self::Vec(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                return slice_partial_cmp(op, &a, &b);
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                return slice_partial_cmp(op, &a, &b);
+            }
+            (Self::Option(a), Self::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Some(a), Some(b)) => return Self::value_ptr_partial_cmp(op, a, b),
+                (a, b) => return Ok(a.is_some().partial_cmp(&b.is_some())),
+            },
+            (Self::Result(a), Self::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Ok(a), Ok(b)) => return Self::value_ptr_partial_cmp(op, a, b),
+                (Err(a), Err(b)) => return Self::value_ptr_partial_cmp(op, a, b),
+                (a, b) => return Ok(a.is_err().partial_cmp(&b.is_err())),
+            },
+            (a, b) => {
+                if let Some(ordering) = Self::string_cmp(a, b)? {
+                    return Ok(Some(ordering));
+                }
+
+                // NB: values of different types are never compared through
+                // protocols, unless they are both numbers.
+                if a.type_hash()? == b.type_hash()?
+                    || Number::is_number(a)? && Number::is_number(b)?
+                {
+                    if let CallResult::Ok(value) = EnvProtocolCaller.try_call_protocol_fn(
+                        Protocol::PARTIAL_CMP,
+                        a.clone(),
+                        (b.clone(),),
+                    )? {
+                        return <Option<cmp::Ordering>>::from_value(value);
+                    }
+
+                    if let CallResult::Ok(value) = EnvProtocolCaller.try_call_protocol_fn(
+                        Protocol::CMP,
+                        a.clone(),
+                        (b.clone(),),
+                    )? {
+                        return Ok(Some(cmp::Ordering::from_value(value)?));
+                    }
+                }
+            }
+        }
+
+        Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op,
+            lhs: a.type_info()?,
+            rhs: b.type_info()?,
+        }))
+    }
+
+    /// Perform a total comparison of two values, as used when sorting.
+    ///
+    /// This works like [Value::value_ptr_partial_cmp], except that floats are
+    /// ordered using [f64::total_cmp] and that [Protocol::CMP] is preferred
+    /// over [Protocol::PARTIAL_CMP]. Values which a partial comparison
+    /// considers incomparable are treated as equal.
+    ///
+    /// This must be called inside of a virtual machine, see [Vm::with].
+    pub(crate) fn value_ptr_cmp(a: &Value, b: &Value) -> Result<cmp::Ordering, VmError> {
+        match (a, b) {
+            (Self::Unit, Self::Unit) => return Ok(cmp::Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => return Ok(a.cmp(b)),
+            (Self::Byte(a), Self::Byte(b)) => return Ok(a.cmp(b)),
+            (Self::Char(a), Self::Char(b)) => return Ok(a.cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => return Ok(a.cmp(b)),
+            (Self::Float(a), Self::Float(b)) => return Ok(a.total_cmp(b)),
+            (Self::Bytes(a), Self::Bytes(b)) => {
+                return Ok((**a.borrow_ref()?).cmp(&**b.borrow_ref()?));
+            }
+            (Self::Vec(a), Self::Vec(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                return slice_cmp(&a, &b);
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                return slice_cmp(&a, &b);
+            }
+            (Self::Option(a), Self::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Some(a), Some(b)) => return Self::value_ptr_cmp(a, b),
+                (a, b) => return Ok(a.is_some().cmp(&b.is_some())),
+            },
+            (Self::Result(a), Self::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Ok(a), Ok(b)) => return Self::value_ptr_cmp(a, b),
+                (Err(a), Err(b)) => return Self::value_ptr_cmp(a, b),
+                (a, b) => return Ok(a.is_err().cmp(&b.is_err())),
+            },
+            (a, b) => {
+                if let Some(ordering) = Self::string_cmp(a, b)? {
+                    return Ok(ordering);
+                }
+
+                if a.type_hash()? == b.type_hash()?
+                    || Number::is_number(a)? && Number::is_number(b)?
+                {
+                    if let CallResult::Ok(value) = EnvProtocolCaller.try_call_protocol_fn(
+                        Protocol::CMP,
+                        a.clone(),
+                        (b.clone(),),
+                    )? {
+                        return cmp::Ordering::from_value(value);
+                    }
+
+                    if let CallResult::Ok(value) = EnvProtocolCaller.try_call_protocol_fn(
+                        Protocol::PARTIAL_CMP,
+                        a.clone(),
+                        (b.clone(),),
+                    )? {
+                        let ordering = <Option<cmp::Ordering>>::from_value(value)?;
+                        return Ok(ordering.unwrap_or(cmp::Ordering::Equal));
+                    }
+                }
+            }
+        }
+
+        Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op: "cmp",
+            lhs: a.type_info()?,
+            rhs: b.type_info()?,
+        }))
+    }
+
+    /// Compare two values if they are both strings.
+    fn string_cmp(a: &Value, b: &Value) -> Result<Option<cmp::Ordering>, VmError> {
+        let ordering = match (a, b) {
+            (Self::String(a), Self::String(b)) => a.borrow_ref()?.cmp(&*b.borrow_ref()?),
+            (Self::StaticString(a), Self::String(b)) => a.as_str().cmp(b.borrow_ref()?.as_str()),
+            (Self::String(a), Self::StaticString(b)) => a.borrow_ref()?.as_str().cmp(b.as_str()),
+            (Self::StaticString(a), Self::StaticString(b)) => a.as_str().cmp(b.as_str()),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(ordering))
+    }
 }
 
 impl fmt::Debug for Value {
@@ -1245,6 +1577,19 @@ impl_from! {
     Float => f64,
     Option => Shared<Option<Value>>,
     Result => Shared<Result<Value, Value>>,
+    String => Shared<String>,
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(Shared::new_counted(value))
+    }
+}
+
+impl ToValue for String {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(Value::from(self))
+    }
 }
 
 impl_from_wrapper! {
@@ -1252,7 +1597,6 @@ impl_from_wrapper! {
     Format => Box<Format>,
     Iterator => Shared<Iterator>,
     Bytes => Shared<Bytes>,
-    String => Shared<String>,
     Vec => Shared<Vec>,
     Tuple => Shared<Tuple>,
     Object => Shared<Object>,
@@ -1354,7 +1698,18 @@ impl ser::Serialize for Value {
             Value::Format(..) => Err(ser::Error::custom("cannot serialize format specifications")),
             Value::Iterator(..) => Err(ser::Error::custom("cannot serialize iterators")),
             Value::Range(..) => Err(ser::Error::custom("cannot serialize ranges")),
-            Value::Any(..) => Err(ser::Error::custom("cannot serialize external objects")),
+            Value::Any(..) => {
+                let result = EnvProtocolCaller
+                    .try_call_protocol_fn(Protocol::SERIALIZE, self.clone(), ())
+                    .map_err(ser::Error::custom)?;
+
+                match result {
+                    CallResult::Ok(value) => value.serialize(serializer),
+                    CallResult::Unsupported(..) => {
+                        Err(ser::Error::custom("cannot serialize external objects"))
+                    }
+                }
+            }
         }
     }
 }
@@ -1373,7 +1728,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::String(Shared::new(value.to_owned())))
+        Ok(Value::from(value.to_owned()))
     }
 
     #[inline]
@@ -1381,7 +1736,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::String(Shared::new(value)))
+        Ok(Value::from(value))
     }
 
     #[inline]
@@ -1549,10 +1904,33 @@ impl<'de> de::Visitor<'de> for VmVisitor {
             object.insert(key, value);
         }
 
+        if let Some(value) = deserialize_tagged(&object).map_err(de::Error::custom)? {
+            return Ok(value);
+        }
+
         Ok(Value::Object(Shared::new(object)))
     }
 }
 
+/// Turn a map with a single entry keyed by a registered tag back into the
+/// native value it was serialized from, see
+/// [Module::deserialize_tagged][crate::Module::deserialize_tagged].
+fn deserialize_tagged(object: &Object) -> Result<Option<Value>, VmError> {
+    let (tag, value) = match object.iter().next() {
+        Some(entry) if object.len() == 1 => entry,
+        _ => return Ok(None),
+    };
+
+    // NB: outside of a virtual machine there are no registered tags.
+    let handler =
+        match crate::runtime::env::with(|context, _| Ok(context.deserializer(tag).cloned())) {
+            Ok(Some(handler)) => handler,
+            _ => return Ok(None),
+        };
+
+    Ok(Some(handler(value.clone())?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::Value;
@@ -1566,3 +1944,71 @@ mod tests {
         };
     }
 }
+
+/// Lexicographically compare two slices of values.
+fn slice_partial_cmp(
+    op: &'static str,
+    a: &[Value],
+    b: &[Value],
+) -> Result<Option<cmp::Ordering>, VmError> {
+    for (a, b) in a.iter().zip(b.iter()) {
+        match Value::value_ptr_partial_cmp(op, a, b)? {
+            Some(cmp::Ordering::Equal) => continue,
+            ordering => return Ok(ordering),
+        }
+    }
+
+    Ok(a.len().partial_cmp(&b.len()))
+}
+
+/// Lexicographically compare two slices of values using a total ordering.
+fn slice_cmp(a: &[Value], b: &[Value]) -> Result<cmp::Ordering, VmError> {
+    for (a, b) in a.iter().zip(b.iter()) {
+        match Value::value_ptr_cmp(a, b)? {
+            cmp::Ordering::Equal => continue,
+            ordering => return Ok(ordering),
+        }
+    }
+
+    Ok(a.len().cmp(&b.len()))
+}
+
+/// Clone every value in the given slice.
+fn clone_values(values: &[Value]) -> Result<vec::Vec<Value>, VmError> {
+    values.iter().map(Value::value_ptr_clone).collect()
+}
+
+/// Deep clone the value in the given shared container through `clone`.
+///
+/// The `empty` placeholder is registered as the clone before the content is
+/// cloned, so that a container which contains itself is cloned into one which
+/// contains its clone instead of recursing forever.
+fn clone_shared<T>(
+    shared: &Shared<T>,
+    wrap: fn(Shared<T>) -> Value,
+    empty: T,
+    clone: impl FnOnce(&T) -> Result<T, VmError>,
+) -> Result<Value, VmError> {
+    let source = wrap(shared.clone());
+
+    if let Some(value) = cloned::get(&source) {
+        return Ok(value);
+    }
+
+    let output = Shared::new(empty);
+    cloned::insert(source, wrap(output.clone()));
+    let value = clone(&*shared.borrow_ref()?)?;
+    *output.borrow_mut()? = value;
+    Ok(wrap(output))
+}
+
+/// Clone every value in the given object.
+fn clone_object(object: &Object) -> Result<Object, VmError> {
+    let mut output = Object::with_capacity(object.len());
+
+    for (key, value) in object {
+        output.insert(key.clone(), Value::value_ptr_clone(value)?);
+    }
+
+    Ok(output)
+}
@@ -1,16 +1,20 @@
 use crate::compile::{InstallWith, Named};
 use crate::runtime::{
-    FromValue, Iterator, Mut, RawMut, RawRef, RawStr, Ref, Shared, ToValue, UnsafeFromValue, Value,
-    Vm, VmError, VmErrorKind,
+    memory, FromValue, Iterator, Mut, ProtocolCaller, Range, RangeLimits, RawMut, RawRef, RawStr,
+    Ref, Shared, ToValue, UnsafeFromValue, Value, VmError, VmErrorKind,
 };
 use std::cmp;
 use std::fmt;
+use std::mem;
 use std::ops;
 use std::slice;
 use std::vec;
 
 /// Struct representing a dynamic vector.
 ///
+/// The memory it allocates is accounted for in the [memory quota][crate::runtime::memory],
+/// and is returned to it when it's dropped.
+///
 /// # Examples
 ///
 /// ```
@@ -27,7 +31,6 @@ use std::vec;
 /// assert_eq!(None::<bool>, vec.get_value(2)?);
 /// # Ok(()) }
 /// ```
-#[derive(Clone)]
 #[repr(transparent)]
 pub struct Vec {
     inner: vec::Vec<Value>,
@@ -49,17 +52,72 @@ impl Vec {
         self.inner.sort_by(compare)
     }
 
+    /// Sort the vector with the given fallible comparison function.
+    ///
+    /// The sort is stable, and stops at the first comparison which fails. In
+    /// that case the error is returned and the vector is left unmodified.
+    pub fn try_sort_by<F>(&mut self, mut compare: F) -> Result<(), VmError>
+    where
+        F: FnMut(&Value, &Value) -> Result<cmp::Ordering, VmError>,
+    {
+        let len = self.inner.len();
+        let mut from = self.inner.clone();
+        let mut to = self.inner.clone();
+        let mut width = 1;
+
+        // NB: a bottom-up merge sort, since the sort in std can't be
+        // interrupted once a comparison has failed.
+        while width < len {
+            for start in (0..len).step_by(width * 2) {
+                let mid = usize::min(start + width, len);
+                let end = usize::min(start + width * 2, len);
+                let (mut a, mut b) = (start, mid);
+
+                for slot in &mut to[start..end] {
+                    let take_a = a < mid
+                        && (b >= end || compare(&from[b], &from[a])? != cmp::Ordering::Less);
+
+                    if take_a {
+                        *slot = from[a].clone();
+                        a += 1;
+                    } else {
+                        *slot = from[b].clone();
+                        b += 1;
+                    }
+                }
+            }
+
+            mem::swap(&mut from, &mut to);
+            width *= 2;
+        }
+
+        for (slot, value) in self.inner.iter_mut().zip(from) {
+            *slot = value;
+        }
+
+        Ok(())
+    }
+
     /// Construct a new dynamic vector guaranteed to have at least the given
     /// capacity.
     pub fn with_capacity(cap: usize) -> Self {
-        Self {
-            inner: vec::Vec::with_capacity(cap),
-        }
+        let inner = vec::Vec::with_capacity(cap);
+        memory::resize::<Value>(0, inner.capacity());
+        Self { inner }
+    }
+
+    /// Construct a new dynamic vector with the given capacity, erroring
+    /// before it's allocated if that would exceed the memory quota.
+    pub(crate) fn try_with_capacity(cap: usize) -> Result<Self, VmError> {
+        memory::ensure(cap.saturating_mul(mem::size_of::<Value>()))?;
+        Ok(Self::with_capacity(cap))
     }
 
     /// Convert into inner std vector.
-    pub fn into_inner(self) -> vec::Vec<Value> {
-        self.inner
+    pub fn into_inner(mut self) -> vec::Vec<Value> {
+        let inner = mem::take(&mut self.inner);
+        memory::resize::<Value>(inner.capacity(), 0);
+        inner
     }
 
     /// Returns `true` if the dynamic vector contains no elements.
@@ -88,7 +146,17 @@ impl Vec {
 
     /// Appends an element to the back of a dynamic vector.
     pub fn push(&mut self, value: Value) {
+        let capacity = self.inner.capacity();
         self.inner.push(value);
+        memory::resize::<Value>(capacity, self.inner.capacity());
+    }
+
+    /// Appends an element to the back of a dynamic vector, erroring before it
+    /// grows if that would exceed the memory quota.
+    pub(crate) fn try_push(&mut self, value: Value) -> Result<(), VmError> {
+        memory::ensure_push::<Value>(self.inner.len(), self.inner.capacity())?;
+        self.push(value);
+        Ok(())
     }
 
     /// Appends an element to the back of a dynamic vector, converting it as
@@ -97,7 +165,7 @@ impl Vec {
     where
         T: ToValue,
     {
-        self.inner.push(value.to_value()?);
+        self.push(value.to_value()?);
         Ok(())
     }
 
@@ -146,7 +214,9 @@ impl Vec {
     /// Inserts an element at position index within the vector, shifting all
     /// elements after it to the right.
     pub fn insert(&mut self, index: usize, value: Value) {
+        let capacity = self.inner.capacity();
         self.inner.insert(index, value);
+        memory::resize::<Value>(capacity, self.inner.capacity());
     }
 
     /// Extend this vector with something that implements the into_iter
@@ -155,25 +225,253 @@ impl Vec {
         let mut it = value.into_iter()?;
 
         while let Some(value) = it.next()? {
-            self.push(value);
+            self.try_push(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Splits the vector into two at the given index, returning a newly
+    /// allocated vector containing the elements in the range `[at, len)`.
+    pub fn split_off(&mut self, at: usize) -> Result<Self, VmError> {
+        if at > self.len() {
+            return Err(VmError::from(VmErrorKind::OutOfRange {
+                index: at.into(),
+                len: self.len().into(),
+            }));
+        }
+
+        Ok(Self::from(self.inner.split_off(at)))
+    }
+
+    /// Swaps two elements in the vector.
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), VmError> {
+        for index in [a, b] {
+            if index >= self.len() {
+                return Err(VmError::from(VmErrorKind::OutOfRange {
+                    index: index.into(),
+                    len: self.len().into(),
+                }));
+            }
         }
 
+        self.inner.swap(a, b);
         Ok(())
     }
 
+    /// Retains only the elements for which the predicate returns `true`.
+    ///
+    /// If the predicate errors, the first error is returned and the remaining
+    /// elements are retained.
+    pub fn retain<F>(&mut self, mut f: F) -> Result<(), VmError>
+    where
+        F: FnMut(&Value) -> Result<bool, VmError>,
+    {
+        let mut error = None;
+
+        self.inner.retain(|value| {
+            if error.is_some() {
+                return true;
+            }
+
+            match f(value) {
+                Ok(retain) => retain,
+                Err(e) => {
+                    error = Some(e);
+                    true
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Test if the vector contains an element equal to the given value.
+    ///
+    /// Elements are compared using [Protocol::EQ][crate::runtime::Protocol::EQ]
+    /// where necessary, so this must be called inside of a virtual machine.
+    pub fn contains(&self, value: &Value) -> Result<bool, VmError> {
+        for element in &self.inner {
+            if Value::value_ptr_eq_env(element, value)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Removes consecutive repeated elements in the vector.
+    ///
+    /// Elements are compared using [Protocol::EQ][crate::runtime::Protocol::EQ]
+    /// where necessary, so this must be called inside of a virtual machine.
+    pub fn dedup(&mut self) -> Result<(), VmError> {
+        let mut error = None;
+
+        self.inner.dedup_by(|a, b| {
+            if error.is_some() {
+                return false;
+            }
+
+            match Value::value_ptr_eq_env(a, b) {
+                Ok(eq) => eq,
+                Err(e) => {
+                    error = Some(e);
+                    false
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Binary searches this sorted vector for the given value.
+    ///
+    /// Returns `Ok` with the index of a matching element, or `Err` with the
+    /// index where the value could be inserted while maintaining sorted order.
+    /// Elements are ordered using [Protocol::CMP][crate::runtime::Protocol::CMP]
+    /// where necessary, so this must be called inside of a virtual machine.
+    pub fn binary_search(&self, value: &Value) -> Result<Result<usize, usize>, VmError> {
+        let mut error = None;
+
+        let result = self.inner.binary_search_by(|element| {
+            if error.is_some() {
+                return cmp::Ordering::Equal;
+            }
+
+            match Value::value_ptr_cmp(element, value) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    error = Some(e);
+                    cmp::Ordering::Equal
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    /// Join a vector of strings into a single string, placing the given
+    /// separator between each of them.
+    pub fn join(&self, sep: &str) -> Result<String, VmError> {
+        let mut out = String::new();
+
+        for (n, value) in self.inner.iter().enumerate() {
+            if n > 0 {
+                out.push_str(sep);
+            }
+
+            match value {
+                Value::String(string) => out.push_str(&string.borrow_ref()?),
+                Value::StaticString(string) => out.push_str(string.as_ref()),
+                value => return Err(VmError::expected::<String>(value.type_info()?)),
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Get a copy of the elements in the given range.
+    pub fn get_range(&self, range: &Range) -> Result<Self, VmError> {
+        let start = match &range.start {
+            Some(start) => <usize as FromValue>::from_value(start.clone())?,
+            None => 0,
+        };
+
+        let end = match (&range.end, range.limits) {
+            (Some(end), RangeLimits::HalfOpen) => <usize as FromValue>::from_value(end.clone())?,
+            (Some(end), RangeLimits::Closed) => <usize as FromValue>::from_value(end.clone())?
+                .checked_add(1)
+                .ok_or(VmErrorKind::UnsupportedRange)?,
+            (None, RangeLimits::HalfOpen) => self.len(),
+            (None, RangeLimits::Closed) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedRange))
+            }
+        };
+
+        if end > self.len() {
+            return Err(VmError::from(VmErrorKind::OutOfRange {
+                index: end.into(),
+                len: self.len().into(),
+            }));
+        }
+
+        if start > end {
+            return Err(VmError::from(VmErrorKind::UnsupportedRange));
+        }
+
+        Ok(Self::from(self.inner[start..end].to_vec()))
+    }
+
+    /// Construct an iterator over all contiguous windows of length `size`,
+    /// where each window is a new vector.
+    pub fn windows(&self, size: usize) -> Result<Iterator, VmError> {
+        if size == 0 {
+            return Err(VmError::panic("window size must be non-zero"));
+        }
+
+        let values = self.inner.clone();
+        let count = (values.len() + 1).saturating_sub(size);
+
+        Ok(Iterator::from_double_ended(
+            "std::vec::Windows",
+            (0..count).map(move |n| Self::from(values[n..n + size].to_vec())),
+        ))
+    }
+
+    /// Construct an iterator over chunks of length `size`, where each chunk is
+    /// a new vector. The last chunk is shorter if the length of the vector
+    /// isn't evenly divided by `size`.
+    pub fn chunks(&self, size: usize) -> Result<Iterator, VmError> {
+        if size == 0 {
+            return Err(VmError::panic("chunk size must be non-zero"));
+        }
+
+        let values = self.inner.clone();
+        let len = values.len();
+
+        Ok(Iterator::from_double_ended(
+            "std::vec::Chunks",
+            (0..len)
+                .step_by(size)
+                .map(move |n| Self::from(values[n..usize::min(n + size, len)].to_vec())),
+        ))
+    }
+
     /// Convert into a rune iterator.
     pub fn into_iterator(&self) -> Iterator {
         Iterator::from_double_ended("std::vec::Iter", self.clone().into_iter())
     }
 
     /// Compare two vectors for equality.
-    pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Self, b: &Self) -> Result<bool, VmError> {
+    pub(crate) fn value_ptr_eq(
+        caller: &mut impl ProtocolCaller,
+        a: &Self,
+        b: &Self,
+    ) -> Result<bool, VmError> {
         if a.len() != b.len() {
             return Ok(false);
         }
 
         for (a, b) in a.iter().zip(b.iter()) {
-            if !Value::value_ptr_eq(vm, a, b)? {
+            if !Value::value_ptr_eq(caller, a, b)? {
                 return Ok(false);
             }
         }
@@ -194,6 +492,18 @@ impl fmt::Debug for Vec {
     }
 }
 
+impl Clone for Vec {
+    fn clone(&self) -> Self {
+        Self::from(self.inner.clone())
+    }
+}
+
+impl Drop for Vec {
+    fn drop(&mut self) {
+        memory::resize::<Value>(self.inner.capacity(), 0);
+    }
+}
+
 impl ops::Deref for Vec {
     type Target = [Value];
 
@@ -213,7 +523,7 @@ impl IntoIterator for Vec {
     type IntoIter = vec::IntoIter<Value>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
+        self.into_inner().into_iter()
     }
 }
 
@@ -237,15 +547,14 @@ impl<'a> IntoIterator for &'a mut Vec {
 
 impl From<vec::Vec<Value>> for Vec {
     fn from(inner: vec::Vec<Value>) -> Self {
+        memory::resize::<Value>(0, inner.capacity());
         Self { inner }
     }
 }
 
 impl From<Box<[Value]>> for Vec {
     fn from(inner: Box<[Value]>) -> Self {
-        Self {
-            inner: inner.to_vec(),
-        }
+        Self::from(inner.into_vec())
     }
 }
 
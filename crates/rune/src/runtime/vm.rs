@@ -1,14 +1,18 @@
 use crate::runtime::budget;
 use crate::runtime::future::SelectFuture;
+use crate::runtime::limits::{self, Limits};
+use crate::runtime::memory;
 use crate::runtime::unit::UnitFn;
 use crate::runtime::{
     Args, Awaited, BorrowMut, Bytes, Call, Format, FormatSpec, FromValue, Function, Future,
     Generator, GuardedArgs, Inst, InstAddress, InstAssignOp, InstOp, InstRangeLimits, InstTarget,
-    InstValue, InstVariant, Object, Panic, Protocol, Range, RangeLimits, RuntimeContext, Select,
-    Shared, Stack, Stream, Struct, Tuple, TypeCheck, Unit, UnitStruct, Value, Variant, VariantData,
-    Vec, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr, VmSendExecution,
+    InstValue, InstVariant, Iterator, Object, Panic, Protocol, Range, RangeLimits, RuntimeContext,
+    Select, Shared, Stack, Stream, Struct, Tuple, TypeCheck, Unit, UnitStruct, Value, Variant,
+    VariantData, Vec, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr, VmSendExecution,
+    ITERATOR_TYPE,
 };
 use crate::{Hash, IntoTypeHash};
+use std::cmp::Ordering;
 use std::fmt;
 use std::mem;
 use std::sync::Arc;
@@ -82,6 +86,13 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: vec::Vec<CallFrame>,
+    /// The maximum number of call frames.
+    max_call_depth: usize,
+    /// The maximum number of values on the stack.
+    max_stack_size: usize,
+    /// The limits in effect, which include the usage of the virtual machines
+    /// this one is called from. Updated each time the virtual machine runs.
+    limits: Limits,
 }
 
 impl Vm {
@@ -98,6 +109,9 @@ impl Vm {
             ip: 0,
             stack,
             call_frames: vec::Vec::new(),
+            max_call_depth: usize::MAX,
+            max_stack_size: usize::MAX,
+            limits: Limits::UNLIMITED,
         }
     }
 
@@ -137,6 +151,76 @@ impl Vm {
         &mut self.stack
     }
 
+    /// Set the maximum number of call frames the virtual machine can have.
+    ///
+    /// Calling a function beyond this depth errors with
+    /// [VmErrorKind::StackOverflow]. By default there is no limit.
+    ///
+    /// The limit includes the call frames of virtual machines which are
+    /// called from this one, like when a native function calls back into a
+    /// script.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Source, Sources, Vm};
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("test", r#"
+    /// fn recurse(n) { recurse(n + 1) }
+    /// pub fn main() { recurse(0) }
+    /// "#));
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    /// vm.set_max_call_depth(64);
+    ///
+    /// let error = vm.call(&["main"], ()).unwrap_err();
+    /// assert!(matches!(error.as_unwound().0, VmErrorKind::StackOverflow { depth: 64, .. }));
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Set the maximum number of values the stack of the virtual machine can
+    /// hold.
+    ///
+    /// Exceeding it errors with [VmErrorKind::StackOverflow] before the next
+    /// instruction is executed. By default there is no limit.
+    ///
+    /// Like [Vm::set_max_call_depth], the limit includes the stacks of virtual
+    /// machines which are called from this one.
+    #[inline]
+    pub fn set_max_stack_size(&mut self, max_stack_size: usize) {
+        self.max_stack_size = max_stack_size;
+    }
+
+    /// Use the same limits as another virtual machine.
+    pub(crate) fn copy_limits(&mut self, other: &Vm) {
+        self.max_call_depth = other.max_call_depth;
+        self.max_stack_size = other.max_stack_size;
+    }
+
+    /// Limit a virtual machine which is called from `parent` to what remains
+    /// of the limits of `parent`.
+    ///
+    /// This is used for virtual machines which don't run while `parent` is
+    /// running, like the ones pushed onto an execution or the ones driving
+    /// generators, streams and futures.
+    pub(crate) fn inherit_limits(&mut self, parent: &Vm) {
+        let depth = parent.limits.call_depth + parent.call_frames.len() + 1;
+        let size = parent.limits.stack_size + parent.stack.len();
+        self.max_call_depth = parent.limits.max_call_depth.saturating_sub(depth);
+        self.max_stack_size = parent.limits.max_stack_size.saturating_sub(size);
+    }
+
     /// Access the context related to the virtual machine.
     #[inline]
     pub fn context(&self) -> &Arc<RuntimeContext> {
@@ -567,6 +651,7 @@ impl Vm {
         &mut self,
         int_op: fn(i64, i64) -> bool,
         float_op: fn(f64, f64) -> bool,
+        ordering_op: fn(Ordering) -> bool,
         op: &'static str,
         lhs: InstAddress,
         rhs: InstAddress,
@@ -577,13 +662,9 @@ impl Vm {
         let out = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => int_op(lhs, rhs),
             (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
-            (lhs, rhs) => {
-                return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
-                    op,
-                    lhs: lhs.type_info()?,
-                    rhs: rhs.type_info()?,
-                }))
-            }
+            // NB: values which are incomparable compare false for every
+            // operator, just like NaN does.
+            (lhs, rhs) => Value::value_ptr_partial_cmp(op, &lhs, &rhs)?.is_some_and(ordering_op),
         };
 
         self.stack.push(out);
@@ -595,6 +676,10 @@ impl Vm {
     /// This will cause the `args` number of elements on the stack to be
     /// associated and accessible to the new call frame.
     pub(crate) fn push_call_frame(&mut self, ip: usize, args: usize) -> Result<(), VmError> {
+        if self.limits.call_depth + self.call_frames.len() >= self.limits.max_call_depth {
+            return Err(self.stack_overflow());
+        }
+
         let stack_top = self.stack.swap_stack_bottom(args)?;
 
         self.call_frames.push(CallFrame {
@@ -606,6 +691,71 @@ impl Vm {
         Ok(())
     }
 
+    /// Construct a stack overflow error for the current state.
+    #[cold]
+    fn stack_overflow(&self) -> VmError {
+        VmError::from(VmErrorKind::StackOverflow {
+            depth: self.limits.call_depth + self.call_frames.len(),
+            size: self.limits.stack_size + self.stack.len(),
+        })
+    }
+
+    /// Set up the limits in effect for this virtual machine from its own
+    /// limits and the ones of the virtual machines it's called from.
+    ///
+    /// Entering a nested virtual machine is treated like pushing a call frame,
+    /// so this errors if the call depth of the virtual machines it's called
+    /// from is already at the limit.
+    fn enter_limits(&mut self) -> Result<(), VmError> {
+        let outer = limits::get();
+
+        self.limits = Limits {
+            max_call_depth: outer
+                .max_call_depth
+                .min(outer.call_depth.saturating_add(self.max_call_depth)),
+            max_stack_size: outer
+                .max_stack_size
+                .min(outer.stack_size.saturating_add(self.max_stack_size)),
+            call_depth: outer.call_depth,
+            stack_size: outer.stack_size,
+        };
+
+        if outer.call_depth > outer.max_call_depth {
+            return Err(VmError::from(VmErrorKind::StackOverflow {
+                depth: outer.max_call_depth,
+                size: outer.stack_size + self.stack.len(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Check the stack size against the limits in effect, and publish the
+    /// usage of this virtual machine so that it's accounted for by virtual
+    /// machines called from it.
+    #[inline]
+    fn check_limits(&self) -> Result<(), VmError> {
+        if !self.limits.is_limited() {
+            return Ok(());
+        }
+
+        let stack_size = self.limits.stack_size + self.stack.len();
+
+        if stack_size > self.limits.max_stack_size {
+            return Err(self.stack_overflow());
+        }
+
+        // NB: a nested virtual machine is entered without a call frame, so
+        // it's accounted for as one.
+        limits::set(Limits {
+            call_depth: self.limits.call_depth + self.call_frames.len() + 1,
+            stack_size,
+            ..self.limits
+        });
+
+        Ok(())
+    }
+
     /// Pop a call frame and return it.
     fn pop_call_frame(&mut self) -> Result<bool, VmError> {
         let frame = match self.call_frames.pop() {
@@ -1085,6 +1235,7 @@ impl Vm {
     fn call_generator_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
+        vm.inherit_limits(self);
         vm.ip = offset;
         self.stack.push(Generator::new(vm));
         Ok(())
@@ -1094,6 +1245,7 @@ impl Vm {
     fn call_stream_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
+        vm.inherit_limits(self);
         vm.ip = offset;
         self.stack.push(Stream::new(vm));
         Ok(())
@@ -1103,6 +1255,7 @@ impl Vm {
     fn call_async_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
+        vm.inherit_limits(self);
         vm.ip = offset;
         self.stack.push(Future::new(vm.async_complete()));
         Ok(())
@@ -1738,27 +1891,55 @@ impl Vm {
                 self.internal_infallible_bitwise(Protocol::SHR, std::ops::Shr::shr, lhs, rhs)?;
             }
             InstOp::Gt => {
-                self.internal_boolean_ops(|a, b| a > b, |a, b| a > b, ">", lhs, rhs)?;
+                self.internal_boolean_ops(
+                    |a, b| a > b,
+                    |a, b| a > b,
+                    Ordering::is_gt,
+                    ">",
+                    lhs,
+                    rhs,
+                )?;
             }
             InstOp::Gte => {
-                self.internal_boolean_ops(|a, b| a >= b, |a, b| a >= b, ">=", lhs, rhs)?;
+                self.internal_boolean_ops(
+                    |a, b| a >= b,
+                    |a, b| a >= b,
+                    Ordering::is_ge,
+                    ">=",
+                    lhs,
+                    rhs,
+                )?;
             }
             InstOp::Lt => {
-                self.internal_boolean_ops(|a, b| a < b, |a, b| a < b, "<", lhs, rhs)?;
+                self.internal_boolean_ops(
+                    |a, b| a < b,
+                    |a, b| a < b,
+                    Ordering::is_lt,
+                    "<",
+                    lhs,
+                    rhs,
+                )?;
             }
             InstOp::Lte => {
-                self.internal_boolean_ops(|a, b| a <= b, |a, b| a <= b, "<=", lhs, rhs)?;
+                self.internal_boolean_ops(
+                    |a, b| a <= b,
+                    |a, b| a <= b,
+                    Ordering::is_le,
+                    "<=",
+                    lhs,
+                    rhs,
+                )?;
             }
             InstOp::Eq => {
                 let rhs = self.stack.address(rhs)?;
                 let lhs = self.stack.address(lhs)?;
-                let test = Value::value_ptr_eq(self, &lhs, &rhs)?;
+                let test = Value::value_ptr_eq(&mut &mut *self, &lhs, &rhs)?;
                 self.stack.push(test);
             }
             InstOp::Neq => {
                 let rhs = self.stack.address(rhs)?;
                 let lhs = self.stack.address(lhs)?;
-                let test = Value::value_ptr_eq(self, &lhs, &rhs)?;
+                let test = Value::value_ptr_eq(&mut &mut *self, &lhs, &rhs)?;
                 self.stack.push(!test);
             }
             InstOp::And => {
@@ -2394,6 +2575,57 @@ impl Vm {
         Ok(())
     }
 
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_in_range_byte(
+        &mut self,
+        start: u8,
+        end: u8,
+        limits: InstRangeLimits,
+    ) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Byte(actual) => limits.contains(start, end, actual),
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_in_range_character(
+        &mut self,
+        start: char,
+        end: char,
+        limits: InstRangeLimits,
+    ) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Char(actual) => limits.contains(start, end, actual),
+            _ => false,
+        });
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_in_range_integer(
+        &mut self,
+        start: i64,
+        end: i64,
+        limits: InstRangeLimits,
+    ) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        self.stack.push(match value {
+            Value::Integer(actual) => limits.contains(start, end, actual),
+            _ => false,
+        });
+
+        Ok(())
+    }
+
     /// Test if the top of stack is equal to the string at the given static
     /// string slot.
     #[cfg_attr(feature = "bench", inline(never))]
@@ -2764,12 +2996,12 @@ impl Vm {
     }
 
     #[cfg_attr(feature = "bench", inline(never))]
-    fn op_call_instance(&mut self, hash: Hash, args: usize) -> Result<(), VmError> {
+    fn op_call_instance(&mut self, name: Hash, args: usize) -> Result<(), VmError> {
         // NB: +1 to include the instance itself.
         let args = args + 1;
         let instance = self.stack.at_offset_from_top(args)?;
         let type_hash = instance.type_hash()?;
-        let hash = Hash::instance_function(type_hash, hash);
+        let hash = Hash::instance_function(type_hash, name);
 
         if let Some(UnitFn::Offset {
             offset,
@@ -2787,6 +3019,32 @@ impl Vm {
             return Ok(());
         }
 
+        // NB: values which don't implement the clone protocol are cloned
+        // deeply.
+        if name == Protocol::CLONE.hash && args == 1 {
+            let value = Value::value_ptr_clone(instance)?;
+            self.stack.pop()?;
+            self.stack.push(value);
+            return Ok(());
+        }
+
+        // NB: values which implement the next protocol can be used with all
+        // the functions available for iterators by wrapping them in one.
+        if let Some(handler) = self
+            .context
+            .function(Hash::instance_function(ITERATOR_TYPE.hash, name))
+        {
+            let next = Hash::instance_function(type_hash, Protocol::NEXT.hash);
+
+            if self.unit.function(next).is_some() || self.context.function(next).is_some() {
+                let value = self.stack.at_offset_from_top_mut(args)?;
+                let iterator = Iterator::from_next(value.clone());
+                *value = Value::from(iterator);
+                handler(&mut self.stack, args)?;
+                return Ok(());
+            }
+        }
+
         Err(VmError::from(VmErrorKind::MissingInstanceFunction {
             instance: instance.type_info()?,
             hash,
@@ -2887,12 +3145,16 @@ impl Vm {
         // NB: set up environment so that native function can access context and
         // unit.
         let _guard = crate::runtime::env::Guard::new(&self.context, &self.unit);
+        let _limits = limits::Guard::new();
+        self.enter_limits()?;
 
         loop {
             if !budget::take() {
                 return Ok(VmHalt::Limited);
             }
 
+            self.check_limits()?;
+
             let inst = *self
                 .unit
                 .instruction_at(self.ip)
@@ -3092,6 +3354,15 @@ impl Vm {
                 Inst::EqInteger { integer } => {
                     self.op_eq_integer(integer)?;
                 }
+                Inst::InRangeByte { start, end, limits } => {
+                    self.op_in_range_byte(start, end, limits)?;
+                }
+                Inst::InRangeChar { start, end, limits } => {
+                    self.op_in_range_character(start, end, limits)?;
+                }
+                Inst::InRangeInteger { start, end, limits } => {
+                    self.op_in_range_integer(start, end, limits)?;
+                }
                 Inst::EqBool { boolean } => {
                     self.op_eq_bool(boolean)?;
                 }
@@ -3152,11 +3423,57 @@ impl Vm {
                 }
             }
 
+            // NB: growing the stack is accounted for in the memory quota too,
+            // but it's only checked once an instruction which allocates runs.
+            if allocates(inst) {
+                memory::check()?;
+            }
+
             self.advance();
         }
     }
 }
 
+/// Test if the given instruction might allocate memory which is accounted for
+/// in the memory quota, either by constructing a value or by calling a
+/// function or protocol which does.
+fn allocates(inst: Inst) -> bool {
+    matches!(
+        inst,
+        Inst::Closure { .. }
+            | Inst::Call { .. }
+            | Inst::CallInstance { .. }
+            | Inst::CallFn { .. }
+            | Inst::LoadInstanceFn { .. }
+            | Inst::LoadFn { .. }
+            | Inst::IndexGet { .. }
+            | Inst::TupleIndexSet { .. }
+            | Inst::ObjectIndexSet { .. }
+            | Inst::IndexSet
+            | Inst::Vec { .. }
+            | Inst::Tuple { .. }
+            | Inst::Tuple1 { .. }
+            | Inst::Tuple2 { .. }
+            | Inst::Tuple3 { .. }
+            | Inst::Tuple4 { .. }
+            | Inst::PushTuple
+            | Inst::Object { .. }
+            | Inst::Range { .. }
+            | Inst::UnitStruct { .. }
+            | Inst::Struct { .. }
+            | Inst::UnitVariant { .. }
+            | Inst::StructVariant { .. }
+            | Inst::String { .. }
+            | Inst::Bytes { .. }
+            | Inst::StringConcat { .. }
+            | Inst::Format { .. }
+            | Inst::Variant { .. }
+            | Inst::Op { .. }
+            | Inst::Assign { .. }
+            | Inst::IterNext { .. }
+    )
+}
+
 impl AsMut<Vm> for Vm {
     fn as_mut(&mut self) -> &mut Vm {
         self
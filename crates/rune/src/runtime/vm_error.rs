@@ -1,8 +1,8 @@
 use crate::compile::ItemBuf;
 use crate::runtime::panic::BoxedPanic;
 use crate::runtime::{
-    AccessError, CallFrame, ExecutionState, Key, Panic, Protocol, StackError, TypeInfo, TypeOf,
-    Unit, Value, VmHaltInfo,
+    AccessError, CallFrame, DebugInst, ExecutionState, Key, Panic, Protocol, StackError, TypeInfo,
+    TypeOf, Unit, Value, Vm, VmHaltInfo,
 };
 use crate::Hash;
 use std::fmt;
@@ -65,20 +65,109 @@ impl VmError {
         *self.kind
     }
 
-    /// Convert into an unwinded vm error.
-    pub(crate) fn into_unwinded(self, unit: &Arc<Unit>, ip: usize, frames: Vec<CallFrame>) -> Self {
-        if let VmErrorKind::Unwound { .. } = &*self.kind {
-            return self;
+    /// Convert into an unwinded vm error, by unwinding it through the given
+    /// virtual machines in order from innermost to outermost.
+    ///
+    /// If the error has already been unwound, it has propagated through
+    /// native code and the frames of the virtual machines are added to the end
+    /// of its backtrace.
+    pub(crate) fn into_unwinded<'a, I>(self, vms: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Vm>,
+    {
+        let mut vms = vms.into_iter().peekable();
+
+        let first = match vms.peek() {
+            Some(vm) => *vm,
+            None => return self,
+        };
+
+        let (kind, unit, ip, frames, mut backtrace) = match *self.kind {
+            VmErrorKind::Unwound {
+                kind,
+                unit,
+                ip,
+                frames,
+                mut backtrace,
+            } => {
+                backtrace.push(BacktraceFrame::Native);
+                (kind, unit, ip, frames, backtrace)
+            }
+            kind => (
+                Box::new(kind),
+                first.unit().clone(),
+                first.ip(),
+                first.call_frames().to_vec(),
+                Vec::new(),
+            ),
+        };
+
+        for vm in vms {
+            backtrace.push(BacktraceFrame::Vm {
+                unit: vm.unit().clone(),
+                ip: vm.ip(),
+            });
+
+            for frame in vm.call_frames().iter().rev() {
+                backtrace.push(BacktraceFrame::Vm {
+                    unit: vm.unit().clone(),
+                    ip: frame.ip(),
+                });
+            }
         }
 
         Self::from(VmErrorKind::Unwound {
-            kind: self.kind,
-            unit: unit.clone(),
+            kind,
+            unit,
             ip,
             frames,
+            backtrace,
         })
     }
 
+    /// Get the backtrace of the error, starting with the innermost frame.
+    ///
+    /// The backtrace is stitched together from every virtual machine the error
+    /// propagated through, including ones which were called from native
+    /// functions or awaited as futures. Points where the error passed through
+    /// native code are marked with [BacktraceFrame::Native].
+    ///
+    /// This is empty if the error hasn't been unwound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Source, Sources, Vm};
+    /// use rune::runtime::BacktraceFrame;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("test", r#"
+    /// pub fn main() {
+    ///     [1, 2, 3].iter().map(|n| n / 0).collect::<Vec>()
+    /// }
+    /// "#));
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let error = vm.call(&["main"], ()).unwrap_err();
+    /// let backtrace = error.backtrace();
+    ///
+    /// assert!(matches!(backtrace[0], BacktraceFrame::Vm { .. }));
+    /// assert!(backtrace.iter().any(|frame| matches!(frame, BacktraceFrame::Native)));
+    /// # Ok(()) }
+    /// ```
+    pub fn backtrace(&self) -> &[BacktraceFrame] {
+        match &*self.kind {
+            VmErrorKind::Unwound { backtrace, .. } => backtrace,
+            _ => &[],
+        }
+    }
+
     /// Unpack an unwinded error, if it is present.
     pub fn as_unwound(&self) -> (&VmErrorKind, Option<(&Arc<Unit>, usize, &[CallFrame])>) {
         match &*self.kind {
@@ -87,6 +176,7 @@ impl VmError {
                 unit,
                 ip,
                 frames,
+                ..
             } => (kind, Some((unit, *ip, frames))),
             kind => (kind, None),
         }
@@ -100,6 +190,7 @@ impl VmError {
                 unit,
                 ip,
                 frames,
+                ..
             } => {
                 let error = Self { kind };
                 (error, Some((unit, ip, frames)))
@@ -159,6 +250,9 @@ pub enum VmErrorKind {
         ip: usize,
         /// All lower call frames before the unwind trigger point
         frames: Vec<CallFrame>,
+        /// The backtrace stitched together from all virtual machines the error
+        /// propagated through.
+        backtrace: Vec<BacktraceFrame>,
     },
     #[error("{error}")]
     AccessError {
@@ -178,6 +272,14 @@ pub enum VmErrorKind {
         #[from]
         error: StackError,
     },
+    #[error("stack overflow with call depth {depth} and stack size {size}")]
+    StackOverflow { depth: usize, size: usize },
+    #[error("values nested deeper than {depth} levels can't be cloned")]
+    CloneDepthExceeded { depth: usize },
+    #[error("can't clone a value which contains itself through the clone protocol")]
+    CloneCycle,
+    #[error("memory limit of {limit} bytes exceeded")]
+    MemoryLimitExceeded { limit: usize },
     #[error("numerical overflow")]
     Overflow,
     #[error("numerical underflow")]
@@ -345,12 +447,43 @@ impl VmErrorKind {
                 unit,
                 ip,
                 frames,
+                ..
             } => (kind, Some((unit.clone(), *ip, frames.clone()))),
             kind => (kind, None),
         }
     }
 }
 
+/// A single frame in the [backtrace][VmError::backtrace] of an error.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum BacktraceFrame {
+    /// An instruction being executed in a virtual machine.
+    Vm {
+        /// The unit the instruction belongs to.
+        unit: Arc<Unit>,
+        /// The instruction pointer.
+        ip: usize,
+    },
+    /// The error propagated through native code, like a native function
+    /// calling back into a script or a future being awaited.
+    Native,
+}
+
+impl BacktraceFrame {
+    /// Get the debug information for the instruction of the frame, which
+    /// includes its source and span.
+    ///
+    /// This is `None` for native frames and units compiled without debug
+    /// information.
+    pub fn debug_inst(&self) -> Option<&DebugInst> {
+        match self {
+            Self::Vm { unit, ip } => unit.debug_info()?.instruction_at(*ip),
+            Self::Native => None,
+        }
+    }
+}
+
 /// A type-erased rust number.
 #[derive(Debug, Clone)]
 pub struct VmIntegerRepr(num_bigint::BigInt);
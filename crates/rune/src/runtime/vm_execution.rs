@@ -5,6 +5,7 @@ use crate::runtime::{
 use crate::shared::AssertSend;
 use std::fmt;
 use std::future::Future;
+use std::iter;
 use std::mem::take;
 
 /// The state of an execution. We keep track of this because it's important to
@@ -159,6 +160,19 @@ where
         vm!(self)
     }
 
+    /// Get the current call depth of the execution, which is the number of
+    /// call frames across all virtual machines involved in it.
+    pub fn depth(&self) -> usize
+    where
+        T: AsRef<Vm>,
+    {
+        let head = self.head.as_ref().call_frames().len();
+
+        self.vms
+            .iter()
+            .fold(head, |depth, (vm, _)| depth + vm.call_frames().len() + 1)
+    }
+
     /// Get a mutable reference the current virtual machine.
     pub fn vm_mut(&mut self) -> &mut Vm {
         vm_mut!(self)
@@ -221,12 +235,12 @@ where
     async fn inner_async_resume(&mut self) -> Result<GeneratorState, VmError> {
         loop {
             let len = self.vms.len();
-            let vm = vm_mut!(self);
 
-            match Self::run(vm)? {
+            match self.run()? {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    awaited.into_vm(vm).await?;
+                    let result = awaited.into_vm(vm_mut!(self)).await;
+                    result.map_err(|error| self.unwind(error))?;
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -234,7 +248,7 @@ where
                     continue;
                 }
                 VmHalt::Yielded => {
-                    let value = vm.stack_mut().pop()?;
+                    let value = vm_mut!(self).stack_mut().pop()?;
                     return Ok(GeneratorState::Yielded(value));
                 }
                 halt => {
@@ -288,16 +302,15 @@ where
     fn inner_resume(&mut self) -> Result<GeneratorState, VmError> {
         loop {
             let len = self.vms.len();
-            let vm = vm_mut!(self);
 
-            match Self::run(vm)? {
+            match self.run()? {
                 VmHalt::Exited => (),
                 VmHalt::VmCall(vm_call) => {
                     vm_call.into_execution(self)?;
                     continue;
                 }
                 VmHalt::Yielded => {
-                    let value = vm.stack_mut().pop()?;
+                    let value = vm_mut!(self).stack_mut().pop()?;
                     return Ok(GeneratorState::Yielded(value));
                 }
                 halt => {
@@ -322,9 +335,8 @@ where
     /// If any async instructions are encountered, this will error.
     pub fn step(&mut self) -> Result<Option<Value>, VmError> {
         let len = self.vms.len();
-        let vm = vm_mut!(self);
 
-        match budget::with(1, || Self::run(vm)).call()? {
+        match budget::with(1, || self.run()).call()? {
             VmHalt::Exited => (),
             VmHalt::VmCall(vm_call) => {
                 vm_call.into_execution(self)?;
@@ -351,12 +363,12 @@ where
     /// instructions.
     pub async fn async_step(&mut self) -> Result<Option<Value>, VmError> {
         let len = self.vms.len();
-        let vm = vm_mut!(self);
 
-        match budget::with(1, || Self::run(vm)).call()? {
+        match budget::with(1, || self.run()).call()? {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                awaited.into_vm(vm).await?;
+                let result = awaited.into_vm(vm_mut!(self)).await;
+                result.map_err(|error| self.unwind(error))?;
                 return Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -411,19 +423,27 @@ where
     }
 
     #[inline]
-    fn run(vm: &mut Vm) -> Result<VmHalt, VmError> {
-        match vm.run() {
+    fn run(&mut self) -> Result<VmHalt, VmError> {
+        match vm_mut!(self).run() {
             Ok(reason) => Ok(reason),
-            Err(error) => Err(error.into_unwinded(vm.unit(), vm.ip(), vm.call_frames().to_vec())),
+            Err(error) => Err(self.unwind(error)),
         }
     }
+
+    /// Unwind an error through all virtual machines of the execution.
+    #[cold]
+    fn unwind(&mut self, error: VmError) -> VmError {
+        let vms = self.vms.iter().rev().map(|(vm, _)| vm);
+        error.into_unwinded(vms.chain(iter::once(&*self.head.as_mut())))
+    }
 }
 
 impl VmExecution<&mut Vm> {
     /// Convert the current execution into one which owns its virtual machine.
     pub fn into_owned(self) -> VmExecution<Vm> {
         let stack = take(self.head.stack_mut());
-        let head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack);
+        let mut head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack);
+        head.copy_limits(self.head);
 
         VmExecution {
             head,
@@ -116,20 +116,71 @@ where
         Err(e) => e,
     };
 
+    emit_run_error(&e, &sources, &diagnostics);
+    Err(e)
+}
+
+/// Same as [run], except that the source is run inside of a tokio runtime
+/// with all drivers enabled, as required by native modules like `fs`,
+/// `process` and `time`.
+pub fn run_async<N, A, T>(
+    context: &Context,
+    source: &str,
+    function: N,
+    args: A,
+) -> Result<T, RunError>
+where
+    N: IntoIterator,
+    N::Item: IntoComponent,
+    A: Args,
+    T: FromValue,
+{
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let mut diagnostics = Default::default();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build runtime");
+
+    let result = runtime.block_on(async {
+        let mut vm = vm(context, &mut sources, &mut diagnostics)?;
+
+        let output = vm
+            .execute(&ItemBuf::with_item(function), args)
+            .map_err(RunError::VmError)?
+            .async_complete()
+            .await
+            .map_err(RunError::VmError)?;
+
+        T::from_value(output).map_err(RunError::VmError)
+    });
+
+    let e = match result {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    emit_run_error(&e, &sources, &diagnostics);
+    Err(e)
+}
+
+/// Emit the diagnostics of a failed run to stdout.
+fn emit_run_error(e: &RunError, sources: &Sources, diagnostics: &Diagnostics) {
     let mut writer = termcolor::StandardStream::stdout(termcolor::ColorChoice::Never);
 
-    match &e {
+    match e {
         RunError::BuildError(..) => {
             diagnostics
-                .emit(&mut writer, &sources)
+                .emit(&mut writer, sources)
                 .expect("emit diagnostics");
         }
         RunError::VmError(e) => {
-            e.emit(&mut writer, &sources).expect("emit diagnostics");
+            e.emit(&mut writer, sources).expect("emit diagnostics");
         }
     }
-
-    Err(e)
 }
 
 /// Helper function to construct a context and unit from a Rune source for
@@ -41,3 +41,134 @@ fn test_hash_set_tuple() {
         }
     };
 }
+
+#[test]
+fn test_btree_map() {
+    let _: () = rune! {
+        pub fn main() {
+            use std::collections::BTreeMap;
+
+            let m = BTreeMap::new();
+
+            m.insert(3, "c");
+            m.insert(1, "a");
+            m[2] = "b";
+            m.insert(5, "e");
+
+            assert_eq!(m.keys().collect::<Vec>(), [1, 2, 3, 5]);
+            assert_eq!(m.first_key_value(), Some((1, "a")));
+            assert_eq!(m.last_key_value(), Some((5, "e")));
+            assert_eq!(m.range(2..5).collect::<Vec>(), [(2, "b"), (3, "c")]);
+            assert_eq!(m.range(2..=5).map(|(k, _)| k).collect::<Vec>(), [2, 3, 5]);
+            assert_eq!(m.range(3..).rev().map(|(k, _)| k).collect::<Vec>(), [5, 3]);
+            assert_eq!(m.range(4..2).count(), 0);
+            assert_eq!(m[2], "b");
+            assert_eq!(m, BTreeMap::from([(5, "e"), (3, "c"), (2, "b"), (1, "a")]));
+            assert!(m != BTreeMap::from([(5, "e"), (3, "c"), (2, "b"), (1, "x")]));
+
+            assert_eq!(m.pop_first(), Some((1, "a")));
+            assert_eq!(m.pop_last(), Some((5, "e")));
+            assert_eq!(m.remove(2), Some("b"));
+            assert_eq!(m.len(), 1);
+        }
+    };
+}
+
+#[test]
+fn test_btree_set() {
+    let _: () = rune! {
+        pub fn main() {
+            use std::collections::BTreeSet;
+
+            let s = ["pear", "apple", "fig", "apple"].iter().collect::<BTreeSet>();
+
+            assert_eq!(s.len(), 3);
+            assert_eq!(s.iter().collect::<Vec>(), ["apple", "fig", "pear"]);
+            assert_eq!(s.first(), Some("apple"));
+            assert_eq!(s.last(), Some("pear"));
+            assert_eq!(s.range("b".."p").collect::<Vec>(), ["fig"]);
+            assert!(s.contains("fig"));
+            assert!(s.remove("fig"));
+            assert_eq!(s.pop_last(), Some("pear"));
+            assert_eq!(s.pop_first(), Some("apple"));
+            assert!(s.is_empty());
+        }
+    };
+}
+
+#[test]
+fn test_btree_script_order() {
+    let _: () = rune! {
+        struct Reversed { value }
+
+        impl Reversed {
+            fn cmp(self, other) {
+                other.value.cmp(self.value)
+            }
+        }
+
+        enum Size { Small(n) }
+
+        pub fn main() {
+            use std::collections::{BTreeMap, BTreeSet};
+
+            let m = BTreeMap::new();
+            m.insert(Reversed { value: 1 }, "one");
+            m.insert(Reversed { value: 3 }, "three");
+            m.insert(Reversed { value: 2 }, "two");
+
+            assert_eq!(m.values().collect::<Vec>(), ["three", "two", "one"]);
+            assert_eq!(m.get(Reversed { value: 2 }), Some("two"));
+
+            let s = [(2, Reversed { value: 1 }), (1, Reversed { value: 5 }), (2, Reversed { value: 4 })]
+                .iter()
+                .collect::<BTreeSet>();
+
+            let values = s.iter().map(|(n, r)| (n, r.value)).collect::<Vec>();
+            assert_eq!(values, [(1, 5), (2, 4), (2, 1)]);
+
+            let s = [Size::Small(3), Size::Small(1), Size::Small(2)].iter().collect::<BTreeSet>();
+            assert_eq!(s.first(), Some(Size::Small(1)));
+            assert_eq!(s.last(), Some(Size::Small(3)));
+        }
+    };
+}
+
+#[test]
+fn test_binary_heap() {
+    let _: () = rune! {
+        struct Job { priority, name }
+
+        impl Job {
+            fn cmp(self, other) {
+                self.priority.cmp(other.priority)
+            }
+        }
+
+        pub fn main() {
+            use std::collections::BinaryHeap;
+
+            let heap = BinaryHeap::from([4, 1, 8, 3, 8, 2]);
+
+            assert_eq!(heap.len(), 6);
+            assert_eq!(heap.peek(), Some(8));
+            assert_eq!(heap.clone().into_sorted_vec(), [1, 2, 3, 4, 8, 8]);
+            assert_eq!(heap.pop(), Some(8));
+            assert_eq!(heap.pop(), Some(8));
+            assert_eq!(heap.pop(), Some(4));
+
+            let jobs = BinaryHeap::new();
+            jobs.push(Job { priority: 1, name: "low" });
+            jobs.push(Job { priority: 10, name: "urgent" });
+            jobs.push(Job { priority: 5, name: "normal" });
+
+            let order = [];
+
+            while let Some(job) = jobs.pop() {
+                order.push(job.name);
+            }
+
+            assert_eq!(order, ["urgent", "normal", "low"]);
+        }
+    };
+}
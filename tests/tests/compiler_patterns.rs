@@ -32,3 +32,42 @@ fn illegal_pattern_in_match() {
         }
     };
 }
+
+#[test]
+fn illegal_or_and_range_patterns() {
+    assert_compile_error! {
+        r#"pub fn main() { match (1, 2) { (a, 1) | (2, b) => {} } }"#,
+        span, OrPatternMissingBinding { name } => {
+            assert_eq!(&*name, "a");
+            assert_eq!(span, span!(40, 46));
+        }
+    };
+
+    assert_compile_error! {
+        r#"pub fn main() { match 1 { 5..=1 => {} } }"#,
+        span, EmptyPatternRange => {
+            assert_eq!(span, span!(26, 31));
+        }
+    };
+
+    assert_compile_error! {
+        r#"pub fn main() { match 1.5 { 1.0..=2.0 => {} } }"#,
+        span, FloatRangeInPattern => {
+            assert_eq!(span, span!(28, 37));
+        }
+    };
+
+    assert_compile_error! {
+        r#"pub fn main() { match 1 { -1..2.5 => {} } }"#,
+        span, FloatRangeInPattern => {
+            assert_eq!(span, span!(26, 33));
+        }
+    };
+
+    assert_compile_error! {
+        r#"pub fn main() { match 1 { 1..'a' => {} } }"#,
+        span, UnsupportedPatternExpr => {
+            assert_eq!(span, span!(26, 32));
+        }
+    };
+}
@@ -0,0 +1,151 @@
+use rune::runtime::debugger::{DebugStep, DebugStop, Debugger};
+use rune::runtime::VmExecution;
+use rune::{Context, FromValue, Source, SourceId, Sources, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn add(a, b) {
+    let sum = a + b;
+    sum
+}
+
+pub fn main() {
+    let x = 1;
+    let y = add(x, 2);
+
+    for n in [10, 20] {
+        y += n;
+    }
+
+    y
+}
+"#;
+
+fn prepare() -> rune::Result<(Vm, Debugger, SourceId)> {
+    let context = Context::with_default_modules()?;
+    let mut sources = Sources::new();
+    let source_id = sources.insert(Source::new("script", SOURCE));
+    let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+    let vm = Vm::new(Arc::new(context.runtime()), unit.clone());
+    let debugger = Debugger::new(unit, &sources);
+    Ok((vm, debugger, source_id))
+}
+
+fn line(debugger: &Debugger, execution: &VmExecution<&mut Vm>) -> usize {
+    debugger
+        .location_at(execution.vm().ip())
+        .expect("missing location")
+        .line
+}
+
+fn locals(debugger: &Debugger, execution: &VmExecution<&mut Vm>) -> Vec<(String, i64)> {
+    let vm = execution.vm();
+    let frame = &debugger.backtrace(vm)[0];
+
+    debugger
+        .locals(vm, frame)
+        .into_iter()
+        .map(|(name, value)| (name, i64::from_value(value).unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_breakpoints() -> rune::Result<()> {
+    let (mut vm, mut debugger, source_id) = prepare()?;
+    assert_eq!(debugger.set_breakpoint(source_id, 3), Some(3));
+    assert_eq!(debugger.set_breakpoint(source_id, 11), Some(11));
+    // NB: empty lines move the breakpoint to the next line with code.
+    assert_eq!(debugger.set_breakpoint(source_id, 9), Some(10));
+    assert!(debugger.remove_breakpoint(source_id, 10));
+    assert_eq!(debugger.set_breakpoint(source_id, 100), None);
+
+    let mut execution = vm.execute(&["main"], ())?;
+
+    let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+    assert!(matches!(stop, DebugStop::Breakpoint));
+    assert_eq!(line(&debugger, &execution), 3);
+    assert_eq!(
+        locals(&debugger, &execution),
+        vec![
+            ("a".to_owned(), 1),
+            ("b".to_owned(), 2),
+            ("sum".to_owned(), 3)
+        ]
+    );
+
+    let backtrace = debugger.backtrace(execution.vm());
+    assert_eq!(backtrace.len(), 2);
+    assert_eq!(backtrace[1].location.map(|l| l.line), Some(8));
+
+    let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+    assert!(matches!(stop, DebugStop::Breakpoint));
+    assert_eq!(line(&debugger, &execution), 11);
+
+    let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+    assert!(matches!(stop, DebugStop::Breakpoint));
+    assert_eq!(line(&debugger, &execution), 11);
+
+    let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+
+    match stop {
+        DebugStop::Complete(value) => assert_eq!(i64::from_value(value)?, 33),
+        stop => panic!("expected completion, got {:?}", stop),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_stepping() -> rune::Result<()> {
+    let (mut vm, mut debugger, source_id) = prepare()?;
+    debugger.set_breakpoint(source_id, 8);
+
+    let mut execution = vm.execute(&["main"], ())?;
+
+    let stop = debugger.resume(&mut execution, DebugStep::Continue)?;
+    assert!(matches!(stop, DebugStop::Breakpoint));
+    assert_eq!(line(&debugger, &execution), 8);
+    assert_eq!(locals(&debugger, &execution), vec![("x".to_owned(), 1)]);
+
+    debugger.resume(&mut execution, DebugStep::Into)?;
+    assert_eq!(line(&debugger, &execution), 1);
+    assert_eq!(execution.depth(), 1);
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 2);
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 3);
+
+    debugger.resume(&mut execution, DebugStep::Out)?;
+    assert_eq!(execution.depth(), 0);
+    assert_eq!(line(&debugger, &execution), 10);
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 11);
+
+    assert_eq!(
+        locals(&debugger, &execution),
+        vec![
+            ("x".to_owned(), 1),
+            ("y".to_owned(), 3),
+            ("n".to_owned(), 10)
+        ]
+    );
+
+    // NB: each iteration of the loop passes through its head.
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 10);
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 11);
+    assert_eq!(locals(&debugger, &execution)[2], ("n".to_owned(), 20));
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 10);
+
+    debugger.resume(&mut execution, DebugStep::Over)?;
+    assert_eq!(line(&debugger, &execution), 14);
+
+    Ok(())
+}
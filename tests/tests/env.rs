@@ -0,0 +1,92 @@
+use rune_modules::env::Config;
+
+fn context(config: Config) -> rune::Context {
+    let mut context = rune_tests::modules::default_context().unwrap();
+    context
+        .install(&rune_modules::env::module_with_config(config).unwrap())
+        .unwrap();
+    context
+}
+
+#[test]
+fn test_env_args() {
+    let context = context(Config::new().with_args(["a", "b c"]));
+
+    let out: Vec<String> =
+        rune_tests::run(&context, "pub fn main() { env::args() }", &["main"], ()).unwrap();
+
+    assert_eq!(out, vec![String::from("a"), String::from("b c")]);
+}
+
+#[test]
+fn test_virtual_env() {
+    let context = context(
+        Config::new()
+            .with_vars([("HOME", "/home/rune"), ("REMOVED", "1")])
+            .with_mutable_vars(true)
+            .with_current_dir("/home/rune"),
+    );
+
+    let source = r#"
+    pub fn main() {
+        env::set_var("GREETING", "hello");
+        env::remove_var("REMOVED");
+
+        let vars = env::vars();
+
+        (
+            env::var("HOME"),
+            env::var("GREETING"),
+            env::var("REMOVED"),
+            env::var("PATH"),
+            vars.len(),
+            `${env::current_dir()?}`,
+        )
+    }
+    "#;
+
+    let out: (
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        usize,
+        String,
+    ) = rune_tests::run(&context, source, &["main"], ()).unwrap();
+
+    assert_eq!(
+        out,
+        (
+            Some(String::from("/home/rune")),
+            Some(String::from("hello")),
+            None,
+            None,
+            2,
+            String::from("/home/rune"),
+        )
+    );
+
+    assert!(std::env::var_os("GREETING").is_none());
+}
+
+#[test]
+fn test_immutable_env() {
+    let context = context(Config::new().with_vars([("HOME", "/home/rune")]));
+
+    let out: Option<String> = rune_tests::run(
+        &context,
+        r#"pub fn main() { env::var("HOME") }"#,
+        &["main"],
+        (),
+    )
+    .unwrap();
+    assert_eq!(out, Some(String::from("/home/rune")));
+
+    for source in [
+        r#"pub fn main() { env::set_var("HOME", "/") }"#,
+        r#"pub fn main() { env::remove_var("HOME") }"#,
+    ] {
+        let result = rune_tests::run::<_, _, ()>(&context, source, &["main"], ());
+        assert!(result.is_err(), "{}", source);
+    }
+}
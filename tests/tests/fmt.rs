@@ -0,0 +1,119 @@
+use rune::fmt::layout_source;
+use std::fs;
+use std::path::Path;
+
+macro_rules! assert_format {
+    ($source:expr, $expected:expr) => {{
+        let formatted = layout_source($source).expect("source should parse");
+        assert_eq!(formatted, $expected);
+        let again = layout_source(&formatted).expect("formatted source should parse");
+        assert_eq!(again, formatted, "formatting should be idempotent");
+    }};
+}
+
+#[test]
+fn test_layout() {
+    assert_format!(
+        "fn main(){let a=[1,2,3];let b=#{\"a\":a,b:2};if a.len()>2{b}else{a}}",
+        "fn main() {\n    let a = [1, 2, 3];\n    let b = #{\"a\": a, b: 2};\n    if a.len() > 2 {\n        b\n    } else {\n        a\n    }\n}\n"
+    );
+
+    assert_format!(
+        "struct Foo{a,b}  enum Bar{A(a),B{c},C}",
+        "struct Foo { a, b }\nenum Bar { A(a), B { c }, C }\n"
+    );
+
+    assert_format!(
+        "fn main(){match x{1=>{a},_=>b}}",
+        "fn main() {\n    match x {\n        1 => {\n            a\n        }\n        _ => b,\n    }\n}\n"
+    );
+
+    assert_format!("const T=(1,);", "const T = (1,);\n");
+
+    assert_format!(
+        "fn main(){match x{1|2=>a,'a'..='z'=>b,n@0..10=>n,_=>c}}",
+        "fn main() {\n    match x {\n        1 | 2 => a,\n        'a'..='z' => b,\n        n @ 0..10 => n,\n        _ => c,\n    }\n}\n"
+    );
+
+    assert_format!(
+        "trait Shape{fn area(self);fn half(self){self.area()/2}}impl Shape for Square{fn area(self){1}}",
+        "trait Shape {\n    fn area(self);\n    fn half(self) {\n        self.area() / 2\n    }\n}\nimpl Shape for Square {\n    fn area(self) {\n        1\n    }\n}\n"
+    );
+}
+
+#[test]
+fn test_comments() {
+    assert_format!(
+        "// leading\nfn main() {\n  let a = 1;   // trailing\n\n\n  /* block */\n  a\n  // dangling\n}\n",
+        "// leading\nfn main() {\n    let a = 1; // trailing\n\n    /* block */\n    a\n    // dangling\n}\n"
+    );
+
+    assert_format!(
+        "/// Documented.\nstruct Foo {\n    /// Field.\n    a,\n}\n",
+        "/// Documented.\nstruct Foo {\n    /// Field.\n    a,\n}\n"
+    );
+}
+
+#[test]
+fn test_inline_comments() {
+    assert_format!(
+        "fn main() {\n    let c = a + /* mid */ b;\n}\n",
+        "fn main() {\n    let c = a + /* mid */ b;\n}\n"
+    );
+
+    assert_format!(
+        "fn foo(a, /* inline */ b) {\n    a\n}\n",
+        "fn foo(a, /* inline */ b) {\n    a\n}\n"
+    );
+
+    assert_format!(
+        "fn main(){foo(/* first */a,b/* last */);let d=[1,/* two */2];}",
+        "fn main() {\n    foo(/* first */ a, b /* last */);\n    let d = [1, /* two */ 2];\n}\n"
+    );
+
+    assert_format!(
+        "fn main() {\n    let c = a + // why\n        b;\n}\n",
+        "fn main() {\n    let c = a + // why\n    b;\n}\n"
+    );
+}
+
+#[test]
+fn test_groups() {
+    assert_format!(
+        "fn main() { foo(1,\n2) }",
+        "fn main() {\n    foo(1, 2)\n}\n"
+    );
+
+    assert_format!(
+        "fn main() { foo(\n1, 2) }",
+        "fn main() {\n    foo(\n        1,\n        2,\n    )\n}\n"
+    );
+
+    assert_format!(
+        "fn main() { a.b()\n.await?\n.c() }",
+        "fn main() {\n    a.b()\n        .await?\n        .c()\n}\n"
+    );
+}
+
+#[test]
+fn test_scripts() {
+    let scripts = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scripts");
+
+    for entry in fs::read_dir(scripts).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().and_then(|e| e.to_str()) != Some("rn") {
+            continue;
+        }
+
+        let source = fs::read_to_string(&path).unwrap();
+
+        let formatted = match layout_source(&source) {
+            Ok(formatted) => formatted,
+            Err(error) => panic!("{}: failed to parse: {}", path.display(), error),
+        };
+
+        let again = layout_source(&formatted).expect("formatted source should parse");
+        assert_eq!(again, formatted, "{}: not idempotent", path.display());
+    }
+}
@@ -0,0 +1,231 @@
+use rune::Context;
+use std::path::{Path, PathBuf};
+
+/// A temporary directory which is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rune-fs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn run<T>(module: rune::Module, source: &str, dir: &Path) -> T
+where
+    T: rune::FromValue,
+{
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+        .install(&rune_modules::path::module(false).unwrap())
+        .unwrap();
+    context
+        .install(&rune_modules::time::module(false).unwrap())
+        .unwrap();
+
+    let dir = dir.to_string_lossy().into_owned();
+    rune_tests::run_async(&context, source, &["main"], (dir,)).unwrap()
+}
+
+#[test]
+fn test_fs() {
+    let dir = TempDir::new("fs");
+
+    let out: (
+        String,
+        String,
+        Vec<String>,
+        u64,
+        bool,
+        bool,
+        Vec<String>,
+        String,
+    ) = run(
+        rune_modules::fs::module(false).unwrap(),
+        r#"
+        pub async fn main(dir) {
+            let a = `${dir}/a.txt`;
+            fs::write(a, "hello\n").await?;
+            fs::append(a, b"world\r\n").await?;
+            fs::create_dir_all(`${dir}/sub/inner`).await?;
+            fs::copy(a, `${dir}/sub/b.txt`).await?;
+            fs::rename(`${dir}/sub/b.txt`, `${dir}/sub/c.txt`).await?;
+
+            let file = fs::File::open(a).await?;
+            let lines = [];
+
+            while let Some(line) = file.read_line().await? {
+                lines.push(line);
+            }
+
+            let out = fs::File::create(`${dir}/out.txt`).await?;
+            out.write("written").await?;
+            out.sync_all().await?;
+
+            let metadata = fs::metadata(a).await?;
+            fs::remove_dir_all(`${dir}/sub/inner`).await?;
+
+            let names = [];
+            let entries = fs::read_dir(`${dir}/sub`).await?;
+
+            while let Some(entry) = entries.next().await? {
+                names.push(entry.file_name());
+            }
+
+            (
+                fs::read_to_string(`${dir}/sub/c.txt`).await?,
+                fs::read_to_string(`${dir}/out.txt`).await?,
+                lines,
+                metadata.len(),
+                metadata.is_file() && metadata.modified()? is time::SystemTime,
+                fs::exists(`${dir}/sub/inner`).await,
+                names,
+                fs::canonicalize(`${dir}/sub/../out.txt`).await?.to_string(),
+            )
+        }
+        "#,
+        &dir.0,
+    );
+
+    assert_eq!(
+        out,
+        (
+            String::from("hello\nworld\r\n"),
+            String::from("written"),
+            vec![String::from("hello"), String::from("world")],
+            13,
+            true,
+            false,
+            vec![String::from("c.txt")],
+            std::fs::canonicalize(dir.0.join("out.txt"))
+                .unwrap()
+                .display()
+                .to_string(),
+        )
+    );
+}
+
+#[test]
+fn test_scoped_fs() {
+    let dir = TempDir::new("scoped");
+    let outside = TempDir::new("outside");
+    std::fs::write(outside.0.join("secret.txt"), "secret").unwrap();
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&outside.0, dir.0.join("link")).unwrap();
+
+    let out: (String, Vec<String>, bool, bool, bool, bool, String, bool) = run(
+        rune_modules::fs::scoped_module(&dir.0).unwrap(),
+        r#"
+        pub async fn main(outside) {
+            fs::create_dir_all("/data").await?;
+            fs::write("data/../data/file.txt", "inside").await?;
+
+            let paths = [];
+            let entries = fs::read_dir("/data").await?;
+
+            while let Some(entry) = entries.next().await? {
+                paths.push(entry.path().to_string());
+            }
+
+            (
+                fs::read_to_string(path::Path::new(".").join("data/file.txt")).await?,
+                paths,
+                fs::read_to_string("../escape.txt").await.is_err(),
+                fs::read_to_string(`${outside}/secret.txt`).await.is_err(),
+                fs::exists("/data/file.txt").await,
+                fs::exists("link/secret.txt").await,
+                fs::canonicalize("data/../data/file.txt").await?.to_string(),
+                fs::canonicalize("link").await.is_err(),
+            )
+        }
+        "#,
+        &outside.0,
+    );
+
+    assert_eq!(
+        out,
+        (
+            String::from("inside"),
+            vec![String::from("/data/file.txt")],
+            true,
+            true,
+            true,
+            false,
+            String::from("/data/file.txt"),
+            cfg!(unix),
+        )
+    );
+
+    assert_eq!(
+        std::fs::read_to_string(dir.0.join("data/file.txt")).unwrap(),
+        "inside"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_scoped_fs_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let dir = TempDir::new("scoped-symlinks");
+    let outside = TempDir::new("outside-symlinks");
+
+    // Dangling links, which point to files that don't exist yet.
+    symlink(outside.0.join("target.txt"), dir.0.join("link")).unwrap();
+    let relative = Path::new("..")
+        .join(outside.0.file_name().unwrap())
+        .join("relative.txt");
+    symlink(relative, dir.0.join("relative")).unwrap();
+
+    // Links which stay inside of the root are fine.
+    std::fs::create_dir(dir.0.join("sub")).unwrap();
+    symlink("sub/inside.txt", dir.0.join("inside")).unwrap();
+    symlink(dir.0.join("sub"), dir.0.join("absolute")).unwrap();
+
+    let out: (bool, bool, bool, bool, bool, String, String) = run(
+        rune_modules::fs::scoped_module(&dir.0).unwrap(),
+        r#"
+        pub async fn main(dir) {
+            fs::write("inside", "inside").await?;
+            fs::append("absolute/inside.txt", "!").await?;
+
+            (
+                fs::write("link", "escaped").await.is_err(),
+                fs::append("link", "escaped").await.is_err(),
+                fs::File::create("link").await.is_err(),
+                fs::write("relative", "escaped").await.is_err(),
+                fs::copy("inside", "link").await.is_err(),
+                fs::read_to_string("sub/inside.txt").await?,
+                fs::read_to_string("absolute/../inside").await?,
+            )
+        }
+        "#,
+        &dir.0,
+    );
+
+    assert_eq!(
+        out,
+        (
+            true,
+            true,
+            true,
+            true,
+            true,
+            String::from("inside!"),
+            String::from("inside!"),
+        )
+    );
+
+    assert!(!outside.0.join("target.txt").exists());
+    assert!(!outside.0.join("relative.txt").exists());
+}
@@ -163,3 +163,158 @@ fn test_flat_map() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn test_adapters() {
+    let out: (Vec<(i64, char)>, Vec<i64>, Vec<i64>, Vec<i64>, Vec<i64>) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4, 5, 1];
+
+            (
+                values.iter().zip(['a', 'b']).collect::<Vec>(),
+                values.iter().take_while(|n| n < 3).collect::<Vec>(),
+                values.iter().skip_while(|n| n < 4).collect::<Vec>(),
+                values.iter().step_by(2).collect::<Vec>(),
+                [[1, 2], [], [3]].iter().flatten().collect::<Vec>(),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            vec![(1, 'a'), (2, 'b')],
+            vec![1, 2],
+            vec![4, 5, 1],
+            vec![1, 3, 5],
+            vec![1, 2, 3]
+        )
+    );
+}
+
+#[test]
+fn test_stateful_adapters() {
+    let out: (Vec<i64>, Vec<i64>, Vec<i64>, Vec<Vec<i64>>, Vec<i64>) = rune! {
+        pub fn main() {
+            let seen = [];
+            let sums = [1, 2, 3, 4].iter().scan(0, |sum, n| if n < 4 { Some(sum + n) } else { None });
+            let sums = sums.inspect(|n| seen.push(n)).collect::<Vec>();
+
+            (
+                sums,
+                seen,
+                [1, 2].iter().cycle().take(5).collect::<Vec>(),
+                [1, 2, 3, 4, 5].iter().chunks(2).collect::<Vec>(),
+                [1, 1, 2, 2, 2, 1].iter().dedup().collect::<Vec>(),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            vec![1, 3, 6],
+            vec![1, 3, 6],
+            vec![1, 2, 1, 2, 1],
+            vec![vec![1, 2], vec![3, 4], vec![5]],
+            vec![1, 2, 1]
+        )
+    );
+}
+
+#[test]
+fn test_consumers() {
+    let out: (
+        bool,
+        Option<usize>,
+        Option<i64>,
+        Option<i64>,
+        (Vec<i64>, Vec<i64>),
+        (Vec<i64>, Vec<char>),
+    ) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4];
+
+            (
+                values.iter().any(|n| n > 3),
+                values.iter().position(|n| n == 3),
+                values.iter().last(),
+                values.iter().nth(1),
+                values.iter().partition(|n| n % 2 == 0),
+                [(1, 'a'), (2, 'b')].iter().unzip(),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            true,
+            Some(2),
+            Some(4),
+            Some(2),
+            (vec![2, 4], vec![1, 3]),
+            (vec![1, 2], vec!['a', 'b'])
+        )
+    );
+}
+
+#[test]
+fn test_collect_containers() {
+    let out: (i64, usize, i64, String) = rune! {
+        use std::collections::{HashMap, HashSet, VecDeque};
+
+        pub fn main() {
+            let map = [("a", 1), ("b", 2)].iter().collect::<HashMap>();
+            let set = [1, 2, 2, 3].iter().collect::<HashSet>();
+            let deque = [1, 2, 3].iter().collect::<VecDeque>();
+            let string = ['a', 'b'].iter().chain(["cd"]).collect::<String>();
+            (map["b"], set.len(), deque.pop_back().unwrap(), string)
+        }
+    };
+    assert_eq!(out, (2, 3, 3, String::from("abcd")));
+
+    assert_vm_error!(
+        r#"pub fn main() { [1, 2].iter().step_by(0) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "step must be non-zero");
+        }
+    );
+}
+
+#[test]
+fn test_unzip_borrows_pairs() {
+    let out: (Vec<i64>, Vec<char>, i64) = rune! {
+        pub fn main() {
+            let pairs = [(1, 'a'), (2, 'b')];
+            let (numbers, chars) = pairs.iter().unzip();
+            (numbers, chars, pairs[1].0)
+        }
+    };
+    assert_eq!(out, (vec![1, 2], vec!['a', 'b'], 2));
+}
+
+#[test]
+fn test_unbounded_size_hint() -> rune::Result<()> {
+    use rune::runtime::Iterator;
+
+    /// An iterator with a lower bound which is too large to preallocate.
+    struct Unbounded(std::ops::Range<i64>);
+
+    impl std::iter::Iterator for Unbounded {
+        type Item = (i64, i64);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next().map(|n| (n, n * 2))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX, None)
+        }
+    }
+
+    let values = Iterator::from("Unbounded", Unbounded(0..3)).collect::<(i64, i64)>()?;
+    assert_eq!(values, vec![(0, 0), (1, 2), (2, 4)]);
+
+    let (left, right) = Iterator::from("Unbounded", Unbounded(0..3)).unzip()?;
+    assert_eq!(left.len(), 3);
+    assert_eq!(right.len(), 3);
+    Ok(())
+}
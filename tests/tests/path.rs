@@ -0,0 +1,66 @@
+use rune_tests::*;
+
+#[test]
+fn test_path() {
+    let out: (
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        String,
+        Vec<String>,
+        bool,
+    ) = rune_s! {r#"
+        use path::Path;
+
+        pub fn main() {
+            let path = Path::new("/target").join("debug").join("rune.tar.gz");
+
+            (
+                `${path}`,
+                path.file_name(),
+                path.extension(),
+                path.parent().and_then(|p| p.file_name()),
+                path.with_extension("zip").to_string(),
+                path.components(),
+                path.is_absolute() && path.starts_with("/target") && !Path::new("a").is_absolute(),
+            )
+        }
+    "#};
+
+    assert_eq!(
+        out,
+        (
+            String::from("/target/debug/rune.tar.gz"),
+            Some(String::from("rune.tar.gz")),
+            Some(String::from("gz")),
+            Some(String::from("debug")),
+            String::from("/target/debug/rune.tar.zip"),
+            vec![
+                String::from("/"),
+                String::from("target"),
+                String::from("debug"),
+                String::from("rune.tar.gz"),
+            ],
+            true,
+        )
+    );
+}
+
+#[test]
+fn test_path_protocols() {
+    let out: (bool, bool, bool, usize) = rune_s! {r#"
+        use path::Path;
+
+        pub fn main() {
+            let a = Path::new("a/b");
+            let set = std::collections::HashSet::new();
+            set.insert(a.clone());
+            set.insert(Path::new("a").join("b"));
+
+            (a == Path::new("a/b"), a < Path::new("a/c"), a.join("/c") == Path::new("/c"), set.len())
+        }
+    "#};
+
+    assert_eq!(out, (true, true, true, 1));
+}
@@ -258,3 +258,120 @@ fn test_bad_pattern() {
         }
     );
 }
+
+#[test]
+fn test_or_patterns() {
+    let out: Vec<i64> = rune!(
+        fn classify(n) {
+            match n {
+                1 | 2 | 3 => 1,
+                4 | 5 => 2,
+                _ => 3,
+            }
+        }
+
+        pub fn main() {
+            [classify(1), classify(3), classify(5), classify(6)]
+        }
+    );
+    assert_eq!(out, vec![1, 1, 2, 3]);
+
+    let out: Vec<i64> = rune!(
+        enum Shape {
+            Square(side),
+            Rect(w, h),
+        }
+
+        fn first(shape) {
+            match shape {
+                Shape::Square(a) | Shape::Rect(_, a) => a,
+            }
+        }
+
+        pub fn main() {
+            [first(Shape::Square(2)), first(Shape::Rect(3, 4))]
+        }
+    );
+    assert_eq!(out, vec![2, 4]);
+
+    let out: Vec<i64> = rune!(
+        fn sum(value) {
+            match value {
+                (a, b, 0) | (b, 0, a) | [a, b] => a * 10 + b,
+                _ => 0,
+            }
+        }
+
+        pub fn main() {
+            let outer = 100;
+            [sum((1, 2, 0)), sum((3, 0, 4)), sum([5, 6]), sum(()), outer]
+        }
+    );
+    assert_eq!(out, vec![12, 43, 56, 0, 100]);
+
+    let out: i64 = rune!(
+        pub fn main() {
+            let n = 0;
+
+            for value in [(1, 2), (2, 3), (4, 5)] {
+                if let (1, x) | (x, 3) = value {
+                    n += x;
+                }
+            }
+
+            n
+        }
+    );
+    assert_eq!(out, 4);
+}
+
+#[test]
+fn test_range_patterns() {
+    let out: Vec<i64> = rune!(
+        fn classify(c) {
+            match c {
+                'a'..='z' => 1,
+                'A'..='Z' => 2,
+                b'0'..b'9' => 3,
+                -10..0 => 4,
+                0..=9 => 5,
+                100.. => 6,
+                _ => 0,
+            }
+        }
+
+        pub fn main() {
+            [
+                classify('q'),
+                classify('Q'),
+                classify(b'8'),
+                classify(b'9'),
+                classify(-10),
+                classify(0),
+                classify(9),
+                classify(200),
+                classify(50),
+            ]
+        }
+    );
+    assert_eq!(out, vec![1, 2, 3, 0, 4, 5, 5, 6, 0]);
+}
+
+#[test]
+fn test_at_patterns() {
+    let out: Vec<i64> = rune!(
+        fn digit(n) {
+            match n {
+                d @ 0..=9 => d,
+                n @ (10 | 20) => n / 10,
+                [first @ 1..=5, ..] => first * 100,
+                _ => -1,
+            }
+        }
+
+        pub fn main() {
+            [digit(7), digit(20), digit([3, 4]), digit([6]), digit(42)]
+        }
+    );
+    assert_eq!(out, vec![7, 2, 300, -1, -1]);
+}
@@ -0,0 +1,112 @@
+#![cfg(unix)]
+
+fn run<T>(source: &str) -> T
+where
+    T: rune::FromValue,
+{
+    let context = rune_tests::modules::default_context().unwrap();
+    rune_tests::run_async(&context, source, &["main"], ()).unwrap()
+}
+
+#[test]
+fn test_process_pipes() {
+    let out: (Vec<String>, String, bool, Option<i64>) = run(r#"
+        use process::{Command, Stdio};
+
+        pub async fn main() {
+            let command = Command::new("cat");
+            command.stdin(Stdio::piped());
+            command.stdout(Stdio::piped());
+            let child = command.spawn()?;
+
+            let stdin = child.stdin().unwrap();
+            stdin.write("first\nsecond\n").await?;
+            stdin.close().await?;
+
+            let stdout = child.stdout().unwrap();
+            let lines = [];
+
+            while let Some(line) = stdout.next().await? {
+                lines.push(line);
+            }
+
+            let status = child.wait().await?;
+            (lines, `${status}`, status.success(), status.code())
+        }
+    "#);
+
+    assert_eq!(
+        out,
+        (
+            vec![String::from("first"), String::from("second")],
+            String::from("exit status: 0"),
+            true,
+            Some(0)
+        )
+    );
+}
+
+#[test]
+fn test_process_env_and_cwd() {
+    let out: (String, Option<i64>, bool, bool) = run(r#"
+        use process::{Command, Stdio};
+
+        pub async fn main() {
+            let command = Command::new("sh");
+            command.args(["-c", "echo \"$GREETING $(pwd)\"; exit 3"]);
+            command.env_clear();
+            command.env("GREETING", "hello");
+            command.current_dir(path::Path::new("/"));
+
+            command.stdout(Stdio::piped());
+
+            let child = command.spawn()?;
+            let stdout = child.stdout().unwrap().read_to_string().await?;
+            let code = child.wait().await?.code();
+
+            let output = Command::new("true").output().await?;
+            let status = Command::new("false").status().await?;
+
+            (
+                stdout,
+                code,
+                output.status.success() && output.stdout.len() == 0 && !status.success(),
+                process::id() > 0,
+            )
+        }
+    "#);
+
+    assert_eq!(out, (String::from("hello /\n"), Some(3), true, true));
+}
+
+#[test]
+fn test_process_kill() {
+    let out: (bool, bool) = run(r#"
+        use process::Command;
+
+        pub async fn main() {
+            let command = Command::new("sleep");
+            command.arg("10");
+            let child = command.spawn()?;
+            let running = child.try_wait()?.is_none();
+            child.kill().await?;
+            (running, child.wait().await?.success())
+        }
+    "#);
+
+    assert_eq!(out, (true, false));
+}
+
+#[test]
+fn test_process_exit_requires_stdio() {
+    let context = rune_modules::with_config(false).unwrap();
+
+    let result = rune_tests::run::<_, _, ()>(
+        &context,
+        "pub fn main() { process::exit(1) }",
+        &["main"],
+        (),
+    );
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,121 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_case_and_search() {
+    let out: (String, String, bool, bool, Option<usize>, Option<usize>) = rune! {
+        pub fn main() {
+            let s = "Hello World";
+            (
+                s.to_uppercase(),
+                s.to_lowercase(),
+                s.contains("World"),
+                s.contains('x'),
+                s.find('o'),
+                s.rfind("o"),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            String::from("HELLO WORLD"),
+            String::from("hello world"),
+            true,
+            false,
+            Some(4),
+            Some(7)
+        )
+    );
+}
+
+#[test]
+fn test_strip_and_trim() {
+    let out: (Option<String>, Option<String>, String, String) = rune! {
+        pub fn main() {
+            let s = "  v1.2.3  ";
+            let trimmed = s.trim();
+            (
+                trimmed.strip_prefix('v'),
+                trimmed.strip_suffix(".4"),
+                s.trim_start(),
+                "ab".repeat(3),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            Some(String::from("1.2.3")),
+            None,
+            String::from("v1.2.3  "),
+            String::from("ababab")
+        )
+    );
+}
+
+#[test]
+fn test_splitting() {
+    let out: (Vec<String>, Vec<String>, Vec<String>, Vec<String>) = rune! {
+        pub fn main() {
+            (
+                "a\nb\r\nc".lines().collect::<Vec>(),
+                "  a  b c ".split_whitespace().collect::<Vec>(),
+                "k=v=w".splitn(2, '=').collect::<Vec>(),
+                "a.b.c".rsplit(".").collect::<Vec>(),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![String::from("k"), String::from("v=w")],
+            vec![String::from("c"), String::from("b"), String::from("a")],
+        )
+    );
+}
+
+#[test]
+fn test_char_indices_and_bytes() {
+    let out: (Vec<(usize, char)>, Vec<u8>) = rune! {
+        pub fn main() {
+            ("aé".char_indices().collect::<Vec>(), "ab".bytes().collect::<Vec>())
+        }
+    };
+    assert_eq!(out, (vec![(0, 'a'), (1, 'é')], vec![b'a', b'b']));
+}
+
+#[test]
+fn test_parse_float() {
+    let out: (f64, bool) = rune! {
+        pub fn main() {
+            (std::string::parse_float("1.5")?, std::string::parse_float("x").is_err())
+        }
+    };
+    assert_eq!(out, (1.5, true));
+}
+
+#[test]
+fn test_range_index() {
+    let out: (String, String, Option<String>) = rune! {
+        pub fn main() {
+            let s = "héllo";
+            (s[3..6], s[..=2], s.get(1..2))
+        }
+    };
+    assert_eq!(out, (String::from("llo"), String::from("hé"), None));
+
+    assert_vm_error!(
+        r#"pub fn main() { "héllo"[1..2] }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "index outside of character boundary");
+        }
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { "hello"[1..10] }"#,
+        OutOfRange { .. } => {}
+    );
+}
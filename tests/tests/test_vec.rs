@@ -0,0 +1,117 @@
+use rune::runtime::VmErrorKind::*;
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+
+#[derive(Debug, Any, PartialEq)]
+#[rune(eq)]
+struct Id(u64);
+
+fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new();
+    module.ty::<Id>()?;
+    module.function(&["Id", "new"], Id)?;
+    Ok(module)
+}
+
+#[test]
+fn test_contains_and_dedup() -> Result<(), ContextError> {
+    let out: (bool, bool, Vec<i64>) = rune! {
+        struct Point { x, y }
+
+        pub fn main() {
+            let points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+            let values = [1, 1, 2, 3, 3, 3, 1];
+            values.dedup();
+            (points.contains(Point { x: 3, y: 4 }), points.contains(Point { x: 4, y: 3 }), values)
+        }
+    };
+    assert_eq!(out, (true, false, vec![1, 2, 3, 1]));
+
+    let out: (bool, bool) = rune_n! {
+        module()?,
+        (),
+        (bool, bool) =>
+        pub fn main() {
+            let ids = [Id::new(1), Id::new(2)];
+            (ids.contains(Id::new(2)), ids.contains(Id::new(3)))
+        }
+    };
+    assert_eq!(out, (true, false));
+    Ok(())
+}
+
+#[test]
+fn test_mutation() {
+    let out: (Vec<i64>, Vec<i64>, Option<i64>, Option<i64>) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4, 5, 6];
+            values.retain(|v| v % 2 == 0);
+            values.reverse();
+            values.swap(0, 2);
+            let rest = values.split_off(1);
+            values.truncate(0);
+            (values, rest, rest.first(), rest.last())
+        }
+    };
+    assert_eq!(out, (vec![], vec![4, 6], Some(4), Some(6)));
+}
+
+#[test]
+fn test_windows_and_chunks() {
+    let out: (Vec<Vec<i64>>, Vec<Vec<i64>>) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4, 5];
+            (values.windows(4).collect::<Vec>(), values.chunks(2).collect::<Vec>())
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            vec![vec![1, 2, 3, 4], vec![2, 3, 4, 5]],
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        )
+    );
+}
+
+#[test]
+fn test_binary_search() {
+    let out: (Result<usize, usize>, Result<usize, usize>) = rune! {
+        pub fn main() {
+            let values = ["a", "c", "e"];
+            (values.binary_search("c"), values.binary_search("d"))
+        }
+    };
+    assert_eq!(out, (Ok(1), Err(2)));
+}
+
+#[test]
+fn test_join() {
+    let out: String = rune! {
+        pub fn main() {
+            let b = String::from_str("b");
+            ["a", b, "c"].join(", ")
+        }
+    };
+    assert_eq!(out, "a, b, c");
+
+    assert_vm_error!(
+        r#"pub fn main() { ["a", 1].join(",") }"#,
+        Expected { .. } => {}
+    );
+}
+
+#[test]
+fn test_range_index() {
+    let out: (Vec<i64>, Vec<i64>, Vec<i64>, Vec<i64>) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4, 5];
+            (values[1..3], values[..2], values[3..], values[1..=1])
+        }
+    };
+    assert_eq!(out, (vec![2, 3], vec![1, 2], vec![4, 5], vec![2]));
+
+    assert_vm_error!(
+        r#"pub fn main() { [1, 2, 3][1..4] }"#,
+        OutOfRange { .. } => {}
+    );
+}
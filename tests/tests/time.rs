@@ -0,0 +1,162 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+fn run<T>(source: &str) -> T
+where
+    T: rune::FromValue,
+{
+    let context = rune_tests::modules::default_context().unwrap();
+    rune_tests::run_async(&context, source, &["main"], ()).unwrap()
+}
+
+#[test]
+fn test_duration() {
+    let out: (i64, i64, f64, bool, bool) = run(r#"
+        use time::Duration;
+
+        pub fn main() {
+            let d = Duration::from_secs(1) + Duration::from_millis(500);
+            d += Duration::from_millis(250);
+            let doubled = d * 2;
+
+            (
+                d.as_millis(),
+                (doubled / 4).as_millis(),
+                Duration::from_secs_f64(0.25).as_secs_f64(),
+                Duration::from_millis(1500) < Duration::from_secs(2),
+                Duration::from_millis(1000) == Duration::from_secs(1),
+            )
+        }
+    "#);
+
+    assert_eq!(out, (1750, 875, 0.25, true, true));
+}
+
+#[test]
+fn test_duration_errors() {
+    assert_vm_error!(
+        r#"pub fn main() { time::Duration::from_secs(1) - time::Duration::from_secs(2) }"#,
+        Underflow => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { time::Duration::from_secs(1) / 0 }"#,
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { time::Duration::from_secs_f64(-1.0) }"#,
+        Underflow => {}
+    );
+}
+
+#[test]
+fn test_instant_and_system_time() {
+    let out: (bool, bool, bool, bool) = run(r#"
+        use time::{Duration, Instant, SystemTime};
+
+        pub async fn main() {
+            let start = Instant::now();
+            time::sleep(Duration::from_millis(10)).await;
+            let elapsed = start.elapsed();
+            let later = start + Duration::from_secs(1);
+
+            let since_epoch = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH())?;
+            let before_epoch = SystemTime::UNIX_EPOCH().duration_since(SystemTime::now());
+
+            (
+                elapsed >= Duration::from_millis(10),
+                later.duration_since(start) == Duration::from_secs(1),
+                since_epoch.as_secs() > 1600000000,
+                before_epoch.is_err(),
+            )
+        }
+    "#);
+
+    assert_eq!(out, (true, true, true, true));
+}
+
+#[test]
+fn test_timeout() {
+    let out: (i64, String) = run(r#"
+        use time::Duration;
+
+        async fn value(d) {
+            time::sleep(d).await;
+            42
+        }
+
+        pub async fn main() {
+            let fast = time::timeout(Duration::from_secs(10), value(Duration::from_millis(1))).await?;
+
+            let slow = match time::timeout(Duration::from_millis(1), value(Duration::from_secs(10))).await {
+                Ok(..) => "completed",
+                Err(error) => `${error}`,
+            };
+
+            (fast, slow)
+        }
+    "#);
+
+    assert_eq!(out, (42, String::from("deadline has elapsed")));
+}
+
+#[test]
+fn test_interval() {
+    let out: (i64, bool) = run(r#"
+        use time::{Duration, Instant};
+
+        pub async fn main() {
+            let start = Instant::now();
+            let interval = time::interval(Duration::from_millis(5));
+            let ticks = 0;
+
+            while let Some(instant) = interval.next().await {
+                ticks += 1;
+
+                if ticks == 3 {
+                    break;
+                }
+            }
+
+            (ticks, start.elapsed() >= Duration::from_millis(10))
+        }
+    "#);
+
+    assert_eq!(out, (3, true));
+}
+
+#[test]
+fn test_interval_missed_ticks() {
+    let out: bool = run(r#"
+        use time::{Duration, Instant};
+
+        pub async fn main() {
+            let interval = time::interval(Duration::from_millis(10));
+            time::sleep(Duration::from_millis(50)).await;
+
+            let start = Instant::now();
+            interval.tick().await;
+            interval.tick().await;
+            interval.tick().await;
+            start.elapsed() >= Duration::from_millis(20)
+        }
+    "#);
+
+    assert!(out, "missed ticks should be delayed rather than burst");
+}
+
+#[test]
+fn test_interval_without_runtime() {
+    let out: i64 = rune_s! { r#"
+        use time::Duration;
+
+        pub fn main() {
+            let interval = time::interval(Duration::from_secs(1));
+            interval.reset();
+            interval.period().as_secs()
+        }
+    "# };
+
+    assert_eq!(out, 1);
+}
@@ -0,0 +1,322 @@
+use rune::compile::CompileErrorKind::*;
+use rune::compile::ItemBuf;
+use rune::runtime::Value;
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+
+#[test]
+fn test_default_methods() {
+    let out: (i64, i64) = rune! {
+        trait Shape {
+            fn area(self);
+
+            fn double_area(self) {
+                self.area() * 2
+            }
+        }
+
+        struct Square { side }
+        struct Rect { w, h }
+
+        impl Shape for Square {
+            fn area(self) {
+                self.side * self.side
+            }
+        }
+
+        impl Shape for Rect {
+            fn area(self) {
+                self.w * self.h
+            }
+
+            fn double_area(self) {
+                0
+            }
+        }
+
+        pub fn main() {
+            (Square { side: 3 }.double_area(), Rect { w: 2, h: 4 }.double_area())
+        }
+    };
+
+    assert_eq!(out, (18, 0));
+}
+
+#[test]
+fn test_dynamic_dispatch() {
+    let out: Vec<String> = rune! {
+        trait Greet {
+            fn name(self);
+
+            fn greet(self) {
+                "Hello, " + self.name()
+            }
+        }
+
+        struct Person { name }
+        struct Robot;
+
+        impl Greet for Person {
+            fn name(self) {
+                self.name
+            }
+        }
+
+        impl Greet for Robot {
+            fn name(self) {
+                "Robot"
+            }
+        }
+
+        pub fn main() {
+            let out = [];
+
+            for value in [Person { name: "Ada" }, Robot] {
+                out.push(value.greet());
+            }
+
+            out
+        }
+    };
+
+    assert_eq!(out, vec!["Hello, Ada", "Hello, Robot"]);
+}
+
+#[test]
+fn test_trait_in_module() {
+    let out: i64 = rune! {
+        mod shapes {
+            pub trait Shape {
+                fn area(self);
+                fn new();
+
+                fn half(self) {
+                    self.area() / 2
+                }
+            }
+        }
+
+        struct Square { side }
+
+        impl shapes::Shape for Square {
+            fn area(self) {
+                self.side * self.side
+            }
+
+            fn new() {
+                Square { side: 4 }
+            }
+        }
+
+        pub fn main() {
+            Square::new().half()
+        }
+    };
+
+    assert_eq!(out, 8);
+}
+
+#[test]
+fn test_trait_errors() {
+    assert_compile_error! {
+        r#"trait Shape { fn area(self); } struct Square; impl Shape for Square {}"#,
+        span, MissingTraitFunction { name, .. } => {
+            assert_eq!(&*name, "area");
+            assert_eq!(span, rune::span!(51, 56));
+        }
+    };
+
+    assert_compile_error! {
+        r#"trait Shape {} struct Square; impl Shape for Square { fn area(self) {} }"#,
+        span, NotTraitFunction { name, .. } => {
+            assert_eq!(&*name, "area");
+            assert_eq!(span, rune::span!(57, 61));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Shape; struct Square; impl Shape for Square {}"#,
+        span, ExpectedMeta { expected: "trait", .. } => {
+            assert_eq!(span, rune::span!(34, 39));
+        }
+    };
+
+    assert_compile_error! {
+        r#"trait Shape { fn new() {} }"#,
+        span, Custom { .. } => {
+            assert_eq!(span, rune::span!(14, 22));
+        }
+    };
+}
+
+#[derive(Any)]
+struct Request;
+
+impl Request {
+    fn handle(&self) -> i64 {
+        42
+    }
+}
+
+fn handler_module(with_handle: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("http");
+    module.trait_(&["Handler"], ["handle"])?;
+    module.ty::<Request>()?;
+    module.function(&["Request", "new"], || Request)?;
+
+    if with_handle {
+        module.inst_fn("handle", Request::handle)?;
+    }
+
+    module.impl_trait::<Request>(ItemBuf::with_crate_item("http", &["Handler"]))?;
+    Ok(module)
+}
+
+#[test]
+fn test_native_trait() -> rune::Result<()> {
+    let out: i64 = rune_n! {
+        handler_module(true)?,
+        (),
+        i64 =>
+        struct Script;
+
+        impl http::Handler for Script {
+            fn handle(self) {
+                1
+            }
+        }
+
+        pub fn main() {
+            http::Request::new().handle() + Script.handle()
+        }
+    };
+
+    assert_eq!(out, 43);
+
+    let mut context = rune::Context::new();
+    let error = context.install(&handler_module(false)?).unwrap_err();
+
+    assert!(matches!(
+        error,
+        ContextError::MissingTraitFunction { name, .. } if &*name == "handle"
+    ));
+
+    assert_compile_error! {
+        r#"struct Script; impl ::std::string::String for Script {}"#,
+        span, ExpectedMeta { expected: "trait", .. } => {
+            assert_eq!(span, rune::span!(20, 41));
+        }
+    };
+
+    Ok(())
+}
+
+#[test]
+fn test_default_self_type() {
+    let out: (i64, i64) = rune! {
+        trait Zero {
+            fn zero();
+
+            fn is_zero(self) {
+                self.value() == Self::zero().value()
+            }
+
+            fn value(self);
+        }
+
+        struct A { v }
+        struct B { v }
+
+        impl Zero for A {
+            fn zero() {
+                A { v: 1 }
+            }
+
+            fn value(self) {
+                self.v
+            }
+        }
+
+        impl Zero for B {
+            fn zero() {
+                B { v: 2 }
+            }
+
+            fn value(self) {
+                self.v
+            }
+        }
+
+        pub fn main() {
+            let a = if (A { v: 1 }).is_zero() { 1 } else { 0 };
+            let b = if (B { v: 1 }).is_zero() { 1 } else { 0 };
+            (a, b)
+        }
+    };
+
+    assert_eq!(out, (1, 0));
+
+    assert_compile_error! {
+        r#"trait Make { fn make(self) { || Self::new() } } struct A; impl Make for A {} pub fn main() { A.make() }"#,
+        span, UnsupportedSelfType => {
+            assert_eq!(span, rune::span!(32, 36));
+        }
+    };
+}
+
+fn greeter_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("http");
+    module.trait_(&["Greeter"], ["greet"])?;
+    module.trait_default(&["Greeter"], "greet", |_: Value| String::from("hello"))?;
+    module.ty::<Request>()?;
+    module.function(&["Request", "new"], || Request)?;
+    module.impl_trait::<Request>(ItemBuf::with_crate_item("http", &["Greeter"]))?;
+    Ok(module)
+}
+
+#[test]
+fn test_native_trait_default() -> rune::Result<()> {
+    let out: (String, String, String) = rune_n! {
+        greeter_module()?,
+        (),
+        (String, String, String) =>
+        struct Quiet;
+        struct Loud;
+
+        impl http::Greeter for Quiet {}
+
+        impl http::Greeter for Loud {
+            fn greet(self) {
+                "HELLO"
+            }
+        }
+
+        pub fn main() {
+            (http::Request::new().greet(), Quiet.greet(), Loud.greet())
+        }
+    };
+
+    assert_eq!(
+        out,
+        (
+            String::from("hello"),
+            String::from("hello"),
+            String::from("HELLO")
+        )
+    );
+
+    let mut module = Module::with_crate("http");
+    module.trait_(&["Greeter"], ["greet"])?;
+
+    assert!(matches!(
+        module.trait_default(&["Greeter"], "wave", |_: Value| ()),
+        Err(ContextError::NotTraitFunction { name, .. }) if &*name == "wave"
+    ));
+
+    assert!(matches!(
+        module.trait_default(&["Waver"], "wave", |_: Value| ()),
+        Err(ContextError::MissingTrait { .. })
+    ));
+
+    Ok(())
+}
@@ -87,3 +87,18 @@ fn test_enum() {
     };
     assert_eq!(out, vec!["E".to_owned(), "E".to_owned(), "E".to_owned()]);
 }
+
+#[test]
+fn test_type_id_of_val() {
+    let out: (bool, bool) = rune_s! { r#"
+        use std::any::TypeId;
+
+        pub fn main() {
+            let a = `${TypeId::of_val(1)}`;
+            let b = `${TypeId::of_val(2)}`;
+            let c = `${TypeId::of_val("s")}`;
+            (a == b, a == c)
+        }
+    "# };
+    assert_eq!(out, (true, false));
+}
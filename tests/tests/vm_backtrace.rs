@@ -0,0 +1,85 @@
+use rune::runtime::{BacktraceFrame, VmError};
+use rune::termcolor::NoColor;
+use rune::{Context, Source, Sources, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn check(n) {
+    10 / (n - 2)
+}
+
+pub fn callback() {
+    [1, 2, 3].iter().map(|n| check(n)).collect::<Vec>()
+}
+
+async fn inner() {
+    check(2)
+}
+
+pub async fn awaited() {
+    inner().await
+}
+"#;
+
+fn vm() -> rune::Result<(Vm, Sources)> {
+    let context = Context::with_default_modules()?;
+    let mut sources = Sources::new();
+    sources.insert(Source::new("foo.rn", SOURCE));
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    let vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    Ok((vm, sources))
+}
+
+/// Get the zero-based line of every frame in the backtrace, where native
+/// frames are `None`.
+fn lines(error: &VmError, sources: &Sources) -> Vec<Option<usize>> {
+    error
+        .backtrace()
+        .iter()
+        .map(|frame| {
+            let inst = frame.debug_inst()?;
+            let (line, _, _) = sources.get(inst.source_id)?.line(inst.span)?;
+            Some(line)
+        })
+        .collect()
+}
+
+fn emit(error: &VmError, sources: &Sources) -> rune::Result<String> {
+    let mut out = NoColor::new(Vec::new());
+    error.emit(&mut out, sources)?;
+    Ok(String::from_utf8(out.into_inner())?)
+}
+
+#[test]
+fn test_backtrace_through_native_callback() -> rune::Result<()> {
+    let (mut vm, sources) = vm()?;
+    let error = vm.call(&["callback"], ()).unwrap_err();
+
+    assert_eq!(lines(&error, &sources), [Some(2), Some(6), None, Some(6)]);
+    assert!(matches!(error.backtrace()[2], BacktraceFrame::Native));
+
+    let out = emit(&error, &sources)?;
+    assert!(out.contains("division by zero"), "{}", out);
+
+    let expected = "\
+backtrace:
+foo.rn:3:5:     10 / (n - 2)
+foo.rn:7:30:     [1, 2, 3].iter().map(|n| check(n)).collect::<Vec>()
+<native>
+foo.rn:7:5:     [1, 2, 3].iter().map(|n| check(n)).collect::<Vec>()
+";
+
+    assert!(out.ends_with(expected), "{}", out);
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_through_awaited_future() -> rune::Result<()> {
+    let (mut vm, sources) = vm()?;
+
+    let error =
+        futures_executor::block_on(async { vm.async_call(&["awaited"], ()).await.unwrap_err() });
+
+    assert_eq!(lines(&error, &sources), [Some(2), Some(10), None, Some(14)]);
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use rune::runtime::VmErrorKind::*;
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Any, Clone, PartialEq)]
+#[rune(clone)]
+struct Handle(#[rune(get)] u32);
+
+#[derive(Debug, Any)]
+struct Resource;
+
+impl Drop for Resource {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new();
+    module.ty::<Handle>()?;
+    module.ty::<Resource>()?;
+    module.function(&["Handle", "new"], Handle)?;
+    module.function(&["Resource", "new"], || Resource)?;
+    module.function(&["dropped"], || DROPPED.load(Ordering::SeqCst))?;
+    Ok(module)
+}
+
+#[test]
+fn test_deep_clone_script_struct() {
+    let out: (usize, usize, usize) = rune! {
+        struct Inventory { items, tags }
+
+        pub fn main() {
+            let a = Inventory { items: [1, 2], tags: #{ first: [1] } };
+            let b = a.clone();
+            b.items.push(3);
+            b.tags.first.push(2);
+            (a.items.len(), b.items.len(), a.tags.first.len())
+        }
+    };
+    assert_eq!(out, (2, 3, 1));
+}
+
+#[test]
+fn test_std_clone() {
+    let out: (usize, i64) = rune! {
+        struct Counter { n }
+
+        impl Counter {
+            fn clone(self) {
+                Counter { n: self.n + 100 }
+            }
+        }
+
+        pub fn main() {
+            let a = #{ values: [1] };
+            let b = std::clone::clone(a);
+            b.values.push(2);
+
+            let counters = std::clone::clone((Counter { n: 1 },));
+            (a.values.len(), counters.0.n)
+        }
+    };
+    assert_eq!(out, (1, 101));
+}
+
+#[test]
+fn test_native_clone() -> Result<(), ContextError> {
+    let out: (u32, u32) = rune_n! {
+        module()?,
+        (),
+        (u32, u32) =>
+        pub fn main() {
+            let a = [Handle::new(1)];
+            let b = a.clone();
+            let c = std::clone::clone(a);
+            (b[0].0, c[0].0)
+        }
+    };
+    assert_eq!(out, (1, 1));
+
+    let out: (u32, u32) = rune_n! {
+        module()?,
+        (),
+        (u32, u32) =>
+        pub fn main() {
+            let a = Handle::new(1);
+            let b = a.clone();
+            (a.0, b.0)
+        }
+    };
+    assert_eq!(out, (1, 1));
+    Ok(())
+}
+
+#[test]
+fn test_clone_nested_collections() {
+    let out: (usize, usize, usize, usize) = rune! {
+        use std::collections::{HashMap, VecDeque};
+
+        pub fn main() {
+            let a = [[1]];
+            let b = a.clone();
+            b[0].push(2);
+
+            let map = HashMap::new();
+            map.insert("values", [1]);
+            let copy = map.clone();
+            copy["values"].push(2);
+
+            let deque = VecDeque::new();
+            deque.push_back([1]);
+            let other = std::clone::clone(deque);
+            other[0].push(2);
+
+            (a[0].len(), b[0].len(), map["values"].len(), deque[0].len())
+        }
+    };
+    assert_eq!(out, (1, 2, 1, 1));
+}
+
+/// Assigning a collection aliases it, while cloning it produces an independent
+/// copy. All collections behave the same way.
+#[test]
+fn test_clone_aliasing() {
+    let out: Vec<(usize, usize, usize)> = rune! {
+        use std::collections::{BinaryHeap, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+        fn check(a, add) {
+            let alias = a;
+            let copy = a.clone();
+            add(alias, 10);
+            (a.len(), alias.len(), copy.len())
+        }
+
+        pub fn main() {
+            [
+                check([1], |c, v| c.push(v)),
+                check(#{ "a": 1 }, |c, v| c.insert("b", v)),
+                check(HashMap::from([(1, 1)]), |c, v| c.insert(v, v)),
+                check(HashSet::from([1]), |c, v| c.insert(v)),
+                check(VecDeque::from([1]), |c, v| c.push_back(v)),
+                check(BTreeMap::from([(1, 1)]), |c, v| c.insert(v, v)),
+                check(BTreeSet::from([1]), |c, v| c.insert(v)),
+                check(BinaryHeap::from([1]), |c, v| c.push(v)),
+            ]
+        }
+    };
+    assert_eq!(out, vec![(2, 2, 1); 8]);
+}
+
+#[test]
+fn test_clone_cycle() {
+    let out: (bool, bool) = rune! {
+        pub fn main() {
+            let a = #{};
+            a.me = a;
+            let b = std::clone::clone(a);
+            b.value = 1;
+            (b.me.value == 1, a.get("value").is_none())
+        }
+    };
+    assert_eq!(out, (true, true));
+
+    assert_vm_error!(
+        r#"
+        struct Node { next }
+
+        impl Node {
+            fn clone(self) {
+                Node { next: std::clone::clone(self.next) }
+            }
+        }
+
+        pub fn main() {
+            let node = Node { next: None };
+            node.next = Some(node);
+            std::clone::clone(node)
+        }
+        "#,
+        CloneCycle => {}
+    );
+}
+
+#[test]
+fn test_clone_too_deep() {
+    assert_vm_error!(
+        r#"
+        pub fn main() {
+            let value = [];
+
+            for _ in 0..1000 {
+                value = [value];
+            }
+
+            value.clone()
+        }
+        "#,
+        CloneDepthExceeded { .. } => {}
+    );
+}
+
+#[test]
+fn test_clone_not_supported() {
+    assert_vm_error!(
+        r#"pub fn main() { let f = async { 1 }; f.clone() }"#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
+
+#[test]
+fn test_drop_protocol() -> Result<(), ContextError> {
+    let out: Vec<String> = rune! {
+        struct Connection { log }
+
+        impl Connection {
+            fn drop(self) {
+                self.log.push("closed");
+            }
+        }
+
+        pub fn main() {
+            let log = [];
+            let connection = Connection { log };
+            std::mem::drop(connection);
+            log
+        }
+    };
+    assert_eq!(out, vec![String::from("closed")]);
+
+    let (before, after): (usize, usize) = rune_n! {
+        module()?,
+        (),
+        (usize, usize) =>
+        pub fn main() {
+            let resource = Resource::new();
+            let other = resource;
+            let before = dropped();
+            std::mem::drop(resource);
+            (before, dropped())
+        }
+    };
+
+    assert_eq!(after, before + 1);
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use rune::runtime::VmErrorKind::*;
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+
+#[derive(Debug, Any, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[rune(cmp, partial_cmp)]
+struct Version(u32, u32);
+
+fn version_module() -> Result<Module, ContextError> {
+    let mut module = Module::new();
+    module.ty::<Version>()?;
+    module.function(&["Version", "new"], Version)?;
+    Ok(module)
+}
+
+#[test]
+fn test_builtin_comparisons() {
+    let out: (bool, bool, bool, bool, bool) = rune! {
+        pub fn main() {
+            ("a" < "b", [1, 2] < [1, 3], (1, "b") > (1, "a"), Some(1) > None, 1.0 < 0.0 / 0.0)
+        }
+    };
+    assert_eq!(out, (true, true, true, true, false));
+}
+
+#[test]
+fn test_script_struct_cmp() {
+    let out: (bool, bool, Vec<i64>) = rune! {
+        struct Age { years }
+
+        impl Age {
+            fn cmp(self, other) {
+                self.years.cmp(other.years)
+            }
+        }
+
+        pub fn main() {
+            let ages = [Age { years: 3 }, Age { years: 1 }, Age { years: 2 }];
+            ages.sort();
+            let years = ages.iter().map(|a| a.years).collect::<Vec>();
+            (Age { years: 1 } < Age { years: 2 }, Age { years: 1 } >= Age { years: 2 }, years)
+        }
+    };
+    assert_eq!(out, (true, false, vec![1, 2, 3]));
+}
+
+#[test]
+fn test_native_derive_cmp() -> Result<(), ContextError> {
+    let out: (bool, Vec<Version>, Version) = rune_n! {
+        version_module()?,
+        (),
+        (bool, Vec<Version>, Version) =>
+        pub fn main() {
+            let versions = [Version::new(1, 2), Version::new(0, 9), Version::new(1, 0)];
+            versions.sort();
+            let max = [Version::new(0, 1), Version::new(1, 2)].iter().max().unwrap();
+            (Version::new(1, 0) < Version::new(1, 2), versions, max)
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            true,
+            vec![Version(0, 9), Version(1, 0), Version(1, 2)],
+            Version(1, 2)
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sort() {
+    let out: (Vec<String>, Vec<f64>, Vec<(i64, String)>) = rune! {
+        pub fn main() {
+            let strings = ["c", "a", "b"];
+            strings.sort();
+            let floats = [2.5, -1.0, 0.5];
+            floats.sort();
+            let tuples = [(2, "a"), (1, "b"), (1, "a")];
+            tuples.sort();
+            (strings, floats, tuples)
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![-1.0, 0.5, 2.5],
+            vec![
+                (1, String::from("a")),
+                (1, String::from("b")),
+                (2, String::from("a"))
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_min_max() {
+    let out: (String, String, f64, Option<i64>, Option<String>) = rune! {
+        pub fn main() {
+            let words = ["pear", "fig", "banana"];
+            (
+                std::cmp::min("b", "a"),
+                std::cmp::max("b", "a"),
+                std::cmp::max(1.5, -2.0),
+                [3, 1, 2].iter().min(),
+                words.iter().max_by_key(|w| w.len()),
+            )
+        }
+    };
+    assert_eq!(
+        out,
+        (
+            String::from("a"),
+            String::from("b"),
+            1.5,
+            Some(1),
+            Some(String::from("banana"))
+        )
+    );
+}
+
+#[test]
+fn test_ordering_variants() {
+    let out: (bool, i64) = rune! {
+        use std::cmp::Ordering;
+
+        pub fn main() {
+            let n = match 1.cmp(2) {
+                Ordering::Less => 1,
+                Ordering::Equal => 2,
+                Ordering::Greater => 3,
+            };
+
+            (Ordering::Less == Ordering::Greater.reverse(), n)
+        }
+    };
+    assert_eq!(out, (true, 1));
+}
+
+#[test]
+fn test_incomparable() {
+    assert_vm_error!(
+        r#"pub fn main() { 1 < "a" }"#,
+        UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(op, "<");
+        }
+    );
+}
+
+#[test]
+fn test_sort_stable() {
+    let out: Vec<(i64, String)> = rune! {
+        pub fn main() {
+            let values = [(2, "a"), (1, "b"), (2, "c"), (1, "d"), (0, "e")];
+            values.sort_by(|a, b| a.0.cmp(b.0));
+            values
+        }
+    };
+    assert_eq!(
+        out,
+        vec![
+            (0, String::from("e")),
+            (1, String::from("b")),
+            (1, String::from("d")),
+            (2, String::from("a")),
+            (2, String::from("c"))
+        ]
+    );
+}
+
+#[test]
+fn test_sort_incomparable() {
+    assert_vm_error!(
+        r#"pub fn main() { let values = [3, 1, "a", 2]; values.sort(); }"#,
+        UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(op, "cmp");
+        }
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { let values = [3, 1, "a", 2]; values.sort_by(|a, b| a.cmp(b)); }"#,
+        UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(op, "cmp");
+        }
+    );
+}
@@ -0,0 +1,139 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+use rust_decimal::Decimal;
+
+#[test]
+fn test_decimal_arithmetic() {
+    let out: (String, bool, String, bool) = rune_s! {r#"
+        use std::decimal::Decimal;
+
+        pub fn main() {
+            let a = Decimal::parse("0.1")?;
+            let b = Decimal::parse("0.2")?;
+            let total = Decimal::new(1999, 2)? * 3;
+            total += 1;
+            total -= Decimal::parse("0.5")?;
+            (`${a + b}`, a + b == Decimal::parse("0.30")?, `${total / 4}`, total > a)
+        }
+    "#};
+
+    assert_eq!(
+        out,
+        (String::from("0.3"), true, String::from("15.1175"), true)
+    );
+}
+
+#[test]
+fn test_decimal_integer_lhs() {
+    let out: (String, String, bool, bool) = rune_s! {r#"
+        use std::decimal::Decimal;
+
+        pub fn main() {
+            let d = Decimal::parse("1.5")?;
+            (`${2 * d}`, `${3 - d}`, 1 < d && d > 1, 2 == Decimal::parse("2.00")?)
+        }
+    "#};
+
+    assert_eq!(out, (String::from("3.0"), String::from("1.5"), true, true));
+}
+
+#[test]
+fn test_decimal_rounding_and_scale() {
+    let out: (String, String, String, String, u32) = rune_s! {r#"
+        use std::decimal::{Decimal, RoundingStrategy};
+
+        pub fn main() {
+            let n = Decimal::parse("2.675")?;
+            (
+                `${n.round_dp(2)}`,
+                `${n.round_dp_with_strategy(2, RoundingStrategy::ToZero)}`,
+                `${n.rescale(5)}`,
+                `${Decimal::parse("1e-3")?}`,
+                n.scale(),
+            )
+        }
+    "#};
+
+    assert_eq!(
+        out,
+        (
+            String::from("2.68"),
+            String::from("2.67"),
+            String::from("2.67500"),
+            String::from("0.001"),
+            3
+        )
+    );
+}
+
+#[test]
+fn test_decimal_host_conversions() {
+    let context = rune_tests::modules::default_context().unwrap();
+
+    let out: Decimal = rune_tests::run(
+        &context,
+        "pub fn main(price, quantity) { price * quantity }",
+        &["main"],
+        (Decimal::new(1999, 2), 3i64),
+    )
+    .unwrap();
+
+    assert_eq!(out, Decimal::new(5997, 2));
+}
+
+#[test]
+fn test_decimal_serialize() {
+    let context = rune_tests::modules::default_context().unwrap();
+
+    let out: String = rune_tests::run(
+        &context,
+        r#"
+        use std::decimal::Decimal;
+
+        pub fn main() {
+            json::to_string(#{ "total": Decimal::parse("12345678901234567890.123")? })?
+        }
+        "#,
+        &["main"],
+        (),
+    )
+    .unwrap();
+
+    assert_eq!(out, r#"{"total":{"$decimal":"12345678901234567890.123"}}"#);
+}
+
+#[test]
+fn test_decimal_deserialize() {
+    let context = rune_tests::modules::default_context().unwrap();
+
+    let out: (bool, Decimal, bool) = rune_tests::run(
+        &context,
+        r#"
+        use std::decimal::Decimal;
+
+        pub fn main() {
+            let object = json::from_string(json::to_string(#{ "total": Decimal::parse("0.1")? })?)?;
+            let other = json::from_string("{\"$decimal\": \"1\", \"other\": 2}")?;
+            (object.total is Decimal, object.total, other is Object)
+        }
+        "#,
+        &["main"],
+        (),
+    )
+    .unwrap();
+
+    assert_eq!(out, (true, Decimal::new(1, 1), true));
+}
+
+#[test]
+fn test_decimal_errors() {
+    assert_vm_error!(
+        r#"pub fn main() { std::decimal::Decimal::from_int(1) / 0 }"#,
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { std::decimal::Decimal::from_int(1) + 1.0 }"#,
+        UnsupportedBinaryOperation { op: "+", .. } => {}
+    );
+}
@@ -0,0 +1,217 @@
+use rune::runtime::VmErrorKind::*;
+use rune::runtime::{Key, VmError};
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+
+#[derive(Debug, Any, Clone, PartialEq, Eq, Hash)]
+#[rune(eq, hash)]
+struct Id(u64);
+
+#[derive(Debug, Any, Clone, PartialEq, Eq, Hash)]
+#[rune(eq, hash, clone)]
+struct Slot {
+    #[rune(get, set)]
+    value: i64,
+}
+
+#[derive(Debug, Any, Clone, Hash)]
+#[rune(hash)]
+struct Bucket;
+
+#[derive(Debug, Any)]
+struct Opaque;
+
+fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new();
+    module.ty::<Id>()?;
+    module.ty::<Slot>()?;
+    module.ty::<Bucket>()?;
+    module.ty::<Opaque>()?;
+    module.function(&["Id", "new"], Id)?;
+    module.function(&["Slot", "new"], |value| Slot { value })?;
+    module.function(&["Bucket", "new"], || Bucket)?;
+    module.function(&["Opaque", "new"], || Opaque)?;
+    Ok(module)
+}
+
+/// Run the given source, expecting it to error.
+fn vm_error(source: &str) -> Result<VmError, ContextError> {
+    let mut context = rune_tests::modules::default_context()?;
+    context.install(&module()?)?;
+
+    match rune_tests::run::<_, _, ()>(&context, source, &["main"], ()) {
+        Err(RunError::VmError(error)) => Ok(error),
+        _ => panic!("expected a virtual machine error"),
+    }
+}
+
+#[test]
+fn test_script_struct_keys() {
+    let out: (usize, bool, i64, bool) = rune! {
+        use std::collections::{HashMap, HashSet};
+
+        struct Point { x, y }
+        struct Pair(a, b);
+        struct Origin;
+
+        pub fn main() {
+            let points = HashSet::new();
+            points.insert(Point { x: 1, y: 2 });
+            points.insert(Point { x: 1, y: 2 });
+            points.insert(Point { x: 2, y: 1 });
+            points.insert(Pair(1, 2));
+            points.insert(Origin);
+            points.insert(Origin);
+
+            let names = HashMap::new();
+            names.insert(Pair(1, "one"), 1);
+            names.insert(Pair(2, "two"), 2);
+
+            (
+                points.len(),
+                points.contains(Point { x: 2, y: 1 }),
+                names[Pair(2, "two")],
+                names.contains_key(Pair(2, "one")),
+            )
+        }
+    };
+    assert_eq!(out, (4, true, 2, false));
+}
+
+#[test]
+fn test_native_keys() -> Result<(), ContextError> {
+    let out: (usize, bool, bool) = rune_n! {
+        module()?,
+        (),
+        (usize, bool, bool) =>
+        pub fn main() {
+            let ids = std::collections::HashSet::new();
+            ids.insert(Id::new(1));
+            ids.insert(Id::new(1));
+            ids.insert(Id::new(2));
+            (ids.len(), ids.contains(Id::new(2)), ids.contains(Id::new(3)))
+        }
+    };
+    assert_eq!(out, (2, true, false));
+    Ok(())
+}
+
+#[test]
+fn test_native_key_not_supported() -> Result<(), ContextError> {
+    let mut context = rune_tests::modules::default_context()?;
+    context.install(&module()?)?;
+
+    let result = rune_tests::run::<_, _, ()>(
+        &context,
+        r#"
+        pub fn main() {
+            let set = std::collections::HashSet::new();
+            set.insert(Opaque::new());
+        }
+        "#,
+        &["main"],
+        (),
+    );
+
+    match result {
+        Err(RunError::VmError(error)) => {
+            let (error, _) = error.into_unwound();
+
+            match error.into_kind() {
+                BadArgument { error, arg: 1 } => {
+                    assert!(matches!(error.into_kind(), KeyNotSupported { .. }));
+                }
+                kind => panic!("expected bad argument, got {:?}", kind),
+            }
+        }
+        _ => panic!("expected key not supported error"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_native_key_snapshot() -> Result<(), ContextError> {
+    let out: (bool, bool, i64) = rune_n! {
+        module()?,
+        (),
+        (bool, bool, i64) =>
+        pub fn main() {
+            let slots = std::collections::HashSet::new();
+            let slot = Slot::new(1);
+            slots.insert(slot);
+            slot.value = 2;
+            (slots.contains(Slot::new(1)), slots.contains(Slot::new(2)), slot.value)
+        }
+    };
+    assert_eq!(out, (true, false, 2));
+    Ok(())
+}
+
+#[test]
+fn test_native_key_copied() -> Result<(), ContextError> {
+    let out: (bool, bool, usize) = rune_n! {
+        module()?,
+        (),
+        (bool, bool, usize) =>
+        pub fn main() {
+            let ids = std::collections::HashSet::new();
+            let id = Id::new(1);
+            (ids.insert(id), ids.insert(id), ids.len())
+        }
+    };
+    assert_eq!(out, (true, false, 1));
+    Ok(())
+}
+
+#[test]
+fn test_native_key_eq_error() -> Result<(), ContextError> {
+    let error = vm_error(
+        r#"
+        pub fn main() {
+            let buckets = std::collections::HashSet::new();
+            buckets.insert(Bucket::new());
+            buckets.insert(Bucket::new());
+        }
+        "#,
+    )?;
+
+    let (error, _) = error.into_unwound();
+    assert!(matches!(
+        error.into_kind(),
+        UnsupportedBinaryOperation { op: "==", .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_missing_native_key() -> Result<(), ContextError> {
+    let error = vm_error(
+        r#"
+        pub fn main() {
+            let names = std::collections::HashMap::new();
+            names[Id::new(1)] = "one";
+            names[Id::new(2)]
+        }
+        "#,
+    )?;
+
+    // NB: errors holding native keys can be sent to other threads, since keys
+    // hold on to their own copy of native values.
+    let error = std::thread::spawn(move || {
+        let (error, _) = error.into_unwound();
+
+        match error.into_kind() {
+            MissingIndexKey { index, .. } => {
+                assert!(matches!(index, Key::Any(..)));
+                format!("{:?}", index)
+            }
+            kind => panic!("expected missing index key, got {:?}", kind),
+        }
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(error, "Id");
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_next_protocol() {
+    let out: (Vec<i64>, Vec<i64>, i64) = rune! {
+        struct Countdown { n }
+
+        impl Countdown {
+            fn next(self) {
+                if self.n == 0 {
+                    return None;
+                }
+
+                self.n -= 1;
+                Some(self.n)
+            }
+        }
+
+        pub fn main() {
+            let values = [];
+
+            for n in (Countdown { n: 3 }) {
+                values.push(n);
+            }
+
+            let doubled = Countdown { n: 4 }.map(|n| n * 2).collect::<Vec>();
+            (values, doubled, Countdown { n: 5 }.sum())
+        }
+    };
+    assert_eq!(out, (vec![2, 1, 0], vec![6, 4, 2, 0], 10));
+}
+
+#[test]
+fn test_into_iter_protocol() {
+    let out: (Vec<i64>, Vec<i64>) = rune! {
+        struct Bag { items }
+
+        impl Bag {
+            fn into_iter(self) {
+                self.items.iter().rev()
+            }
+        }
+
+        pub fn main() {
+            let values = [];
+
+            for n in (Bag { items: [1, 2, 3] }) {
+                values.push(n);
+            }
+
+            let extended = [0];
+            extended.extend(Bag { items: [4, 5] });
+            (values, extended)
+        }
+    };
+    assert_eq!(out, (vec![3, 2, 1], vec![0, 5, 4]));
+}
+
+#[test]
+fn test_not_iterable() {
+    assert_vm_error!(
+        r#"struct Point { x, y } pub fn main() { let p = Point { x: 1, y: 2 }; for v in p {} }"#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
+
+#[test]
+fn test_native_next_is_not_the_protocol() {
+    let out: Option<i64> = rune! {
+        async fn numbers() {
+            yield 1;
+        }
+
+        pub async fn main() {
+            numbers().next().await
+        }
+    };
+    assert_eq!(out, Some(1));
+
+    assert_vm_error!(
+        r#"async fn numbers() { yield 1; } pub fn main() { numbers().map(|n| n) }"#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
@@ -0,0 +1,159 @@
+use rune::runtime::{memory, VmErrorKind};
+use rune::{Context, FromValue, Source, Sources, Vm};
+use std::sync::Arc;
+
+fn vm(source: &str) -> rune::Result<Vm> {
+    let context = Context::with_default_modules()?;
+    let mut sources = Sources::new();
+    sources.insert(Source::new("script", source));
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()), Arc::new(unit)))
+}
+
+#[test]
+fn test_vec_growth_limited() -> rune::Result<()> {
+    let mut vm = vm(r#"
+        pub fn main() {
+            let values = [];
+
+            loop {
+                values.push(values.len());
+            }
+        }
+    "#)?;
+
+    let error = memory::with(64 * 1024, || vm.call(&["main"], ()))
+        .call()
+        .unwrap_err();
+    assert!(matches!(
+        error.as_unwound().0,
+        VmErrorKind::MemoryLimitExceeded { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_string_growth_limited() -> rune::Result<()> {
+    let mut vm = vm(r#"
+        pub fn main() {
+            let s = String::new();
+
+            loop {
+                s += "hello world";
+            }
+        }
+    "#)?;
+
+    let error = memory::with(64 * 1024, || vm.call(&["main"], ()))
+        .call()
+        .unwrap_err();
+    assert!(matches!(
+        error.as_unwound().0,
+        VmErrorKind::MemoryLimitExceeded { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_capacity_rejected_up_front() -> rune::Result<()> {
+    for source in [
+        "pub fn main() { String::with_capacity(1024 * 1024 * 1024 * 1024) }",
+        "pub fn main() { std::collections::BinaryHeap::with_capacity(1024 * 1024 * 1024 * 1024) }",
+        "pub fn main() { std::collections::VecDeque::with_capacity(1024 * 1024 * 1024 * 1024) }",
+        "pub fn main() { 3n.pow(4000000000) }",
+        "pub fn main() { 1n << 4000000000 }",
+    ] {
+        let mut vm = vm(source)?;
+
+        let error = memory::with(64 * 1024, || vm.call(&["main"], ()))
+            .call()
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                error.as_unwound().0,
+                VmErrorKind::MemoryLimitExceeded { .. }
+            ),
+            "{}: {}",
+            source,
+            error
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_within_limit() -> rune::Result<()> {
+    let mut vm = vm(r#"
+        pub fn main() {
+            let values = [];
+
+            for n in 0..10 {
+                values.push(`value {n}`);
+            }
+
+            values.len()
+        }
+    "#)?;
+
+    let output = memory::with(64 * 1024, || vm.call(&["main"], ())).call()?;
+    assert_eq!(i64::from_value(output)?, 10);
+    assert_eq!(memory::used(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_freed_memory_returned() -> rune::Result<()> {
+    let mut vm = vm(r#"
+        pub fn main() {
+            for n in 0..1000 {
+                let values = [];
+                let object = #{};
+
+                for i in 0..100 {
+                    values.push((i, n));
+                    object[`${i}`] = values;
+                }
+            }
+        }
+    "#)?;
+
+    let used = memory::with(64 * 1024, || {
+        vm.call(&["main"], ())?;
+        Ok::<_, rune::runtime::VmError>(memory::used())
+    })
+    .call()?;
+
+    assert!(used < 1024, "used {} bytes", used);
+    Ok(())
+}
+
+#[test]
+fn test_native_growth_limited() -> rune::Result<()> {
+    for source in [
+        "pub fn main() { (0..100000000).iter().collect::<Vec>() }",
+        "pub fn main() { let v = []; v.extend(0..100000000); }",
+        "pub fn main() { (0..100000000).iter().map(|n| (n, n)).unzip() }",
+        "pub fn main() { (0..100000000).iter().map(|n| (`${n}`, n)).collect::<Object>() }",
+        "pub fn main() { let h = std::collections::BinaryHeap::new(); h.extend(0..100000000); }",
+    ] {
+        let mut vm = vm(source)?;
+
+        let error = memory::with(64 * 1024, || vm.call(&["main"], ()))
+            .call()
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                error.as_unwound().0,
+                VmErrorKind::MemoryLimitExceeded { .. }
+            ),
+            "{}: {}",
+            source,
+            error
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use rune::runtime::{Function, Value, VmError, VmErrorKind};
+use rune::termcolor::NoColor;
+use rune::{Context, Module, Source, Sources, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn recurse(n) {
+    recurse(n + 1)
+}
+
+pub fn grow(n) {
+    let a = n;
+    let b = n;
+    let c = n;
+    grow(n + 1)
+}
+
+pub fn main() {
+    recurse(0)
+}
+"#;
+
+fn vm() -> rune::Result<(Vm, Sources)> {
+    vm_with_source(SOURCE)
+}
+
+fn vm_with_source(source: &str) -> rune::Result<(Vm, Sources)> {
+    let mut context = Context::with_default_modules()?;
+
+    let mut module = Module::new();
+    module.function(&["call"], |f: Function, n: i64| -> Result<Value, VmError> {
+        f.call((n,))
+    })?;
+    context.install(&module)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("foo.rn", source));
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    let vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    Ok((vm, sources))
+}
+
+#[test]
+fn test_call_depth_limit() -> rune::Result<()> {
+    let (mut vm, sources) = vm()?;
+    vm.set_max_call_depth(100);
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    let (kind, unwound) = error.as_unwound();
+
+    assert!(matches!(
+        kind,
+        VmErrorKind::StackOverflow { depth: 100, .. }
+    ));
+    let (_, _, frames) = unwound.expect("missing backtrace");
+    assert_eq!(frames.len(), 100);
+
+    let mut out = NoColor::new(Vec::new());
+    error.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("stack overflow"), "{}", out);
+    assert!(out.contains("recursion too deep"), "{}", out);
+    assert!(out.contains("foo.rn:3:5:     recurse(n + 1)"), "{}", out);
+    assert!(out.contains("(repeated 99 more times)"), "{}", out);
+    Ok(())
+}
+
+#[test]
+fn test_stack_size_limit() -> rune::Result<()> {
+    let (mut vm, _) = vm()?;
+    vm.set_max_stack_size(64);
+
+    let error = vm.call(&["grow"], (0i64,)).unwrap_err();
+
+    match error.as_unwound().0 {
+        VmErrorKind::StackOverflow { size, .. } => assert!(*size > 64),
+        kind => panic!("expected stack overflow, got {:?}", kind),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_call_depth_limit_through_iterator() -> rune::Result<()> {
+    let (mut vm, _) = vm_with_source(
+        r#"
+        fn r(n) {
+            [n].iter().map(|x| r(x + 1)).collect::<Vec>()
+        }
+
+        pub fn main() {
+            r(0)
+        }
+        "#,
+    )?;
+    vm.set_max_call_depth(100);
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        VmErrorKind::StackOverflow { depth, .. } => assert_eq!(*depth, 100),
+        kind => panic!("expected stack overflow, got {:?}", kind),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_limits_through_native_callback() -> rune::Result<()> {
+    let source = r#"
+    fn r(n) {
+        let a = n;
+        let b = n;
+        call(r, n + 1)
+    }
+
+    pub fn main() {
+        r(0)
+    }
+    "#;
+
+    let (mut vm, _) = vm_with_source(source)?;
+    vm.set_max_call_depth(100);
+
+    match vm.call(&["main"], ()).unwrap_err().as_unwound().0 {
+        VmErrorKind::StackOverflow { depth, .. } => assert_eq!(*depth, 100),
+        kind => panic!("expected stack overflow, got {:?}", kind),
+    }
+
+    let (mut vm, _) = vm_with_source(source)?;
+    vm.set_max_stack_size(256);
+
+    match vm.call(&["main"], ()).unwrap_err().as_unwound().0 {
+        VmErrorKind::StackOverflow { size, .. } => assert!(*size > 256),
+        kind => panic!("expected stack overflow, got {:?}", kind),
+    }
+
+    Ok(())
+}
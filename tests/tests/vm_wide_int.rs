@@ -0,0 +1,164 @@
+use rune::compile::CompileErrorKind::QueryError;
+use rune::compile::IrErrorKind::ConstSuffixedNumber;
+use rune::query::QueryErrorKind::IrError;
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_u64() {
+    let out: (u64, u64, u64, bool, String) = rune_s! {r#"
+        pub fn main() {
+            let max = 18446744073709551615u64;
+            let n = 10u64;
+            n += 5;
+            n <<= 1;
+            (max, max - 1, n + 1u64, 1 + 2u64 == 3, `${n}`)
+        }
+    "#};
+    assert_eq!(out, (u64::MAX, u64::MAX - 1, 31, true, String::from("30")));
+}
+
+#[test]
+fn test_i128() {
+    let out: (i128, i128, bool, Option<i64>) = rune_s! {r#"
+        pub fn main() {
+            let n = 10i128.pow(30);
+            (n * -2, -0xffi128, n > 10i128, 42i128.to_int())
+        }
+    "#};
+    assert_eq!(out, (-2 * 10i128.pow(30), -255, true, Some(42)));
+}
+
+#[test]
+fn test_big_int() {
+    let out: (String, String, bool) = rune_s! {r#"
+        use std::num::BigInt;
+
+        pub fn main() {
+            let n = 2n.pow(100) + 1;
+            let parsed = BigInt::parse("1267650600228229401496703205377")?;
+            (`${n}`, `${n % 1000n}`, n == parsed)
+        }
+    "#};
+    assert_eq!(
+        out,
+        (
+            String::from("1267650600228229401496703205377"),
+            String::from("377"),
+            true
+        )
+    );
+}
+
+#[test]
+fn test_integer_lhs() {
+    let out: (u64, bool, bool, bool, bool, bool, i128) = rune_s! {r#"
+        pub fn main() {
+            (
+                2 * 3u64,
+                1 == 1u64,
+                1u64 == 1,
+                10u64 < 11,
+                1 < 2n,
+                1u64 < 2i128,
+                1 << 3i128,
+            )
+        }
+    "#};
+    assert_eq!(out, (6, true, true, true, true, true, 8));
+}
+
+#[test]
+fn test_wide_int_keys() {
+    let out: (Option<i64>, Option<i64>, bool, usize) = rune_s! {r#"
+        use std::collections::{HashMap, HashSet};
+
+        pub fn main() {
+            let map = HashMap::new();
+            map.insert(1u64, 1);
+            map.insert(18446744073709551615u64, 2);
+
+            let set = HashSet::new();
+            set.insert(1);
+            set.insert(1n);
+            set.insert(1i128);
+
+            (map.get(1), map.get(18446744073709551615n), set.contains(1u64), set.len())
+        }
+    "#};
+    assert_eq!(out, (Some(1), Some(2), true, 1));
+}
+
+#[test]
+fn test_host_conversions() {
+    let context = rune_tests::modules::default_context().unwrap();
+
+    let out: (u64, u128) = rune_tests::run(
+        &context,
+        "pub fn main(a, b) { (a + 1, b * 2) }",
+        &["main"],
+        (u64::MAX - 1, u128::MAX / 4),
+    )
+    .unwrap();
+
+    assert_eq!(out, (u64::MAX, u128::MAX / 4 * 2));
+
+    let out: i64 = rune_tests::run(&context, "pub fn main(a) { a }", &["main"], (42u64,)).unwrap();
+    assert_eq!(out, 42);
+}
+
+#[test]
+fn test_wide_int_errors() {
+    assert_vm_error!(
+        "pub fn main() { 18446744073709551615u64 + 1 }",
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 0u64 - 1 }",
+        Underflow => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 1n / 0 }",
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        "pub fn main() { 1 + 1.0 }",
+        UnsupportedBinaryOperation { op: "+", .. } => {}
+    );
+}
+
+#[test]
+fn test_wide_literals() {
+    let out: (u64, i128, i128, String) = rune_s! {r#"
+        pub fn main() {
+            (
+                0xffffffffffffffffu64,
+                170141183460469231731687303715884105727i128,
+                -170141183460469231731687303715884105728i128,
+                `${-340282366920938463463374607431768211456n}`,
+            )
+        }
+    "#};
+    assert_eq!(
+        out,
+        (
+            u64::MAX,
+            i128::MAX,
+            i128::MIN,
+            String::from("-340282366920938463463374607431768211456")
+        )
+    );
+}
+
+#[test]
+fn test_const_suffixed_literal() {
+    assert_compile_error! {
+        r#"const X = 10u64; pub fn main() { X }"#,
+        span, QueryError { error: IrError { error: ConstSuffixedNumber { .. } } } => {
+            assert_eq!(span, rune::span!(10, 15));
+        }
+    };
+}